@@ -1,6 +1,7 @@
 use crate::cli::config::Config;
 use crate::compiler::CompilationUnit;
 use crate::runtime::{ExpressionValue, Runtime, RuntimeError, load_program};
+use crate::types::ENGINE_THOUGHT_TARGET;
 use agent_client_protocol as acp;
 use std::fs::OpenOptions;
 use std::sync::Arc;
@@ -143,8 +144,17 @@ impl Agent {
         let runtime = self.runtime.clone();
         let session_id = self.session_id.clone();
         let update_tx = self.update_tx.clone();
+        let thought_updates = self
+            .config
+            .as_ref()
+            .is_some_and(|config| config.thought_updates);
 
-        let handle = AGENT_RUNTIME.spawn(Self::run_agent_task(runtime, session_id, update_tx));
+        let handle = AGENT_RUNTIME.spawn(Self::run_agent_task(
+            runtime,
+            session_id,
+            update_tx,
+            thought_updates,
+        ));
 
         self.task_handle = Some(handle);
         debug!("Agent started successfully");
@@ -155,10 +165,12 @@ impl Agent {
         runtime: Arc<Runtime>,
         session_id: acp::SessionId,
         update_tx: mpsc::UnboundedSender<(acp::SessionNotification, oneshot::Sender<()>)>,
+        thought_updates: bool,
     ) -> Result<ExpressionValue, AgentError> {
         debug!("Agent task spawned for session {}", session_id.0);
 
-        let tracing_layer = SessionTracingLayer::new(session_id.clone(), update_tx.clone());
+        let tracing_layer = SessionTracingLayer::new(session_id.clone(), update_tx.clone())
+            .with_thought_updates(thought_updates);
 
         let log_dir = dirs::home_dir()
             .map(|home| home.join(".structured-agent").join("acp-logs"))
@@ -192,8 +204,15 @@ impl Agent {
             session_id = %session_id.0
         );
 
-        let env_filter =
+        let mut env_filter =
             EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
+        if thought_updates {
+            env_filter = env_filter.add_directive(
+                format!("{}=debug", ENGINE_THOUGHT_TARGET)
+                    .parse()
+                    .expect("thought target directive is valid"),
+            );
+        }
 
         let registry = tracing_subscriber::registry()
             .with(env_filter)
@@ -330,6 +349,18 @@ mod tests {
             with_default_functions: false,
             with_unstable_functions: false,
             with_acp_functions: false,
+            describe_bindings: false,
+            analysis_stats: false,
+            check_providers: false,
+            disabled_analyzers: Vec::new(),
+            profile: None,
+            audit_log: None,
+            thought_updates: false,
+            eager_connect: false,
+            max_call_depth: None,
+            run_stats: false,
+            relaxed_signature_matching: false,
+            confirm_destructive: false,
             mode: Mode::Acp,
         };
 
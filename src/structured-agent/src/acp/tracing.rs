@@ -1,3 +1,4 @@
+use crate::types::ENGINE_THOUGHT_TARGET;
 use agent_client_protocol as acp;
 use tokio::sync::{mpsc, oneshot};
 use tracing_subscriber::Layer;
@@ -5,6 +6,7 @@ use tracing_subscriber::Layer;
 pub struct SessionTracingLayer {
     session_id: acp::SessionId,
     update_tx: mpsc::UnboundedSender<(acp::SessionNotification, oneshot::Sender<()>)>,
+    thought_updates: bool,
 }
 
 impl SessionTracingLayer {
@@ -15,20 +17,41 @@ impl SessionTracingLayer {
         Self {
             session_id,
             update_tx,
+            thought_updates: false,
         }
     }
 
-    fn send_message(&self, message: String) {
+    /// Forward engine prompts, responses and thoughts as thought updates.
+    /// When disabled they are dropped rather than sent as message content.
+    pub fn with_thought_updates(mut self, enabled: bool) -> Self {
+        self.thought_updates = enabled;
+        self
+    }
+
+    fn send_update(&self, update: acp::SessionUpdate) {
         let (tx, _rx) = oneshot::channel();
-        let notification = acp::SessionNotification::new(
-            self.session_id.clone(),
-            acp::SessionUpdate::AgentMessageChunk(acp::ContentChunk::new(acp::ContentBlock::Text(
-                acp::TextContent::new(format!("{}\n\n", message)),
-            ))),
-        );
+        let notification = acp::SessionNotification::new(self.session_id.clone(), update);
 
         self.update_tx.send((notification, tx)).ok();
     }
+
+    fn send_message(&self, message: String) {
+        self.send_update(acp::SessionUpdate::AgentMessageChunk(
+            acp::ContentChunk::new(acp::ContentBlock::Text(acp::TextContent::new(format!(
+                "{}\n\n",
+                message
+            )))),
+        ));
+    }
+
+    fn send_thought(&self, thought: String) {
+        self.send_update(acp::SessionUpdate::AgentThoughtChunk(
+            acp::ContentChunk::new(acp::ContentBlock::Text(acp::TextContent::new(format!(
+                "{}\n\n",
+                thought
+            )))),
+        ));
+    }
 }
 
 impl<S> Layer<S> for SessionTracingLayer
@@ -71,7 +94,15 @@ where
         };
         event.record(&mut visitor);
 
-        if !visitor.message.is_empty() {
+        if visitor.message.is_empty() {
+            return;
+        }
+
+        if target == ENGINE_THOUGHT_TARGET {
+            if self.thought_updates {
+                self.send_thought(visitor.message);
+            }
+        } else {
             self.send_message(visitor.message);
         }
     }
@@ -120,3 +151,76 @@ impl tracing::field::Visit for EventVisitor {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracing_subscriber::layer::SubscriberExt;
+    use tracing_subscriber::util::SubscriberInitExt;
+
+    fn collect_updates(thought_updates: bool) -> Vec<acp::SessionUpdate> {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let layer = SessionTracingLayer::new(acp::SessionId::new("test-thoughts".to_string()), tx)
+            .with_thought_updates(thought_updates);
+
+        {
+            let _guard = tracing_subscriber::registry().with(layer).set_default();
+            tracing::debug!(target: ENGINE_THOUGHT_TARGET, "Prompt: secret prompt");
+            tracing::info!("Starting runtime execution");
+        }
+
+        let mut updates = Vec::new();
+        while let Ok((notification, _)) = rx.try_recv() {
+            updates.push(notification.update);
+        }
+        updates
+    }
+
+    fn update_text(update: &acp::SessionUpdate) -> Option<&str> {
+        match update {
+            acp::SessionUpdate::AgentMessageChunk(chunk)
+            | acp::SessionUpdate::AgentThoughtChunk(chunk) => match &chunk.content {
+                acp::ContentBlock::Text(text) => Some(text.text.as_str()),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_engine_events_become_thought_updates_when_enabled() {
+        let updates = collect_updates(true);
+
+        let thoughts: Vec<_> = updates
+            .iter()
+            .filter(|update| matches!(update, acp::SessionUpdate::AgentThoughtChunk(_)))
+            .filter_map(update_text)
+            .collect();
+        assert_eq!(thoughts, vec!["Prompt: secret prompt\n\n"]);
+
+        let messages: Vec<_> = updates
+            .iter()
+            .filter(|update| matches!(update, acp::SessionUpdate::AgentMessageChunk(_)))
+            .filter_map(update_text)
+            .collect();
+        assert_eq!(messages, vec!["Starting runtime execution\n\n"]);
+    }
+
+    #[test]
+    fn test_engine_events_are_dropped_when_disabled() {
+        let updates = collect_updates(false);
+
+        assert!(
+            !updates
+                .iter()
+                .any(|update| matches!(update, acp::SessionUpdate::AgentThoughtChunk(_)))
+        );
+        assert!(
+            updates
+                .iter()
+                .filter_map(update_text)
+                .all(|text| !text.contains("secret prompt"))
+        );
+        assert_eq!(updates.len(), 1);
+    }
+}
@@ -0,0 +1,202 @@
+use crate::analysis::{Analyzer, Warning};
+use crate::ast::{Definition, Expression, Module, Statement};
+use crate::types::FileId;
+use std::collections::HashMap;
+
+pub struct ArgumentSwapAnalyzer {
+    signatures: HashMap<String, Vec<String>>,
+}
+
+impl ArgumentSwapAnalyzer {
+    pub fn new() -> Self {
+        Self {
+            signatures: HashMap::new(),
+        }
+    }
+
+    fn collect_signatures(&mut self, module: &Module) {
+        self.signatures.clear();
+        for definition in &module.definitions {
+            let (name, parameters) = match definition {
+                Definition::Function(func) => (&func.name, &func.parameters),
+                Definition::ExternalFunction(func) => (&func.name, &func.parameters),
+                Definition::ExternalConst(_) | Definition::Constant(_) => continue,
+            };
+            self.signatures.insert(
+                name.clone(),
+                parameters.iter().map(|p| p.name.clone()).collect(),
+            );
+        }
+    }
+
+    fn variable_name(expr: &Expression) -> Option<&str> {
+        match expr {
+            Expression::Variable { name, .. } => Some(name),
+            _ => None,
+        }
+    }
+
+    // Only a pair of variables that each carry the other position's parameter
+    // name counts; a single mismatched name is too common to be a signal.
+    fn find_swap<'a>(
+        parameters: &'a [String],
+        arguments: &[Expression],
+    ) -> Option<(&'a str, &'a str)> {
+        for (i, first) in parameters.iter().enumerate() {
+            for (j, second) in parameters.iter().enumerate().skip(i + 1) {
+                if first == second {
+                    continue;
+                }
+                let passed_as_first = arguments.get(i).and_then(Self::variable_name);
+                let passed_as_second = arguments.get(j).and_then(Self::variable_name);
+                if passed_as_first == Some(second.as_str())
+                    && passed_as_second == Some(first.as_str())
+                {
+                    return Some((first, second));
+                }
+            }
+        }
+        None
+    }
+
+    fn analyze_expression(&self, expr: &Expression, file_id: FileId, warnings: &mut Vec<Warning>) {
+        match expr {
+            Expression::Call {
+                function,
+                arguments,
+                span,
+            } => {
+                if let Some(parameters) = self.signatures.get(function)
+                    && let Some((first, second)) = Self::find_swap(parameters, arguments)
+                {
+                    warnings.push(Warning::LikelyArgumentSwap {
+                        function: function.clone(),
+                        first_parameter: first.to_string(),
+                        second_parameter: second.to_string(),
+                        span: *span,
+                        file_id,
+                    });
+                }
+                for arg in arguments {
+                    self.analyze_expression(arg, file_id, warnings);
+                }
+            }
+            Expression::Select(select_expr) => {
+                for clause in &select_expr.clauses {
+                    if let Some(guard) = &clause.guard {
+                        self.analyze_expression(guard, file_id, warnings);
+                    }
+                    self.analyze_expression(&clause.expression_to_run, file_id, warnings);
+                    self.analyze_expression(&clause.expression_next, file_id, warnings);
+                }
+            }
+            Expression::IfElse {
+                condition,
+                then_expr,
+                else_expr,
+                ..
+            } => {
+                self.analyze_expression(condition, file_id, warnings);
+                self.analyze_expression(then_expr, file_id, warnings);
+                self.analyze_expression(else_expr, file_id, warnings);
+            }
+            Expression::Match {
+                scrutinee, arms, ..
+            } => {
+                self.analyze_expression(scrutinee, file_id, warnings);
+                for arm in arms {
+                    self.analyze_expression(&arm.expression, file_id, warnings);
+                }
+            }
+            Expression::ListLiteral { elements, .. } => {
+                for element in elements {
+                    self.analyze_expression(element, file_id, warnings);
+                }
+            }
+            Expression::OptionLiteral {
+                value: Some(value), ..
+            } => {
+                self.analyze_expression(value, file_id, warnings);
+            }
+            Expression::Return { value, .. } => {
+                self.analyze_expression(value, file_id, warnings);
+            }
+            Expression::FieldAccess { object, .. } => {
+                self.analyze_expression(object, file_id, warnings);
+            }
+            _ => {}
+        }
+    }
+
+    fn analyze_statement(&self, stmt: &Statement, file_id: FileId, warnings: &mut Vec<Warning>) {
+        match stmt {
+            Statement::Injection {
+                expression: expr, ..
+            }
+            | Statement::ExpressionStatement {
+                expression: expr, ..
+            }
+            | Statement::Return {
+                expression: expr, ..
+            } => {
+                self.analyze_expression(expr, file_id, warnings);
+            }
+            Statement::Assignment { expression, .. }
+            | Statement::VariableAssignment { expression, .. } => {
+                self.analyze_expression(expression, file_id, warnings);
+            }
+            Statement::If {
+                condition,
+                body,
+                else_body,
+                ..
+            } => {
+                self.analyze_expression(condition, file_id, warnings);
+                for stmt in body.iter().chain(else_body.iter().flatten()) {
+                    self.analyze_statement(stmt, file_id, warnings);
+                }
+            }
+            Statement::While {
+                condition, body, ..
+            } => {
+                self.analyze_expression(condition, file_id, warnings);
+                for stmt in body {
+                    self.analyze_statement(stmt, file_id, warnings);
+                }
+            }
+            Statement::Assert {
+                condition, message, ..
+            } => {
+                self.analyze_expression(condition, file_id, warnings);
+                self.analyze_expression(message, file_id, warnings);
+            }
+        }
+    }
+}
+
+impl Default for ArgumentSwapAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Analyzer for ArgumentSwapAnalyzer {
+    fn name(&self) -> &str {
+        "argument_swaps"
+    }
+
+    fn analyze_module(&mut self, module: &Module, file_id: FileId) -> Vec<Warning> {
+        self.collect_signatures(module);
+        let mut warnings = Vec::new();
+
+        for definition in &module.definitions {
+            if let Definition::Function(func) = definition {
+                for statement in &func.body.statements {
+                    self.analyze_statement(statement, file_id, &mut warnings);
+                }
+            }
+        }
+
+        warnings
+    }
+}
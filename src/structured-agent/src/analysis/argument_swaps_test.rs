@@ -0,0 +1,95 @@
+#[cfg(test)]
+mod tests {
+    use crate::analysis::{Analyzer, ArgumentSwapAnalyzer, Warning};
+    use crate::ast::Module;
+    use crate::compiler::{CodespanParser, CompilationUnit};
+    use crate::diagnostics::DiagnosticManager;
+
+    fn parse_code(code: &str) -> Module {
+        let unit = CompilationUnit::from_string(code.to_string());
+        let manager = DiagnosticManager::new();
+        let parser = CodespanParser::new();
+        parser.parse(&unit, 0, manager.reporter()).unwrap()
+    }
+
+    #[test]
+    fn detects_swapped_arguments() {
+        let code = r#"
+extern fn concat(value: String, id: String): String
+
+fn test(id: String, value: String): String {
+    return concat(id, value)
+}
+"#;
+
+        let module = parse_code(code);
+        let mut analyzer = ArgumentSwapAnalyzer::new();
+        let warnings = analyzer.analyze_module(&module, 0);
+
+        assert_eq!(warnings.len(), 1);
+        match &warnings[0] {
+            Warning::LikelyArgumentSwap {
+                function,
+                first_parameter,
+                second_parameter,
+                ..
+            } => {
+                assert_eq!(function, "concat");
+                assert_eq!(first_parameter, "value");
+                assert_eq!(second_parameter, "id");
+            }
+            other => panic!("Expected LikelyArgumentSwap, got: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn no_warning_for_arguments_in_order() {
+        let code = r#"
+extern fn concat(value: String, id: String): String
+
+fn test(id: String, value: String): String {
+    return concat(value, id)
+}
+"#;
+
+        let module = parse_code(code);
+        let mut analyzer = ArgumentSwapAnalyzer::new();
+        let warnings = analyzer.analyze_module(&module, 0);
+
+        assert_eq!(warnings.len(), 0);
+    }
+
+    #[test]
+    fn no_warning_for_unrelated_names() {
+        let code = r#"
+extern fn concat(value: String, id: String): String
+
+fn test(name: String, key: String): String {
+    return concat(key, name)
+}
+"#;
+
+        let module = parse_code(code);
+        let mut analyzer = ArgumentSwapAnalyzer::new();
+        let warnings = analyzer.analyze_module(&module, 0);
+
+        assert_eq!(warnings.len(), 0);
+    }
+
+    #[test]
+    fn no_warning_when_only_one_argument_matches_another_parameter() {
+        let code = r#"
+extern fn concat(value: String, id: String): String
+
+fn test(id: String): String {
+    return concat(id, "fixed")
+}
+"#;
+
+        let module = parse_code(code);
+        let mut analyzer = ArgumentSwapAnalyzer::new();
+        let warnings = analyzer.analyze_module(&module, 0);
+
+        assert_eq!(warnings.len(), 0);
+    }
+}
@@ -1,6 +1,6 @@
 use crate::analysis::{Analyzer, Warning};
 use crate::ast::{Definition, Expression, Module, Statement};
-use crate::types::{FileId, Spanned};
+use crate::types::{FileId, Span, Spanned};
 use std::collections::HashMap;
 
 pub struct ConstantConditionAnalyzer;
@@ -22,6 +22,45 @@ impl ConstantConditionAnalyzer {
         }
     }
 
+    fn statements_span(statements: &[Statement]) -> Option<Span> {
+        let first = statements.first()?.span();
+        let last = statements.last()?.span();
+        Some(Span::new(first.start, last.end))
+    }
+
+    // A literal condition makes the untaken branch dead, and that warning
+    // already names the constant condition, so only one of the two is reported.
+    fn report_condition(
+        &self,
+        condition: &Expression,
+        then_span: Option<Span>,
+        else_span: Option<Span>,
+        file_id: FileId,
+        variable_values: &HashMap<String, bool>,
+        warnings: &mut Vec<Warning>,
+    ) {
+        let Some(value) = self.is_constant_condition(condition, variable_values) else {
+            return;
+        };
+
+        let dead_span = if value { else_span } else { then_span };
+        match dead_span {
+            Some(span) if matches!(condition, Expression::BooleanLiteral { .. }) => {
+                warnings.push(Warning::UnreachableBranch {
+                    condition_value: value,
+                    condition_span: condition.span(),
+                    span,
+                    file_id,
+                });
+            }
+            _ => warnings.push(Warning::ConstantCondition {
+                condition_value: value,
+                span: condition.span(),
+                file_id,
+            }),
+        }
+    }
+
     fn collect_assignments(statements: &[Statement], values: &mut HashMap<String, bool>) {
         for stmt in statements {
             match stmt {
@@ -39,8 +78,13 @@ impl ConstantConditionAnalyzer {
                 Statement::VariableAssignment { variable, .. } => {
                     values.remove(variable);
                 }
-                Statement::If { body, .. } => {
+                Statement::If {
+                    body, else_body, ..
+                } => {
                     Self::collect_assignments(body, values);
+                    if let Some(else_body) = else_body {
+                        Self::collect_assignments(else_body, values);
+                    }
                 }
                 Statement::While { body, .. } => {
                     Self::collect_assignments(body, values);
@@ -65,6 +109,16 @@ impl ConstantConditionAnalyzer {
             }
             Expression::Select(select_expr) => {
                 for clause in &select_expr.clauses {
+                    if let Some(guard) = &clause.guard {
+                        self.report_condition(
+                            guard,
+                            None,
+                            None,
+                            file_id,
+                            variable_values,
+                            warnings,
+                        );
+                    }
                     self.analyze_expression(
                         &clause.expression_to_run,
                         file_id,
@@ -85,16 +139,45 @@ impl ConstantConditionAnalyzer {
                 else_expr,
                 ..
             } => {
-                if let Some(value) = self.is_constant_condition(condition, variable_values) {
-                    warnings.push(Warning::ConstantCondition {
-                        condition_value: value,
-                        span: condition.span(),
-                        file_id,
-                    });
-                }
+                self.report_condition(
+                    condition,
+                    Some(then_expr.span()),
+                    Some(else_expr.span()),
+                    file_id,
+                    variable_values,
+                    warnings,
+                );
                 self.analyze_expression(then_expr, file_id, variable_values, warnings);
                 self.analyze_expression(else_expr, file_id, variable_values, warnings);
             }
+            Expression::Match {
+                scrutinee, arms, ..
+            } => {
+                let arm_span = |pattern: bool| {
+                    arms.iter()
+                        .find(|arm| arm.pattern == pattern)
+                        .map(|arm| arm.expression.span())
+                };
+                self.report_condition(
+                    scrutinee,
+                    arm_span(true),
+                    arm_span(false),
+                    file_id,
+                    variable_values,
+                    warnings,
+                );
+                for arm in arms {
+                    self.analyze_expression(&arm.expression, file_id, variable_values, warnings);
+                }
+            }
+            Expression::FieldAccess { object, .. } => {
+                self.analyze_expression(object, file_id, variable_values, warnings);
+            }
+            Expression::OptionLiteral {
+                value: Some(value), ..
+            } => {
+                self.analyze_expression(value, file_id, variable_values, warnings);
+            }
             _ => {}
         }
     }
@@ -108,17 +191,21 @@ impl ConstantConditionAnalyzer {
     ) {
         match stmt {
             Statement::If {
-                condition, body, ..
+                condition,
+                body,
+                else_body,
+                ..
             } => {
-                if let Some(value) = self.is_constant_condition(condition, variable_values) {
-                    warnings.push(Warning::ConstantCondition {
-                        condition_value: value,
-                        span: condition.span(),
-                        file_id,
-                    });
-                }
+                self.report_condition(
+                    condition,
+                    Self::statements_span(body),
+                    else_body.as_deref().and_then(Self::statements_span),
+                    file_id,
+                    variable_values,
+                    warnings,
+                );
 
-                for stmt in body {
+                for stmt in body.iter().chain(else_body.iter().flatten()) {
                     self.analyze_statement(stmt, file_id, variable_values, warnings);
                 }
             }
@@ -133,13 +220,25 @@ impl ConstantConditionAnalyzer {
             Statement::VariableAssignment { expression, .. } => {
                 self.analyze_expression(expression, file_id, variable_values, warnings);
             }
-            Statement::Injection(expr) => {
+            Statement::Injection {
+                expression: expr, ..
+            } => {
                 self.analyze_expression(expr, file_id, variable_values, warnings);
             }
-            Statement::ExpressionStatement(expr) => {
+            Statement::ExpressionStatement {
+                expression: expr, ..
+            } => {
                 self.analyze_expression(expr, file_id, variable_values, warnings);
             }
-            Statement::Return(expr) => {
+            Statement::Assert {
+                condition, message, ..
+            } => {
+                self.analyze_expression(condition, file_id, variable_values, warnings);
+                self.analyze_expression(message, file_id, variable_values, warnings);
+            }
+            Statement::Return {
+                expression: expr, ..
+            } => {
                 self.analyze_expression(expr, file_id, variable_values, warnings);
             }
         }
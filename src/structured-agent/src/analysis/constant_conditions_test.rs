@@ -1,6 +1,6 @@
 #[cfg(test)]
 mod tests {
-    use crate::analysis::{Analyzer, ConstantConditionAnalyzer};
+    use crate::analysis::{Analyzer, ConstantConditionAnalyzer, Warning};
     use crate::ast::Module;
     use crate::compiler::{CodespanParser, CompilationUnit};
     use crate::diagnostics::DiagnosticManager;
@@ -152,4 +152,103 @@ fn test(): String {
 
         assert_eq!(warnings.len(), 2);
     }
+
+    #[test]
+    fn reports_else_branch_of_true_condition_as_unreachable() {
+        let code = r#"
+fn test(): () {
+    if true {
+        "taken"!
+    } else {
+        "dead"!
+    }
+}
+"#;
+
+        let module = parse_code(code);
+        let mut analyzer = ConstantConditionAnalyzer::new();
+        let warnings = analyzer.analyze_module(&module, 0);
+
+        assert_eq!(warnings.len(), 1);
+        match &warnings[0] {
+            Warning::UnreachableBranch {
+                condition_value,
+                span,
+                ..
+            } => {
+                assert!(condition_value);
+                assert_eq!(&code[span.start..span.end], r#""dead""#);
+            }
+            other => panic!("Expected UnreachableBranch, got: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn reports_then_branch_of_false_condition_as_unreachable() {
+        let code = r#"
+fn test(): () {
+    if false {
+        "dead"!
+    } else {
+        "taken"!
+    }
+}
+"#;
+
+        let module = parse_code(code);
+        let mut analyzer = ConstantConditionAnalyzer::new();
+        let warnings = analyzer.analyze_module(&module, 0);
+
+        assert_eq!(warnings.len(), 1);
+        match &warnings[0] {
+            Warning::UnreachableBranch {
+                condition_value,
+                span,
+                ..
+            } => {
+                assert!(!condition_value);
+                assert_eq!(&code[span.start..span.end], r#""dead""#);
+            }
+            other => panic!("Expected UnreachableBranch, got: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn reports_dead_branch_of_if_else_expression() {
+        let code = r#"
+fn test(): String {
+    return if true { "taken" } else { "dead" }
+}
+"#;
+
+        let module = parse_code(code);
+        let mut analyzer = ConstantConditionAnalyzer::new();
+        let warnings = analyzer.analyze_module(&module, 0);
+
+        assert_eq!(warnings.len(), 1);
+        match &warnings[0] {
+            Warning::UnreachableBranch { span, .. } => {
+                assert_eq!(&code[span.start..span.end], r#""dead""#);
+            }
+            other => panic!("Expected UnreachableBranch, got: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn true_condition_without_else_is_only_a_constant_condition() {
+        let code = r#"
+fn test(): () {
+    if true {
+        "always"
+    }
+}
+"#;
+
+        let module = parse_code(code);
+        let mut analyzer = ConstantConditionAnalyzer::new();
+        let warnings = analyzer.analyze_module(&module, 0);
+
+        assert_eq!(warnings.len(), 1);
+        assert!(matches!(warnings[0], Warning::ConstantCondition { .. }));
+    }
 }
@@ -0,0 +1,110 @@
+use crate::analysis::{Analyzer, Warning};
+use crate::ast::{Definition, Expression, Function, Module, Statement, Type};
+use crate::types::{FileId, Spanned};
+use std::collections::HashMap;
+
+/// Warns when a `()` function ends in an expression that produces a value.
+/// Only `return` is checked against the declared type, so a trailing value is
+/// silently dropped at runtime.
+pub struct DiscardedReturnAnalyzer {
+    return_types: HashMap<String, Type>,
+}
+
+impl DiscardedReturnAnalyzer {
+    pub fn new() -> Self {
+        Self {
+            return_types: HashMap::new(),
+        }
+    }
+
+    fn collect_return_types(&mut self, module: &Module) {
+        self.return_types.clear();
+        for definition in &module.definitions {
+            match definition {
+                Definition::Function(func) => {
+                    self.return_types
+                        .insert(func.name.clone(), func.return_type.clone());
+                }
+                Definition::ExternalFunction(func) => {
+                    self.return_types
+                        .insert(func.name.clone(), func.return_type.clone());
+                }
+                Definition::ExternalConst(_) | Definition::Constant(_) => {}
+            }
+        }
+    }
+
+    // Whether the expression is known to evaluate to something other than
+    // `()`. Variables and placeholders are not typed here, so they never warn.
+    fn produces_value(&self, expression: &Expression) -> bool {
+        match expression {
+            Expression::StringLiteral { .. }
+            | Expression::BooleanLiteral { .. }
+            | Expression::NumberLiteral { .. }
+            | Expression::ListLiteral { .. }
+            | Expression::OptionLiteral { .. } => true,
+            Expression::Call { function, .. } => self
+                .return_types
+                .get(function)
+                .is_some_and(|return_type| *return_type != Type::Unit),
+            Expression::IfElse {
+                then_expr,
+                else_expr,
+                ..
+            } => self.produces_value(then_expr) || self.produces_value(else_expr),
+            Expression::Match { arms, .. } => {
+                arms.iter().any(|arm| self.produces_value(&arm.expression))
+            }
+            Expression::Select(select_expr) => select_expr
+                .clauses
+                .iter()
+                .any(|clause| self.produces_value(&clause.expression_next)),
+            Expression::Variable { .. }
+            | Expression::UnitLiteral { .. }
+            | Expression::Return { .. }
+            | Expression::FieldAccess { .. }
+            | Expression::Placeholder { .. } => false,
+        }
+    }
+
+    fn analyze_function(&self, func: &Function, file_id: FileId) -> Option<Warning> {
+        if func.return_type != Type::Unit {
+            return None;
+        }
+        match func.body.statements.last() {
+            Some(Statement::ExpressionStatement {
+                expression: expr, ..
+            }) if self.produces_value(expr) => Some(Warning::DiscardedReturnInUnitFunction {
+                function: func.name.clone(),
+                span: expr.span(),
+                file_id,
+            }),
+            _ => None,
+        }
+    }
+}
+
+impl Default for DiscardedReturnAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Analyzer for DiscardedReturnAnalyzer {
+    fn name(&self) -> &str {
+        "discarded-returns"
+    }
+
+    fn analyze_module(&mut self, module: &Module, file_id: FileId) -> Vec<Warning> {
+        self.collect_return_types(module);
+
+        module
+            .definitions
+            .iter()
+            .filter_map(|definition| match definition {
+                Definition::Function(func) => self.analyze_function(func, file_id),
+                _ => None,
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,103 @@
+#[cfg(test)]
+mod tests {
+    use crate::analysis::{
+        AnalysisRunner, Analyzer, DiscardedReturnAnalyzer, UnusedReturnValueAnalyzer, Warning,
+    };
+    use crate::ast::Module;
+    use crate::compiler::{CodespanParser, CompilationUnit};
+    use crate::diagnostics::DiagnosticManager;
+
+    fn parse_code(code: &str) -> Module {
+        let unit = CompilationUnit::from_string(code.to_string());
+        let manager = DiagnosticManager::new();
+        let parser = CodespanParser::new();
+        parser.parse(&unit, 0, manager.reporter()).unwrap()
+    }
+
+    fn analyze(code: &str) -> Vec<Warning> {
+        DiscardedReturnAnalyzer::new().analyze_module(&parse_code(code), 0)
+    }
+
+    #[test]
+    fn unit_function_ending_in_string_call_warns() {
+        let code = r#"
+extern fn summarise(text: String): String
+
+fn main(): () {
+    summarise("notes")
+}
+"#;
+
+        let warnings = analyze(code);
+
+        assert_eq!(warnings.len(), 1);
+        match &warnings[0] {
+            Warning::DiscardedReturnInUnitFunction { function, span, .. } => {
+                assert_eq!(function, "main");
+                assert_eq!(&code[span.to_byte_range()], "summarise(\"notes\")");
+            }
+            other => panic!("Expected DiscardedReturnInUnitFunction, got: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn unit_function_ending_in_injection_does_not_warn() {
+        let code = r#"
+extern fn summarise(text: String): String
+
+fn main(): () {
+    summarise("notes")!
+}
+"#;
+
+        assert!(analyze(code).is_empty());
+    }
+
+    #[test]
+    fn unit_function_ending_in_unit_call_does_not_warn() {
+        let code = r#"
+extern fn log(text: String): ()
+
+fn main(): () {
+    log("done")
+}
+"#;
+
+        assert!(analyze(code).is_empty());
+    }
+
+    #[test]
+    fn value_returning_function_does_not_warn() {
+        let code = r#"
+extern fn summarise(text: String): String
+
+fn summary(): String {
+    summarise("notes")
+}
+"#;
+
+        assert!(analyze(code).is_empty());
+    }
+
+    #[test]
+    fn runner_reports_discarded_call_once() {
+        let code = r#"
+extern fn summarise(text: String): String
+
+fn main(): () {
+    summarise("notes")
+}
+"#;
+
+        let mut runner = AnalysisRunner::new()
+            .with_analyzer(Box::new(UnusedReturnValueAnalyzer::new()))
+            .with_analyzer(Box::new(DiscardedReturnAnalyzer::new()));
+        let warnings = runner.run(&parse_code(code), 0);
+
+        assert_eq!(warnings.len(), 1);
+        assert!(matches!(
+            warnings[0],
+            Warning::DiscardedReturnInUnitFunction { .. }
+        ));
+    }
+}
@@ -30,7 +30,9 @@ impl DuplicateInjectionAnalyzer {
 
         for stmt in statements {
             match stmt {
-                Statement::Injection(value) => {
+                Statement::Injection {
+                    expression: value, ..
+                } => {
                     if let Some(current_value) = Self::extract_injection_value(value) {
                         if let Some(last_value) = &last_injection
                             && *last_value == current_value
@@ -16,6 +16,7 @@ impl EmptyBlockAnalyzer {
                 body,
                 else_body,
                 span,
+                ..
             } => {
                 if body.is_empty() {
                     warnings.push(Warning::EmptyBlock {
@@ -45,6 +46,7 @@ impl EmptyBlockAnalyzer {
                 condition: _,
                 body,
                 span,
+                ..
             } => {
                 if body.is_empty() {
                     warnings.push(Warning::EmptyBlock {
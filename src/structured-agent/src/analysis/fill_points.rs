@@ -0,0 +1,230 @@
+use crate::ast::{Definition, Expression, Function, Module, Parameter, Statement, Type};
+use crate::types::Span;
+use std::collections::HashMap;
+use std::fmt;
+
+/// A place where running the program asks the engine for a value.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FillPoint {
+    /// The function whose body contains the fill.
+    pub function: String,
+    pub kind: FillKind,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FillKind {
+    /// A `_` argument. The callee and parameter are unknown when the call
+    /// does not resolve to a function declared in the module.
+    Argument {
+        callee: String,
+        parameter: Option<String>,
+        param_type: Option<Type>,
+    },
+    /// A non-Unit function that ends without returning a value, so the
+    /// engine generates its return value.
+    Return { return_type: Type },
+}
+
+impl fmt::Display for FillPoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}..{} in {}: ",
+            self.span.start, self.span.end, self.function
+        )?;
+        match &self.kind {
+            FillKind::Argument {
+                callee,
+                parameter,
+                param_type,
+            } => {
+                let parameter = parameter.as_deref().unwrap_or("?");
+                match param_type {
+                    Some(param_type) => {
+                        write!(f, "argument {}({}: {})", callee, parameter, param_type)
+                    }
+                    None => write!(f, "argument {}({})", callee, parameter),
+                }
+            }
+            FillKind::Return { return_type } => write!(f, "return value: {}", return_type),
+        }
+    }
+}
+
+/// Every fill point in `module`, in source order, found without compiling or
+/// running it.
+pub fn fill_points(module: &Module) -> Vec<FillPoint> {
+    let mut signatures: HashMap<&str, &[Parameter]> = HashMap::new();
+    for definition in &module.definitions {
+        match definition {
+            Definition::Function(func) => {
+                signatures.insert(&func.name, &func.parameters);
+            }
+            Definition::ExternalFunction(func) => {
+                signatures.insert(&func.name, &func.parameters);
+            }
+            Definition::ExternalConst(_) | Definition::Constant(_) => {}
+        }
+    }
+
+    let mut points = Vec::new();
+    for definition in &module.definitions {
+        if let Definition::Function(func) = definition {
+            let mut collector = FillCollector {
+                function: &func.name,
+                signatures: &signatures,
+                points: &mut points,
+            };
+            collector.statements(&func.body.statements);
+            if generates_return_value(func) {
+                points.push(FillPoint {
+                    function: func.name.clone(),
+                    kind: FillKind::Return {
+                        return_type: func.return_type.clone(),
+                    },
+                    span: func.return_type_span,
+                });
+            }
+        }
+    }
+    points
+}
+
+// Mirrors the bytecode compiler, which asks the engine for the return value
+// when no top-level `return` or trailing expression provides one.
+fn generates_return_value(func: &Function) -> bool {
+    func.return_type != Type::Unit
+        && func.implicit_return().is_none()
+        && !func
+            .body
+            .statements
+            .iter()
+            .any(|statement| matches!(statement, Statement::Return { expression: _, .. }))
+}
+
+struct FillCollector<'a> {
+    function: &'a str,
+    signatures: &'a HashMap<&'a str, &'a [Parameter]>,
+    points: &'a mut Vec<FillPoint>,
+}
+
+impl FillCollector<'_> {
+    fn statements(&mut self, statements: &[Statement]) {
+        for statement in statements {
+            self.statement(statement);
+        }
+    }
+
+    fn statement(&mut self, statement: &Statement) {
+        match statement {
+            Statement::Injection {
+                expression: expr, ..
+            }
+            | Statement::ExpressionStatement {
+                expression: expr, ..
+            }
+            | Statement::Return {
+                expression: expr, ..
+            } => self.expression(expr),
+            Statement::Assignment { expression, .. }
+            | Statement::VariableAssignment { expression, .. } => self.expression(expression),
+            Statement::If {
+                condition,
+                body,
+                else_body,
+                ..
+            } => {
+                self.expression(condition);
+                self.statements(body);
+                if let Some(else_body) = else_body {
+                    self.statements(else_body);
+                }
+            }
+            Statement::While {
+                condition, body, ..
+            } => {
+                self.expression(condition);
+                self.statements(body);
+            }
+            Statement::Assert {
+                condition, message, ..
+            } => {
+                self.expression(condition);
+                self.expression(message);
+            }
+        }
+    }
+
+    fn expression(&mut self, expression: &Expression) {
+        match expression {
+            Expression::Call {
+                function,
+                arguments,
+                ..
+            } => {
+                let parameters = self.signatures.get(function.as_str());
+                for (index, arg) in arguments.iter().enumerate() {
+                    if let Expression::Placeholder { span } = arg {
+                        let parameter = parameters.and_then(|parameters| parameters.get(index));
+                        self.points.push(FillPoint {
+                            function: self.function.to_string(),
+                            kind: FillKind::Argument {
+                                callee: function.clone(),
+                                parameter: parameter.map(|p| p.name.clone()),
+                                param_type: parameter.map(|p| p.param_type.clone()),
+                            },
+                            span: *span,
+                        });
+                    } else {
+                        self.expression(arg);
+                    }
+                }
+            }
+            Expression::ListLiteral { elements, .. } => {
+                for element in elements {
+                    self.expression(element);
+                }
+            }
+            Expression::OptionLiteral {
+                value: Some(value), ..
+            } => self.expression(value),
+            Expression::Select(select_expr) => {
+                for clause in &select_expr.clauses {
+                    self.expression(&clause.expression_to_run);
+                    if let Some(guard) = &clause.guard {
+                        self.expression(guard);
+                    }
+                    self.expression(&clause.expression_next);
+                }
+            }
+            Expression::IfElse {
+                condition,
+                then_expr,
+                else_expr,
+                ..
+            } => {
+                self.expression(condition);
+                self.expression(then_expr);
+                self.expression(else_expr);
+            }
+            Expression::Match {
+                scrutinee, arms, ..
+            } => {
+                self.expression(scrutinee);
+                for arm in arms {
+                    self.expression(&arm.expression);
+                }
+            }
+            Expression::Return { value, .. } => self.expression(value),
+            Expression::FieldAccess { object, .. } => self.expression(object),
+            Expression::Variable { .. }
+            | Expression::StringLiteral { .. }
+            | Expression::BooleanLiteral { .. }
+            | Expression::NumberLiteral { .. }
+            | Expression::OptionLiteral { value: None, .. }
+            | Expression::UnitLiteral { .. }
+            | Expression::Placeholder { .. } => {}
+        }
+    }
+}
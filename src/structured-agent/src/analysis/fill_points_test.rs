@@ -0,0 +1,120 @@
+#[cfg(test)]
+mod tests {
+    use crate::analysis::{FillKind, FillPoint, fill_points};
+    use crate::ast::{Module, Type};
+    use crate::compiler::{CodespanParser, CompilationUnit};
+
+    const CALCULATOR: &str = r#"
+extern fn add(ctx: String, x: String, y: String): String
+extern fn subtract(ctx: String, x: String, y: String): String
+
+fn calculator_agent(ctx: String, request: String): String {
+    "You are a calculator. Use the tools provided."!
+    request!
+
+    let result = select {
+        add(ctx, _, _) as sum => sum,
+        subtract(ctx, _, _) as diff => diff
+    }
+
+    result
+}
+
+fn explain(result: String): String {
+    "Explain how this result was reached."!
+    result!
+}
+
+fn main(): () {
+    let answer = calculator_agent("", "What is 2 + 2?")
+    explain(answer)!
+}
+"#;
+
+    fn parse(code: &str) -> Module {
+        let unit = CompilationUnit::from_string(code.to_string());
+        CodespanParser::new().parse_collecting(&unit, 0).unwrap()
+    }
+
+    fn argument(callee: &str, parameter: &str) -> FillKind {
+        FillKind::Argument {
+            callee: callee.to_string(),
+            parameter: Some(parameter.to_string()),
+            param_type: Some(Type::String),
+        }
+    }
+
+    fn nth_placeholder(after: &str, n: usize) -> (usize, usize) {
+        let call = CALCULATOR.find(after).unwrap();
+        let start = CALCULATOR[call..]
+            .match_indices('_')
+            .nth(n)
+            .map(|(offset, _)| call + offset)
+            .unwrap();
+        (start, start + 1)
+    }
+
+    #[test]
+    fn calculator_fill_points_are_listed_with_parameters_and_spans() {
+        let module = parse(CALCULATOR);
+
+        let points: Vec<(String, FillKind, (usize, usize))> = fill_points(&module)
+            .into_iter()
+            .map(
+                |FillPoint {
+                     function,
+                     kind,
+                     span,
+                 }| (function, kind, (span.start, span.end)),
+            )
+            .collect();
+
+        let explain_return = CALCULATOR.find("String {\n    \"Explain").unwrap();
+        assert_eq!(
+            points,
+            vec![
+                (
+                    "calculator_agent".to_string(),
+                    argument("add", "x"),
+                    nth_placeholder("add(ctx, _, _)", 0)
+                ),
+                (
+                    "calculator_agent".to_string(),
+                    argument("add", "y"),
+                    nth_placeholder("add(ctx, _, _)", 1)
+                ),
+                (
+                    "calculator_agent".to_string(),
+                    argument("subtract", "x"),
+                    nth_placeholder("subtract(ctx, _, _)", 0)
+                ),
+                (
+                    "calculator_agent".to_string(),
+                    argument("subtract", "y"),
+                    nth_placeholder("subtract(ctx, _, _)", 1)
+                ),
+                (
+                    "explain".to_string(),
+                    FillKind::Return {
+                        return_type: Type::String
+                    },
+                    (explain_return, explain_return + "String".len())
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn fill_point_display_names_the_parameter() {
+        let module = parse(CALCULATOR);
+        let first = fill_points(&module).remove(0);
+
+        assert!(
+            first
+                .to_string()
+                .ends_with("in calculator_agent: argument add(x: String)"),
+            "{}",
+            first
+        );
+    }
+}
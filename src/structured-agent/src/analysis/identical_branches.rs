@@ -0,0 +1,435 @@
+use super::infinite_loops::contains_call;
+use crate::analysis::{Analyzer, Warning};
+use crate::ast::{Definition, Expression, Module, SelectClause, Statement};
+use crate::types::{FileId, Span, Spanned};
+
+pub struct IdenticalBranchAnalyzer;
+
+impl IdenticalBranchAnalyzer {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn check_statements(statements: &[Statement], file_id: FileId, warnings: &mut Vec<Warning>) {
+        for statement in statements {
+            Self::check_statement(statement, file_id, warnings);
+        }
+    }
+
+    fn check_statement(statement: &Statement, file_id: FileId, warnings: &mut Vec<Warning>) {
+        match statement {
+            Statement::Injection {
+                expression: expr, ..
+            }
+            | Statement::ExpressionStatement {
+                expression: expr, ..
+            }
+            | Statement::Return {
+                expression: expr, ..
+            } => Self::check_expression(expr, file_id, warnings),
+            Statement::Assignment { expression, .. }
+            | Statement::VariableAssignment { expression, .. } => {
+                Self::check_expression(expression, file_id, warnings)
+            }
+            Statement::If {
+                condition,
+                body,
+                else_body,
+                span,
+                ..
+            } => {
+                if let Some(else_body) = else_body
+                    && !body.is_empty()
+                    && statements_equal(body, else_body)
+                {
+                    Self::report(condition, *span, file_id, warnings);
+                }
+                Self::check_expression(condition, file_id, warnings);
+                Self::check_statements(body, file_id, warnings);
+                if let Some(else_body) = else_body {
+                    Self::check_statements(else_body, file_id, warnings);
+                }
+            }
+            Statement::While {
+                condition, body, ..
+            } => {
+                Self::check_expression(condition, file_id, warnings);
+                Self::check_statements(body, file_id, warnings);
+            }
+            Statement::Assert {
+                condition, message, ..
+            } => {
+                Self::check_expression(condition, file_id, warnings);
+                Self::check_expression(message, file_id, warnings);
+            }
+        }
+    }
+
+    fn check_expression(expression: &Expression, file_id: FileId, warnings: &mut Vec<Warning>) {
+        match expression {
+            Expression::IfElse {
+                condition,
+                then_expr,
+                else_expr,
+                span,
+            } => {
+                if expressions_equal(then_expr, else_expr) {
+                    Self::report(condition, *span, file_id, warnings);
+                }
+                Self::check_expression(condition, file_id, warnings);
+                Self::check_expression(then_expr, file_id, warnings);
+                Self::check_expression(else_expr, file_id, warnings);
+            }
+            Expression::Match {
+                scrutinee,
+                arms,
+                span,
+            } => {
+                if let [first, second] = arms.as_slice()
+                    && expressions_equal(&first.expression, &second.expression)
+                {
+                    Self::report(scrutinee, *span, file_id, warnings);
+                }
+                Self::check_expression(scrutinee, file_id, warnings);
+                for arm in arms {
+                    Self::check_expression(&arm.expression, file_id, warnings);
+                }
+            }
+            Expression::Call { arguments, .. } => {
+                for arg in arguments {
+                    Self::check_expression(arg, file_id, warnings);
+                }
+            }
+            Expression::ListLiteral { elements, .. } => {
+                for element in elements {
+                    Self::check_expression(element, file_id, warnings);
+                }
+            }
+            Expression::OptionLiteral {
+                value: Some(value), ..
+            } => Self::check_expression(value, file_id, warnings),
+            Expression::Select(select_expr) => {
+                for clause in &select_expr.clauses {
+                    if let Some(guard) = &clause.guard {
+                        Self::check_expression(guard, file_id, warnings);
+                    }
+                    Self::check_expression(&clause.expression_to_run, file_id, warnings);
+                    Self::check_expression(&clause.expression_next, file_id, warnings);
+                }
+            }
+            Expression::Return { value, .. } => Self::check_expression(value, file_id, warnings),
+            Expression::FieldAccess { object, .. } => {
+                Self::check_expression(object, file_id, warnings)
+            }
+            Expression::Variable { .. }
+            | Expression::StringLiteral { .. }
+            | Expression::BooleanLiteral { .. }
+            | Expression::NumberLiteral { .. }
+            | Expression::OptionLiteral { value: None, .. }
+            | Expression::UnitLiteral { .. }
+            | Expression::Placeholder { .. } => {}
+        }
+    }
+
+    fn report(condition: &Expression, span: Span, file_id: FileId, warnings: &mut Vec<Warning>) {
+        warnings.push(Warning::IdenticalBranches {
+            span,
+            condition_span: condition.span(),
+            condition_calls: contains_call(condition),
+            file_id,
+        });
+    }
+}
+
+// Structural equality that ignores spans, so copies of the same code at
+// different places in the source compare equal. The fallback arms name every
+// variant, so a new variant has to be given a comparison here.
+fn statements_equal(left: &[Statement], right: &[Statement]) -> bool {
+    left.len() == right.len()
+        && left
+            .iter()
+            .zip(right)
+            .all(|(left, right)| statement_equal(left, right))
+}
+
+fn statement_equal(left: &Statement, right: &Statement) -> bool {
+    match (left, right) {
+        (
+            Statement::Injection {
+                expression: left, ..
+            },
+            Statement::Injection {
+                expression: right, ..
+            },
+        )
+        | (
+            Statement::ExpressionStatement {
+                expression: left, ..
+            },
+            Statement::ExpressionStatement {
+                expression: right, ..
+            },
+        )
+        | (
+            Statement::Return {
+                expression: left, ..
+            },
+            Statement::Return {
+                expression: right, ..
+            },
+        ) => expressions_equal(left, right),
+        (
+            Statement::Assignment {
+                variable: left_variable,
+                expression: left_expression,
+                ..
+            },
+            Statement::Assignment {
+                variable: right_variable,
+                expression: right_expression,
+                ..
+            },
+        )
+        | (
+            Statement::VariableAssignment {
+                variable: left_variable,
+                expression: left_expression,
+                ..
+            },
+            Statement::VariableAssignment {
+                variable: right_variable,
+                expression: right_expression,
+                ..
+            },
+        ) => {
+            left_variable == right_variable && expressions_equal(left_expression, right_expression)
+        }
+        (
+            Statement::If {
+                condition: left_condition,
+                body: left_body,
+                else_body: left_else,
+                ..
+            },
+            Statement::If {
+                condition: right_condition,
+                body: right_body,
+                else_body: right_else,
+                ..
+            },
+        ) => {
+            expressions_equal(left_condition, right_condition)
+                && statements_equal(left_body, right_body)
+                && match (left_else, right_else) {
+                    (Some(left_else), Some(right_else)) => statements_equal(left_else, right_else),
+                    (None, None) => true,
+                    _ => false,
+                }
+        }
+        (
+            Statement::While {
+                condition: left_condition,
+                body: left_body,
+                ..
+            },
+            Statement::While {
+                condition: right_condition,
+                body: right_body,
+                ..
+            },
+        ) => {
+            expressions_equal(left_condition, right_condition)
+                && statements_equal(left_body, right_body)
+        }
+        (
+            Statement::Assert {
+                condition: left_condition,
+                message: left_message,
+                ..
+            },
+            Statement::Assert {
+                condition: right_condition,
+                message: right_message,
+                ..
+            },
+        ) => {
+            expressions_equal(left_condition, right_condition)
+                && expressions_equal(left_message, right_message)
+        }
+        (Statement::Injection { .. }, _)
+        | (Statement::ExpressionStatement { .. }, _)
+        | (Statement::Return { .. }, _)
+        | (Statement::Assignment { .. }, _)
+        | (Statement::VariableAssignment { .. }, _)
+        | (Statement::If { .. }, _)
+        | (Statement::While { .. }, _)
+        | (Statement::Assert { .. }, _) => false,
+    }
+}
+
+fn expressions_equal(left: &Expression, right: &Expression) -> bool {
+    match (left, right) {
+        (
+            Expression::Call {
+                function: left_function,
+                arguments: left_arguments,
+                ..
+            },
+            Expression::Call {
+                function: right_function,
+                arguments: right_arguments,
+                ..
+            },
+        ) => left_function == right_function && all_equal(left_arguments, right_arguments),
+        (Expression::Variable { name: left, .. }, Expression::Variable { name: right, .. }) => {
+            left == right
+        }
+        (
+            Expression::StringLiteral { value: left, .. },
+            Expression::StringLiteral { value: right, .. },
+        ) => left == right,
+        (
+            Expression::BooleanLiteral { value: left, .. },
+            Expression::BooleanLiteral { value: right, .. },
+        ) => left == right,
+        (
+            Expression::NumberLiteral { value: left, .. },
+            Expression::NumberLiteral { value: right, .. },
+        ) => left == right,
+        (
+            Expression::ListLiteral { elements: left, .. },
+            Expression::ListLiteral {
+                elements: right, ..
+            },
+        ) => all_equal(left, right),
+        (
+            Expression::OptionLiteral { value: left, .. },
+            Expression::OptionLiteral { value: right, .. },
+        ) => match (left, right) {
+            (Some(left), Some(right)) => expressions_equal(left, right),
+            (None, None) => true,
+            _ => false,
+        },
+        (Expression::Placeholder { .. }, Expression::Placeholder { .. })
+        | (Expression::UnitLiteral { .. }, Expression::UnitLiteral { .. }) => true,
+        (Expression::Select(left), Expression::Select(right)) => {
+            left.ranked == right.ranked
+                && left.retry == right.retry
+                && left.clauses.len() == right.clauses.len()
+                && left
+                    .clauses
+                    .iter()
+                    .zip(&right.clauses)
+                    .all(|(left, right)| clauses_equal(left, right))
+        }
+        (
+            Expression::IfElse {
+                condition: left_condition,
+                then_expr: left_then,
+                else_expr: left_else,
+                ..
+            },
+            Expression::IfElse {
+                condition: right_condition,
+                then_expr: right_then,
+                else_expr: right_else,
+                ..
+            },
+        ) => {
+            expressions_equal(left_condition, right_condition)
+                && expressions_equal(left_then, right_then)
+                && expressions_equal(left_else, right_else)
+        }
+        (
+            Expression::Match {
+                scrutinee: left_scrutinee,
+                arms: left_arms,
+                ..
+            },
+            Expression::Match {
+                scrutinee: right_scrutinee,
+                arms: right_arms,
+                ..
+            },
+        ) => {
+            expressions_equal(left_scrutinee, right_scrutinee)
+                && left_arms.len() == right_arms.len()
+                && left_arms.iter().zip(right_arms).all(|(left, right)| {
+                    left.pattern == right.pattern
+                        && expressions_equal(&left.expression, &right.expression)
+                })
+        }
+        (Expression::Return { value: left, .. }, Expression::Return { value: right, .. }) => {
+            expressions_equal(left, right)
+        }
+        (
+            Expression::FieldAccess {
+                object: left_object,
+                field: left_field,
+                ..
+            },
+            Expression::FieldAccess {
+                object: right_object,
+                field: right_field,
+                ..
+            },
+        ) => left_field == right_field && expressions_equal(left_object, right_object),
+        (Expression::Call { .. }, _)
+        | (Expression::Variable { .. }, _)
+        | (Expression::StringLiteral { .. }, _)
+        | (Expression::BooleanLiteral { .. }, _)
+        | (Expression::NumberLiteral { .. }, _)
+        | (Expression::ListLiteral { .. }, _)
+        | (Expression::OptionLiteral { .. }, _)
+        | (Expression::Placeholder { .. }, _)
+        | (Expression::UnitLiteral { .. }, _)
+        | (Expression::Select(_), _)
+        | (Expression::IfElse { .. }, _)
+        | (Expression::Match { .. }, _)
+        | (Expression::Return { .. }, _)
+        | (Expression::FieldAccess { .. }, _) => false,
+    }
+}
+
+fn all_equal(left: &[Expression], right: &[Expression]) -> bool {
+    left.len() == right.len()
+        && left
+            .iter()
+            .zip(right)
+            .all(|(left, right)| expressions_equal(left, right))
+}
+
+fn clauses_equal(left: &SelectClause, right: &SelectClause) -> bool {
+    left.result_variable == right.result_variable
+        && expressions_equal(&left.expression_to_run, &right.expression_to_run)
+        && expressions_equal(&left.expression_next, &right.expression_next)
+        && match (&left.guard, &right.guard) {
+            (Some(left), Some(right)) => expressions_equal(left, right),
+            (None, None) => true,
+            _ => false,
+        }
+}
+
+impl Default for IdenticalBranchAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Analyzer for IdenticalBranchAnalyzer {
+    fn name(&self) -> &str {
+        "identical-branches"
+    }
+
+    fn analyze_module(&mut self, module: &Module, file_id: FileId) -> Vec<Warning> {
+        let mut warnings = Vec::new();
+
+        for definition in &module.definitions {
+            if let Definition::Function(func) = definition {
+                Self::check_statements(&func.body.statements, file_id, &mut warnings);
+            }
+        }
+
+        warnings
+    }
+}
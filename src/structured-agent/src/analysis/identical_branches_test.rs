@@ -0,0 +1,124 @@
+#[cfg(test)]
+mod tests {
+    use crate::analysis::{Analyzer, IdenticalBranchAnalyzer, Warning};
+    use crate::compiler::{CodespanParser, CompilationUnit};
+    use crate::diagnostics::DiagnosticManager;
+
+    fn analyze(code: &str) -> Vec<Warning> {
+        let unit = CompilationUnit::from_string(code.to_string());
+        let manager = DiagnosticManager::new();
+        let parser = CodespanParser::new();
+        let module = parser.parse(&unit, 0, manager.reporter()).unwrap();
+        IdenticalBranchAnalyzer::new().analyze_module(&module, 0)
+    }
+
+    #[test]
+    fn identical_if_statement_branches_warn() {
+        let code = r#"
+extern fn ready(): Boolean
+extern fn greet(name: String): String
+
+fn main(): () {
+    if ready() {
+        greet("world")!
+    } else {
+        greet("world")!
+    }
+}
+"#;
+
+        let warnings = analyze(code);
+
+        assert_eq!(warnings.len(), 1);
+        match &warnings[0] {
+            Warning::IdenticalBranches {
+                condition_span,
+                condition_calls,
+                ..
+            } => {
+                assert_eq!(&code[condition_span.to_byte_range()], "ready()");
+                assert!(condition_calls);
+            }
+            other => panic!("Expected IdenticalBranches, got: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn identical_if_else_expression_branches_warn() {
+        let warnings = analyze(
+            r#"
+extern fn ready(): Boolean
+
+fn main(): () {
+    let answer = if ready() { "yes" } else { "yes" }
+    answer!
+}
+"#,
+        );
+
+        assert_eq!(warnings.len(), 1);
+        assert!(matches!(warnings[0], Warning::IdenticalBranches { .. }));
+    }
+
+    #[test]
+    fn condition_without_a_call_can_be_dropped() {
+        let warnings = analyze(
+            r#"
+fn main(ready: Boolean): () {
+    if ready {
+        "yes"!
+    } else {
+        "yes"!
+    }
+}
+"#,
+        );
+
+        assert_eq!(warnings.len(), 1);
+        assert!(matches!(
+            warnings[0],
+            Warning::IdenticalBranches {
+                condition_calls: false,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn differing_branches_do_not_warn() {
+        let warnings = analyze(
+            r#"
+extern fn ready(): Boolean
+
+fn main(): () {
+    if ready() {
+        "yes"!
+    } else {
+        "no"!
+    }
+    let answer = if ready() { "yes" } else { "no" }
+    answer!
+}
+"#,
+        );
+
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn if_without_else_does_not_warn() {
+        let warnings = analyze(
+            r#"
+extern fn ready(): Boolean
+
+fn main(): () {
+    if ready() {
+        "yes"!
+    }
+}
+"#,
+        );
+
+        assert!(warnings.is_empty());
+    }
+}
@@ -4,7 +4,9 @@ use crate::types::{FileId, Spanned};
 use std::collections::HashMap;
 
 pub struct InfiniteLoopAnalyzer {
-    variable_assignments: HashMap<String, bool>,
+    // The Boolean literal each variable is bound to, or None when its value
+    // is not known before a loop runs.
+    variable_assignments: HashMap<String, Option<bool>>,
 }
 
 impl InfiniteLoopAnalyzer {
@@ -30,14 +32,14 @@ impl InfiniteLoopAnalyzer {
                     expression,
                     ..
                 } => {
-                    if let Expression::BooleanLiteral { value: true, .. } = expression {
-                        self.variable_assignments.insert(variable.clone(), true);
-                    } else {
-                        self.variable_assignments.insert(variable.clone(), false);
-                    }
+                    let value = match expression {
+                        Expression::BooleanLiteral { value, .. } => Some(*value),
+                        _ => None,
+                    };
+                    self.variable_assignments.insert(variable.clone(), value);
                 }
                 Statement::VariableAssignment { variable, .. } => {
-                    self.variable_assignments.insert(variable.clone(), false);
+                    self.variable_assignments.insert(variable.clone(), None);
                 }
                 Statement::If { body, .. } | Statement::While { body, .. } => {
                     self.collect_variable_assignments(body);
@@ -80,10 +82,11 @@ impl InfiniteLoopAnalyzer {
                     let is_infinite = if self.is_constant_true(condition) {
                         true
                     } else if let Expression::Variable { name, .. } = condition {
-                        if let Some(&is_true) = self.variable_assignments.get(name) {
-                            is_true && !self.is_variable_modified_in_loop(name, body)
-                        } else {
-                            false
+                        match self.variable_assignments.get(name).copied().flatten() {
+                            Some(true) => !self.is_variable_modified_in_loop(name, body),
+                            // A loop over a variable that starts out false never runs.
+                            Some(false) => false,
+                            None => !self.body_can_affect_condition(name, body),
                         }
                     } else {
                         false
@@ -105,6 +108,41 @@ impl InfiniteLoopAnalyzer {
         }
     }
 
+    // Without a reassignment of the condition variable or a function call that
+    // could interact with the outside world, nothing in the body can end the
+    // loop once it has started.
+    fn body_can_affect_condition(&self, var_name: &str, statements: &[Statement]) -> bool {
+        statements.iter().any(|statement| match statement {
+            Statement::VariableAssignment {
+                variable,
+                expression,
+                ..
+            } => variable == var_name || contains_call(expression),
+            Statement::Assignment { expression, .. }
+            | Statement::Injection { expression, .. }
+            | Statement::ExpressionStatement { expression, .. }
+            | Statement::Return { expression, .. } => contains_call(expression),
+            Statement::If {
+                condition,
+                body,
+                else_body,
+                ..
+            } => {
+                contains_call(condition)
+                    || self.body_can_affect_condition(var_name, body)
+                    || else_body.as_ref().is_some_and(|else_body| {
+                        self.body_can_affect_condition(var_name, else_body)
+                    })
+            }
+            Statement::While {
+                condition, body, ..
+            } => contains_call(condition) || self.body_can_affect_condition(var_name, body),
+            Statement::Assert {
+                condition, message, ..
+            } => contains_call(condition) || contains_call(message),
+        })
+    }
+
     fn is_constant_true(&self, expr: &Expression) -> bool {
         match expr {
             Expression::BooleanLiteral { value: true, .. } => true,
@@ -116,7 +154,7 @@ impl InfiniteLoopAnalyzer {
     fn has_return_statement(&self, statements: &[Statement]) -> bool {
         for statement in statements {
             match statement {
-                Statement::Return(_) => return true,
+                Statement::Return { expression: _, .. } => return true,
                 Statement::If { body, .. } | Statement::While { body, .. } => {
                     if self.has_return_statement(body) {
                         return true;
@@ -147,6 +185,33 @@ impl Analyzer for InfiniteLoopAnalyzer {
     }
 }
 
+/// Whether evaluating `expr` calls a function or the model, so it may
+/// have effects beyond its value.
+pub(super) fn contains_call(expr: &Expression) -> bool {
+    match expr {
+        Expression::Call { .. } | Expression::Select(_) => true,
+        Expression::ListLiteral { elements, .. } => elements.iter().any(contains_call),
+        Expression::OptionLiteral { value, .. } => value.as_deref().is_some_and(contains_call),
+        Expression::IfElse {
+            condition,
+            then_expr,
+            else_expr,
+            ..
+        } => contains_call(condition) || contains_call(then_expr) || contains_call(else_expr),
+        Expression::Match {
+            scrutinee, arms, ..
+        } => contains_call(scrutinee) || arms.iter().any(|arm| contains_call(&arm.expression)),
+        Expression::Return { value, .. } => contains_call(value),
+        Expression::FieldAccess { object, .. } => contains_call(object),
+        Expression::Variable { .. }
+        | Expression::StringLiteral { .. }
+        | Expression::BooleanLiteral { .. }
+        | Expression::NumberLiteral { .. }
+        | Expression::Placeholder { .. }
+        | Expression::UnitLiteral { .. } => false,
+    }
+}
+
 impl Default for InfiniteLoopAnalyzer {
     fn default() -> Self {
         Self::new()
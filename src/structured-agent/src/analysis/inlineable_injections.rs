@@ -0,0 +1,195 @@
+use crate::analysis::{Analyzer, Warning};
+use crate::ast::{Definition, Expression, Function, Module, Statement};
+use crate::types::FileId;
+
+pub struct InlineableInjectionAnalyzer;
+
+impl InlineableInjectionAnalyzer {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn analyze_function(&self, func: &Function, file_id: FileId, warnings: &mut Vec<Warning>) {
+        self.analyze_block(func, &func.body.statements, file_id, warnings);
+    }
+
+    fn analyze_block(
+        &self,
+        func: &Function,
+        statements: &[Statement],
+        file_id: FileId,
+        warnings: &mut Vec<Warning>,
+    ) {
+        for pair in statements.windows(2) {
+            let Statement::Assignment { variable, span, .. } = &pair[0] else {
+                continue;
+            };
+
+            let (Statement::Injection {
+                expression: next, ..
+            }
+            | Statement::ExpressionStatement {
+                expression: next, ..
+            }) = &pair[1]
+            else {
+                continue;
+            };
+
+            let Expression::Variable {
+                name,
+                span: use_span,
+            } = next
+            else {
+                continue;
+            };
+
+            if name == variable && Self::statements_reads(&func.body.statements, variable) == 1 {
+                warnings.push(Warning::InlineableInjection {
+                    name: variable.clone(),
+                    span: *span,
+                    use_span: *use_span,
+                    file_id,
+                });
+            }
+        }
+
+        for statement in statements {
+            match statement {
+                Statement::If {
+                    body, else_body, ..
+                } => {
+                    self.analyze_block(func, body, file_id, warnings);
+                    if let Some(else_body) = else_body {
+                        self.analyze_block(func, else_body, file_id, warnings);
+                    }
+                }
+                Statement::While { body, .. } => {
+                    self.analyze_block(func, body, file_id, warnings);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn statements_reads(statements: &[Statement], name: &str) -> usize {
+        statements
+            .iter()
+            .map(|stmt| Self::statement_reads(stmt, name))
+            .sum()
+    }
+
+    fn statement_reads(statement: &Statement, name: &str) -> usize {
+        match statement {
+            Statement::Injection {
+                expression: expr, ..
+            }
+            | Statement::ExpressionStatement {
+                expression: expr, ..
+            }
+            | Statement::Return {
+                expression: expr, ..
+            } => Self::expression_reads(expr, name),
+            Statement::Assignment { expression, .. }
+            | Statement::VariableAssignment { expression, .. } => {
+                Self::expression_reads(expression, name)
+            }
+            Statement::If {
+                condition,
+                body,
+                else_body,
+                ..
+            } => {
+                Self::expression_reads(condition, name)
+                    + Self::statements_reads(body, name)
+                    + else_body
+                        .as_ref()
+                        .map_or(0, |else_body| Self::statements_reads(else_body, name))
+            }
+            Statement::While {
+                condition, body, ..
+            } => Self::expression_reads(condition, name) + Self::statements_reads(body, name),
+            Statement::Assert {
+                condition, message, ..
+            } => Self::expression_reads(condition, name) + Self::expression_reads(message, name),
+        }
+    }
+
+    fn expression_reads(expression: &Expression, name: &str) -> usize {
+        match expression {
+            Expression::Variable { name: var, .. } => usize::from(var == name),
+            Expression::Call { arguments, .. } => arguments
+                .iter()
+                .map(|arg| Self::expression_reads(arg, name))
+                .sum(),
+            Expression::ListLiteral { elements, .. } => elements
+                .iter()
+                .map(|elem| Self::expression_reads(elem, name))
+                .sum(),
+            Expression::OptionLiteral { value, .. } => value
+                .as_ref()
+                .map_or(0, |value| Self::expression_reads(value, name)),
+            Expression::Select(select_expr) => select_expr
+                .clauses
+                .iter()
+                .map(|clause| {
+                    clause
+                        .guard
+                        .as_ref()
+                        .map_or(0, |guard| Self::expression_reads(guard, name))
+                        + Self::expression_reads(&clause.expression_to_run, name)
+                        + Self::expression_reads(&clause.expression_next, name)
+                })
+                .sum(),
+            Expression::IfElse {
+                condition,
+                then_expr,
+                else_expr,
+                ..
+            } => {
+                Self::expression_reads(condition, name)
+                    + Self::expression_reads(then_expr, name)
+                    + Self::expression_reads(else_expr, name)
+            }
+            Expression::Match {
+                scrutinee, arms, ..
+            } => {
+                Self::expression_reads(scrutinee, name)
+                    + arms
+                        .iter()
+                        .map(|arm| Self::expression_reads(&arm.expression, name))
+                        .sum::<usize>()
+            }
+            Expression::Return { value, .. } => Self::expression_reads(value, name),
+            Expression::FieldAccess { object, .. } => Self::expression_reads(object, name),
+            Expression::StringLiteral { .. }
+            | Expression::BooleanLiteral { .. }
+            | Expression::NumberLiteral { .. }
+            | Expression::UnitLiteral { .. }
+            | Expression::Placeholder { .. } => 0,
+        }
+    }
+}
+
+impl Default for InlineableInjectionAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Analyzer for InlineableInjectionAnalyzer {
+    fn name(&self) -> &str {
+        "inlineable-injections"
+    }
+
+    fn analyze_module(&mut self, module: &Module, file_id: FileId) -> Vec<Warning> {
+        let mut warnings = Vec::new();
+
+        for definition in &module.definitions {
+            if let Definition::Function(func) = definition {
+                self.analyze_function(func, file_id, &mut warnings);
+            }
+        }
+
+        warnings
+    }
+}
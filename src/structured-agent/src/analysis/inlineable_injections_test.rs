@@ -0,0 +1,93 @@
+#[cfg(test)]
+mod tests {
+    use crate::analysis::{Analyzer, InlineableInjectionAnalyzer, Warning};
+    use crate::ast::Module;
+    use crate::compiler::{CodespanParser, CompilationUnit};
+    use crate::diagnostics::DiagnosticManager;
+
+    fn parse_code(code: &str) -> Module {
+        let unit = CompilationUnit::from_string(code.to_string());
+        let manager = DiagnosticManager::new();
+        let parser = CodespanParser::new();
+        parser.parse(&unit, 0, manager.reporter()).unwrap()
+    }
+
+    fn analyze(code: &str) -> Vec<Warning> {
+        let module = parse_code(code);
+        let mut analyzer = InlineableInjectionAnalyzer::new();
+        analyzer.analyze_module(&module, 0)
+    }
+
+    #[test]
+    fn detects_assignment_injected_on_next_line() {
+        let warnings = analyze(
+            r#"
+extern fn fetch(): String
+
+fn test(): () {
+    let issue = fetch()
+    issue!
+}
+"#,
+        );
+
+        assert_eq!(warnings.len(), 1);
+        match &warnings[0] {
+            Warning::InlineableInjection { name, .. } => assert_eq!(name, "issue"),
+            other => panic!("Expected InlineableInjection, got: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn detects_pattern_inside_nested_block() {
+        let warnings = analyze(
+            r#"
+extern fn fetch(): String
+
+fn test(ready: Boolean): () {
+    if ready {
+        let issue = fetch()
+        issue!
+    }
+}
+"#,
+        );
+
+        assert_eq!(warnings.len(), 1);
+    }
+
+    #[test]
+    fn no_warning_when_variable_is_used_twice() {
+        let warnings = analyze(
+            r#"
+extern fn fetch(): String
+extern fn log(value: String): ()
+
+fn test(): () {
+    let issue = fetch()
+    issue!
+    log(issue)
+}
+"#,
+        );
+
+        assert!(warnings.is_empty(), "unexpected warnings: {:?}", warnings);
+    }
+
+    #[test]
+    fn no_warning_when_injection_is_not_immediately_after() {
+        let warnings = analyze(
+            r#"
+extern fn fetch(): String
+
+fn test(): () {
+    let issue = fetch()
+    "Summarise the following issue"!
+    issue!
+}
+"#,
+        );
+
+        assert!(warnings.is_empty(), "unexpected warnings: {:?}", warnings);
+    }
+}
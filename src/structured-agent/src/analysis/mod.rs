@@ -1,13 +1,20 @@
+mod argument_swaps;
 mod constant_conditions;
+mod discarded_returns;
 mod duplicate_injections;
 mod empty_blocks;
 mod empty_functions;
+mod fill_points;
+mod identical_branches;
 mod infinite_loops;
+mod inlineable_injections;
 mod overwritten_values;
 mod placeholder_overuse;
+mod pure_functions;
 mod redundant_select;
 mod unreachable_code;
 mod unused_expressions;
+mod unused_parameter_rebinding;
 mod unused_return_values;
 mod unused_variables;
 mod variable_shadowing;
@@ -45,16 +52,44 @@ mod unused_expressions_test;
 #[cfg(test)]
 mod unused_return_values_test;
 
+#[cfg(test)]
+mod unused_parameter_rebinding_test;
+
+#[cfg(test)]
+mod argument_swaps_test;
+
+#[cfg(test)]
+mod inlineable_injections_test;
+
+#[cfg(test)]
+mod pure_functions_test;
+
+#[cfg(test)]
+mod identical_branches_test;
+
+#[cfg(test)]
+mod fill_points_test;
+
+#[cfg(test)]
+mod discarded_returns_test;
+
+pub use argument_swaps::ArgumentSwapAnalyzer;
 pub use constant_conditions::ConstantConditionAnalyzer;
+pub use discarded_returns::DiscardedReturnAnalyzer;
 pub use duplicate_injections::DuplicateInjectionAnalyzer;
 pub use empty_blocks::EmptyBlockAnalyzer;
 pub use empty_functions::EmptyFunctionAnalyzer;
+pub use fill_points::{FillKind, FillPoint, fill_points};
+pub use identical_branches::IdenticalBranchAnalyzer;
 pub use infinite_loops::InfiniteLoopAnalyzer;
+pub use inlineable_injections::InlineableInjectionAnalyzer;
 pub use overwritten_values::OverwrittenValueAnalyzer;
 pub use placeholder_overuse::PlaceholderOveruseAnalyzer;
+pub use pure_functions::PureFunctionAnalyzer;
 pub use redundant_select::RedundantSelectAnalyzer;
 pub use unreachable_code::ReachabilityAnalyzer;
 pub use unused_expressions::UnusedExpressionAnalyzer;
+pub use unused_parameter_rebinding::UnusedParameterRebindingAnalyzer;
 pub use unused_return_values::UnusedReturnValueAnalyzer;
 pub use unused_variables::UnusedVariableAnalyzer;
 pub use variable_shadowing::VariableShadowingAnalyzer;
@@ -62,6 +97,9 @@ pub use variable_shadowing::VariableShadowingAnalyzer;
 use crate::ast::Module;
 use crate::types::{FileId, Span};
 use codespan_reporting::diagnostic::Diagnostic;
+use std::collections::HashMap;
+use std::fmt;
+use std::time::{Duration, Instant};
 
 pub trait Analyzer {
     fn name(&self) -> &str;
@@ -111,6 +149,12 @@ pub enum Warning {
         span: Span,
         file_id: FileId,
     },
+    UnreachableBranch {
+        condition_value: bool,
+        condition_span: Span,
+        span: Span,
+        file_id: FileId,
+    },
     VariableShadowing {
         name: String,
         inner_span: Span,
@@ -131,9 +175,132 @@ pub enum Warning {
         span: Span,
         file_id: FileId,
     },
+    UnusedParameterRebinding {
+        name: String,
+        span: Span,
+        parameter_span: Span,
+        file_id: FileId,
+    },
+    LikelyArgumentSwap {
+        function: String,
+        first_parameter: String,
+        second_parameter: String,
+        span: Span,
+        file_id: FileId,
+    },
+    InlineableInjection {
+        name: String,
+        span: Span,
+        use_span: Span,
+        file_id: FileId,
+    },
+    ImpureCallInPureFunction {
+        function: String,
+        callee: String,
+        span: Span,
+        file_id: FileId,
+    },
+    IdenticalBranches {
+        span: Span,
+        condition_span: Span,
+        /// The condition calls a function, so dropping it would drop the
+        /// call too.
+        condition_calls: bool,
+        file_id: FileId,
+    },
+    DiscardedReturnInUnitFunction {
+        function: String,
+        span: Span,
+        file_id: FileId,
+    },
 }
 
 impl Warning {
+    /// The span the warning's primary label points at.
+    pub fn span(&self) -> Span {
+        match self {
+            Warning::UnusedVariable { span, .. }
+            | Warning::UnreachableCode { span, .. }
+            | Warning::PotentialInfiniteLoop { span, .. }
+            | Warning::EmptyBlock { span, .. }
+            | Warning::EmptyFunction { span, .. }
+            | Warning::DuplicateInjection { span, .. }
+            | Warning::PlaceholderOveruse { span, .. }
+            | Warning::RedundantSelect { span, .. }
+            | Warning::ConstantCondition { span, .. }
+            | Warning::UnreachableBranch { span, .. }
+            | Warning::OverwrittenValue { span, .. }
+            | Warning::UnusedReturnValue { span, .. }
+            | Warning::UnusedExpression { span, .. }
+            | Warning::UnusedParameterRebinding { span, .. }
+            | Warning::LikelyArgumentSwap { span, .. }
+            | Warning::InlineableInjection { span, .. }
+            | Warning::ImpureCallInPureFunction { span, .. }
+            | Warning::IdenticalBranches { span, .. }
+            | Warning::DiscardedReturnInUnitFunction { span, .. } => *span,
+            Warning::VariableShadowing { inner_span, .. } => *inner_span,
+        }
+    }
+
+    fn location(&self) -> (FileId, Span) {
+        let file_id = match self {
+            Warning::UnusedVariable { file_id, .. }
+            | Warning::UnreachableCode { file_id, .. }
+            | Warning::PotentialInfiniteLoop { file_id, .. }
+            | Warning::EmptyBlock { file_id, .. }
+            | Warning::EmptyFunction { file_id, .. }
+            | Warning::DuplicateInjection { file_id, .. }
+            | Warning::PlaceholderOveruse { file_id, .. }
+            | Warning::RedundantSelect { file_id, .. }
+            | Warning::ConstantCondition { file_id, .. }
+            | Warning::UnreachableBranch { file_id, .. }
+            | Warning::VariableShadowing { file_id, .. }
+            | Warning::OverwrittenValue { file_id, .. }
+            | Warning::UnusedReturnValue { file_id, .. }
+            | Warning::UnusedExpression { file_id, .. }
+            | Warning::UnusedParameterRebinding { file_id, .. }
+            | Warning::LikelyArgumentSwap { file_id, .. }
+            | Warning::InlineableInjection { file_id, .. }
+            | Warning::ImpureCallInPureFunction { file_id, .. }
+            | Warning::IdenticalBranches { file_id, .. }
+            | Warning::DiscardedReturnInUnitFunction { file_id, .. } => *file_id,
+        };
+        (file_id, self.span())
+    }
+
+    /// When several warnings share a span, [`AnalysisRunner`] keeps only
+    /// those with the highest priority:
+    ///
+    /// 3. warnings that explain the others, such as a rebinding explaining
+    ///    why the variable is unused and shadows the parameter
+    /// 2. likely bugs
+    /// 1. unused values and variables, which are often a symptom
+    /// 0. style suggestions
+    pub fn priority(&self) -> u8 {
+        match self {
+            Warning::UnusedParameterRebinding { .. }
+            | Warning::DiscardedReturnInUnitFunction { .. }
+            | Warning::ConstantCondition { .. }
+            | Warning::PotentialInfiniteLoop { .. }
+            | Warning::IdenticalBranches { .. } => 3,
+            Warning::UnreachableCode { .. }
+            | Warning::UnreachableBranch { .. }
+            | Warning::OverwrittenValue { .. }
+            | Warning::DuplicateInjection { .. }
+            | Warning::LikelyArgumentSwap { .. }
+            | Warning::ImpureCallInPureFunction { .. } => 2,
+            Warning::UnusedVariable { .. }
+            | Warning::VariableShadowing { .. }
+            | Warning::UnusedReturnValue { .. }
+            | Warning::UnusedExpression { .. } => 1,
+            Warning::EmptyBlock { .. }
+            | Warning::EmptyFunction { .. }
+            | Warning::PlaceholderOveruse { .. }
+            | Warning::RedundantSelect { .. }
+            | Warning::InlineableInjection { .. } => 0,
+        }
+    }
+
     pub fn to_diagnostic(&self) -> Diagnostic<FileId> {
         use codespan_reporting::diagnostic::Label;
 
@@ -220,6 +387,19 @@ impl Warning {
                         Label::primary(*file_id, span.to_byte_range()).with_message(msg),
                     ])
             }
+            Warning::UnreachableBranch {
+                condition_value,
+                condition_span,
+                span,
+                file_id,
+            } => Diagnostic::warning()
+                .with_message("unreachable branch")
+                .with_labels(vec![
+                    Label::primary(*file_id, span.to_byte_range())
+                        .with_message("this branch will never execute"),
+                    Label::secondary(*file_id, condition_span.to_byte_range())
+                        .with_message(format!("condition is always {}", condition_value)),
+                ]),
             Warning::VariableShadowing {
                 name,
                 inner_span,
@@ -261,6 +441,107 @@ impl Warning {
                     Label::primary(*file_id, span.to_byte_range())
                         .with_message("expression result is not used; add `!` to inject it"),
                 ]),
+            Warning::UnusedParameterRebinding {
+                name,
+                span,
+                parameter_span,
+                file_id,
+            } => Diagnostic::warning()
+                .with_message(format!(
+                    "parameter `{}` is rebound but the new binding is never read",
+                    name
+                ))
+                .with_labels(vec![
+                    Label::primary(*file_id, span.to_byte_range())
+                        .with_message("rebinding is never read"),
+                    Label::secondary(*file_id, parameter_span.to_byte_range())
+                        .with_message("parameter declared here"),
+                ]),
+            Warning::LikelyArgumentSwap {
+                function,
+                first_parameter,
+                second_parameter,
+                span,
+                file_id,
+            } => Diagnostic::warning()
+                .with_message(format!("arguments to `{}` may be swapped", function))
+                .with_labels(vec![
+                    Label::primary(*file_id, span.to_byte_range()).with_message(format!(
+                        "`{}` is passed as `{}` and `{}` as `{}`",
+                        second_parameter, first_parameter, first_parameter, second_parameter
+                    )),
+                ]),
+            Warning::InlineableInjection {
+                name,
+                span,
+                use_span,
+                file_id,
+            } => Diagnostic::warning()
+                .with_message(format!(
+                    "variable `{}` is only used by the next statement",
+                    name
+                ))
+                .with_labels(vec![
+                    Label::primary(*file_id, span.to_byte_range())
+                        .with_message("consider inlining this expression"),
+                    Label::secondary(*file_id, use_span.to_byte_range())
+                        .with_message("only use is here"),
+                ]),
+            Warning::ImpureCallInPureFunction {
+                function,
+                callee,
+                span,
+                file_id,
+            } => Diagnostic::warning()
+                .with_message(format!(
+                    "function `{}` is marked `# pure` but calls `{}`",
+                    function, callee
+                ))
+                .with_labels(vec![
+                    Label::primary(*file_id, span.to_byte_range())
+                        .with_message("this call may have side effects"),
+                ]),
+            Warning::IdenticalBranches {
+                span,
+                condition_span,
+                condition_calls: false,
+                file_id,
+            } => Diagnostic::warning()
+                .with_message("both branches of this `if` are identical")
+                .with_labels(vec![
+                    Label::primary(*file_id, span.to_byte_range())
+                        .with_message("consider replacing the `if` with either branch"),
+                    Label::secondary(*file_id, condition_span.to_byte_range())
+                        .with_message("this condition has no effect"),
+                ]),
+            Warning::IdenticalBranches {
+                span,
+                condition_span,
+                condition_calls: true,
+                file_id,
+            } => Diagnostic::warning()
+                .with_message("both branches of this `if` are identical")
+                .with_labels(vec![
+                    Label::primary(*file_id, span.to_byte_range())
+                        .with_message("both branches run the same code"),
+                    Label::secondary(*file_id, condition_span.to_byte_range()).with_message(
+                        "the result of this condition is unused, but its calls still run",
+                    ),
+                ]),
+            Warning::DiscardedReturnInUnitFunction {
+                function,
+                span,
+                file_id,
+            } => Diagnostic::warning()
+                .with_message(format!(
+                    "function `{}` returns `()`, so its last value is discarded",
+                    function
+                ))
+                .with_labels(vec![
+                    Label::primary(*file_id, span.to_byte_range()).with_message(
+                        "this value is discarded; add `!` to inject it or change the return type",
+                    ),
+                ]),
         }
     }
 }
@@ -286,8 +567,81 @@ impl AnalysisRunner {
         for analyzer in &mut self.analyzers {
             all_warnings.extend(analyzer.analyze_module(module, file_id));
         }
+
+        Self::drop_overlapping(&mut all_warnings);
         all_warnings
     }
+
+    /// Like [`AnalysisRunner::run`], but also records how long each analyzer
+    /// took and how many warnings it reported.
+    pub fn run_with_report(&mut self, module: &Module, file_id: FileId) -> AnalysisReport {
+        let mut warnings = Vec::new();
+        let mut timings = Vec::with_capacity(self.analyzers.len());
+        for analyzer in &mut self.analyzers {
+            let started = Instant::now();
+            let found = analyzer.analyze_module(module, file_id);
+            timings.push(AnalyzerTiming {
+                name: analyzer.name().to_string(),
+                duration: started.elapsed(),
+                warnings: found.len(),
+            });
+            warnings.extend(found);
+        }
+
+        Self::drop_overlapping(&mut warnings);
+        AnalysisReport { warnings, timings }
+    }
+
+    // Warnings on the same span usually describe one problem from different
+    // angles, so only the highest priority ones there are kept.
+    fn drop_overlapping(all_warnings: &mut Vec<Warning>) {
+        let mut highest: HashMap<(FileId, Span), u8> = HashMap::new();
+        for warning in all_warnings.iter() {
+            let priority = highest.entry(warning.location()).or_default();
+            *priority = (*priority).max(warning.priority());
+        }
+        all_warnings.retain(|warning| highest[&warning.location()] == warning.priority());
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct AnalyzerTiming {
+    pub name: String,
+    pub duration: Duration,
+    /// Warnings the analyzer reported, before overlapping ones are dropped.
+    pub warnings: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct AnalysisReport {
+    pub warnings: Vec<Warning>,
+    /// One entry per analyzer, in the order they ran.
+    pub timings: Vec<AnalyzerTiming>,
+}
+
+impl AnalysisReport {
+    pub fn total_duration(&self) -> Duration {
+        self.timings.iter().map(|timing| timing.duration).sum()
+    }
+}
+
+impl fmt::Display for AnalysisReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let width = self
+            .timings
+            .iter()
+            .map(|timing| timing.name.len())
+            .max()
+            .unwrap_or(0);
+        for timing in &self.timings {
+            writeln!(
+                f,
+                "  {:<width$}  {:>10.3?}  {} warning(s)",
+                timing.name, timing.duration, timing.warnings
+            )?;
+        }
+        write!(f, "  {:<width$}  {:>10.3?}", "total", self.total_duration())
+    }
 }
 
 impl Default for AnalysisRunner {
@@ -22,6 +22,9 @@ impl OverwrittenValueAnalyzer {
             }
             Expression::Select(select_expr) => {
                 for clause in &select_expr.clauses {
+                    if let Some(guard) = &clause.guard {
+                        Self::collect_reads_in_expression(guard, reads);
+                    }
                     Self::collect_reads_in_expression(&clause.expression_to_run, reads);
                     Self::collect_reads_in_expression(&clause.expression_next, reads);
                 }
@@ -36,13 +39,31 @@ impl OverwrittenValueAnalyzer {
                 Self::collect_reads_in_expression(then_expr, reads);
                 Self::collect_reads_in_expression(else_expr, reads);
             }
+            Expression::Match {
+                scrutinee, arms, ..
+            } => {
+                Self::collect_reads_in_expression(scrutinee, reads);
+                for arm in arms {
+                    Self::collect_reads_in_expression(&arm.expression, reads);
+                }
+            }
+            Expression::FieldAccess { object, .. } => {
+                Self::collect_reads_in_expression(object, reads);
+            }
+            Expression::OptionLiteral {
+                value: Some(value), ..
+            } => {
+                Self::collect_reads_in_expression(value, reads);
+            }
             _ => {}
         }
     }
 
     fn collect_reads_in_statement(stmt: &Statement, reads: &mut HashSet<String>) {
         match stmt {
-            Statement::Injection(expr) => {
+            Statement::Injection {
+                expression: expr, ..
+            } => {
                 Self::collect_reads_in_expression(expr, reads);
             }
             Statement::Assignment { expression, .. } => {
@@ -56,7 +77,9 @@ impl OverwrittenValueAnalyzer {
                 reads.insert(variable.clone());
                 Self::collect_reads_in_expression(expression, reads);
             }
-            Statement::ExpressionStatement(expr) => {
+            Statement::ExpressionStatement {
+                expression: expr, ..
+            } => {
                 Self::collect_reads_in_expression(expr, reads);
             }
             Statement::If {
@@ -75,7 +98,15 @@ impl OverwrittenValueAnalyzer {
                     Self::collect_reads_in_statement(stmt, reads);
                 }
             }
-            Statement::Return(expr) => {
+            Statement::Assert {
+                condition, message, ..
+            } => {
+                Self::collect_reads_in_expression(condition, reads);
+                Self::collect_reads_in_expression(message, reads);
+            }
+            Statement::Return {
+                expression: expr, ..
+            } => {
                 Self::collect_reads_in_expression(expr, reads);
             }
         }
@@ -94,6 +125,7 @@ impl OverwrittenValueAnalyzer {
                     variable,
                     expression,
                     span,
+                    ..
                 } => {
                     if let Some(&old_span) = assignments.get(variable) {
                         if !reads.contains(variable) {
@@ -111,13 +143,19 @@ impl OverwrittenValueAnalyzer {
                 Statement::VariableAssignment { variable, .. } => {
                     reads.insert(variable.clone());
                 }
-                Statement::Injection(expr) => {
+                Statement::Injection {
+                    expression: expr, ..
+                } => {
                     Self::collect_reads_in_expression(expr, reads);
                 }
-                Statement::ExpressionStatement(expr) => {
+                Statement::ExpressionStatement {
+                    expression: expr, ..
+                } => {
                     Self::collect_reads_in_expression(expr, reads);
                 }
-                Statement::Return(expr) => {
+                Statement::Return {
+                    expression: expr, ..
+                } => {
                     Self::collect_reads_in_expression(expr, reads);
                 }
                 Statement::If {
@@ -136,6 +174,12 @@ impl OverwrittenValueAnalyzer {
                         Self::collect_reads_in_statement(stmt, reads);
                     }
                 }
+                Statement::Assert {
+                    condition, message, ..
+                } => {
+                    Self::collect_reads_in_expression(condition, reads);
+                    Self::collect_reads_in_expression(message, reads);
+                }
             }
         }
     }
@@ -2,11 +2,24 @@ use crate::analysis::{Analyzer, Warning};
 use crate::ast::{Definition, Expression, Module, Statement};
 use crate::types::FileId;
 
-pub struct PlaceholderOveruseAnalyzer;
+const DEFAULT_MIN_ARGUMENTS: usize = 2;
+
+pub struct PlaceholderOveruseAnalyzer {
+    min_arguments: usize,
+}
 
 impl PlaceholderOveruseAnalyzer {
     pub fn new() -> Self {
-        Self
+        Self {
+            min_arguments: DEFAULT_MIN_ARGUMENTS,
+        }
+    }
+
+    /// Only warn about calls with at least this many arguments. Filling the
+    /// single argument of a one-parameter function is usually intentional.
+    pub fn with_min_arguments(mut self, min_arguments: usize) -> Self {
+        self.min_arguments = min_arguments.max(1);
+        self
     }
 
     fn count_placeholders(args: &[Expression]) -> usize {
@@ -20,7 +33,7 @@ impl PlaceholderOveruseAnalyzer {
             Expression::Call {
                 arguments, span, ..
             } => {
-                if !arguments.is_empty() {
+                if arguments.len() >= self.min_arguments {
                     let placeholder_count = Self::count_placeholders(arguments);
                     if placeholder_count == arguments.len() {
                         warnings.push(Warning::PlaceholderOveruse {
@@ -36,6 +49,9 @@ impl PlaceholderOveruseAnalyzer {
             }
             Expression::Select(select_expr) => {
                 for clause in &select_expr.clauses {
+                    if let Some(guard) = &clause.guard {
+                        self.analyze_expression(guard, file_id, warnings);
+                    }
                     self.analyze_expression(&clause.expression_to_run, file_id, warnings);
                     self.analyze_expression(&clause.expression_next, file_id, warnings);
                 }
@@ -50,13 +66,31 @@ impl PlaceholderOveruseAnalyzer {
                 self.analyze_expression(then_expr, file_id, warnings);
                 self.analyze_expression(else_expr, file_id, warnings);
             }
+            Expression::Match {
+                scrutinee, arms, ..
+            } => {
+                self.analyze_expression(scrutinee, file_id, warnings);
+                for arm in arms {
+                    self.analyze_expression(&arm.expression, file_id, warnings);
+                }
+            }
+            Expression::FieldAccess { object, .. } => {
+                self.analyze_expression(object, file_id, warnings);
+            }
+            Expression::OptionLiteral {
+                value: Some(value), ..
+            } => {
+                self.analyze_expression(value, file_id, warnings);
+            }
             _ => {}
         }
     }
 
     fn analyze_statement(&self, stmt: &Statement, file_id: FileId, warnings: &mut Vec<Warning>) {
         match stmt {
-            Statement::Injection(expr) => {
+            Statement::Injection {
+                expression: expr, ..
+            } => {
                 self.analyze_expression(expr, file_id, warnings);
             }
             Statement::Assignment { expression, .. } => {
@@ -65,7 +99,9 @@ impl PlaceholderOveruseAnalyzer {
             Statement::VariableAssignment { expression, .. } => {
                 self.analyze_expression(expression, file_id, warnings);
             }
-            Statement::ExpressionStatement(expr) => {
+            Statement::ExpressionStatement {
+                expression: expr, ..
+            } => {
                 self.analyze_expression(expr, file_id, warnings);
             }
             Statement::If {
@@ -84,7 +120,15 @@ impl PlaceholderOveruseAnalyzer {
                     self.analyze_statement(stmt, file_id, warnings);
                 }
             }
-            Statement::Return(expr) => {
+            Statement::Assert {
+                condition, message, ..
+            } => {
+                self.analyze_expression(condition, file_id, warnings);
+                self.analyze_expression(message, file_id, warnings);
+            }
+            Statement::Return {
+                expression: expr, ..
+            } => {
                 self.analyze_expression(expr, file_id, warnings);
             }
         }
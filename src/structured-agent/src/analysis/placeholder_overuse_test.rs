@@ -1,6 +1,8 @@
 #[cfg(test)]
 mod tests {
-    use crate::analysis::{Analyzer, PlaceholderOveruseAnalyzer};
+    use crate::analysis::{
+        AnalysisRunner, Analyzer, PlaceholderOveruseAnalyzer, UnusedReturnValueAnalyzer, Warning,
+    };
     use crate::ast::Module;
     use crate::compiler::{CodespanParser, CompilationUnit};
     use crate::diagnostics::DiagnosticManager;
@@ -64,7 +66,7 @@ fn test(): () {
     }
 
     #[test]
-    fn detects_single_placeholder_argument() {
+    fn no_warning_for_single_placeholder_argument() {
         let code = r#"
 extern fn process(a: String): ()
 
@@ -77,9 +79,43 @@ fn test(): () {
         let mut analyzer = PlaceholderOveruseAnalyzer::new();
         let warnings = analyzer.analyze_module(&module, 0);
 
+        assert_eq!(warnings.len(), 0);
+    }
+
+    #[test]
+    fn detects_single_placeholder_argument_with_lower_threshold() {
+        let code = r#"
+extern fn process(a: String): ()
+
+fn test(): () {
+    process(_)
+}
+"#;
+
+        let module = parse_code(code);
+        let mut analyzer = PlaceholderOveruseAnalyzer::new().with_min_arguments(1);
+        let warnings = analyzer.analyze_module(&module, 0);
+
         assert_eq!(warnings.len(), 1);
     }
 
+    #[test]
+    fn no_warning_below_configured_threshold() {
+        let code = r#"
+extern fn process(a: String, b: String, c: String): ()
+
+fn test(): () {
+    process(_, _, _)
+}
+"#;
+
+        let module = parse_code(code);
+        let mut analyzer = PlaceholderOveruseAnalyzer::new().with_min_arguments(4);
+        let warnings = analyzer.analyze_module(&module, 0);
+
+        assert_eq!(warnings.len(), 0);
+    }
+
     #[test]
     fn detects_nested_calls_with_placeholders() {
         let code = r#"
@@ -115,4 +151,30 @@ fn test(): () {
 
         assert_eq!(warnings.len(), 0);
     }
+
+    #[test]
+    fn runner_keeps_higher_priority_warning_on_the_same_call() {
+        let code = r#"
+extern fn draft(topic: String, tone: String): String
+
+fn test(): () {
+    draft(_, _)
+    "done"!
+}
+"#;
+
+        let module = parse_code(code);
+        let mut runner = AnalysisRunner::new()
+            .with_analyzer(Box::new(PlaceholderOveruseAnalyzer::new()))
+            .with_analyzer(Box::new(UnusedReturnValueAnalyzer::new()));
+        let warnings = runner.run(&module, 0);
+
+        assert_eq!(warnings.len(), 1);
+        match &warnings[0] {
+            Warning::UnusedReturnValue { span, .. } => {
+                assert_eq!(&code[span.to_byte_range()], "draft(_, _)");
+            }
+            other => panic!("Expected UnusedReturnValue, got: {:?}", other),
+        }
+    }
 }
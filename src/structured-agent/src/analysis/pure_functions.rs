@@ -0,0 +1,220 @@
+use crate::analysis::{Analyzer, Warning};
+use crate::ast::{Definition, Expression, Module, Statement};
+use crate::types::{FileId, Span};
+use std::collections::{HashMap, HashSet};
+
+/// Natives that only compute a value from their arguments. Every other
+/// extern function, including `print`, `input` and MCP tools, is treated as
+/// having side effects.
+const PURE_NATIVES: &[&str] = &[
+    "head",
+    "tail",
+    "is_some",
+    "some_value",
+    "is_some_list",
+    "some_value_list",
+    "json_get",
+    "to_string",
+    "parse_int",
+];
+
+pub struct PureFunctionAnalyzer {
+    impure: HashSet<String>,
+}
+
+impl PureFunctionAnalyzer {
+    pub fn new() -> Self {
+        Self {
+            impure: HashSet::new(),
+        }
+    }
+
+    // A local function is impure when it calls an impure extern, directly or
+    // through other local functions, so iterate until nothing new is found.
+    fn collect_impure(&mut self, module: &Module) {
+        self.impure.clear();
+        let mut callees: HashMap<&str, Vec<&str>> = HashMap::new();
+
+        for definition in &module.definitions {
+            match definition {
+                Definition::Function(func) => {
+                    let mut calls = Vec::new();
+                    Self::statements_calls(&func.body.statements, &mut calls);
+                    callees.insert(
+                        &func.name,
+                        calls.into_iter().map(|(name, _)| name).collect(),
+                    );
+                }
+                Definition::ExternalFunction(func) => {
+                    if !PURE_NATIVES.contains(&func.name.as_str()) {
+                        self.impure.insert(func.name.clone());
+                    }
+                }
+                Definition::ExternalConst(_) | Definition::Constant(_) => {}
+            }
+        }
+
+        loop {
+            let newly_impure: Vec<&str> = callees
+                .iter()
+                .filter(|(name, _)| !self.impure.contains(**name))
+                .filter(|(_, calls)| calls.iter().any(|call| self.impure.contains(*call)))
+                .map(|(name, _)| *name)
+                .collect();
+
+            if newly_impure.is_empty() {
+                break;
+            }
+            self.impure
+                .extend(newly_impure.into_iter().map(str::to_string));
+        }
+    }
+
+    fn statements_calls<'a>(statements: &'a [Statement], calls: &mut Vec<(&'a str, Span)>) {
+        for statement in statements {
+            Self::statement_calls(statement, calls);
+        }
+    }
+
+    fn statement_calls<'a>(statement: &'a Statement, calls: &mut Vec<(&'a str, Span)>) {
+        match statement {
+            Statement::Injection {
+                expression: expr, ..
+            }
+            | Statement::ExpressionStatement {
+                expression: expr, ..
+            }
+            | Statement::Return {
+                expression: expr, ..
+            } => Self::expression_calls(expr, calls),
+            Statement::Assignment { expression, .. }
+            | Statement::VariableAssignment { expression, .. } => {
+                Self::expression_calls(expression, calls)
+            }
+            Statement::If {
+                condition,
+                body,
+                else_body,
+                ..
+            } => {
+                Self::expression_calls(condition, calls);
+                Self::statements_calls(body, calls);
+                if let Some(else_body) = else_body {
+                    Self::statements_calls(else_body, calls);
+                }
+            }
+            Statement::While {
+                condition, body, ..
+            } => {
+                Self::expression_calls(condition, calls);
+                Self::statements_calls(body, calls);
+            }
+            Statement::Assert {
+                condition, message, ..
+            } => {
+                Self::expression_calls(condition, calls);
+                Self::expression_calls(message, calls);
+            }
+        }
+    }
+
+    fn expression_calls<'a>(expression: &'a Expression, calls: &mut Vec<(&'a str, Span)>) {
+        match expression {
+            Expression::Call {
+                function,
+                arguments,
+                span,
+            } => {
+                calls.push((function, *span));
+                for arg in arguments {
+                    Self::expression_calls(arg, calls);
+                }
+            }
+            Expression::ListLiteral { elements, .. } => {
+                for element in elements {
+                    Self::expression_calls(element, calls);
+                }
+            }
+            Expression::OptionLiteral {
+                value: Some(value), ..
+            } => Self::expression_calls(value, calls),
+            Expression::Select(select_expr) => {
+                for clause in &select_expr.clauses {
+                    if let Some(guard) = &clause.guard {
+                        Self::expression_calls(guard, calls);
+                    }
+                    Self::expression_calls(&clause.expression_to_run, calls);
+                    Self::expression_calls(&clause.expression_next, calls);
+                }
+            }
+            Expression::IfElse {
+                condition,
+                then_expr,
+                else_expr,
+                ..
+            } => {
+                Self::expression_calls(condition, calls);
+                Self::expression_calls(then_expr, calls);
+                Self::expression_calls(else_expr, calls);
+            }
+            Expression::Match {
+                scrutinee, arms, ..
+            } => {
+                Self::expression_calls(scrutinee, calls);
+                for arm in arms {
+                    Self::expression_calls(&arm.expression, calls);
+                }
+            }
+            Expression::Return { value, .. } => Self::expression_calls(value, calls),
+            Expression::FieldAccess { object, .. } => Self::expression_calls(object, calls),
+            Expression::Variable { .. }
+            | Expression::StringLiteral { .. }
+            | Expression::BooleanLiteral { .. }
+            | Expression::NumberLiteral { .. }
+            | Expression::OptionLiteral { value: None, .. }
+            | Expression::UnitLiteral { .. }
+            | Expression::Placeholder { .. } => {}
+        }
+    }
+}
+
+impl Default for PureFunctionAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Analyzer for PureFunctionAnalyzer {
+    fn name(&self) -> &str {
+        "pure-functions"
+    }
+
+    fn analyze_module(&mut self, module: &Module, file_id: FileId) -> Vec<Warning> {
+        self.collect_impure(module);
+        let mut warnings = Vec::new();
+
+        for definition in &module.definitions {
+            let Definition::Function(func) = definition else {
+                continue;
+            };
+            if !func.pure {
+                continue;
+            }
+
+            let mut calls = Vec::new();
+            Self::statements_calls(&func.body.statements, &mut calls);
+            for (callee, span) in calls {
+                if self.impure.contains(callee) {
+                    warnings.push(Warning::ImpureCallInPureFunction {
+                        function: func.name.clone(),
+                        callee: callee.to_string(),
+                        span,
+                        file_id,
+                    });
+                }
+            }
+        }
+
+        warnings
+    }
+}
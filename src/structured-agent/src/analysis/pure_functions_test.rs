@@ -0,0 +1,105 @@
+#[cfg(test)]
+mod tests {
+    use crate::analysis::{Analyzer, PureFunctionAnalyzer, Warning};
+    use crate::ast::Module;
+    use crate::compiler::{CodespanParser, CompilationUnit};
+    use crate::diagnostics::DiagnosticManager;
+
+    fn parse_code(code: &str) -> Module {
+        let unit = CompilationUnit::from_string(code.to_string());
+        let manager = DiagnosticManager::new();
+        let parser = CodespanParser::new();
+        parser.parse(&unit, 0, manager.reporter()).unwrap()
+    }
+
+    fn analyze(code: &str) -> Vec<Warning> {
+        let module = parse_code(code);
+        let mut analyzer = PureFunctionAnalyzer::new();
+        analyzer.analyze_module(&module, 0)
+    }
+
+    #[test]
+    fn pure_function_without_side_effects_passes() {
+        let module = parse_code(
+            r#"
+extern fn head(list: List<String>): Option<String>
+
+# pure
+## Picks the first candidate.
+fn first(candidates: List<String>): Option<String> {
+    return head(candidates)
+}
+"#,
+        );
+
+        let mut analyzer = PureFunctionAnalyzer::new();
+        assert!(analyzer.analyze_module(&module, 0).is_empty());
+        assert!(module.definitions.iter().any(
+            |definition| matches!(definition, crate::ast::Definition::Function(func) if func.pure)
+        ));
+    }
+
+    #[test]
+    fn pure_function_calling_print_warns() {
+        let warnings = analyze(
+            r#"
+extern fn print(value: String): ()
+
+# pure
+fn greet(name: String): () {
+    print(name)
+}
+"#,
+        );
+
+        assert_eq!(warnings.len(), 1);
+        match &warnings[0] {
+            Warning::ImpureCallInPureFunction {
+                function, callee, ..
+            } => {
+                assert_eq!(function, "greet");
+                assert_eq!(callee, "print");
+            }
+            other => panic!("Expected ImpureCallInPureFunction, got: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn impure_call_through_local_function_warns() {
+        let warnings = analyze(
+            r#"
+extern fn print(value: String): ()
+
+fn log(value: String): () {
+    print(value)
+}
+
+# pure
+fn greet(name: String): () {
+    log(name)
+}
+"#,
+        );
+
+        assert_eq!(warnings.len(), 1);
+        match &warnings[0] {
+            Warning::ImpureCallInPureFunction { callee, .. } => assert_eq!(callee, "log"),
+            other => panic!("Expected ImpureCallInPureFunction, got: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn unmarked_function_is_not_checked() {
+        let warnings = analyze(
+            r#"
+extern fn print(value: String): ()
+
+fn greet(name: String): () {
+    print(name)
+}
+"#,
+        );
+
+        assert!(warnings.is_empty());
+    }
+}
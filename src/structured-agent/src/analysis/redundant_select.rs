@@ -19,6 +19,9 @@ impl RedundantSelectAnalyzer {
                     });
                 }
                 for clause in &select_expr.clauses {
+                    if let Some(guard) = &clause.guard {
+                        self.analyze_expression(guard, file_id, warnings);
+                    }
                     self.analyze_expression(&clause.expression_to_run, file_id, warnings);
                     self.analyze_expression(&clause.expression_next, file_id, warnings);
                 }
@@ -28,13 +31,18 @@ impl RedundantSelectAnalyzer {
                     self.analyze_expression(arg, file_id, warnings);
                 }
             }
+            Expression::FieldAccess { object, .. } => {
+                self.analyze_expression(object, file_id, warnings);
+            }
             _ => {}
         }
     }
 
     fn analyze_statement(&self, stmt: &Statement, file_id: FileId, warnings: &mut Vec<Warning>) {
         match stmt {
-            Statement::Injection(expr) => {
+            Statement::Injection {
+                expression: expr, ..
+            } => {
                 self.analyze_expression(expr, file_id, warnings);
             }
             Statement::Assignment { expression, .. } => {
@@ -43,7 +51,9 @@ impl RedundantSelectAnalyzer {
             Statement::VariableAssignment { expression, .. } => {
                 self.analyze_expression(expression, file_id, warnings);
             }
-            Statement::ExpressionStatement(expr) => {
+            Statement::ExpressionStatement {
+                expression: expr, ..
+            } => {
                 self.analyze_expression(expr, file_id, warnings);
             }
             Statement::If {
@@ -62,7 +72,15 @@ impl RedundantSelectAnalyzer {
                     self.analyze_statement(stmt, file_id, warnings);
                 }
             }
-            Statement::Return(expr) => {
+            Statement::Assert {
+                condition, message, ..
+            } => {
+                self.analyze_expression(condition, file_id, warnings);
+                self.analyze_expression(message, file_id, warnings);
+            }
+            Statement::Return {
+                expression: expr, ..
+            } => {
                 self.analyze_expression(expr, file_id, warnings);
             }
         }
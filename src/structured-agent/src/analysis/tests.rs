@@ -18,11 +18,13 @@ mod tests {
             name: name.to_string(),
             parameters,
             return_type,
+            return_type_span: Span::dummy(),
             body: FunctionBody {
                 statements,
                 span: Span::dummy(),
             },
             documentation: None,
+            pure: false,
             span: Span::dummy(),
         }
     }
@@ -45,9 +47,11 @@ mod tests {
                 variable: "unused".to_string(),
                 expression: Expression::StringLiteral {
                     value: "hello".to_string(),
+                    multiline: false,
                     span: Span::new(10, 17),
                 },
                 span: Span::new(0, 17),
+                comment: None,
             }],
         );
 
@@ -75,14 +79,19 @@ mod tests {
                     variable: "used".to_string(),
                     expression: Expression::StringLiteral {
                         value: "hello".to_string(),
+                        multiline: false,
                         span: Span::dummy(),
                     },
                     span: Span::dummy(),
+                    comment: None,
+                },
+                Statement::Injection {
+                    expression: Expression::Variable {
+                        name: "used".to_string(),
+                        span: Span::dummy(),
+                    },
+                    comment: None,
                 },
-                Statement::Injection(Expression::Variable {
-                    name: "used".to_string(),
-                    span: Span::dummy(),
-                }),
             ],
         );
 
@@ -100,13 +109,19 @@ mod tests {
             vec![Parameter {
                 name: "param".to_string(),
                 param_type: Type::String,
+                type_span: Span::dummy(),
                 span: Span::new(10, 15),
+                comment: None,
             }],
             Type::Unit,
-            vec![Statement::Injection(Expression::StringLiteral {
-                value: "hello".to_string(),
-                span: Span::dummy(),
-            })],
+            vec![Statement::Injection {
+                expression: Expression::StringLiteral {
+                    value: "hello".to_string(),
+                    multiline: false,
+                    span: Span::dummy(),
+                },
+                comment: None,
+            }],
         );
 
         let module = create_test_module(vec![Definition::Function(func)]);
@@ -129,13 +144,18 @@ mod tests {
             vec![Parameter {
                 name: "param".to_string(),
                 param_type: Type::String,
+                type_span: Span::dummy(),
                 span: Span::dummy(),
+                comment: None,
             }],
             Type::Unit,
-            vec![Statement::Injection(Expression::Variable {
-                name: "param".to_string(),
-                span: Span::dummy(),
-            })],
+            vec![Statement::Injection {
+                expression: Expression::Variable {
+                    name: "param".to_string(),
+                    span: Span::dummy(),
+                },
+                comment: None,
+            }],
         );
 
         let module = create_test_module(vec![Definition::Function(func)]);
@@ -156,21 +176,27 @@ mod tests {
                     variable: "used".to_string(),
                     expression: Expression::StringLiteral {
                         value: "hello".to_string(),
+                        multiline: false,
                         span: Span::dummy(),
                     },
                     span: Span::dummy(),
+                    comment: None,
                 },
                 Statement::If {
                     condition: Expression::BooleanLiteral {
                         value: true,
                         span: Span::dummy(),
                     },
-                    body: vec![Statement::Injection(Expression::Variable {
-                        name: "used".to_string(),
-                        span: Span::dummy(),
-                    })],
+                    body: vec![Statement::Injection {
+                        expression: Expression::Variable {
+                            name: "used".to_string(),
+                            span: Span::dummy(),
+                        },
+                        comment: None,
+                    }],
                     else_body: None,
                     span: Span::dummy(),
+                    comment: None,
                 },
             ],
         );
@@ -195,25 +221,36 @@ mod tests {
                     variable: "unused".to_string(),
                     expression: Expression::StringLiteral {
                         value: "hello".to_string(),
+                        multiline: false,
                         span: Span::new(0, 5),
                     },
                     span: Span::new(0, 5),
+                    comment: None,
                 },
                 Statement::While {
                     condition: Expression::BooleanLiteral {
                         value: true,
                         span: Span::dummy(),
                     },
-                    body: vec![Statement::Injection(Expression::StringLiteral {
-                        value: "loop".to_string(),
-                        span: Span::dummy(),
-                    })],
+                    body: vec![Statement::Injection {
+                        expression: Expression::StringLiteral {
+                            value: "loop".to_string(),
+                            multiline: false,
+                            span: Span::dummy(),
+                        },
+                        comment: None,
+                    }],
                     span: Span::new(10, 20),
+                    comment: None,
+                },
+                Statement::Injection {
+                    expression: Expression::StringLiteral {
+                        value: "unreachable".to_string(),
+                        multiline: false,
+                        span: Span::new(30, 40),
+                    },
+                    comment: None,
                 },
-                Statement::Injection(Expression::StringLiteral {
-                    value: "unreachable".to_string(),
-                    span: Span::new(30, 40),
-                }),
             ],
         );
 
@@ -242,6 +279,54 @@ mod tests {
         assert!(has_infinite);
     }
 
+    #[test]
+    fn test_analysis_report_times_every_analyzer() {
+        use crate::analysis::AnalysisRunner;
+        use std::time::Duration;
+
+        let func = create_test_function(
+            "test",
+            vec![],
+            Type::Unit,
+            vec![Statement::Assignment {
+                variable: "unused".to_string(),
+                expression: Expression::StringLiteral {
+                    value: "hello".to_string(),
+                    multiline: false,
+                    span: Span::new(0, 5),
+                },
+                span: Span::new(0, 5),
+                comment: None,
+            }],
+        );
+
+        let module = create_test_module(vec![Definition::Function(func)]);
+        let mut runner = AnalysisRunner::new()
+            .with_analyzer(Box::new(UnusedVariableAnalyzer::new()))
+            .with_analyzer(Box::new(ReachabilityAnalyzer::new()))
+            .with_analyzer(Box::new(InfiniteLoopAnalyzer::new()));
+
+        let report = runner.run_with_report(&module, 0);
+
+        let names: Vec<&str> = report
+            .timings
+            .iter()
+            .map(|timing| timing.name.as_str())
+            .collect();
+        assert_eq!(
+            names,
+            vec!["unused-variables", "unreachable-code", "infinite-loops"]
+        );
+        assert!(
+            report
+                .timings
+                .iter()
+                .all(|timing| timing.duration >= Duration::ZERO)
+        );
+        assert_eq!(report.timings[0].warnings, 1);
+        assert_eq!(report.warnings.len(), runner.run(&module, 0).len());
+    }
+
     #[test]
     fn test_unreachable_code_after_return() {
         let func = create_test_function(
@@ -249,14 +334,23 @@ mod tests {
             vec![],
             Type::Unit,
             vec![
-                Statement::Return(Expression::StringLiteral {
-                    value: "early".to_string(),
-                    span: Span::new(0, 5),
-                }),
-                Statement::Injection(Expression::StringLiteral {
-                    value: "unreachable".to_string(),
-                    span: Span::new(10, 20),
-                }),
+                Statement::Return {
+                    expression: Expression::StringLiteral {
+                        value: "early".to_string(),
+                        multiline: false,
+                        span: Span::new(0, 5),
+                    },
+                    comment: None,
+                    span: crate::types::Span::dummy(),
+                },
+                Statement::Injection {
+                    expression: Expression::StringLiteral {
+                        value: "unreachable".to_string(),
+                        multiline: false,
+                        span: Span::new(10, 20),
+                    },
+                    comment: None,
+                },
             ],
         );
 
@@ -274,6 +368,56 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_unreachable_after_if_else_that_always_returns() {
+        let returns = |value: &str, start: usize| {
+            vec![Statement::Return {
+                expression: Expression::StringLiteral {
+                    value: value.to_string(),
+                    multiline: false,
+                    span: Span::new(start, start + 5),
+                },
+                comment: None,
+                span: crate::types::Span::dummy(),
+            }]
+        };
+        let func = create_test_function(
+            "test",
+            vec![],
+            Type::String,
+            vec![
+                Statement::If {
+                    condition: Expression::Variable {
+                        name: "flag".to_string(),
+                        span: Span::new(0, 4),
+                    },
+                    body: returns("a", 10),
+                    else_body: Some(returns("b", 20)),
+                    span: Span::new(0, 30),
+                    comment: None,
+                },
+                Statement::Injection {
+                    expression: Expression::StringLiteral {
+                        value: "unreachable".to_string(),
+                        multiline: false,
+                        span: Span::new(40, 50),
+                    },
+                    comment: None,
+                },
+            ],
+        );
+
+        let module = create_test_module(vec![Definition::Function(func)]);
+        let mut analyzer = ReachabilityAnalyzer::new();
+        let warnings = analyzer.analyze_module(&module, 0);
+
+        assert_eq!(warnings.len(), 1);
+        match &warnings[0] {
+            Warning::UnreachableCode { span, .. } => assert_eq!(span.start, 40),
+            _ => panic!("Expected UnreachableCode warning"),
+        }
+    }
+
     #[test]
     fn test_no_unreachable_code() {
         let func = create_test_function(
@@ -281,14 +425,22 @@ mod tests {
             vec![],
             Type::Unit,
             vec![
-                Statement::Injection(Expression::StringLiteral {
-                    value: "hello".to_string(),
-                    span: Span::dummy(),
-                }),
-                Statement::Injection(Expression::StringLiteral {
-                    value: "world".to_string(),
-                    span: Span::dummy(),
-                }),
+                Statement::Injection {
+                    expression: Expression::StringLiteral {
+                        value: "hello".to_string(),
+                        multiline: false,
+                        span: Span::dummy(),
+                    },
+                    comment: None,
+                },
+                Statement::Injection {
+                    expression: Expression::StringLiteral {
+                        value: "world".to_string(),
+                        multiline: false,
+                        span: Span::dummy(),
+                    },
+                    comment: None,
+                },
             ],
         );
 
@@ -311,16 +463,25 @@ mod tests {
                         value: true,
                         span: Span::dummy(),
                     },
-                    body: vec![Statement::Injection(Expression::StringLiteral {
-                        value: "looping".to_string(),
-                        span: Span::dummy(),
-                    })],
+                    body: vec![Statement::Injection {
+                        expression: Expression::StringLiteral {
+                            value: "looping".to_string(),
+                            multiline: false,
+                            span: Span::dummy(),
+                        },
+                        comment: None,
+                    }],
                     span: Span::new(0, 10),
+                    comment: None,
+                },
+                Statement::Injection {
+                    expression: Expression::StringLiteral {
+                        value: "unreachable".to_string(),
+                        multiline: false,
+                        span: Span::new(20, 30),
+                    },
+                    comment: None,
                 },
-                Statement::Injection(Expression::StringLiteral {
-                    value: "unreachable".to_string(),
-                    span: Span::new(20, 30),
-                }),
             ],
         );
 
@@ -349,11 +510,16 @@ mod tests {
                     value: true,
                     span: Span::new(5, 15),
                 },
-                body: vec![Statement::Injection(Expression::StringLiteral {
-                    value: "forever".to_string(),
-                    span: Span::dummy(),
-                })],
+                body: vec![Statement::Injection {
+                    expression: Expression::StringLiteral {
+                        value: "forever".to_string(),
+                        multiline: false,
+                        span: Span::dummy(),
+                    },
+                    comment: None,
+                }],
                 span: Span::new(5, 15),
+                comment: None,
             }],
         );
 
@@ -382,11 +548,17 @@ mod tests {
                     value: true,
                     span: Span::new(10, 20),
                 },
-                body: vec![Statement::Return(Expression::StringLiteral {
-                    value: "escape".to_string(),
-                    span: Span::dummy(),
-                })],
+                body: vec![Statement::Return {
+                    expression: Expression::StringLiteral {
+                        value: "escape".to_string(),
+                        multiline: false,
+                        span: Span::dummy(),
+                    },
+                    comment: None,
+                    span: crate::types::Span::dummy(),
+                }],
                 span: Span::dummy(),
+                comment: None,
             }],
         );
 
@@ -408,11 +580,16 @@ mod tests {
                     name: "condition".to_string(),
                     span: Span::new(5, 10),
                 },
-                body: vec![Statement::Injection(Expression::StringLiteral {
-                    value: "maybe".to_string(),
-                    span: Span::dummy(),
-                })],
+                body: vec![Statement::Injection {
+                    expression: Expression::Call {
+                        function: "maybe".to_string(),
+                        arguments: vec![],
+                        span: Span::dummy(),
+                    },
+                    comment: None,
+                }],
                 span: Span::dummy(),
+                comment: None,
             }],
         );
 
@@ -437,17 +614,23 @@ mod tests {
                         span: Span::dummy(),
                     },
                     span: Span::dummy(),
+                    comment: None,
                 },
                 Statement::While {
                     condition: Expression::Variable {
                         name: "continue_loop".to_string(),
                         span: Span::new(5, 10),
                     },
-                    body: vec![Statement::Injection(Expression::StringLiteral {
-                        value: "forever".to_string(),
-                        span: Span::dummy(),
-                    })],
+                    body: vec![Statement::Injection {
+                        expression: Expression::StringLiteral {
+                            value: "forever".to_string(),
+                            multiline: false,
+                            span: Span::dummy(),
+                        },
+                        comment: None,
+                    }],
                     span: Span::new(10, 20),
+                    comment: None,
                 },
             ],
         );
@@ -480,6 +663,7 @@ mod tests {
                         span: Span::dummy(),
                     },
                     span: Span::dummy(),
+                    comment: None,
                 },
                 Statement::While {
                     condition: Expression::Variable {
@@ -493,8 +677,146 @@ mod tests {
                             span: Span::dummy(),
                         },
                         span: Span::dummy(),
+                        comment: None,
+                    }],
+                    span: Span::dummy(),
+                    comment: None,
+                },
+            ],
+        );
+
+        let module = create_test_module(vec![Definition::Function(func)]);
+        let mut analyzer = InfiniteLoopAnalyzer::new();
+        let warnings = analyzer.analyze_module(&module, 0);
+
+        assert_eq!(warnings.len(), 0);
+    }
+
+    fn while_over_parameter(body: Vec<Statement>) -> Function {
+        create_test_function(
+            "test",
+            vec![Parameter {
+                name: "running".to_string(),
+                param_type: Type::Boolean,
+                type_span: Span::dummy(),
+                span: Span::dummy(),
+                comment: None,
+            }],
+            Type::Unit,
+            vec![Statement::While {
+                condition: Expression::Variable {
+                    name: "running".to_string(),
+                    span: Span::new(20, 27),
+                },
+                body,
+                span: Span::dummy(),
+                comment: None,
+            }],
+        )
+    }
+
+    #[test]
+    fn test_loop_body_without_effects_on_condition_warns() {
+        let func = while_over_parameter(vec![
+            Statement::Injection {
+                expression: Expression::StringLiteral {
+                    value: "tick".to_string(),
+                    multiline: false,
+                    span: Span::dummy(),
+                },
+                comment: None,
+            },
+            Statement::Injection {
+                expression: Expression::StringLiteral {
+                    value: "tock".to_string(),
+                    multiline: false,
+                    span: Span::dummy(),
+                },
+                comment: None,
+            },
+        ]);
+
+        let module = create_test_module(vec![Definition::Function(func)]);
+        let mut analyzer = InfiniteLoopAnalyzer::new();
+        let warnings = analyzer.analyze_module(&module, 0);
+
+        assert_eq!(warnings.len(), 1);
+        match &warnings[0] {
+            Warning::PotentialInfiniteLoop { span, .. } => assert_eq!(*span, Span::new(20, 27)),
+            _ => panic!("Expected PotentialInfiniteLoop warning"),
+        }
+    }
+
+    #[test]
+    fn test_loop_body_reassigning_condition_no_warning() {
+        let func = while_over_parameter(vec![Statement::VariableAssignment {
+            variable: "running".to_string(),
+            expression: Expression::BooleanLiteral {
+                value: false,
+                span: Span::dummy(),
+            },
+            span: Span::dummy(),
+            comment: None,
+        }]);
+
+        let module = create_test_module(vec![Definition::Function(func)]);
+        let mut analyzer = InfiniteLoopAnalyzer::new();
+        let warnings = analyzer.analyze_module(&module, 0);
+
+        assert_eq!(warnings.len(), 0);
+    }
+
+    #[test]
+    fn test_loop_body_calling_function_no_warning() {
+        let func = while_over_parameter(vec![Statement::Assignment {
+            variable: "reply".to_string(),
+            expression: Expression::Call {
+                function: "receive".to_string(),
+                arguments: vec![],
+                span: Span::dummy(),
+            },
+            span: Span::dummy(),
+            comment: None,
+        }]);
+
+        let module = create_test_module(vec![Definition::Function(func)]);
+        let mut analyzer = InfiniteLoopAnalyzer::new();
+        let warnings = analyzer.analyze_module(&module, 0);
+
+        assert_eq!(warnings.len(), 0);
+    }
+
+    #[test]
+    fn test_loop_over_variable_that_starts_false_no_warning() {
+        let func = create_test_function(
+            "test",
+            vec![],
+            Type::Unit,
+            vec![
+                Statement::Assignment {
+                    variable: "running".to_string(),
+                    expression: Expression::BooleanLiteral {
+                        value: false,
+                        span: Span::dummy(),
+                    },
+                    span: Span::dummy(),
+                    comment: None,
+                },
+                Statement::While {
+                    condition: Expression::Variable {
+                        name: "running".to_string(),
+                        span: Span::dummy(),
+                    },
+                    body: vec![Statement::Injection {
+                        expression: Expression::StringLiteral {
+                            value: "tick".to_string(),
+                            multiline: false,
+                            span: Span::dummy(),
+                        },
+                        comment: None,
                     }],
                     span: Span::dummy(),
+                    comment: None,
                 },
             ],
         );
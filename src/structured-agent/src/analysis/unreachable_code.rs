@@ -29,19 +29,34 @@ impl ReachabilityAnalyzer {
     fn collect_all_statements(&mut self, statements: &[Statement]) {
         for statement in statements {
             let span = match statement {
-                Statement::Injection(expr) => expr.span(),
+                Statement::Injection {
+                    expression: expr, ..
+                } => expr.span(),
                 Statement::Assignment { span, .. } => *span,
                 Statement::VariableAssignment { span, .. } => *span,
-                Statement::ExpressionStatement(expr) => expr.span(),
-                Statement::If { span, body, .. } => {
+                Statement::ExpressionStatement {
+                    expression: expr, ..
+                } => expr.span(),
+                Statement::If {
+                    span,
+                    body,
+                    else_body,
+                    ..
+                } => {
                     self.collect_all_statements(body);
+                    if let Some(else_body) = else_body {
+                        self.collect_all_statements(else_body);
+                    }
                     *span
                 }
                 Statement::While { span, body, .. } => {
                     self.collect_all_statements(body);
                     *span
                 }
-                Statement::Return(expr) => expr.span(),
+                Statement::Assert { span, .. } => *span,
+                Statement::Return {
+                    expression: expr, ..
+                } => expr.span(),
             };
             self.all_statements.push(span);
         }
@@ -53,26 +68,36 @@ impl ReachabilityAnalyzer {
         for statement in statements {
             if current_reachable {
                 let span = match statement {
-                    Statement::Injection(expr) => expr.span(),
+                    Statement::Injection {
+                        expression: expr, ..
+                    } => expr.span(),
                     Statement::Assignment { span, .. } => *span,
                     Statement::VariableAssignment { span, .. } => *span,
-                    Statement::ExpressionStatement(expr) => expr.span(),
+                    Statement::ExpressionStatement {
+                        expression: expr, ..
+                    } => expr.span(),
                     Statement::If { span, .. } => *span,
                     Statement::While { span, .. } => *span,
-                    Statement::Return(expr) => expr.span(),
+                    Statement::Assert { span, .. } => *span,
+                    Statement::Return {
+                        expression: expr, ..
+                    } => expr.span(),
                 };
                 self.reachable.insert(span);
             }
 
             match statement {
                 Statement::If {
-                    condition, body, ..
+                    body, else_body, ..
                 } => {
                     if current_reachable {
-                        if self.is_constant_true(condition) {
-                            self.analyze_statements(body, true);
-                        } else {
-                            self.analyze_statements(body, current_reachable);
+                        let body_falls_through = self.analyze_statements(body, true);
+                        // A branch a literal condition never takes is left to
+                        // the constant-condition analyzer. Without an else
+                        // branch the if can always be skipped.
+                        if let Some(else_body) = else_body {
+                            let else_falls_through = self.analyze_statements(else_body, true);
+                            current_reachable = body_falls_through || else_falls_through;
                         }
                     }
                 }
@@ -86,7 +111,7 @@ impl ReachabilityAnalyzer {
                         }
                     }
                 }
-                Statement::Return(_) => {
+                Statement::Return { expression: _, .. } => {
                     current_reachable = false;
                 }
                 _ => {}
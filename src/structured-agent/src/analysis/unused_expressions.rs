@@ -1,5 +1,5 @@
 use crate::analysis::{Analyzer, Warning};
-use crate::ast::{Definition, Expression, Module, Statement};
+use crate::ast::{Definition, Expression, Function, Module, Statement};
 use crate::types::FileId;
 
 pub struct UnusedExpressionAnalyzer {
@@ -17,10 +17,14 @@ impl UnusedExpressionAnalyzer {
 
     fn analyze_statement(&mut self, statement: &Statement) {
         match statement {
-            Statement::ExpressionStatement(expr) => match expr {
+            Statement::ExpressionStatement {
+                expression: expr, ..
+            } => match expr {
                 Expression::StringLiteral { span, .. }
                 | Expression::BooleanLiteral { span, .. }
+                | Expression::NumberLiteral { span, .. }
                 | Expression::ListLiteral { span, .. }
+                | Expression::OptionLiteral { span, .. }
                 | Expression::UnitLiteral { span } => {
                     self.warnings.push(Warning::UnusedExpression {
                         span: *span,
@@ -31,11 +35,16 @@ impl UnusedExpressionAnalyzer {
                 | Expression::Call { .. }
                 | Expression::Select(_)
                 | Expression::IfElse { .. }
+                | Expression::Match { .. }
+                | Expression::Return { .. }
+                | Expression::FieldAccess { .. }
                 | Expression::Placeholder { .. } => {
                     self.analyze_expression(expr);
                 }
             },
-            Statement::Injection(expr) => {
+            Statement::Injection {
+                expression: expr, ..
+            } => {
                 self.analyze_expression(expr);
             }
             Statement::Assignment { expression, .. } => {
@@ -68,7 +77,15 @@ impl UnusedExpressionAnalyzer {
                     self.analyze_statement(stmt);
                 }
             }
-            Statement::Return(expr) => {
+            Statement::Assert {
+                condition, message, ..
+            } => {
+                self.analyze_expression(condition);
+                self.analyze_expression(message);
+            }
+            Statement::Return {
+                expression: expr, ..
+            } => {
                 self.analyze_expression(expr);
             }
         }
@@ -83,6 +100,9 @@ impl UnusedExpressionAnalyzer {
             }
             Expression::Select(select_expr) => {
                 for clause in &select_expr.clauses {
+                    if let Some(guard) = &clause.guard {
+                        self.analyze_expression(guard);
+                    }
                     self.analyze_expression(&clause.expression_to_run);
                     self.analyze_expression(&clause.expression_next);
                 }
@@ -97,19 +117,45 @@ impl UnusedExpressionAnalyzer {
                 self.analyze_expression(then_expr);
                 self.analyze_expression(else_expr);
             }
+            Expression::Match {
+                scrutinee, arms, ..
+            } => {
+                self.analyze_expression(scrutinee);
+                for arm in arms {
+                    self.analyze_expression(&arm.expression);
+                }
+            }
+            Expression::Return { value, .. } => {
+                self.analyze_expression(value);
+            }
+            Expression::FieldAccess { object, .. } => {
+                self.analyze_expression(object);
+            }
             Expression::Variable { .. }
             | Expression::StringLiteral { .. }
             | Expression::BooleanLiteral { .. }
+            | Expression::NumberLiteral { .. }
             | Expression::ListLiteral { .. }
+            | Expression::OptionLiteral { .. }
             | Expression::UnitLiteral { .. }
             | Expression::Placeholder { .. } => {}
         }
     }
 
-    fn analyze_function_body(&mut self, statements: &[Statement]) {
-        for statement in statements {
+    fn analyze_function(&mut self, func: &Function) {
+        let statements = &func.body.statements;
+        let implicit_return = func.implicit_return();
+        let discarded = match implicit_return {
+            Some(_) => &statements[..statements.len() - 1],
+            None => &statements[..],
+        };
+
+        for statement in discarded {
             self.analyze_statement(statement);
         }
+        if let Some(expr) = implicit_return {
+            self.analyze_expression(expr);
+        }
     }
 }
 
@@ -130,7 +176,7 @@ impl Analyzer for UnusedExpressionAnalyzer {
 
         for definition in &module.definitions {
             if let Definition::Function(func) = definition {
-                self.analyze_function_body(&func.body.statements);
+                self.analyze_function(func);
             }
         }
 
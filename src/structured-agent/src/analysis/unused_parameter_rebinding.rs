@@ -0,0 +1,166 @@
+use crate::analysis::{Analyzer, Warning};
+use crate::ast::{Definition, Expression, Function, Module, Statement};
+use crate::types::FileId;
+
+pub struct UnusedParameterRebindingAnalyzer;
+
+impl UnusedParameterRebindingAnalyzer {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn analyze_function(&self, func: &Function, file_id: FileId, warnings: &mut Vec<Warning>) {
+        let statements = &func.body.statements;
+
+        for (index, statement) in statements.iter().enumerate() {
+            let Statement::Assignment {
+                variable,
+                expression,
+                span,
+                ..
+            } = statement
+            else {
+                continue;
+            };
+
+            let Some(param) = func.parameters.iter().find(|p| &p.name == variable) else {
+                continue;
+            };
+
+            if Self::is_trivial_rebinding(expression, variable)
+                && !Self::statements_read(&statements[index + 1..], variable)
+            {
+                warnings.push(Warning::UnusedParameterRebinding {
+                    name: variable.clone(),
+                    span: *span,
+                    parameter_span: param.span,
+                    file_id,
+                });
+            }
+        }
+    }
+
+    fn is_trivial_rebinding(expression: &Expression, param: &str) -> bool {
+        match expression {
+            Expression::Variable { name, .. } => name == param,
+            Expression::Call { arguments, .. } => arguments
+                .iter()
+                .any(|arg| matches!(arg, Expression::Variable { name, .. } if name == param)),
+            _ => false,
+        }
+    }
+
+    fn statements_read(statements: &[Statement], name: &str) -> bool {
+        statements
+            .iter()
+            .any(|stmt| Self::statement_reads(stmt, name))
+    }
+
+    fn statement_reads(statement: &Statement, name: &str) -> bool {
+        match statement {
+            Statement::Injection {
+                expression: expr, ..
+            }
+            | Statement::ExpressionStatement {
+                expression: expr, ..
+            }
+            | Statement::Return {
+                expression: expr, ..
+            } => Self::expression_reads(expr, name),
+            Statement::Assignment { expression, .. }
+            | Statement::VariableAssignment { expression, .. } => {
+                Self::expression_reads(expression, name)
+            }
+            Statement::If {
+                condition,
+                body,
+                else_body,
+                ..
+            } => {
+                Self::expression_reads(condition, name)
+                    || Self::statements_read(body, name)
+                    || else_body
+                        .as_ref()
+                        .is_some_and(|else_body| Self::statements_read(else_body, name))
+            }
+            Statement::While {
+                condition, body, ..
+            } => Self::expression_reads(condition, name) || Self::statements_read(body, name),
+            Statement::Assert {
+                condition, message, ..
+            } => Self::expression_reads(condition, name) || Self::expression_reads(message, name),
+        }
+    }
+
+    fn expression_reads(expression: &Expression, name: &str) -> bool {
+        match expression {
+            Expression::Variable { name: var, .. } => var == name,
+            Expression::Call { arguments, .. } => arguments
+                .iter()
+                .any(|arg| Self::expression_reads(arg, name)),
+            Expression::ListLiteral { elements, .. } => elements
+                .iter()
+                .any(|elem| Self::expression_reads(elem, name)),
+            Expression::OptionLiteral { value, .. } => value
+                .as_ref()
+                .is_some_and(|value| Self::expression_reads(value, name)),
+            Expression::Select(select_expr) => select_expr.clauses.iter().any(|clause| {
+                clause
+                    .guard
+                    .as_ref()
+                    .is_some_and(|guard| Self::expression_reads(guard, name))
+                    || Self::expression_reads(&clause.expression_to_run, name)
+                    || Self::expression_reads(&clause.expression_next, name)
+            }),
+            Expression::IfElse {
+                condition,
+                then_expr,
+                else_expr,
+                ..
+            } => {
+                Self::expression_reads(condition, name)
+                    || Self::expression_reads(then_expr, name)
+                    || Self::expression_reads(else_expr, name)
+            }
+            Expression::Match {
+                scrutinee, arms, ..
+            } => {
+                Self::expression_reads(scrutinee, name)
+                    || arms
+                        .iter()
+                        .any(|arm| Self::expression_reads(&arm.expression, name))
+            }
+            Expression::Return { value, .. } => Self::expression_reads(value, name),
+            Expression::FieldAccess { object, .. } => Self::expression_reads(object, name),
+            Expression::StringLiteral { .. }
+            | Expression::BooleanLiteral { .. }
+            | Expression::NumberLiteral { .. }
+            | Expression::UnitLiteral { .. }
+            | Expression::Placeholder { .. } => false,
+        }
+    }
+}
+
+impl Default for UnusedParameterRebindingAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Analyzer for UnusedParameterRebindingAnalyzer {
+    fn name(&self) -> &str {
+        "unused-parameter-rebinding"
+    }
+
+    fn analyze_module(&mut self, module: &Module, file_id: FileId) -> Vec<Warning> {
+        let mut warnings = Vec::new();
+
+        for definition in &module.definitions {
+            if let Definition::Function(func) = definition {
+                self.analyze_function(func, file_id, &mut warnings);
+            }
+        }
+
+        warnings
+    }
+}
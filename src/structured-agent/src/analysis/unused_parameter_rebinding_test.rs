@@ -0,0 +1,108 @@
+#[cfg(test)]
+mod tests {
+    use crate::analysis::{
+        AnalysisRunner, Analyzer, UnusedParameterRebindingAnalyzer, UnusedVariableAnalyzer,
+        VariableShadowingAnalyzer, Warning,
+    };
+    use crate::ast::Module;
+    use crate::compiler::{CodespanParser, CompilationUnit};
+    use crate::diagnostics::DiagnosticManager;
+
+    fn parse_code(code: &str) -> Module {
+        let unit = CompilationUnit::from_string(code.to_string());
+        let manager = DiagnosticManager::new();
+        let parser = CodespanParser::new();
+        parser.parse(&unit, 0, manager.reporter()).unwrap()
+    }
+
+    #[test]
+    fn detects_unused_rebinding_of_parameter_to_itself() {
+        let code = r#"
+fn test(code: String): () {
+    let code = code
+}
+"#;
+
+        let module = parse_code(code);
+        let mut analyzer = UnusedParameterRebindingAnalyzer::new();
+        let warnings = analyzer.analyze_module(&module, 0);
+
+        assert_eq!(warnings.len(), 1);
+        match &warnings[0] {
+            Warning::UnusedParameterRebinding { name, .. } => assert_eq!(name, "code"),
+            other => panic!("Expected UnusedParameterRebinding, got: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn detects_unused_trivial_transform_of_parameter() {
+        let code = r#"
+extern fn trim(value: String): String
+
+fn test(code: String): () {
+    let code = trim(code)
+}
+"#;
+
+        let module = parse_code(code);
+        let mut analyzer = UnusedParameterRebindingAnalyzer::new();
+        let warnings = analyzer.analyze_module(&module, 0);
+
+        assert_eq!(warnings.len(), 1);
+    }
+
+    #[test]
+    fn no_warning_when_rebinding_is_used() {
+        let code = r#"
+extern fn transform(value: String): String
+
+fn test(code: String): String {
+    let code = transform(code)
+    return code
+}
+"#;
+
+        let module = parse_code(code);
+        let mut analyzer = UnusedParameterRebindingAnalyzer::new();
+        let warnings = analyzer.analyze_module(&module, 0);
+
+        assert_eq!(warnings.len(), 0);
+    }
+
+    #[test]
+    fn no_warning_for_rebinding_to_unrelated_value() {
+        let code = r#"
+fn test(code: String): () {
+    let code = "replacement"
+}
+"#;
+
+        let module = parse_code(code);
+        let mut analyzer = UnusedParameterRebindingAnalyzer::new();
+        let warnings = analyzer.analyze_module(&module, 0);
+
+        assert_eq!(warnings.len(), 0);
+    }
+
+    #[test]
+    fn runner_reports_single_diagnostic_for_rebinding() {
+        let code = r#"
+fn test(code: String): () {
+    let code = code
+}
+"#;
+
+        let module = parse_code(code);
+        let mut runner = AnalysisRunner::new()
+            .with_analyzer(Box::new(UnusedVariableAnalyzer::new()))
+            .with_analyzer(Box::new(VariableShadowingAnalyzer::new()))
+            .with_analyzer(Box::new(UnusedParameterRebindingAnalyzer::new()));
+        let warnings = runner.run(&module, 0);
+
+        assert_eq!(warnings.len(), 1);
+        assert!(matches!(
+            warnings[0],
+            Warning::UnusedParameterRebinding { .. }
+        ));
+    }
+}
@@ -1,5 +1,5 @@
 use crate::analysis::{Analyzer, Warning};
-use crate::ast::{Definition, Expression, Module, Statement};
+use crate::ast::{Definition, Expression, Function, Module, Statement};
 use crate::types::FileId;
 use std::collections::HashMap;
 
@@ -31,13 +31,16 @@ impl UnusedReturnValueAnalyzer {
                     self.function_return_types
                         .insert(ext_func.name.clone(), returns_value);
                 }
+                Definition::ExternalConst(_) | Definition::Constant(_) => {}
             }
         }
     }
 
     fn analyze_statement(&mut self, statement: &Statement) {
         match statement {
-            Statement::ExpressionStatement(expr) => {
+            Statement::ExpressionStatement {
+                expression: expr, ..
+            } => {
                 if let Expression::Call { function, span, .. } = expr {
                     if let Some(&returns_value) = self.function_return_types.get(function) {
                         if returns_value {
@@ -51,7 +54,9 @@ impl UnusedReturnValueAnalyzer {
                 }
                 self.analyze_expression(expr);
             }
-            Statement::Injection(value) => {
+            Statement::Injection {
+                expression: value, ..
+            } => {
                 self.analyze_expression(value);
             }
             Statement::Assignment { expression, .. } => {
@@ -61,12 +66,20 @@ impl UnusedReturnValueAnalyzer {
                 self.analyze_expression(expression);
             }
             Statement::If {
-                condition, body, ..
+                condition,
+                body,
+                else_body,
+                ..
             } => {
                 self.analyze_expression(condition);
                 for stmt in body {
                     self.analyze_statement(stmt);
                 }
+                if let Some(else_stmts) = else_body {
+                    for stmt in else_stmts {
+                        self.analyze_statement(stmt);
+                    }
+                }
             }
             Statement::While {
                 condition, body, ..
@@ -76,7 +89,15 @@ impl UnusedReturnValueAnalyzer {
                     self.analyze_statement(stmt);
                 }
             }
-            Statement::Return(expr) => {
+            Statement::Assert {
+                condition, message, ..
+            } => {
+                self.analyze_expression(condition);
+                self.analyze_expression(message);
+            }
+            Statement::Return {
+                expression: expr, ..
+            } => {
                 self.analyze_expression(expr);
             }
         }
@@ -91,6 +112,9 @@ impl UnusedReturnValueAnalyzer {
             }
             Expression::Select(select_expr) => {
                 for clause in &select_expr.clauses {
+                    if let Some(guard) = &clause.guard {
+                        self.analyze_expression(guard);
+                    }
                     self.analyze_expression(&clause.expression_to_run);
                     self.analyze_expression(&clause.expression_next);
                 }
@@ -105,19 +129,45 @@ impl UnusedReturnValueAnalyzer {
                 self.analyze_expression(then_expr);
                 self.analyze_expression(else_expr);
             }
+            Expression::Match {
+                scrutinee, arms, ..
+            } => {
+                self.analyze_expression(scrutinee);
+                for arm in arms {
+                    self.analyze_expression(&arm.expression);
+                }
+            }
+            Expression::Return { value, .. } => {
+                self.analyze_expression(value);
+            }
+            Expression::FieldAccess { object, .. } => {
+                self.analyze_expression(object);
+            }
             Expression::Variable { .. }
             | Expression::StringLiteral { .. }
             | Expression::BooleanLiteral { .. }
+            | Expression::NumberLiteral { .. }
             | Expression::ListLiteral { .. }
+            | Expression::OptionLiteral { .. }
             | Expression::UnitLiteral { .. }
             | Expression::Placeholder { .. } => {}
         }
     }
 
-    fn analyze_function_body(&mut self, statements: &[Statement]) {
-        for statement in statements {
+    fn analyze_function(&mut self, func: &Function) {
+        let statements = &func.body.statements;
+        let implicit_return = func.implicit_return();
+        let discarded = match implicit_return {
+            Some(_) => &statements[..statements.len() - 1],
+            None => &statements[..],
+        };
+
+        for statement in discarded {
             self.analyze_statement(statement);
         }
+        if let Some(expr) = implicit_return {
+            self.analyze_expression(expr);
+        }
     }
 }
 
@@ -141,7 +191,7 @@ impl Analyzer for UnusedReturnValueAnalyzer {
 
         for definition in &module.definitions {
             if let Definition::Function(func) = definition {
-                self.analyze_function_body(&func.body.statements);
+                self.analyze_function(func);
             }
         }
 
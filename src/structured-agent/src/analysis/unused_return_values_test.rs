@@ -1,6 +1,6 @@
 #[cfg(test)]
 mod tests {
-    use crate::analysis::{Analyzer, UnusedReturnValueAnalyzer};
+    use crate::analysis::{Analyzer, UnusedReturnValueAnalyzer, Warning};
     use crate::ast::Module;
     use crate::compiler::{CodespanParser, CompilationUnit};
     use crate::diagnostics::DiagnosticManager;
@@ -117,4 +117,67 @@ fn test(): () {
 
         assert_eq!(warnings.len(), 2);
     }
+
+    #[test]
+    fn no_warning_for_trailing_call_used_as_implicit_return() {
+        let code = r#"
+extern fn get_data(): String
+
+fn test(): String {
+    get_data()
+}
+"#;
+
+        let module = parse_code(code);
+        let mut analyzer = UnusedReturnValueAnalyzer::new();
+        let warnings = analyzer.analyze_module(&module, 0);
+
+        assert_eq!(warnings.len(), 0);
+    }
+
+    #[test]
+    fn detects_discarded_value_before_implicit_return() {
+        let code = r#"
+extern fn get_data1(): String
+extern fn get_data2(): String
+
+fn test(): String {
+    get_data1()
+    get_data2()
+}
+"#;
+
+        let module = parse_code(code);
+        let mut analyzer = UnusedReturnValueAnalyzer::new();
+        let warnings = analyzer.analyze_module(&module, 0);
+
+        assert_eq!(warnings.len(), 1);
+        match &warnings[0] {
+            Warning::UnusedReturnValue { function_name, .. } => {
+                assert_eq!(function_name, "get_data1")
+            }
+            other => panic!("Expected UnusedReturnValue, got: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn detects_unused_return_in_else_branch() {
+        let code = r#"
+extern fn get_data(): String
+
+fn test(flag: Boolean): () {
+    if flag {
+        "yes"!
+    } else {
+        get_data()
+    }
+}
+"#;
+
+        let module = parse_code(code);
+        let mut analyzer = UnusedReturnValueAnalyzer::new();
+        let warnings = analyzer.analyze_module(&module, 0);
+
+        assert_eq!(warnings.len(), 1);
+    }
 }
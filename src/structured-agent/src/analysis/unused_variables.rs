@@ -56,6 +56,7 @@ impl UnusedVariableAnalyzer {
                 variable,
                 expression,
                 span,
+                ..
             } => {
                 self.track_declaration(variable, *span);
                 self.analyze_expression(expression);
@@ -64,13 +65,18 @@ impl UnusedVariableAnalyzer {
                 variable: _,
                 expression,
                 span: _,
+                ..
             } => {
                 self.analyze_expression(expression);
             }
-            Statement::Injection(expr) => {
+            Statement::Injection {
+                expression: expr, ..
+            } => {
                 self.analyze_expression(expr);
             }
-            Statement::ExpressionStatement(expr) => {
+            Statement::ExpressionStatement {
+                expression: expr, ..
+            } => {
                 self.analyze_expression(expr);
             }
             Statement::If {
@@ -89,7 +95,15 @@ impl UnusedVariableAnalyzer {
                     self.analyze_statement(stmt);
                 }
             }
-            Statement::Return(expr) => {
+            Statement::Assert {
+                condition, message, ..
+            } => {
+                self.analyze_expression(condition);
+                self.analyze_expression(message);
+            }
+            Statement::Return {
+                expression: expr, ..
+            } => {
                 self.analyze_expression(expr);
             }
         }
@@ -107,6 +121,9 @@ impl UnusedVariableAnalyzer {
             }
             Expression::Select(select_expr) => {
                 for clause in &select_expr.clauses {
+                    if let Some(guard) = &clause.guard {
+                        self.analyze_expression(guard);
+                    }
                     self.analyze_expression(&clause.expression_to_run);
                     self.analyze_expression(&clause.expression_next);
                 }
@@ -121,9 +138,30 @@ impl UnusedVariableAnalyzer {
                 self.analyze_expression(then_expr);
                 self.analyze_expression(else_expr);
             }
+            Expression::Match {
+                scrutinee, arms, ..
+            } => {
+                self.analyze_expression(scrutinee);
+                for arm in arms {
+                    self.analyze_expression(&arm.expression);
+                }
+            }
+            Expression::Return { value, .. } => {
+                self.analyze_expression(value);
+            }
+            Expression::FieldAccess { object, .. } => {
+                self.analyze_expression(object);
+            }
+            Expression::OptionLiteral {
+                value: Some(value), ..
+            } => {
+                self.analyze_expression(value);
+            }
             Expression::StringLiteral { .. }
             | Expression::BooleanLiteral { .. }
+            | Expression::NumberLiteral { .. }
             | Expression::ListLiteral { .. }
+            | Expression::OptionLiteral { value: None, .. }
             | Expression::UnitLiteral { .. }
             | Expression::Placeholder { .. } => {}
         }
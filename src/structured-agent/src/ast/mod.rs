@@ -12,6 +12,8 @@ pub struct Module {
 pub enum Definition {
     Function(Function),
     ExternalFunction(ExternalFunction),
+    ExternalConst(ExternalConst),
+    Constant(Constant),
 }
 
 impl Spanned for Definition {
@@ -19,6 +21,8 @@ impl Spanned for Definition {
         match self {
             Definition::Function(f) => f.span,
             Definition::ExternalFunction(f) => f.span,
+            Definition::ExternalConst(c) => c.span,
+            Definition::Constant(c) => c.span,
         }
     }
 }
@@ -28,8 +32,12 @@ pub struct Function {
     pub name: String,
     pub parameters: Vec<Parameter>,
     pub return_type: Type,
+    pub return_type_span: Span,
     pub body: FunctionBody,
     pub documentation: Option<String>,
+    /// Set by a `# pure` line above the function: the body promises not to
+    /// call natives or tools with side effects.
+    pub pure: bool,
     pub span: Span,
 }
 
@@ -37,7 +45,9 @@ pub struct Function {
 pub struct Parameter {
     pub name: String,
     pub param_type: Type,
+    pub type_span: Span,
     pub span: Span,
+    pub comment: Option<String>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -45,6 +55,23 @@ pub struct ExternalFunction {
     pub name: String,
     pub parameters: Vec<Parameter>,
     pub return_type: Type,
+    pub return_type_span: Span,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExternalConst {
+    pub name: String,
+    pub const_type: Type,
+    pub type_span: Span,
+    pub span: Span,
+}
+
+/// A module-level `const NAME = "..."`, readable from every function.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Constant {
+    pub name: String,
+    pub value: Expression,
     pub span: Span,
 }
 
@@ -52,15 +79,12 @@ pub struct ExternalFunction {
 pub enum Type {
     Unit,
     Boolean,
+    Number,
     String,
     List(Box<Type>),
     Option(Box<Type>),
-}
-
-impl Spanned for Type {
-    fn span(&self) -> Span {
-        Span::dummy()
-    }
+    Json,
+    Custom(String),
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -69,44 +93,122 @@ pub struct FunctionBody {
     pub span: Span,
 }
 
+impl Function {
+    /// The trailing expression statement whose value is returned when a
+    /// non-Unit function has no explicit `return` at the end of its body.
+    pub fn implicit_return(&self) -> Option<&Expression> {
+        if self.return_type == Type::Unit {
+            return None;
+        }
+        match self.body.statements.last() {
+            Some(Statement::ExpressionStatement {
+                expression: expr, ..
+            }) => Some(expr),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum Statement {
-    Injection(Expression),
+    Injection {
+        expression: Expression,
+        comment: Option<String>,
+    },
     Assignment {
         variable: String,
         expression: Expression,
         span: Span,
+        comment: Option<String>,
     },
     VariableAssignment {
         variable: String,
         expression: Expression,
         span: Span,
+        comment: Option<String>,
+    },
+    ExpressionStatement {
+        expression: Expression,
+        comment: Option<String>,
     },
-    ExpressionStatement(Expression),
     If {
         condition: Expression,
         body: Vec<Statement>,
         else_body: Option<Vec<Statement>>,
         span: Span,
+        comment: Option<String>,
     },
     While {
         condition: Expression,
         body: Vec<Statement>,
         span: Span,
+        comment: Option<String>,
+    },
+    /// `assert condition, message`: stops the program with `message` when
+    /// the condition is false.
+    Assert {
+        condition: Expression,
+        message: Expression,
+        span: Span,
+        comment: Option<String>,
+    },
+    Return {
+        expression: Expression,
+        span: Span,
+        comment: Option<String>,
     },
-    Return(Expression),
+}
+
+impl Statement {
+    /// The `#` comment written directly above the statement, if any.
+    pub fn comment(&self) -> Option<&str> {
+        match self {
+            Statement::Injection { comment, .. }
+            | Statement::Assignment { comment, .. }
+            | Statement::VariableAssignment { comment, .. }
+            | Statement::ExpressionStatement { comment, .. }
+            | Statement::If { comment, .. }
+            | Statement::While { comment, .. }
+            | Statement::Assert { comment, .. }
+            | Statement::Return { comment, .. } => comment.as_deref(),
+        }
+    }
+
+    fn comment_mut(&mut self) -> &mut Option<String> {
+        match self {
+            Statement::Injection { comment, .. }
+            | Statement::Assignment { comment, .. }
+            | Statement::VariableAssignment { comment, .. }
+            | Statement::ExpressionStatement { comment, .. }
+            | Statement::If { comment, .. }
+            | Statement::While { comment, .. }
+            | Statement::Assert { comment, .. }
+            | Statement::Return { comment, .. } => comment,
+        }
+    }
+
+    /// The statement with `comment` attached above it.
+    pub fn with_comment(mut self, comment: Option<String>) -> Self {
+        *self.comment_mut() = comment;
+        self
+    }
 }
 
 impl Spanned for Statement {
     fn span(&self) -> Span {
         match self {
-            Statement::Injection(expr) => expr.span(),
+            Statement::Injection {
+                expression: expr, ..
+            } => expr.span(),
             Statement::Assignment { span, .. } => *span,
             Statement::VariableAssignment { span, .. } => *span,
-            Statement::ExpressionStatement(expr) => expr.span(),
+            Statement::ExpressionStatement {
+                expression: expr, ..
+            } => expr.span(),
             Statement::If { span, .. } => *span,
             Statement::While { span, .. } => *span,
-            Statement::Return(expr) => expr.span(),
+            Statement::Assert { span, .. } => *span,
+            Statement::Return { span, .. } => *span,
         }
     }
 }
@@ -114,6 +216,12 @@ impl Spanned for Statement {
 #[derive(Debug, Clone, PartialEq)]
 pub struct SelectExpression {
     pub clauses: Vec<SelectClause>,
+    /// `select ranked`: the engine orders every clause and they are tried in
+    /// turn until one produces something other than an empty Option.
+    pub ranked: bool,
+    /// `select retry`: a clause that fails is left out and the engine is
+    /// asked to choose again from the rest.
+    pub retry: bool,
     pub span: Span,
 }
 
@@ -121,10 +229,21 @@ pub struct SelectExpression {
 pub struct SelectClause {
     pub expression_to_run: Expression,
     pub result_variable: String,
+    /// `if <condition>` before the `=>`: the clause is only offered to the
+    /// engine when this evaluates to true.
+    pub guard: Option<Expression>,
     pub expression_next: Expression,
     pub span: Span,
 }
 
+/// `true => ...` or `false => ...` in a `match`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MatchArm {
+    pub pattern: bool,
+    pub expression: Expression,
+    pub span: Span,
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum Expression {
     Call {
@@ -138,16 +257,28 @@ pub enum Expression {
     },
     StringLiteral {
         value: String,
+        /// Written as `'''...'''`, so formatting keeps the literal's line breaks.
+        multiline: bool,
         span: Span,
     },
     BooleanLiteral {
         value: bool,
         span: Span,
     },
+    NumberLiteral {
+        value: f64,
+        span: Span,
+    },
     ListLiteral {
         elements: Vec<Expression>,
         span: Span,
     },
+    /// `Some(value)`, or `None` when there is no value. A bare `None` takes
+    /// its type from where it is used.
+    OptionLiteral {
+        value: Option<Box<Expression>>,
+        span: Span,
+    },
     Placeholder {
         span: Span,
     },
@@ -161,6 +292,24 @@ pub enum Expression {
         else_expr: Box<Expression>,
         span: Span,
     },
+    /// `match scrutinee { true => ..., false => ... }`. The type checker
+    /// requires exactly one arm for each Boolean value.
+    Match {
+        scrutinee: Box<Expression>,
+        arms: Vec<MatchArm>,
+        span: Span,
+    },
+    Return {
+        value: Box<Expression>,
+        span: Span,
+    },
+    /// `object.field` on a Json value. A field the object does not have, or
+    /// any field of a value that is not an object, reads as Json `null`.
+    FieldAccess {
+        object: Box<Expression>,
+        field: String,
+        span: Span,
+    },
 }
 
 impl Spanned for Expression {
@@ -170,11 +319,16 @@ impl Spanned for Expression {
             Expression::Variable { span, .. } => *span,
             Expression::StringLiteral { span, .. } => *span,
             Expression::BooleanLiteral { span, .. } => *span,
+            Expression::NumberLiteral { span, .. } => *span,
             Expression::ListLiteral { span, .. } => *span,
+            Expression::OptionLiteral { span, .. } => *span,
             Expression::Placeholder { span } => *span,
             Expression::UnitLiteral { span } => *span,
             Expression::Select(select) => select.span,
             Expression::IfElse { span, .. } => *span,
+            Expression::Match { span, .. } => *span,
+            Expression::Return { span, .. } => *span,
+            Expression::FieldAccess { span, .. } => *span,
         }
     }
 }
@@ -193,107 +347,321 @@ impl Spanned for SelectClause {
 
 impl fmt::Display for Type {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            Type::Unit => write!(f, "()"),
-            Type::Boolean => write!(f, "Boolean"),
-            Type::String => write!(f, "String"),
-            Type::List(inner) => write!(f, "List<{}>", inner),
-            Type::Option(inner) => write!(f, "Option<{}>", inner),
+        write!(f, "{}", crate::types::Type::from(self).name())
+    }
+}
+
+// Blocks are written four spaces deeper than the line that opens them. The
+// text of multiline strings is written as is, so nesting never changes it.
+const INDENT: &str = "    ";
+
+fn write_indent(f: &mut fmt::Formatter<'_>, depth: usize) -> fmt::Result {
+    for _ in 0..depth {
+        f.write_str(INDENT)?;
+    }
+    Ok(())
+}
+
+fn write_comment(f: &mut fmt::Formatter<'_>, comment: &str, depth: usize) -> fmt::Result {
+    for line in comment.lines() {
+        write_indent(f, depth)?;
+        if line.is_empty() {
+            writeln!(f, "#")?;
+        } else {
+            writeln!(f, "# {}", line)?;
         }
     }
+    Ok(())
 }
 
-impl fmt::Display for Function {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        if let Some(doc) = &self.documentation {
-            for line in doc.lines() {
-                writeln!(f, "## {}", line)?;
+// Parameters go on one line unless one of them has a comment, which needs a
+// line of its own above the parameter.
+fn write_parameters(f: &mut fmt::Formatter<'_>, parameters: &[Parameter]) -> fmt::Result {
+    write!(f, "(")?;
+    if parameters.iter().any(|param| param.comment.is_some()) {
+        writeln!(f)?;
+        for (i, param) in parameters.iter().enumerate() {
+            if let Some(comment) = &param.comment {
+                write_comment(f, comment, 1)?;
+            }
+            write!(f, "{}{}: {}", INDENT, param.name, param.param_type)?;
+            if i + 1 < parameters.len() {
+                write!(f, ",")?;
             }
+            writeln!(f)?;
         }
-        write!(f, "fn {}(", self.name)?;
-        for (i, param) in self.parameters.iter().enumerate() {
+    } else {
+        for (i, param) in parameters.iter().enumerate() {
             if i > 0 {
                 write!(f, ", ")?;
             }
             write!(f, "{}: {}", param.name, param.param_type)?;
         }
-        write!(f, "): {}", self.return_type)?;
-        Ok(())
     }
+    write!(f, ")")
 }
 
-impl fmt::Display for Statement {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            Statement::Injection(expr) => write!(f, "{}!", expr),
-            Statement::Assignment {
-                variable,
-                expression,
-                ..
-            } => {
-                write!(f, "let {} = {}", variable, expression)
-            }
-            Statement::VariableAssignment {
-                variable,
-                expression,
-                ..
-            } => {
-                write!(f, "{} = {}", variable, expression)
-            }
-            Statement::ExpressionStatement(expr) => write!(f, "{}", expr),
-            Statement::If {
-                condition,
-                body,
-                else_body,
-                ..
-            } => {
-                writeln!(f, "if {} {{", condition)?;
-                for stmt in body {
-                    writeln!(f, "    {}", stmt)?;
+fn write_block(f: &mut fmt::Formatter<'_>, statements: &[Statement], depth: usize) -> fmt::Result {
+    for statement in statements {
+        if let Some(comment) = statement.comment() {
+            write_comment(f, comment, depth)?;
+        }
+        write_indent(f, depth)?;
+        write_statement(f, statement, depth)?;
+        writeln!(f)?;
+    }
+    Ok(())
+}
+
+fn write_statement(f: &mut fmt::Formatter<'_>, statement: &Statement, depth: usize) -> fmt::Result {
+    match statement {
+        Statement::Injection {
+            expression: expr, ..
+        } => {
+            write_expression(f, expr, depth)?;
+            write!(f, "!")
+        }
+        Statement::Assignment {
+            variable,
+            expression,
+            ..
+        } => {
+            write!(f, "let {} = ", variable)?;
+            write_expression(f, expression, depth)
+        }
+        Statement::VariableAssignment {
+            variable,
+            expression,
+            ..
+        } => {
+            write!(f, "{} = ", variable)?;
+            write_expression(f, expression, depth)
+        }
+        Statement::ExpressionStatement {
+            expression: expr, ..
+        } => write_expression(f, expr, depth),
+        Statement::If {
+            condition,
+            body,
+            else_body,
+            ..
+        } => {
+            write!(f, "if ")?;
+            write_expression(f, condition, depth)?;
+            writeln!(f, " {{")?;
+            write_block(f, body, depth + 1)?;
+            write_indent(f, depth)?;
+            write!(f, "}}")?;
+            match else_body.as_deref() {
+                // A lone `if` with no comment above it reads as `else if`.
+                Some([nested @ Statement::If { comment: None, .. }]) => {
+                    write!(f, " else ")?;
+                    write_statement(f, nested, depth)
                 }
-                if let Some(else_stmts) = else_body {
-                    writeln!(f, "}} else {{")?;
-                    for stmt in else_stmts {
-                        writeln!(f, "    {}", stmt)?;
-                    }
-                    write!(f, "}}")
-                } else {
+                Some(else_body) => {
+                    writeln!(f, " else {{")?;
+                    write_block(f, else_body, depth + 1)?;
+                    write_indent(f, depth)?;
                     write!(f, "}}")
                 }
+                None => Ok(()),
             }
-            Statement::While {
-                condition, body, ..
-            } => {
-                writeln!(f, "while {} {{", condition)?;
-                for stmt in body {
-                    writeln!(f, "    {}", stmt)?;
-                }
-                write!(f, "}}")
+        }
+        Statement::While {
+            condition, body, ..
+        } => {
+            write!(f, "while ")?;
+            write_expression(f, condition, depth)?;
+            writeln!(f, " {{")?;
+            write_block(f, body, depth + 1)?;
+            write_indent(f, depth)?;
+            write!(f, "}}")
+        }
+        Statement::Assert {
+            condition, message, ..
+        } => {
+            write!(f, "assert ")?;
+            write_expression(f, condition, depth)?;
+            write!(f, ", ")?;
+            write_expression(f, message, depth)
+        }
+        Statement::Return {
+            expression: expr, ..
+        } => {
+            write!(f, "return ")?;
+            write_expression(f, expr, depth)
+        }
+    }
+}
+
+fn write_select(
+    f: &mut fmt::Formatter<'_>,
+    select: &SelectExpression,
+    depth: usize,
+) -> fmt::Result {
+    if select.ranked {
+        writeln!(f, "select ranked {{")?;
+    } else if select.retry {
+        writeln!(f, "select retry {{")?;
+    } else {
+        writeln!(f, "select {{")?;
+    }
+    for (i, clause) in select.clauses.iter().enumerate() {
+        write_indent(f, depth + 1)?;
+        write_expression(f, &clause.expression_to_run, depth + 1)?;
+        write!(f, " as {}", clause.result_variable)?;
+        if let Some(guard) = &clause.guard {
+            write!(f, " if ")?;
+            write_expression(f, guard, depth + 1)?;
+        }
+        write!(f, " => ")?;
+        write_expression(f, &clause.expression_next, depth + 1)?;
+        // Clauses are separated by commas, and the parser takes no trailing one.
+        if i + 1 < select.clauses.len() {
+            writeln!(f, ",")?;
+        } else {
+            writeln!(f)?;
+        }
+    }
+    write_indent(f, depth)?;
+    write!(f, "}}")
+}
+
+fn write_expressions(
+    f: &mut fmt::Formatter<'_>,
+    expressions: &[Expression],
+    depth: usize,
+) -> fmt::Result {
+    for (i, expr) in expressions.iter().enumerate() {
+        if i > 0 {
+            write!(f, ", ")?;
+        }
+        write_expression(f, expr, depth)?;
+    }
+    Ok(())
+}
+
+fn write_expression(f: &mut fmt::Formatter<'_>, expr: &Expression, depth: usize) -> fmt::Result {
+    match expr {
+        Expression::Call {
+            function,
+            arguments,
+            ..
+        } => {
+            write!(f, "{}(", function)?;
+            write_expressions(f, arguments, depth)?;
+            write!(f, ")")
+        }
+        Expression::Variable { name, .. } => write!(f, "{}", name),
+        Expression::StringLiteral {
+            value, multiline, ..
+        } => {
+            if *multiline {
+                write!(f, "'''{}'''", escape_multiline_string(value))
+            } else {
+                write!(f, "\"{}\"", escape_string(value))
             }
-            Statement::Return(expr) => write!(f, "return {}", expr),
+        }
+        Expression::BooleanLiteral { value, .. } => write!(f, "{}", value),
+        Expression::NumberLiteral { value, .. } => write!(f, "{}", value),
+        Expression::ListLiteral { elements, .. } => {
+            write!(f, "[")?;
+            write_expressions(f, elements, depth)?;
+            write!(f, "]")
+        }
+        Expression::OptionLiteral { value, .. } => match value {
+            Some(value) => {
+                write!(f, "Some(")?;
+                write_expression(f, value, depth)?;
+                write!(f, ")")
+            }
+            None => write!(f, "None"),
+        },
+        Expression::Placeholder { .. } => write!(f, "_"),
+        Expression::UnitLiteral { .. } => write!(f, "()"),
+        Expression::Select(select) => write_select(f, select, depth),
+        Expression::IfElse {
+            condition,
+            then_expr,
+            else_expr,
+            ..
+        } => {
+            write!(f, "if ")?;
+            write_expression(f, condition, depth)?;
+            write!(f, " {{ ")?;
+            write_expression(f, then_expr, depth)?;
+            write!(f, " }} else {{ ")?;
+            write_expression(f, else_expr, depth)?;
+            write!(f, " }}")
+        }
+        Expression::Match {
+            scrutinee, arms, ..
+        } => {
+            write!(f, "match ")?;
+            write_expression(f, scrutinee, depth)?;
+            writeln!(f, " {{")?;
+            for arm in arms {
+                write_indent(f, depth + 1)?;
+                write!(f, "{} => ", arm.pattern)?;
+                write_expression(f, &arm.expression, depth + 1)?;
+                writeln!(f, ",")?;
+            }
+            write_indent(f, depth)?;
+            write!(f, "}}")
+        }
+        Expression::Return { value, .. } => {
+            write!(f, "return ")?;
+            write_expression(f, value, depth)
+        }
+        Expression::FieldAccess { object, field, .. } => {
+            write_expression(f, object, depth)?;
+            write!(f, ".{}", field)
         }
     }
 }
 
-impl fmt::Display for SelectExpression {
+impl fmt::Display for Function {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        writeln!(f, "select {{")?;
-        for clause in &self.clauses {
-            writeln!(
-                f,
-                "    {} as {} => {},",
-                clause.expression_to_run, clause.result_variable, clause.expression_next
-            )?;
+        if self.pure {
+            writeln!(f, "# pure")?;
         }
+        if let Some(doc) = &self.documentation {
+            for line in doc.lines() {
+                writeln!(f, "## {}", line)?;
+            }
+        }
+        write!(f, "fn {}", self.name)?;
+        write_parameters(f, &self.parameters)?;
+        writeln!(f, ": {} {{", self.return_type)?;
+        write_block(f, &self.body.statements, 1)?;
         write!(f, "}}")
     }
 }
 
+impl fmt::Display for Statement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_statement(f, self, 0)
+    }
+}
+
+impl fmt::Display for SelectExpression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_select(f, self, 0)
+    }
+}
+
 impl fmt::Display for Module {
+    // Functions are set apart by a blank line, while runs of extern and
+    // const declarations stay together.
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         for (i, definition) in self.definitions.iter().enumerate() {
             if i > 0 {
                 writeln!(f)?;
+                let both_declarations = !matches!(definition, Definition::Function(_))
+                    && !matches!(self.definitions[i - 1], Definition::Function(_));
+                if !both_declarations {
+                    writeln!(f)?;
+                }
             }
             write!(f, "{}", definition)?;
         }
@@ -306,67 +674,70 @@ impl fmt::Display for Definition {
         match self {
             Definition::Function(func) => write!(f, "{}", func),
             Definition::ExternalFunction(ext_func) => write!(f, "{}", ext_func),
+            Definition::ExternalConst(ext_const) => write!(f, "{}", ext_const),
+            Definition::Constant(constant) => write!(f, "{}", constant),
         }
     }
 }
 
 impl fmt::Display for ExternalFunction {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "extern fn {}(", self.name)?;
-        for (i, param) in self.parameters.iter().enumerate() {
-            if i > 0 {
-                write!(f, ", ")?;
+        write!(f, "extern fn {}", self.name)?;
+        write_parameters(f, &self.parameters)?;
+        write!(f, ": {}", self.return_type)
+    }
+}
+
+impl fmt::Display for ExternalConst {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "extern const {}: {}", self.name, self.const_type)
+    }
+}
+
+impl fmt::Display for Constant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "const {} = {}", self.name, self.value)
+    }
+}
+
+fn escape_string(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '"' => escaped.push_str("\\\""),
+            '\n' => escaped.push_str("\\n"),
+            '\t' => escaped.push_str("\\t"),
+            '\r' => escaped.push_str("\\r"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+// Line breaks stay literal. The parser skips whitespace straight after the
+// opening `'''`, so leading whitespace is escaped to survive a re-parse.
+fn escape_multiline_string(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for (i, c) in value.chars().enumerate() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '\'' => escaped.push_str("\\'"),
+            '\n' if i == 0 => escaped.push_str("\\n"),
+            '\t' if i == 0 => escaped.push_str("\\t"),
+            '\r' if i == 0 => escaped.push_str("\\r"),
+            c if i == 0 && c.is_whitespace() => {
+                escaped.push('\\');
+                escaped.push(c);
             }
-            write!(f, "{}: {}", param.name, param.param_type)?;
+            c => escaped.push(c),
         }
-        write!(f, "): {}", self.return_type)
     }
+    escaped
 }
 
 impl fmt::Display for Expression {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            Expression::Call {
-                function,
-                arguments,
-                ..
-            } => {
-                write!(f, "{}", function)?;
-                write!(f, "(")?;
-                for (i, arg) in arguments.iter().enumerate() {
-                    if i > 0 {
-                        write!(f, ", ")?;
-                    }
-                    write!(f, "{}", arg)?;
-                }
-                write!(f, ")")
-            }
-            Expression::Variable { name, .. } => write!(f, "{}", name),
-            Expression::StringLiteral { value, .. } => write!(f, "\"{}\"", value),
-            Expression::BooleanLiteral { value, .. } => write!(f, "{}", value),
-            Expression::ListLiteral { elements, .. } => {
-                write!(f, "[")?;
-                for (i, elem) in elements.iter().enumerate() {
-                    if i > 0 {
-                        write!(f, ", ")?;
-                    }
-                    write!(f, "{}", elem)?;
-                }
-                write!(f, "]")
-            }
-            Expression::Placeholder { .. } => write!(f, "_"),
-            Expression::UnitLiteral { .. } => write!(f, "()"),
-            Expression::Select(select) => write!(f, "{}", select),
-            Expression::IfElse {
-                condition,
-                then_expr,
-                else_expr,
-                ..
-            } => write!(
-                f,
-                "if {} {{ {} }} else {{ {} }}",
-                condition, then_expr, else_expr
-            ),
-        }
+        write_expression(f, self, 0)
     }
 }
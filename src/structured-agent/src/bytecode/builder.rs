@@ -13,6 +13,7 @@ enum PendingJumpKind {
     BrFalse(String),
     BrTrue(String),
     SwitchCase(String, usize),
+    TryBegin(String),
 }
 
 impl InstructionBuilder {
@@ -76,6 +77,17 @@ impl InstructionBuilder {
         self.instructions.push(Instruction::Switch { var, offsets });
     }
 
+    pub fn emit_try_begin(&mut self, error: String, label: &str) {
+        let position = self.instructions.len();
+        self.pending_labels.push((
+            position,
+            label.to_string(),
+            PendingJumpKind::TryBegin(error.clone()),
+        ));
+        self.instructions
+            .push(Instruction::TryBegin { error, offset: 0 });
+    }
+
     pub fn next_temp(&mut self) -> String {
         let temp = format!("$tmp{}", self.temp_counter);
         self.temp_counter += 1;
@@ -113,6 +125,12 @@ impl InstructionBuilder {
                         offset: target_position,
                     };
                 }
+                PendingJumpKind::TryBegin(error) => {
+                    self.instructions[position] = Instruction::TryBegin {
+                        error,
+                        offset: target_position,
+                    };
+                }
                 PendingJumpKind::SwitchCase(var, index) => {
                     if let Instruction::Switch {
                         var: switch_var,
@@ -1,6 +1,6 @@
 use super::{BytecodeFunctionExpr, Instruction, builder::InstructionBuilder};
 use crate::ast::{self, Expression, Statement};
-use crate::types::{ExecutableFunction, Parameter};
+use crate::types::{ExecutableFunction, Parameter, Span, Spanned};
 use std::fmt;
 
 #[derive(Clone)]
@@ -19,14 +19,26 @@ impl BytecodeCompiler {
     pub fn compile_to_bytecode(ast_func: &ast::Function) -> Result<CompiledFunction, String> {
         let mut builder = InstructionBuilder::new();
 
+        let statements = &ast_func.body.statements;
+        let implicit_return = ast_func.implicit_return();
+        let body = match implicit_return {
+            Some(_) => &statements[..statements.len() - 1],
+            None => &statements[..],
+        };
+
         let mut has_explicit_return = false;
-        for stmt in &ast_func.body.statements {
-            if matches!(stmt, Statement::Return(_)) {
+        for stmt in body {
+            if matches!(stmt, Statement::Return { expression: _, .. }) {
                 has_explicit_return = true;
             }
             Self::compile_statement(&mut builder, stmt)?;
         }
 
+        if let Some(expr) = implicit_return {
+            Self::compile_return_statement(&mut builder, expr)?;
+            has_explicit_return = true;
+        }
+
         if !has_explicit_return {
             let return_temp = builder.next_temp();
             builder.emit(Instruction::Decl {
@@ -64,7 +76,9 @@ impl BytecodeCompiler {
 
     fn compile_statement(builder: &mut InstructionBuilder, stmt: &Statement) -> Result<(), String> {
         match stmt {
-            Statement::Injection(expr) => Self::compile_injection(builder, expr),
+            Statement::Injection {
+                expression: expr, ..
+            } => Self::compile_injection(builder, expr),
             Statement::Assignment {
                 variable,
                 expression,
@@ -75,9 +89,9 @@ impl BytecodeCompiler {
                 expression,
                 ..
             } => Self::compile_variable_assignment(builder, variable, expression),
-            Statement::ExpressionStatement(expr) => {
-                Self::compile_expression_statement(builder, expr)
-            }
+            Statement::ExpressionStatement {
+                expression: expr, ..
+            } => Self::compile_expression_statement(builder, expr),
             Statement::If {
                 condition,
                 body,
@@ -87,7 +101,15 @@ impl BytecodeCompiler {
             Statement::While {
                 condition, body, ..
             } => Self::compile_while_statement(builder, condition, body),
-            Statement::Return(expr) => Self::compile_return_statement(builder, expr),
+            Statement::Assert {
+                condition,
+                message,
+                span,
+                ..
+            } => Self::compile_assert_statement(builder, condition, message, *span),
+            Statement::Return {
+                expression: expr, ..
+            } => Self::compile_return_statement(builder, expr),
         }
     }
 
@@ -102,6 +124,7 @@ impl BytecodeCompiler {
         Self::compile_expression(builder, expr, &dest_var)?;
         builder.emit(Instruction::CtxEvent {
             var: dest_var.clone(),
+            span: expr.span(),
         });
         builder.emit_drop(dest_var);
         Ok(())
@@ -235,6 +258,37 @@ impl BytecodeCompiler {
         Ok(())
     }
 
+    fn compile_assert_statement(
+        builder: &mut InstructionBuilder,
+        condition: &Expression,
+        message: &Expression,
+        span: Span,
+    ) -> Result<(), String> {
+        let assert_end = format!("assert_end_{}", builder.next_temp());
+
+        let cond_var = builder.next_temp();
+        builder.emit(Instruction::Decl {
+            name: cond_var.clone(),
+        });
+        Self::compile_expression(builder, condition, &cond_var)?;
+        builder.emit_brtrue(cond_var, &assert_end);
+
+        // The message is only evaluated once the assertion has failed.
+        let message_var = builder.next_temp();
+        builder.emit(Instruction::Decl {
+            name: message_var.clone(),
+        });
+        Self::compile_expression(builder, message, &message_var)?;
+        builder.emit(Instruction::AssertFailed {
+            message: message_var,
+            span,
+        });
+
+        builder.emit_label(&assert_end);
+        builder.emit(Instruction::Nop);
+        Ok(())
+    }
+
     fn compile_return_statement(
         builder: &mut InstructionBuilder,
         expr: &Expression,
@@ -268,10 +322,16 @@ impl BytecodeCompiler {
             Expression::BooleanLiteral { value, .. } => {
                 Self::compile_boolean_literal(builder, *value, dest_var)
             }
+            Expression::NumberLiteral { value, .. } => {
+                Self::compile_number_literal(builder, *value, dest_var)
+            }
             Expression::UnitLiteral { .. } => Self::compile_unit_literal(builder, dest_var),
             Expression::ListLiteral { elements, .. } => {
                 Self::compile_list_literal(builder, elements, dest_var)
             }
+            Expression::OptionLiteral { value, .. } => {
+                Self::compile_option_literal(builder, value.as_deref(), dest_var)
+            }
             Expression::Placeholder { .. } => Self::compile_placeholder(builder, dest_var),
             Expression::Select(select_expr) => {
                 Self::compile_select_expression(builder, select_expr, dest_var)
@@ -284,9 +344,37 @@ impl BytecodeCompiler {
             } => {
                 Self::compile_if_else_expression(builder, condition, then_expr, else_expr, dest_var)
             }
+            Expression::Match {
+                scrutinee, arms, ..
+            } => Self::compile_match_expression(builder, scrutinee, arms, dest_var),
+            Expression::Return { .. } => {
+                Err("'return' is a statement and cannot be used as an expression".to_string())
+            }
+            Expression::FieldAccess { object, field, .. } => {
+                Self::compile_field_access(builder, object, field, dest_var)
+            }
         }
     }
 
+    fn compile_field_access(
+        builder: &mut InstructionBuilder,
+        object: &Expression,
+        field: &str,
+        dest_var: &str,
+    ) -> Result<(), String> {
+        let object_var = builder.next_temp();
+        builder.emit(Instruction::Decl {
+            name: object_var.clone(),
+        });
+        Self::compile_expression(builder, object, &object_var)?;
+        builder.emit(Instruction::JsonField {
+            dest: dest_var.to_string(),
+            src: object_var,
+            field: field.to_string(),
+        });
+        Ok(())
+    }
+
     fn compile_call_expression(
         builder: &mut InstructionBuilder,
         function: &str,
@@ -295,12 +383,16 @@ impl BytecodeCompiler {
     ) -> Result<(), String> {
         let mut params = Vec::new();
 
-        for arg_expr in arguments {
+        for (index, arg_expr) in arguments.iter().enumerate() {
             let temp_var = builder.next_temp();
             builder.emit(Instruction::Decl {
                 name: temp_var.clone(),
             });
-            Self::compile_expression(builder, arg_expr, &temp_var)?;
+            if let Expression::Placeholder { .. } = arg_expr {
+                Self::compile_argument_placeholder(builder, function, index, &temp_var)?;
+            } else {
+                Self::compile_expression(builder, arg_expr, &temp_var)?;
+            }
             params.push(temp_var);
         }
 
@@ -348,6 +440,18 @@ impl BytecodeCompiler {
         Ok(())
     }
 
+    fn compile_number_literal(
+        builder: &mut InstructionBuilder,
+        value: f64,
+        dest_var: &str,
+    ) -> Result<(), String> {
+        builder.emit(Instruction::LdcNum {
+            dest: dest_var.to_string(),
+            value,
+        });
+        Ok(())
+    }
+
     fn compile_unit_literal(
         builder: &mut InstructionBuilder,
         dest_var: &str,
@@ -358,12 +462,44 @@ impl BytecodeCompiler {
         Ok(())
     }
 
+    fn compile_option_literal(
+        builder: &mut InstructionBuilder,
+        value: Option<&Expression>,
+        dest_var: &str,
+    ) -> Result<(), String> {
+        let Some(value) = value else {
+            builder.emit(Instruction::LdcNone {
+                dest: dest_var.to_string(),
+            });
+            return Ok(());
+        };
+
+        let value_var = builder.next_temp();
+        builder.emit(Instruction::Decl {
+            name: value_var.clone(),
+        });
+        Self::compile_expression(builder, value, &value_var)?;
+        builder.emit(Instruction::OptSome {
+            dest: dest_var.to_string(),
+            src: value_var,
+        });
+        Ok(())
+    }
+
     fn compile_list_literal(
         builder: &mut InstructionBuilder,
         elements: &[Expression],
         dest_var: &str,
     ) -> Result<(), String> {
-        let element_type = "Unknown".to_string();
+        // Types are not known here, so only a literal first element names the
+        // element type; otherwise the VM takes it from the first item.
+        let element_type = match elements.first() {
+            Some(Expression::StringLiteral { .. }) => "String",
+            Some(Expression::BooleanLiteral { .. }) => "Boolean",
+            Some(Expression::NumberLiteral { .. }) => "Number",
+            _ => "Unknown",
+        }
+        .to_string();
         let mut temp_vars = Vec::new();
 
         for elem in elements {
@@ -398,6 +534,24 @@ impl BytecodeCompiler {
             dest: dest_var.to_string(),
             param_name: "placeholder".to_string(),
             param_type: "Unknown".to_string(),
+            function_name: None,
+            param_index: 0,
+        });
+        Ok(())
+    }
+
+    fn compile_argument_placeholder(
+        builder: &mut InstructionBuilder,
+        function: &str,
+        param_index: usize,
+        dest_var: &str,
+    ) -> Result<(), String> {
+        builder.emit(Instruction::LlmPlaceholder {
+            dest: dest_var.to_string(),
+            param_name: "placeholder".to_string(),
+            param_type: "Unknown".to_string(),
+            function_name: Some(function.to_string()),
+            param_index,
         });
         Ok(())
     }
@@ -433,13 +587,54 @@ impl BytecodeCompiler {
             builder.emit(Instruction::Decl {
                 name: meta_var.clone(),
             });
+
+            // A clause whose guard is false keeps the Unit from its Decl,
+            // which the select instructions treat as not eligible.
+            let guard = match &select_expr.clauses[i].guard {
+                Some(guard) => {
+                    let guard_var = builder.next_temp();
+                    builder.emit(Instruction::Decl {
+                        name: guard_var.clone(),
+                    });
+                    Self::compile_expression(builder, guard, &guard_var)?;
+                    let skip_label = format!("clause_{}_ineligible_{}", i, builder.next_temp());
+                    builder.emit_brfalse(guard_var.clone(), &skip_label);
+                    Some((guard_var, skip_label))
+                }
+                None => None,
+            };
+
             builder.emit(Instruction::MetaFunction {
                 function_name,
                 dest: meta_var.clone(),
             });
+
+            if let Some((guard_var, skip_label)) = guard {
+                builder.emit_label(&skip_label);
+                builder.emit_drop(guard_var);
+            }
             metadata_vars.push(meta_var);
         }
 
+        if select_expr.ranked {
+            return Self::compile_ranked_select(
+                builder,
+                select_expr,
+                dest_var,
+                &clause_labels,
+                &metadata_vars,
+            );
+        }
+        if select_expr.retry {
+            return Self::compile_retry_select(
+                builder,
+                select_expr,
+                dest_var,
+                &clause_labels,
+                &metadata_vars,
+            );
+        }
+
         let choice_var = builder.next_temp();
         builder.emit(Instruction::Decl {
             name: choice_var.clone(),
@@ -463,34 +658,160 @@ impl BytecodeCompiler {
 
         for (i, clause) in select_expr.clauses.iter().enumerate() {
             builder.emit_label(&clause_labels[i]);
+            Self::compile_select_clause(builder, clause, dest_var)?;
+            builder.emit_br(&end_label);
+        }
 
-            builder.emit(Instruction::CtxChild {
-                is_scope_boundary: false,
-            });
+        builder.emit_label(&end_label);
+        builder.emit(Instruction::Nop);
+        Ok(())
+    }
 
-            let temp_result = builder.next_temp();
-            builder.emit(Instruction::Decl {
-                name: temp_result.clone(),
-            });
-            Self::compile_expression(builder, &clause.expression_to_run, &temp_result)?;
+    // Clauses run in the engine's order; an empty Option moves on to the next
+    // ranked clause, anything else ends the select.
+    fn compile_ranked_select(
+        builder: &mut InstructionBuilder,
+        select_expr: &ast::SelectExpression,
+        dest_var: &str,
+        clause_labels: &[String],
+        metadata_vars: &[String],
+    ) -> Result<(), String> {
+        let ranking_var = builder.next_temp();
+        builder.emit(Instruction::Decl {
+            name: ranking_var.clone(),
+        });
+        builder.emit(Instruction::LlmRank {
+            metadata_vars: metadata_vars.to_vec(),
+            dest: ranking_var.clone(),
+        });
 
-            builder.emit(Instruction::Decl {
-                name: clause.result_variable.clone(),
+        for meta_var in metadata_vars {
+            builder.emit(Instruction::Drop {
+                name: meta_var.clone(),
             });
-            builder.emit(Instruction::Mov {
-                dest: clause.result_variable.clone(),
-                src: temp_result,
+        }
+
+        let choice_var = builder.next_temp();
+        builder.emit(Instruction::Decl {
+            name: choice_var.clone(),
+        });
+        let is_none_var = builder.next_temp();
+        builder.emit(Instruction::Decl {
+            name: is_none_var.clone(),
+        });
+
+        let next_label = format!("select_next_{}", builder.next_temp());
+        let end_label = format!("select_end_{}", builder.next_temp());
+
+        builder.emit_label(&next_label);
+        builder.emit(Instruction::RankNext {
+            dest: choice_var.clone(),
+            ranking: ranking_var.clone(),
+        });
+        let mut case_labels = clause_labels.to_vec();
+        case_labels.push(end_label.clone());
+        builder.emit_switch(choice_var.clone(), case_labels);
+
+        for (i, clause) in select_expr.clauses.iter().enumerate() {
+            builder.emit_label(&clause_labels[i]);
+            Self::compile_select_clause(builder, clause, dest_var)?;
+            builder.emit(Instruction::IsNone {
+                dest: is_none_var.clone(),
+                src: dest_var.to_string(),
             });
+            builder.emit_brfalse(is_none_var.clone(), &end_label);
+            builder.emit_br(&next_label);
+        }
 
-            Self::compile_expression(builder, &clause.expression_next, dest_var)?;
+        builder.emit_label(&end_label);
+        builder.emit(Instruction::Drop { name: is_none_var });
+        builder.emit(Instruction::Drop { name: choice_var });
+        builder.emit(Instruction::Drop { name: ranking_var });
+        Ok(())
+    }
 
-            builder.emit(Instruction::CtxRestore);
+    // A clause that fails is rolled back and excluded, and the engine picks
+    // again from the clauses that are left.
+    fn compile_retry_select(
+        builder: &mut InstructionBuilder,
+        select_expr: &ast::SelectExpression,
+        dest_var: &str,
+        clause_labels: &[String],
+        metadata_vars: &[String],
+    ) -> Result<(), String> {
+        let choice_var = builder.next_temp();
+        builder.emit(Instruction::Decl {
+            name: choice_var.clone(),
+        });
+        let error_var = builder.next_temp();
+        builder.emit(Instruction::Decl {
+            name: error_var.clone(),
+        });
+
+        let choose_label = format!("select_choose_{}", builder.next_temp());
+        let end_label = format!("select_end_{}", builder.next_temp());
+
+        builder.emit_label(&choose_label);
+        builder.emit(Instruction::LlmSelect {
+            metadata_vars: metadata_vars.to_vec(),
+            dest: choice_var.clone(),
+        });
+        builder.emit_switch(choice_var.clone(), clause_labels.to_vec());
 
+        for (i, clause) in select_expr.clauses.iter().enumerate() {
+            let failed_label = format!("clause_{}_failed_{}", i, builder.next_temp());
+            builder.emit_label(&clause_labels[i]);
+            builder.emit_try_begin(error_var.clone(), &failed_label);
+            Self::compile_select_clause(builder, clause, dest_var)?;
+            builder.emit(Instruction::TryEnd);
             builder.emit_br(&end_label);
+
+            builder.emit_label(&failed_label);
+            builder.emit(Instruction::SelectExclude {
+                metadata_vars: metadata_vars.to_vec(),
+                clause: i,
+                error: error_var.clone(),
+            });
+            builder.emit_br(&choose_label);
         }
 
         builder.emit_label(&end_label);
-        builder.emit(Instruction::Nop);
+        builder.emit(Instruction::Drop { name: error_var });
+        builder.emit(Instruction::Drop { name: choice_var });
+        for meta_var in metadata_vars {
+            builder.emit(Instruction::Drop {
+                name: meta_var.clone(),
+            });
+        }
+        Ok(())
+    }
+
+    fn compile_select_clause(
+        builder: &mut InstructionBuilder,
+        clause: &ast::SelectClause,
+        dest_var: &str,
+    ) -> Result<(), String> {
+        builder.emit(Instruction::CtxChild {
+            is_scope_boundary: false,
+        });
+
+        let temp_result = builder.next_temp();
+        builder.emit(Instruction::Decl {
+            name: temp_result.clone(),
+        });
+        Self::compile_expression(builder, &clause.expression_to_run, &temp_result)?;
+
+        builder.emit(Instruction::Decl {
+            name: clause.result_variable.clone(),
+        });
+        builder.emit(Instruction::Mov {
+            dest: clause.result_variable.clone(),
+            src: temp_result,
+        });
+
+        Self::compile_expression(builder, &clause.expression_next, dest_var)?;
+
+        builder.emit(Instruction::CtxRestore);
         Ok(())
     }
 
@@ -523,26 +844,29 @@ impl BytecodeCompiler {
         Ok(())
     }
 
+    // The type checker has already required one arm for each Boolean value,
+    // so a match compiles to the same branches as an if/else expression.
+    fn compile_match_expression(
+        builder: &mut InstructionBuilder,
+        scrutinee: &Expression,
+        arms: &[ast::MatchArm],
+        dest_var: &str,
+    ) -> Result<(), String> {
+        let arm = |pattern: bool| {
+            arms.iter()
+                .find(|arm| arm.pattern == pattern)
+                .map(|arm| &arm.expression)
+                .ok_or_else(|| format!("match is missing a `{}` arm", pattern))
+        };
+        Self::compile_if_else_expression(builder, scrutinee, arm(true)?, arm(false)?, dest_var)
+    }
+
     fn convert_type(ast_type: &ast::Type) -> crate::types::Type {
-        match ast_type {
-            ast::Type::Unit => crate::types::Type::Unit,
-            ast::Type::Boolean => crate::types::Type::Boolean,
-            ast::Type::String => crate::types::Type::String,
-            ast::Type::List(inner) => crate::types::Type::List(Box::new(Self::convert_type(inner))),
-            ast::Type::Option(inner) => {
-                crate::types::Type::Option(Box::new(Self::convert_type(inner)))
-            }
-        }
+        crate::types::Type::from(ast_type)
     }
 
     fn type_to_string(ast_type: &ast::Type) -> String {
-        match ast_type {
-            ast::Type::Unit => "Unit".to_string(),
-            ast::Type::Boolean => "Boolean".to_string(),
-            ast::Type::String => "String".to_string(),
-            ast::Type::List(inner) => format!("List<{}>", Self::type_to_string(inner)),
-            ast::Type::Option(inner) => format!("Option<{}>", Self::type_to_string(inner)),
-        }
+        Self::convert_type(ast_type).name()
     }
 }
 
@@ -1,3 +1,4 @@
+use crate::types::Span;
 use std::fmt;
 
 #[derive(Debug, Clone, PartialEq)]
@@ -12,8 +13,12 @@ pub enum Instruction {
     LdcStr { dest: String, value: String },
     /// Load boolean constant into variable
     LdcBool { dest: String, value: bool },
+    /// Load number constant into variable
+    LdcNum { dest: String, value: f64 },
     /// Load unit value into variable
     LdcUnit { dest: String },
+    /// Load an empty Option into variable
+    LdcNone { dest: String },
 
     /// Copy variable value (full ExpressionResult)
     Mov { dest: String, src: String },
@@ -32,6 +37,13 @@ pub enum Instruction {
     Ret { var: String },
     /// Pause execution for durable execution checkpoint
     Yield,
+    /// Stop execution with the message variable's value as the error
+    AssertFailed { message: String, span: Span },
+    /// Until the matching TryEnd, a failing instruction restores the context
+    /// as it was here, stores the error message in `error` and jumps to offset
+    TryBegin { error: String, offset: i32 },
+    /// Leave the innermost TryBegin
+    TryEnd,
 
     /// Call function with parameters and store result in destination
     Call {
@@ -40,8 +52,10 @@ pub enum Instruction {
         dest: String,
     },
 
-    /// Inject variable's value into context events (adds Event to context)
-    CtxEvent { var: String },
+    /// Inject variable's value into context events (adds Event to context).
+    /// The span is the injected expression, reported when the value cannot
+    /// be rendered into a prompt.
+    CtxEvent { var: String, span: Span },
     /// Create child context (true=function boundary, false=nested statement like loop/if/select)
     CtxChild { is_scope_boundary: bool },
     /// Return to parent context
@@ -57,17 +71,46 @@ pub enum Instruction {
     /// Finalize list builder into ListArray
     ListFinish { dest: String },
 
-    /// Await LLM to fill placeholder, store in dest
+    /// Read a field of a Json object into dest (Json null when missing)
+    JsonField {
+        dest: String,
+        src: String,
+        field: String,
+    },
+    /// Pop the next clause index off a ranking into dest
+    RankNext { dest: String, ranking: String },
+    /// Store whether src holds an empty Option in dest
+    IsNone { dest: String, src: String },
+    /// Store src's value wrapped in a present Option in dest
+    OptSome { dest: String, src: String },
+
+    /// Await LLM to fill placeholder, store in dest.
+    /// When the placeholder is a call argument, `function_name` and `param_index` identify it.
     LlmPlaceholder {
         dest: String,
         param_name: String,
         param_type: String,
+        function_name: Option<String>,
+        param_index: usize,
     },
-    /// Await LLM clause choice, store selected index in dest
+    /// Await LLM clause choice, store selected index in dest. Unit metadata
+    /// marks a clause whose guard was false, which is not offered.
     LlmSelect {
         metadata_vars: Vec<String>,
         dest: String,
     },
+    /// Await LLM ordering of every eligible clause, store the ranking in dest
+    LlmRank {
+        metadata_vars: Vec<String>,
+        dest: String,
+    },
+    /// Stop offering a clause that failed, failing with the error variable's
+    /// message once no clause is left
+    SelectExclude {
+        metadata_vars: Vec<String>,
+        clause: usize,
+        error: String,
+    },
     /// Await LLM generation with context, store result in dest
     LlmGenerate { dest: String, return_type: String },
 }
@@ -85,9 +128,15 @@ impl fmt::Display for Instruction {
             Instruction::LdcBool { dest, value } => {
                 write!(f, "ldc.bool {}, {}", dest, value)
             }
+            Instruction::LdcNum { dest, value } => {
+                write!(f, "ldc.num {}, {}", dest, value)
+            }
             Instruction::LdcUnit { dest } => {
                 write!(f, "ldc.unit {}", dest)
             }
+            Instruction::LdcNone { dest } => {
+                write!(f, "ldc.none {}", dest)
+            }
 
             Instruction::Mov { dest, src } => {
                 write!(f, "mov {}, {}", dest, src)
@@ -121,6 +170,15 @@ impl fmt::Display for Instruction {
             Instruction::Yield => {
                 write!(f, "yield")
             }
+            Instruction::AssertFailed { message, span } => {
+                write!(f, "assert.failed {}, {}..{}", message, span.start, span.end)
+            }
+            Instruction::TryBegin { error, offset } => {
+                write!(f, "try.begin {}, {}", error, offset)
+            }
+            Instruction::TryEnd => {
+                write!(f, "try.end")
+            }
 
             Instruction::Call {
                 function_name,
@@ -137,7 +195,7 @@ impl fmt::Display for Instruction {
                 write!(f, "], {}", dest)
             }
 
-            Instruction::CtxEvent { var } => {
+            Instruction::CtxEvent { var, .. } => {
                 write!(f, "ctx.event {}", var)
             }
             Instruction::CtxChild { is_scope_boundary } => {
@@ -163,17 +221,35 @@ impl fmt::Display for Instruction {
             Instruction::ListFinish { dest } => {
                 write!(f, "list.finish {}", dest)
             }
+            Instruction::JsonField { dest, src, field } => {
+                write!(f, "json.field {}, {}, {}", dest, src, field)
+            }
+            Instruction::RankNext { dest, ranking } => {
+                write!(f, "rank.next {}, {}", dest, ranking)
+            }
+            Instruction::IsNone { dest, src } => {
+                write!(f, "is.none {}, {}", dest, src)
+            }
+            Instruction::OptSome { dest, src } => {
+                write!(f, "opt.some {}, {}", dest, src)
+            }
 
             Instruction::LlmPlaceholder {
                 dest,
                 param_name,
                 param_type,
+                function_name,
+                param_index,
             } => {
                 write!(
                     f,
                     "llm.placeholder {}, {}, {}",
                     dest, param_name, param_type
-                )
+                )?;
+                if let Some(function_name) = function_name {
+                    write!(f, ", {}[{}]", function_name, param_index)?;
+                }
+                Ok(())
             }
             Instruction::LlmSelect {
                 metadata_vars,
@@ -188,6 +264,33 @@ impl fmt::Display for Instruction {
                 }
                 write!(f, "], {}", dest)
             }
+            Instruction::LlmRank {
+                metadata_vars,
+                dest,
+            } => {
+                write!(f, "llm.rank [")?;
+                for (i, var) in metadata_vars.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", var)?;
+                }
+                write!(f, "], {}", dest)
+            }
+            Instruction::SelectExclude {
+                metadata_vars,
+                clause,
+                error,
+            } => {
+                write!(f, "select.exclude [")?;
+                for (i, var) in metadata_vars.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", var)?;
+                }
+                write!(f, "], {}, {}", clause, error)
+            }
             Instruction::LlmGenerate { dest, return_type } => {
                 write!(f, "llm.generate {}, {}", dest, return_type)
             }
@@ -333,7 +333,7 @@ fn main(): String {
       2: ldc.str $tmp1, "a"
       3: decl $tmp2
       4: ldc.str $tmp2, "b"
-      5: list.new $tmp0, Unknown
+      5: list.new $tmp0, String
       6: list.add $tmp0, $tmp1
       7: list.add $tmp0, $tmp2
       8: list.finish $tmp0
@@ -586,7 +586,7 @@ fn greet(name: String): () {
 ): String {
       0: decl $tmp0
       1: decl $tmp1
-      2: llm.placeholder $tmp1, placeholder, Unknown
+      2: llm.placeholder $tmp1, placeholder, Unknown, foo[0]
       3: call foo, [$tmp1], $tmp0
       4: ret $tmp0
 }
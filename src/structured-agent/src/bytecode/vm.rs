@@ -1,11 +1,31 @@
 use super::{CompiledFunction, Instruction};
-use crate::runtime::{Context, ExpressionParameter, ExpressionResult, ExpressionValue, Runtime};
+use crate::runtime::{
+    Context, EventRole, ExpressionParameter, ExpressionResult, ExpressionValue, Runtime,
+};
+use crate::types::{PlaceholderTarget, Span};
+use arrow::array::{BooleanBuilder, Float64Builder, ListBuilder, StringBuilder};
+use std::collections::HashMap;
 use std::sync::Arc;
-use tracing::info;
+use tracing::{Instrument, debug_span, info};
 
 pub struct VMState {
     pc: usize,
     context: Context,
+    // Lists between their ListNew and ListFinish, by destination.
+    lists: HashMap<String, ListInProgress>,
+}
+
+enum Step {
+    Continue(VMState),
+    Return(Context, ExpressionResult),
+}
+
+// Where to resume when an instruction fails after a TryBegin, with the
+// context as it was when the TryBegin ran.
+struct TryHandler {
+    offset: usize,
+    error: String,
+    context: Context,
 }
 
 pub struct VM {
@@ -22,7 +42,12 @@ impl VM {
         function: &CompiledFunction,
         context: Context,
     ) -> Result<(Context, ExpressionResult), String> {
-        let mut state = VMState { pc: 0, context };
+        let mut state = VMState {
+            pc: 0,
+            context,
+            lists: HashMap::new(),
+        };
+        let mut handlers = Vec::new();
 
         loop {
             if state.pc >= function.instructions.len() {
@@ -31,67 +56,143 @@ impl VM {
 
             let instruction = &function.instructions[state.pc];
 
-            state = match instruction {
-                Instruction::Nop => Self::advance_pc(state),
-                Instruction::Drop { name } => self.execute_drop(state, name),
-                Instruction::LdcStr { dest, value } => self.execute_ldc_str(state, dest, value),
-                Instruction::LdcBool { dest, value } => self.execute_ldc_bool(state, dest, *value),
-                Instruction::LdcUnit { dest } => self.execute_ldc_unit(state, dest),
-                Instruction::Mov { dest, src } => self.execute_mov(state, dest, src)?,
-                Instruction::Decl { name } => self.execute_decl(state, name),
-                Instruction::Br { offset } => Self::branch(state, *offset as usize),
-                Instruction::BrFalse { var, offset } => {
-                    Self::branch_if_bool(state, var, *offset, false)?
-                }
-                Instruction::BrTrue { var, offset } => {
-                    Self::branch_if_bool(state, var, *offset, true)?
-                }
-                Instruction::Switch { var, offsets } => self.execute_switch(state, var, offsets)?,
-                Instruction::Ret { var } => {
-                    let (state, result) = self.execute_ret(state, var)?;
-                    return Ok((state.context, result));
+            match self.step(instruction, state, &mut handlers).await {
+                Ok(Step::Continue(next)) => state = next,
+                Ok(Step::Return(context, result)) => return Ok((context, result)),
+                Err(error) => {
+                    let Some(handler) = handlers.pop() else {
+                        return Err(error);
+                    };
+                    state = VMState {
+                        pc: handler.offset,
+                        context: handler.context,
+                        lists: HashMap::new(),
+                    };
+                    Self::write_variable(
+                        &mut state,
+                        &handler.error,
+                        ExpressionResult::new(ExpressionValue::String(error)),
+                    );
                 }
-                Instruction::Yield => return Err("Yield not yet implemented".to_string()),
-                Instruction::Call {
-                    function_name,
-                    params,
-                    dest,
-                } => {
-                    self.execute_call(state, function_name, params, dest)
-                        .await?
-                }
-                Instruction::CtxEvent { var } => self.execute_ctx_event(state, var)?,
-                Instruction::CtxChild { is_scope_boundary } => {
-                    self.execute_ctx_child(state, *is_scope_boundary)
-                }
-                Instruction::CtxRestore => self.execute_ctx_restore(state)?,
-                Instruction::MetaFunction {
-                    function_name,
-                    dest,
-                } => self.execute_meta_function(state, function_name, dest)?,
-                Instruction::ListNew {
-                    dest,
-                    element_type: _,
-                } => self.execute_list_new(state, dest),
-                Instruction::ListAdd { dest: _, src: _ } => Self::advance_pc(state),
-                Instruction::ListFinish { dest: _ } => Self::advance_pc(state),
-                Instruction::LlmPlaceholder {
+            }
+        }
+    }
+
+    async fn step(
+        &self,
+        instruction: &Instruction,
+        state: VMState,
+        handlers: &mut Vec<TryHandler>,
+    ) -> Result<Step, String> {
+        Ok(Step::Continue(match instruction {
+            Instruction::Nop => Self::advance_pc(state),
+            Instruction::Drop { name } => self.execute_drop(state, name),
+            Instruction::LdcStr { dest, value } => self.execute_ldc_str(state, dest, value),
+            Instruction::LdcBool { dest, value } => self.execute_ldc_bool(state, dest, *value),
+            Instruction::LdcNum { dest, value } => self.execute_ldc_num(state, dest, *value),
+            Instruction::LdcUnit { dest } => self.execute_ldc_unit(state, dest),
+            Instruction::LdcNone { dest } => self.execute_ldc_none(state, dest),
+            Instruction::Mov { dest, src } => self.execute_mov(state, dest, src)?,
+            Instruction::Decl { name } => self.execute_decl(state, name),
+            Instruction::Br { offset } => Self::branch(state, *offset as usize),
+            Instruction::BrFalse { var, offset } => {
+                Self::branch_if_bool(state, var, *offset, false)?
+            }
+            Instruction::BrTrue { var, offset } => Self::branch_if_bool(state, var, *offset, true)?,
+            Instruction::Switch { var, offsets } => self.execute_switch(state, var, offsets)?,
+            Instruction::Ret { var } => {
+                let (state, result) = self.execute_ret(state, var)?;
+                return Ok(Step::Return(state.context, result));
+            }
+            Instruction::Yield => return Err("Yield not yet implemented".to_string()),
+            Instruction::AssertFailed { message, span } => {
+                self.execute_assert_failed(state, message, *span)?
+            }
+            Instruction::TryBegin { error, offset } => {
+                Self::execute_try_begin(state, handlers, error, *offset)
+            }
+            Instruction::TryEnd => {
+                handlers.pop();
+                Self::advance_pc(state)
+            }
+            Instruction::Call {
+                function_name,
+                params,
+                dest,
+            } => {
+                self.execute_call(state, function_name, params, dest)
+                    .await?
+            }
+            Instruction::CtxEvent { var, span } => self.execute_ctx_event(state, var, *span)?,
+            Instruction::CtxChild { is_scope_boundary } => {
+                self.execute_ctx_child(state, *is_scope_boundary)
+            }
+            Instruction::CtxRestore => self.execute_ctx_restore(state)?,
+            Instruction::MetaFunction {
+                function_name,
+                dest,
+            } => self.execute_meta_function(state, function_name, dest)?,
+            Instruction::ListNew { dest, element_type } => {
+                self.execute_list_new(state, dest, element_type)
+            }
+            Instruction::ListAdd { dest, src } => self.execute_list_add(state, dest, src)?,
+            Instruction::ListFinish { dest } => self.execute_list_finish(state, dest)?,
+            Instruction::JsonField { dest, src, field } => {
+                self.execute_json_field(state, dest, src, field)?
+            }
+            Instruction::RankNext { dest, ranking } => {
+                self.execute_rank_next(state, dest, ranking)?
+            }
+            Instruction::IsNone { dest, src } => self.execute_is_none(state, dest, src)?,
+            Instruction::OptSome { dest, src } => self.execute_opt_some(state, dest, src)?,
+            Instruction::LlmPlaceholder {
+                dest,
+                param_name,
+                param_type,
+                function_name,
+                param_index,
+            } => {
+                self.execute_llm_placeholder(
+                    state,
                     dest,
                     param_name,
                     param_type,
-                } => {
-                    self.execute_llm_placeholder(state, dest, param_name, param_type)
-                        .await?
-                }
-                Instruction::LlmSelect {
-                    metadata_vars,
-                    dest,
-                } => self.execute_llm_select(state, metadata_vars, dest).await?,
-                Instruction::LlmGenerate { dest, return_type } => {
-                    self.execute_llm_generate(state, dest, return_type).await?
-                }
-            };
-        }
+                    function_name.as_deref(),
+                    *param_index,
+                )
+                .await?
+            }
+            Instruction::LlmSelect {
+                metadata_vars,
+                dest,
+            } => self.execute_llm_select(state, metadata_vars, dest).await?,
+            Instruction::LlmRank {
+                metadata_vars,
+                dest,
+            } => self.execute_llm_rank(state, metadata_vars, dest).await?,
+            Instruction::SelectExclude {
+                metadata_vars,
+                clause,
+                error,
+            } => self.execute_select_exclude(state, metadata_vars, *clause, error)?,
+            Instruction::LlmGenerate { dest, return_type } => {
+                self.execute_llm_generate(state, dest, return_type).await?
+            }
+        }))
+    }
+
+    fn execute_try_begin(
+        state: VMState,
+        handlers: &mut Vec<TryHandler>,
+        error: &str,
+        offset: i32,
+    ) -> VMState {
+        handlers.push(TryHandler {
+            offset: offset as usize,
+            error: error.to_string(),
+            context: state.context.clone(),
+        });
+        Self::advance_pc(state)
     }
 
     fn execute_ldc_str(&self, mut state: VMState, dest: &str, value: &str) -> VMState {
@@ -112,6 +213,15 @@ impl VM {
         Self::advance_pc(state)
     }
 
+    fn execute_ldc_num(&self, mut state: VMState, dest: &str, value: f64) -> VMState {
+        Self::write_variable(
+            &mut state,
+            dest,
+            ExpressionResult::new(ExpressionValue::Number(value)),
+        );
+        Self::advance_pc(state)
+    }
+
     fn execute_ldc_unit(&self, mut state: VMState, dest: &str) -> VMState {
         Self::write_variable(
             &mut state,
@@ -121,12 +231,111 @@ impl VM {
         Self::advance_pc(state)
     }
 
+    fn execute_ldc_none(&self, mut state: VMState, dest: &str) -> VMState {
+        Self::write_variable(
+            &mut state,
+            dest,
+            ExpressionResult::new(ExpressionValue::Option(None)),
+        );
+        Self::advance_pc(state)
+    }
+
     fn execute_mov(&self, mut state: VMState, dest: &str, src: &str) -> Result<VMState, String> {
         let value = Self::read_variable(&state, src)?;
         state.context.assign_variable(dest.to_string(), value)?;
         Ok(Self::advance_pc(state))
     }
 
+    fn execute_json_field(
+        &self,
+        mut state: VMState,
+        dest: &str,
+        src: &str,
+        field: &str,
+    ) -> Result<VMState, String> {
+        let object = Self::read_variable(&state, src)?;
+        let value = match &object.value {
+            ExpressionValue::Json(value) => value.clone(),
+            ExpressionValue::String(s) => serde_json::from_str(s)
+                .map_err(|e| format!("Cannot read field '{}': value is not Json: {}", field, e))?,
+            other => {
+                return Err(format!(
+                    "Cannot read field '{}' of non-Json value {:?}",
+                    field, other
+                ));
+            }
+        };
+
+        let found = value.get(field).cloned().unwrap_or(serde_json::Value::Null);
+        Self::write_variable(
+            &mut state,
+            dest,
+            ExpressionResult::new(ExpressionValue::Json(found)),
+        );
+        Ok(Self::advance_pc(state))
+    }
+
+    fn execute_rank_next(
+        &self,
+        mut state: VMState,
+        dest: &str,
+        ranking: &str,
+    ) -> Result<VMState, String> {
+        let value = Self::read_variable(&state, ranking)?;
+        let remaining = match &value.value {
+            ExpressionValue::Json(serde_json::Value::Array(items)) => items.clone(),
+            other => return Err(format!("Expected ranking in {}, got {:?}", ranking, other)),
+        };
+        let (next, rest) = remaining
+            .split_first()
+            .ok_or_else(|| format!("Ranking in {} is exhausted", ranking))?;
+
+        Self::write_variable(
+            &mut state,
+            ranking,
+            ExpressionResult::new(ExpressionValue::Json(serde_json::Value::Array(
+                rest.to_vec(),
+            ))),
+        );
+        Self::write_variable(
+            &mut state,
+            dest,
+            ExpressionResult::new(ExpressionValue::String(next.to_string())),
+        );
+        Ok(Self::advance_pc(state))
+    }
+
+    fn execute_is_none(
+        &self,
+        mut state: VMState,
+        dest: &str,
+        src: &str,
+    ) -> Result<VMState, String> {
+        let value = Self::read_variable(&state, src)?;
+        let is_none = matches!(value.value, ExpressionValue::Option(None));
+        Self::write_variable(
+            &mut state,
+            dest,
+            ExpressionResult::new(ExpressionValue::Boolean(is_none)),
+        );
+        Ok(Self::advance_pc(state))
+    }
+
+    fn execute_opt_some(
+        &self,
+        mut state: VMState,
+        dest: &str,
+        src: &str,
+    ) -> Result<VMState, String> {
+        let value = Self::read_variable(&state, src)?;
+        Self::write_variable(
+            &mut state,
+            dest,
+            ExpressionResult::new(ExpressionValue::Option(Some(Box::new(value.value)))),
+        );
+        Ok(Self::advance_pc(state))
+    }
+
     fn execute_decl(&self, mut state: VMState, name: &str) -> VMState {
         Self::write_variable(
             &mut state,
@@ -168,6 +377,22 @@ impl VM {
         }
     }
 
+    fn execute_assert_failed(
+        &self,
+        state: VMState,
+        message: &str,
+        span: Span,
+    ) -> Result<VMState, String> {
+        let message = match Self::read_variable(&state, message)?.value {
+            ExpressionValue::String(message) => message,
+            other => return Err(format!("Assert message must be a String, got {:?}", other)),
+        };
+        Err(format!(
+            "Assertion failed at {}..{}: {}",
+            span.start, span.end, message
+        ))
+    }
+
     fn execute_ret(
         &self,
         mut state: VMState,
@@ -206,7 +431,24 @@ impl VM {
             })
             .collect();
 
-        let mut child_context = state.context.create_child(true);
+        let max_depth = self.runtime.max_call_depth();
+        if state.context.call_depth() >= max_depth {
+            let mut chain = state.context.call_chain().to_vec();
+            chain.push(function_name.to_string());
+            return Err(format!(
+                "maximum call depth exceeded: calling '{}' would nest more than {} calls (call chain: {})",
+                function_name,
+                max_depth,
+                chain.join(" -> ")
+            ));
+        }
+
+        let mut child_context = state
+            .context
+            .child()
+            .function_scope()
+            .calling(function_name)
+            .build();
 
         child_context.add_event(
             ExpressionValue::String(format!("## {}", function_name)),
@@ -214,7 +456,10 @@ impl VM {
             None,
         );
 
-        let (returned_child_context, result) = func.execute(child_context, args).await?;
+        let (returned_child_context, result) = func
+            .execute(child_context, args)
+            .instrument(debug_span!("call", function = function_name))
+            .await?;
 
         state.context = returned_child_context.restore_parent()?;
 
@@ -222,11 +467,13 @@ impl VM {
             name: Some(function_name.to_string()),
             params: Some(evaluated_parameters),
             value: result.value.clone(),
+            role: result.role,
         };
 
         let result_display = match &result.value {
             ExpressionValue::String(s) => s.clone(),
             ExpressionValue::Boolean(b) => b.to_string(),
+            ExpressionValue::Number(n) => n.to_string(),
             ExpressionValue::Unit => "()".to_string(),
             _ => format!("{:?}", result.value),
         };
@@ -240,22 +487,40 @@ impl VM {
         Ok(Self::advance_pc(state))
     }
 
-    fn execute_ctx_event(&self, mut state: VMState, var: &str) -> Result<VMState, String> {
+    fn execute_ctx_event(
+        &self,
+        mut state: VMState,
+        var: &str,
+        span: Span,
+    ) -> Result<VMState, String> {
         let expr_result = Self::read_variable(&state, var)?;
+        if !expr_result.value.is_renderable() {
+            return Err(format!(
+                "Cannot inject {} value at {}..{}: it cannot be rendered into the prompt",
+                expr_result.value.type_name(),
+                span.start,
+                span.end
+            ));
+        }
 
-        state.context.add_event(
+        state.context.add_event_with_role(
             expr_result.value.clone(),
             expr_result.name.clone(),
             expr_result.params.clone(),
+            expr_result.role,
         );
         Ok(Self::advance_pc(state))
     }
 
     fn execute_ctx_child(&self, state: VMState, is_scope_boundary: bool) -> VMState {
-        let child_context = state.context.create_child(is_scope_boundary);
+        let child_context = state
+            .context
+            .child()
+            .scope_boundary(is_scope_boundary)
+            .build();
         let new_state = VMState {
-            pc: state.pc,
             context: child_context,
+            ..state
         };
         Self::advance_pc(new_state)
     }
@@ -263,8 +528,8 @@ impl VM {
     fn execute_ctx_restore(&self, state: VMState) -> Result<VMState, String> {
         let parent_context = state.context.restore_parent()?;
         let new_state = VMState {
-            pc: state.pc,
             context: parent_context,
+            ..state
         };
         Ok(Self::advance_pc(new_state))
     }
@@ -289,67 +554,229 @@ impl VM {
         Ok(Self::advance_pc(state))
     }
 
-    fn execute_list_new(&self, mut state: VMState, dest: &str) -> VMState {
-        Self::write_variable(
-            &mut state,
-            dest,
-            ExpressionResult::new(ExpressionValue::Unit),
-        );
+    fn execute_list_new(&self, mut state: VMState, dest: &str, element_type: &str) -> VMState {
+        state
+            .lists
+            .insert(dest.to_string(), ListInProgress::new(element_type));
         Self::advance_pc(state)
     }
 
+    fn execute_list_add(
+        &self,
+        mut state: VMState,
+        dest: &str,
+        src: &str,
+    ) -> Result<VMState, String> {
+        let element = Self::read_variable(&state, src)?;
+        state
+            .lists
+            .get_mut(dest)
+            .ok_or_else(|| format!("List not started: {}", dest))?
+            .push(element.value)?;
+        Ok(Self::advance_pc(state))
+    }
+
+    fn execute_list_finish(&self, mut state: VMState, dest: &str) -> Result<VMState, String> {
+        let list = state
+            .lists
+            .remove(dest)
+            .ok_or_else(|| format!("List not started: {}", dest))?;
+        Self::write_variable(&mut state, dest, ExpressionResult::new(list.finish()));
+        Ok(Self::advance_pc(state))
+    }
+
     async fn execute_llm_placeholder(
         &self,
         mut state: VMState,
         dest: &str,
         param_name: &str,
         param_type: &str,
+        function_name: Option<&str>,
+        param_index: usize,
     ) -> Result<VMState, String> {
-        let param_type_obj = parse_type(param_type)?;
+        let mut param_name = param_name.to_string();
+        let mut param_type_obj = parse_type(param_type)?;
+        let mut target = None;
+
+        if let Some(function_name) = function_name
+            && let Some(func) = self.runtime.get_function(function_name)
+        {
+            if let Some(param) = func.parameters().get(param_index) {
+                param_name = param.name.clone();
+                param_type_obj = param.param_type.clone();
+            }
+            target = Some(PlaceholderTarget::new(
+                function_name.to_string(),
+                func.documentation().map(|s| s.to_string()),
+            ));
+        }
+
+        state.context.runtime().stats().record_engine_call();
         let value = state
             .context
             .runtime()
             .engine()
-            .fill_parameter(&state.context, param_name, &param_type_obj)
+            .fill_parameter(
+                &state.context,
+                &param_name,
+                &param_type_obj,
+                target.as_ref(),
+            )
+            .instrument(debug_span!("engine", operation = "fill_parameter"))
             .await?;
 
-        Self::write_variable(&mut state, dest, ExpressionResult::new(value));
+        Self::write_variable(
+            &mut state,
+            dest,
+            ExpressionResult::new(value).with_role(EventRole::Model),
+        );
         Ok(Self::advance_pc(state))
     }
 
+    /// Reads the metadata of every eligible clause, along with each one's
+    /// clause index. A clause whose guard was false leaves Unit behind.
+    fn read_metadata_values(
+        state: &VMState,
+        metadata_vars: &[String],
+    ) -> Result<(Vec<ExpressionValue>, Vec<usize>), String> {
+        let mut metadata_values = Vec::new();
+        let mut clause_indices = Vec::new();
+
+        for (clause_index, var_name) in metadata_vars.iter().enumerate() {
+            let value = Self::read_variable(state, var_name)?;
+            match &value.value {
+                ExpressionValue::Metadata { .. } => {
+                    metadata_values.push(value.value.clone());
+                    clause_indices.push(clause_index);
+                }
+                ExpressionValue::Unit => {}
+                other => {
+                    return Err(format!(
+                        "Expected Metadata value in variable {}, got {}",
+                        var_name,
+                        other.type_name()
+                    ));
+                }
+            }
+        }
+
+        if metadata_values.is_empty() {
+            return Err("No select clause is eligible: every guard was false".to_string());
+        }
+
+        Ok((metadata_values, clause_indices))
+    }
+
     async fn execute_llm_select(
         &self,
         mut state: VMState,
         metadata_vars: &[String],
         dest: &str,
     ) -> Result<VMState, String> {
-        let mut metadata_values = Vec::new();
-
-        for var_name in metadata_vars {
-            let value = Self::read_variable(&state, var_name)?;
-            if !matches!(&value.value, ExpressionValue::Metadata { .. }) {
-                return Err(format!(
-                    "Expected Metadata value in variable {}, got {}",
-                    var_name,
-                    value.value.type_name()
-                ));
-            }
-            metadata_values.push(value.value.clone());
-        }
+        let (metadata_values, clause_indices) = Self::read_metadata_values(&state, metadata_vars)?;
 
+        state.context.runtime().stats().record_engine_call();
         let selected_index = state
             .context
             .runtime()
             .engine()
             .select(&state.context, &metadata_values)
+            .instrument(debug_span!("engine", operation = "select"))
             .await?;
+        let clause_index = clause_indices.get(selected_index).ok_or_else(|| {
+            format!(
+                "Language engine selected invalid option index: {}",
+                selected_index
+            )
+        })?;
 
-        let result = ExpressionResult::new(ExpressionValue::String(selected_index.to_string()));
+        let result = ExpressionResult::new(ExpressionValue::String(clause_index.to_string()));
 
         Self::write_variable(&mut state, dest, result);
         Ok(Self::advance_pc(state))
     }
 
+    fn execute_select_exclude(
+        &self,
+        mut state: VMState,
+        metadata_vars: &[String],
+        clause: usize,
+        error: &str,
+    ) -> Result<VMState, String> {
+        let meta_var = metadata_vars
+            .get(clause)
+            .ok_or_else(|| format!("Select clause {} out of range", clause))?;
+        Self::write_variable(
+            &mut state,
+            meta_var,
+            ExpressionResult::new(ExpressionValue::Unit),
+        );
+
+        let exhausted = metadata_vars.iter().all(|var| {
+            Self::read_variable(&state, var)
+                .is_ok_and(|value| matches!(value.value, ExpressionValue::Unit))
+        });
+        if exhausted {
+            let cause = match Self::read_variable(&state, error)?.value {
+                ExpressionValue::String(cause) => cause,
+                other => other.format_for_llm(),
+            };
+            return Err(format!(
+                "Every select clause failed; the last one failed with: {}",
+                cause
+            ));
+        }
+        Ok(Self::advance_pc(state))
+    }
+
+    /// Stores the engine's clause order, with any eligible clauses it left out
+    /// appended and a final past-the-end index that tells the select it has
+    /// run out.
+    async fn execute_llm_rank(
+        &self,
+        mut state: VMState,
+        metadata_vars: &[String],
+        dest: &str,
+    ) -> Result<VMState, String> {
+        let (metadata_values, clause_indices) = Self::read_metadata_values(&state, metadata_vars)?;
+
+        state.context.runtime().stats().record_engine_call();
+        let ranking = state
+            .context
+            .runtime()
+            .engine()
+            .rank(&state.context, &metadata_values)
+            .instrument(debug_span!("engine", operation = "rank"))
+            .await?;
+
+        let clause_count = metadata_vars.len();
+        let mut order: Vec<usize> = Vec::with_capacity(clause_indices.len() + 1);
+        for index in ranking {
+            let Some(&clause_index) = clause_indices.get(index) else {
+                return Err(format!(
+                    "Language engine ranked invalid option index: {}",
+                    index
+                ));
+            };
+            if !order.contains(&clause_index) {
+                order.push(clause_index);
+            }
+        }
+        for &clause_index in &clause_indices {
+            if !order.contains(&clause_index) {
+                order.push(clause_index);
+            }
+        }
+        order.push(clause_count);
+
+        Self::write_variable(
+            &mut state,
+            dest,
+            ExpressionResult::new(ExpressionValue::Json(serde_json::json!(order))),
+        );
+        Ok(Self::advance_pc(state))
+    }
+
     async fn execute_llm_generate(
         &self,
         mut state: VMState,
@@ -357,14 +784,20 @@ impl VM {
         return_type: &str,
     ) -> Result<VMState, String> {
         let return_type_obj = parse_type(return_type)?;
+        state.context.runtime().stats().record_engine_call();
         let value = state
             .context
             .runtime()
             .engine()
             .typed(&state.context, &return_type_obj)
+            .instrument(debug_span!("engine", operation = "typed"))
             .await?;
 
-        Self::write_variable(&mut state, dest, ExpressionResult::new(value));
+        Self::write_variable(
+            &mut state,
+            dest,
+            ExpressionResult::new(value).with_role(EventRole::Model),
+        );
         Ok(Self::advance_pc(state))
     }
 
@@ -382,6 +815,13 @@ impl VM {
         state
             .context
             .get_variable(name)
+            .or_else(|| {
+                state
+                    .context
+                    .runtime()
+                    .get_constant(name)
+                    .map(|value| ExpressionResult::new(value.clone()))
+            })
             .ok_or_else(|| format!("Variable not found: {}", name))
     }
 
@@ -414,6 +854,8 @@ fn parse_type(type_str: &str) -> Result<crate::types::Type, String> {
     match type_str {
         "String" => Ok(crate::types::Type::String),
         "Boolean" => Ok(crate::types::Type::Boolean),
+        "Number" => Ok(crate::types::Type::Number),
+        "Json" => Ok(crate::types::Type::Json),
         "Unit" | "()" => Ok(crate::types::Type::Unit),
         "Unknown" => Ok(crate::types::Type::String),
         s if s.starts_with("List<") && s.ends_with(">") => {
@@ -427,3 +869,87 @@ fn parse_type(type_str: &str) -> Result<crate::types::Type, String> {
         _ => Err(format!("Unknown type: {}", type_str)),
     }
 }
+
+// A list literal's items, appended straight into an arrow builder for their
+// element type. When ListNew does not name the type, the first item decides
+// it. Items a ListArray cannot hold, such as Json values, are kept as they
+// are and finish as a Json array.
+enum ListInProgress {
+    Untyped,
+    Strings(ListBuilder<StringBuilder>),
+    Booleans(ListBuilder<BooleanBuilder>),
+    Numbers(ListBuilder<Float64Builder>),
+    Other(Vec<ExpressionValue>),
+}
+
+impl ListInProgress {
+    fn new(element_type: &str) -> Self {
+        match element_type {
+            "String" => Self::Strings(ListBuilder::new(StringBuilder::new())),
+            "Boolean" => Self::Booleans(ListBuilder::new(BooleanBuilder::new())),
+            "Number" => Self::Numbers(ListBuilder::new(Float64Builder::new())),
+            _ => Self::Untyped,
+        }
+    }
+
+    fn push(&mut self, item: ExpressionValue) -> Result<(), String> {
+        if let Self::Untyped = self {
+            *self = match &item {
+                ExpressionValue::String(_) => Self::new("String"),
+                ExpressionValue::Boolean(_) => Self::new("Boolean"),
+                ExpressionValue::Number(_) => Self::new("Number"),
+                _ => Self::Other(Vec::new()),
+            };
+        }
+
+        match (self, item) {
+            (Self::Strings(builder), ExpressionValue::String(s)) => {
+                builder.values().append_value(s)
+            }
+            (Self::Booleans(builder), ExpressionValue::Boolean(b)) => {
+                builder.values().append_value(b)
+            }
+            (Self::Numbers(builder), ExpressionValue::Number(n)) => {
+                builder.values().append_value(n)
+            }
+            (Self::Other(items), item) => items.push(item),
+            (list, item) => {
+                return Err(format!(
+                    "Cannot add a {} item to a list of {}",
+                    item.type_name(),
+                    list.element_type_name()
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    fn element_type_name(&self) -> &'static str {
+        match self {
+            Self::Strings(_) => "String",
+            Self::Booleans(_) => "Boolean",
+            Self::Numbers(_) => "Number",
+            Self::Untyped | Self::Other(_) => "Unknown",
+        }
+    }
+
+    fn finish(self) -> ExpressionValue {
+        let list = match self {
+            Self::Untyped => return ExpressionValue::from(Vec::new()),
+            Self::Other(items) => return ExpressionValue::from(items),
+            Self::Strings(mut builder) => {
+                builder.append(true);
+                builder.finish()
+            }
+            Self::Booleans(mut builder) => {
+                builder.append(true);
+                builder.finish()
+            }
+            Self::Numbers(mut builder) => {
+                builder.append(true);
+                builder.finish()
+            }
+        };
+        ExpressionValue::List(Arc::new(list))
+    }
+}
@@ -299,3 +299,52 @@ async fn test_vm_unit_return() {
         Err(e) => panic!("Test failed with error: {:?}", e),
     }
 }
+
+#[tokio::test]
+async fn test_vm_number_list_literal() {
+    let code = r#"
+        fn main(): List<Number> {
+            return [1, 2.5]
+        }
+    "#;
+
+    let program = CompilationUnit::from_string(code.to_string());
+    let runtime = Runtime::builder(program).build();
+    let result = runtime.run().await;
+
+    match result {
+        Ok(value @ ExpressionValue::List(_)) => {
+            assert_eq!(value.to_string(), "[1, 2.5]");
+            assert_eq!(
+                Vec::<ExpressionValue>::try_from(value),
+                Ok(vec![
+                    ExpressionValue::Number(1.0),
+                    ExpressionValue::Number(2.5)
+                ])
+            );
+        }
+        Ok(other) => panic!("Expected list result, got: {:?}", other),
+        Err(e) => panic!("Test failed with error: {:?}", e),
+    }
+}
+
+#[tokio::test]
+async fn test_vm_list_of_variables_takes_the_first_item_type() {
+    let code = r#"
+        fn main(): List<Number> {
+            let first = 3
+            let second = 4
+            return [first, second]
+        }
+    "#;
+
+    let program = CompilationUnit::from_string(code.to_string());
+    let runtime = Runtime::builder(program).build();
+    let result = runtime.run().await;
+
+    match result {
+        Ok(value @ ExpressionValue::List(_)) => assert_eq!(value.to_string(), "[3, 4]"),
+        Ok(other) => panic!("Expected list result, got: {:?}", other),
+        Err(e) => panic!("Test failed with error: {:?}", e),
+    }
+}
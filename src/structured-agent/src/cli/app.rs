@@ -1,16 +1,36 @@
 use crate::acp;
-use crate::cli::config::{Config, Mode};
+use crate::analysis::fill_points;
+use crate::cli::args;
+use crate::cli::config::{Config, Mode, ProgramSource};
 use crate::cli::errors::CliError;
+use crate::cli::format::format_program;
+use crate::cli::interactive::InteractiveSession;
+use crate::cli::tools;
+use crate::compiler::CodespanParser;
+use crate::diagnostics::DiagnosticManager;
 use crate::runtime::{Runtime, load_program};
 
 pub struct App;
 
 impl App {
     pub async fn run(config: Config) -> Result<(), CliError> {
+        // list-tools reports unreachable servers one by one instead.
+        if !matches!(config.mode, Mode::Completions(_) | Mode::ListTools) {
+            config.validate().map_err(CliError::InvalidConfig)?;
+        }
+
         match config.mode {
             Mode::Acp => Self::run_acp_mode(config).await,
             Mode::Check => Self::run_check_mode(config).await,
+            Mode::ListFills => Self::run_list_fills_mode(config),
             Mode::Run => Self::run_execute_mode(config).await,
+            Mode::Interactive => Self::run_interactive_mode(config).await,
+            Mode::ListTools => Self::run_list_tools_mode(config).await,
+            Mode::Fmt { write } => Self::run_fmt_mode(config, write),
+            Mode::Completions(shell) => {
+                args::write_completions(shell, &mut std::io::stdout());
+                Ok(())
+            }
         }
     }
 
@@ -33,17 +53,54 @@ impl App {
             .await
             .map_err(CliError::RuntimeError)?;
 
+        if config.describe_bindings {
+            Self::display_bindings(&runtime).await?;
+        }
+
         println!("Executing program...");
-        match runtime.run().await {
-            Ok(result) => {
+        let outcome = runtime.run_with_context().await;
+        if config.run_stats {
+            println!("{}", runtime.stats().summary());
+        }
+
+        match outcome {
+            Ok((result, context)) => {
                 println!("Program executed successfully");
                 Self::display_result(&result);
+                if let Some(path) = &config.audit_log {
+                    let mut file = std::io::BufWriter::new(std::fs::File::create(path)?);
+                    context.export_jsonl(&mut file)?;
+                    println!("Audit log written to {}", path.display());
+                }
                 Ok(())
             }
             Err(e) => Err(CliError::RuntimeError(format!("{}", e))),
         }
     }
 
+    async fn run_interactive_mode(config: Config) -> Result<(), CliError> {
+        println!("{}", config.describe_source());
+
+        let program = load_program(&config.program_source).map_err(CliError::from)?;
+
+        println!("Initializing structured agent runtime...");
+
+        let mut session = InteractiveSession::from_config(program, &config)
+            .await
+            .map_err(CliError::RuntimeError)?;
+
+        println!("Interactive session started. Press Ctrl-D to exit.");
+        let stdin = tokio::io::BufReader::new(tokio::io::stdin());
+        session
+            .run(stdin, |result| {
+                Self::display_result(result);
+                print!("> ");
+                let _ = std::io::Write::flush(&mut std::io::stdout());
+            })
+            .await
+            .map_err(|e| CliError::RuntimeError(format!("{}", e)))
+    }
+
     async fn run_check_mode(config: Config) -> Result<(), CliError> {
         println!("{}", config.describe_source());
 
@@ -63,8 +120,27 @@ impl App {
             .await
             .map_err(CliError::RuntimeError)?;
 
+        if config.describe_bindings {
+            Self::display_bindings(&runtime).await?;
+        }
+
         println!("Running checks...");
-        match runtime.check() {
+        let checked = if config.analysis_stats {
+            runtime.check_with_report().map(|report| {
+                println!("Analysis stats:");
+                println!("{}", report);
+            })
+        } else {
+            runtime.check()
+        };
+        let checked = match checked {
+            Ok(()) if config.check_providers => {
+                println!("Checking extern functions against providers...");
+                runtime.check_providers().await
+            }
+            other => other,
+        };
+        match checked {
             Ok(_) => {
                 println!("All checks passed");
                 Ok(())
@@ -73,12 +149,110 @@ impl App {
         }
     }
 
+    fn run_list_fills_mode(config: Config) -> Result<(), CliError> {
+        let program = load_program(&config.program_source).map_err(CliError::from)?;
+
+        let mut diagnostics = DiagnosticManager::new();
+        let file_id =
+            diagnostics.add_file(program.name().to_string(), program.source().to_string());
+        let module = CodespanParser::new()
+            .parse(&program, file_id, diagnostics.reporter())
+            .map_err(CliError::RuntimeError)?;
+
+        let points = fill_points(&module);
+        if points.is_empty() {
+            println!("No fill points");
+        }
+        for point in points {
+            println!("{}", point);
+        }
+        Ok(())
+    }
+
+    fn run_fmt_mode(config: Config, write: bool) -> Result<(), CliError> {
+        let program = load_program(&config.program_source).map_err(CliError::from)?;
+        let formatted = format_program(&program).map_err(CliError::RuntimeError)?;
+
+        match &config.program_source {
+            ProgramSource::File(path) if write => {
+                if formatted != program.source() {
+                    std::fs::write(path, formatted)?;
+                }
+            }
+            _ => print!("{}", formatted),
+        }
+        Ok(())
+    }
+
+    // Succeeds when at least one server could be listed; per-server failures are reported.
+    async fn run_list_tools_mode(config: Config) -> Result<(), CliError> {
+        if config.mcp_servers.is_empty() {
+            return Err(CliError::McpError(
+                "No MCP servers configured. Use --mcp-server or a config file.".to_string(),
+            ));
+        }
+
+        let results = tools::list_server_tools(&config.mcp_servers).await;
+        let mut succeeded = 0;
+
+        for server_tools in &results {
+            println!("MCP server: {}", server_tools.describe_server());
+            match &server_tools.result {
+                Ok(definitions) => {
+                    succeeded += 1;
+                    if definitions.is_empty() {
+                        println!("  (no tools)");
+                    }
+                    for definition in definitions {
+                        for line in tools::format_definition(definition).lines() {
+                            println!("  {}", line);
+                        }
+                    }
+                }
+                Err(e) => {
+                    eprintln!(
+                        "Failed to list tools from MCP server '{}': {}",
+                        server_tools.describe_server(),
+                        e
+                    );
+                }
+            }
+        }
+
+        if succeeded == 0 {
+            Err(CliError::McpError(format!(
+                "Failed to list tools from all {} configured MCP servers",
+                results.len()
+            )))
+        } else {
+            Ok(())
+        }
+    }
+
     async fn run_acp_mode(config: Config) -> Result<(), CliError> {
         acp::run_acp_server(config)
             .await
             .map_err(|e| CliError::RuntimeError(format!("ACP server error: {}", e)))
     }
 
+    async fn display_bindings(runtime: &Runtime) -> Result<(), CliError> {
+        let bindings = runtime
+            .describe_bindings()
+            .await
+            .map_err(|e| CliError::RuntimeError(format!("{}", e)))?;
+
+        println!("Extern function bindings:");
+        if bindings.is_empty() {
+            println!("  (no extern functions)");
+        }
+        for binding in &bindings {
+            for line in binding.to_string().lines() {
+                println!("  {}", line);
+            }
+        }
+        Ok(())
+    }
+
     fn display_result(result: &crate::runtime::ExpressionValue) {
         match result {
             crate::runtime::ExpressionValue::String(s) => {
@@ -122,6 +296,9 @@ impl App {
             crate::runtime::ExpressionValue::Boolean(b) => {
                 println!("Result: {}", b);
             }
+            crate::runtime::ExpressionValue::Number(n) => {
+                println!("Result: {}", n);
+            }
             crate::runtime::ExpressionValue::List(list) => {
                 use arrow::array::Array;
                 println!("Result: List[{}]", list.len());
@@ -136,6 +313,11 @@ impl App {
                     println!("Result: None");
                 }
             },
+            crate::runtime::ExpressionValue::Json(value) => {
+                let rendered =
+                    serde_json::to_string_pretty(value).unwrap_or_else(|_| value.to_string());
+                println!("Result: {}", rendered);
+            }
             crate::runtime::ExpressionValue::Metadata {
                 name,
                 documentation,
@@ -166,6 +348,18 @@ mod tests {
             with_default_functions: true,
             with_unstable_functions: false,
             with_acp_functions: false,
+            describe_bindings: false,
+            analysis_stats: false,
+            check_providers: false,
+            disabled_analyzers: Vec::new(),
+            profile: None,
+            audit_log: None,
+            thought_updates: false,
+            eager_connect: false,
+            max_call_depth: None,
+            run_stats: false,
+            relaxed_signature_matching: false,
+            confirm_destructive: false,
             mode: Mode::Run,
         };
 
@@ -178,6 +372,8 @@ mod tests {
         let functions = runtime.list_functions();
         assert!(functions.contains(&"input"));
         assert!(functions.contains(&"print"));
+        assert!(functions.contains(&"to_string"));
+        assert!(functions.contains(&"parse_int"));
     }
 
     #[tokio::test]
@@ -191,6 +387,18 @@ mod tests {
             with_default_functions: false,
             with_unstable_functions: false,
             with_acp_functions: false,
+            describe_bindings: false,
+            analysis_stats: false,
+            check_providers: false,
+            disabled_analyzers: Vec::new(),
+            profile: None,
+            audit_log: None,
+            thought_updates: false,
+            eager_connect: false,
+            max_call_depth: None,
+            run_stats: false,
+            relaxed_signature_matching: false,
+            confirm_destructive: false,
             mode: Mode::Run,
         };
 
@@ -204,4 +412,69 @@ mod tests {
         assert!(!functions.contains(&"input"));
         assert!(!functions.contains(&"print"));
     }
+
+    fn fmt_config(path: &std::path::Path) -> Config {
+        Config {
+            program_source: crate::cli::config::ProgramSource::File(
+                path.to_string_lossy().into_owned(),
+            ),
+            mcp_servers: vec![],
+            engine: EngineType::Print,
+            with_default_functions: false,
+            with_unstable_functions: false,
+            with_acp_functions: false,
+            describe_bindings: false,
+            analysis_stats: false,
+            check_providers: false,
+            disabled_analyzers: Vec::new(),
+            profile: None,
+            audit_log: None,
+            thought_updates: false,
+            eager_connect: false,
+            max_call_depth: None,
+            run_stats: false,
+            relaxed_signature_matching: false,
+            confirm_destructive: false,
+            mode: Mode::Fmt { write: true },
+        }
+    }
+
+    #[test]
+    fn test_fmt_write_keeps_a_file_it_would_lose_comments_from() {
+        let path = std::env::temp_dir().join(format!("fmt-comments-{}.sa", std::process::id()));
+        let source = r#"# Greeting helpers
+fn   greet(): () {
+    # Say hello
+    "hello"!   // to everyone
+}
+"#;
+        std::fs::write(&path, source).unwrap();
+
+        let result = App::run_fmt_mode(fmt_config(&path), true);
+        let written = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(result.is_err());
+        assert_eq!(written, source);
+    }
+
+    #[test]
+    fn test_fmt_write_round_trips_kept_comments() {
+        let path = std::env::temp_dir().join(format!("fmt-kept-{}.sa", std::process::id()));
+        std::fs::write(
+            &path,
+            "# pure\nfn   greet(): () {\n    # Say hello\n    \"hello\"!\n}\n",
+        )
+        .unwrap();
+
+        let result = App::run_fmt_mode(fmt_config(&path), true);
+        let written = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(result.is_ok());
+        assert_eq!(
+            written,
+            "# pure\nfn greet(): () {\n    # Say hello\n    \"hello\"!\n}\n"
+        );
+    }
 }
@@ -1,5 +1,7 @@
-use clap::{Parser, Subcommand};
+use clap::{CommandFactory, Parser, Subcommand};
+use clap_complete::Shell;
 use serde::Deserialize;
+use std::io::Write;
 use std::path::PathBuf;
 
 #[derive(Parser, Debug)]
@@ -30,6 +32,15 @@ pub enum Command {
 
     #[command(about = "Run as ACP (Agent Client Protocol) server")]
     Acp(AcpArgs),
+
+    #[command(about = "List the tools exposed by configured MCP servers")]
+    ListTools(ListToolsArgs),
+
+    #[command(about = "Print a program in canonical layout")]
+    Fmt(FmtArgs),
+
+    #[command(about = "Print a shell completion script")]
+    Completions(CompletionsArgs),
 }
 
 #[derive(Parser, Debug)]
@@ -48,6 +59,24 @@ pub struct RunArgs {
     )]
     pub mcp_server: Vec<String>,
 
+    #[arg(
+        long,
+        help = "Connect to every MCP server before running instead of on first use"
+    )]
+    pub eager_connect: bool,
+
+    #[arg(
+        long,
+        help = "Bind an extern function to a provider function whose parameter types match by position when none has the declared parameter names"
+    )]
+    pub relaxed_signature_matching: bool,
+
+    #[arg(
+        long,
+        help = "Refuse calls to functions their provider marks destructive, such as MCP tools with destructiveHint"
+    )]
+    pub confirm_destructive: bool,
+
     #[arg(
         short = 'e',
         long,
@@ -57,27 +86,62 @@ pub struct RunArgs {
     )]
     pub engine: String,
 
-    #[arg(long, help = "Include default functions (input, print)")]
+    #[arg(
+        long,
+        help = "Include default functions (input, print, to_string, parse_int)"
+    )]
     pub with_default_functions: bool,
 
     #[arg(
         long,
-        help = "Include unstable functions (head, tail, is_some, some_value, is_some_list, some_value_list)"
+        help = "Include unstable functions (head, tail, is_some, some_value, is_some_list, some_value_list, json_get)"
     )]
     pub with_unstable_functions: bool,
 
     #[arg(long, help = "Include ACP functions (receive, try_receive)")]
     pub with_acp_functions: bool,
 
-    #[arg(long, value_name = "KEY", help = "Gemini API key for authentication")]
-    pub gemini_api_key: Option<String>,
+    #[arg(
+        long,
+        help = "Keep the session open, reading follow-up prompts from stdin after each run"
+    )]
+    pub interactive: bool,
 
     #[arg(
         long,
-        value_name = "MODEL",
-        help = "Gemini model to use: gemini-2.5-pro, gemini-2.5-flash, gemini-2.5-flash-lite, gemini-3-flash-preview, gemini-3-pro-preview, or custom model name"
+        value_name = "DEPTH",
+        help = "Fail a call nested more than DEPTH calls below main (default: 256)"
     )]
-    pub gemini_model: Option<String>,
+    pub max_depth: Option<usize>,
+
+    #[arg(
+        long,
+        help = "Print which provider backs each extern function before continuing"
+    )]
+    pub describe_bindings: bool,
+
+    #[arg(
+        long,
+        value_name = "FILE",
+        help = "Write span timings as folded stacks for flamegraph tools when the run ends"
+    )]
+    pub profile: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "FILE",
+        help = "Write every context event as JSON Lines when the run succeeds"
+    )]
+    pub audit_log: Option<PathBuf>,
+
+    #[arg(
+        long,
+        help = "Print how many engine calls, tool calls and tokens the run used"
+    )]
+    pub stats: bool,
+
+    #[command(flatten)]
+    pub gemini: GeminiArgs,
 }
 
 #[derive(Parser, Debug)]
@@ -96,17 +160,68 @@ pub struct CheckArgs {
     )]
     pub mcp_server: Vec<String>,
 
-    #[arg(long, help = "Include default functions (input, print)")]
+    #[arg(
+        long,
+        help = "Connect to every MCP server before checking, so one that fails to start fails the check"
+    )]
+    pub eager_connect: bool,
+
+    #[arg(
+        long,
+        help = "Bind an extern function to a provider function whose parameter types match by position when none has the declared parameter names"
+    )]
+    pub relaxed_signature_matching: bool,
+
+    #[arg(
+        long,
+        help = "Refuse calls to functions their provider marks destructive, such as MCP tools with destructiveHint"
+    )]
+    pub confirm_destructive: bool,
+
+    #[arg(
+        long,
+        help = "Include default functions (input, print, to_string, parse_int)"
+    )]
     pub with_default_functions: bool,
 
     #[arg(
         long,
-        help = "Include unstable functions (head, tail, is_some, some_value, is_some_list, some_value_list)"
+        help = "Include unstable functions (head, tail, is_some, some_value, is_some_list, some_value_list, json_get)"
     )]
     pub with_unstable_functions: bool,
 
     #[arg(long, help = "Include ACP functions (receive, try_receive)")]
     pub with_acp_functions: bool,
+
+    #[arg(
+        long,
+        help = "Print which provider backs each extern function before continuing"
+    )]
+    pub describe_bindings: bool,
+
+    #[arg(
+        long,
+        help = "Print how long each analyzer took and how many warnings it reported"
+    )]
+    pub analysis_stats: bool,
+
+    #[arg(
+        long,
+        help = "Connect to the configured providers and check every extern function can be bound"
+    )]
+    pub with_providers: bool,
+
+    #[arg(
+        long = "disable-analyzer",
+        value_name = "NAME",
+        help = "Skip the named analyzer (can be repeated)"
+    )]
+    pub disable_analyzer: Vec<String>,
+    #[arg(
+        long,
+        help = "List every place the engine would be asked to fill a value, without running"
+    )]
+    pub list_fills: bool,
 }
 
 #[derive(Parser, Debug)]
@@ -125,6 +240,24 @@ pub struct AcpArgs {
     )]
     pub mcp_server: Vec<String>,
 
+    #[arg(
+        long,
+        help = "Connect to every MCP server before running instead of on first use"
+    )]
+    pub eager_connect: bool,
+
+    #[arg(
+        long,
+        help = "Bind an extern function to a provider function whose parameter types match by position when none has the declared parameter names"
+    )]
+    pub relaxed_signature_matching: bool,
+
+    #[arg(
+        long,
+        help = "Refuse calls to functions their provider marks destructive, such as MCP tools with destructiveHint"
+    )]
+    pub confirm_destructive: bool,
+
     #[arg(
         short = 'e',
         long,
@@ -134,18 +267,40 @@ pub struct AcpArgs {
     )]
     pub engine: String,
 
-    #[arg(long, help = "Include default functions (input, print)")]
+    #[arg(
+        long,
+        help = "Include default functions (input, print, to_string, parse_int)"
+    )]
     pub with_default_functions: bool,
 
     #[arg(
         long,
-        help = "Include unstable functions (head, tail, is_some, some_value, is_some_list, some_value_list)"
+        help = "Include unstable functions (head, tail, is_some, some_value, is_some_list, some_value_list, json_get)"
     )]
     pub with_unstable_functions: bool,
 
     #[arg(long, help = "Include ACP functions (receive, try_receive)")]
     pub with_acp_functions: bool,
 
+    #[arg(
+        long,
+        help = "Forward engine prompts, responses and model thoughts as ACP thought updates"
+    )]
+    pub thought_updates: bool,
+
+    #[arg(
+        long,
+        value_name = "DEPTH",
+        help = "Fail a call nested more than DEPTH calls below main (default: 256)"
+    )]
+    pub max_depth: Option<usize>,
+
+    #[command(flatten)]
+    pub gemini: GeminiArgs,
+}
+
+#[derive(clap::Args, Debug, Default)]
+pub struct GeminiArgs {
     #[arg(long, value_name = "KEY", help = "Gemini API key for authentication")]
     pub gemini_api_key: Option<String>,
 
@@ -155,6 +310,98 @@ pub struct AcpArgs {
         help = "Gemini model to use: gemini-2.5-pro, gemini-2.5-flash, gemini-2.5-flash-lite, gemini-3-flash-preview, gemini-3-pro-preview, or custom model name"
     )]
     pub gemini_model: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "MODEL",
+        help = "Model to fall back to when the previous one is rate limited or unavailable (repeatable, tried in order)"
+    )]
+    pub gemini_fallback_model: Vec<String>,
+
+    #[arg(
+        long,
+        value_name = "PRESET",
+        help = "Gemini generation preset: creative, precise, or balanced"
+    )]
+    pub gemini_preset: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "COMMAND",
+        help = "Command that prints an access token for ADC auth (default: 'gcloud auth print-access-token')"
+    )]
+    pub gemini_token_command: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        help = "How long a fetched access token is reused before running the token command again"
+    )]
+    pub gemini_token_ttl: Option<u64>,
+
+    #[arg(
+        long,
+        help = "Tag each Gemini chat message with run metadata (function, parameter, session id)"
+    )]
+    pub gemini_message_metadata: bool,
+
+    #[arg(
+        long,
+        help = "Send the Gemini API key as a query parameter instead of the x-goog-api-key header"
+    )]
+    pub gemini_api_key_in_query: bool,
+
+    #[arg(
+        long,
+        help = "Force deterministic generation (temperature 0, top_k 1, one candidate) for every call"
+    )]
+    pub deterministic: bool,
+
+    #[arg(
+        long,
+        value_name = "SEED",
+        help = "Sampling seed sent with every Gemini call, for reproducible runs"
+    )]
+    pub seed: Option<u64>,
+}
+
+#[derive(Parser, Debug)]
+pub struct ListToolsArgs {
+    #[arg(
+        short = 'm',
+        long,
+        value_name = "COMMAND",
+        help = "MCP server command (format: 'command arg1 arg2')"
+    )]
+    pub mcp_server: Vec<String>,
+}
+
+#[derive(Parser, Debug)]
+pub struct FmtArgs {
+    #[arg(short = 'f', long, value_name = "FILE", conflicts_with = "inline")]
+    pub file: Option<String>,
+
+    #[arg(short = 'i', long, value_name = "CODE", conflicts_with = "file")]
+    pub inline: Option<String>,
+
+    #[arg(
+        long,
+        conflicts_with = "inline",
+        help = "Rewrite the program file in place instead of printing it"
+    )]
+    pub write: bool,
+}
+
+#[derive(Parser, Debug)]
+pub struct CompletionsArgs {
+    #[arg(value_enum, help = "Shell to generate completions for")]
+    pub shell: Shell,
+}
+
+pub fn write_completions(shell: Shell, out: &mut dyn Write) {
+    let mut command = Args::command();
+    let name = command.get_name().to_string();
+    clap_complete::generate(shell, &mut command, name, out);
 }
 
 #[derive(Deserialize, Debug, Default)]
@@ -166,8 +413,22 @@ pub struct FileConfig {
     pub with_default_functions: Option<bool>,
     pub with_unstable_functions: Option<bool>,
     pub with_acp_functions: Option<bool>,
+    pub thought_updates: Option<bool>,
+    pub eager_connect: Option<bool>,
+    pub max_depth: Option<usize>,
+    pub relaxed_signature_matching: Option<bool>,
+    pub confirm_destructive: Option<bool>,
     pub gemini_api_key: Option<String>,
     pub gemini_model: Option<String>,
+    pub gemini_fallback_models: Option<Vec<String>>,
+    pub gemini_preset: Option<String>,
+    pub gemini_token_command: Option<String>,
+    pub gemini_token_ttl: Option<u64>,
+    pub gemini_message_metadata: Option<bool>,
+    pub gemini_api_key_in_query: Option<bool>,
+    pub deterministic: Option<bool>,
+    pub seed: Option<u64>,
+    pub disabled_analyzers: Option<Vec<String>>,
 }
 
 #[derive(Deserialize, Debug, Clone)]
@@ -175,3 +436,24 @@ pub struct McpServerEntry {
     pub command: String,
     pub args: Vec<String>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bash_completions_mention_subcommands() {
+        let mut out = Vec::new();
+        write_completions(Shell::Bash, &mut out);
+
+        let script = String::from_utf8(out).unwrap();
+        assert!(!script.is_empty());
+        for subcommand in ["run", "check", "acp", "list-tools", "fmt", "completions"] {
+            assert!(
+                script.contains(subcommand),
+                "completion script does not mention `{}`",
+                subcommand
+            );
+        }
+    }
+}
@@ -1,8 +1,17 @@
-use crate::cli::args::{AcpArgs, Args, CheckArgs, Command, FileConfig, RunArgs};
+use crate::cli::args::{
+    AcpArgs, Args, CheckArgs, Command, CompletionsArgs, FileConfig, FmtArgs, GeminiArgs,
+    ListToolsArgs, RunArgs,
+};
+use crate::cli::errors::ConfigError;
+use crate::gemini::config::VERTEX_PROJECT_VARS;
+use clap_complete::Shell;
 use std::env;
 use std::fs;
+use std::path::{Path, PathBuf};
 use std::process;
 
+const DEFAULT_TOKEN_PROGRAM: &str = "gcloud";
+
 #[derive(Debug, Clone)]
 pub struct Config {
     pub program_source: ProgramSource,
@@ -11,14 +20,34 @@ pub struct Config {
     pub with_default_functions: bool,
     pub with_unstable_functions: bool,
     pub with_acp_functions: bool,
+    pub describe_bindings: bool,
+    pub analysis_stats: bool,
+    pub check_providers: bool,
+    pub disabled_analyzers: Vec<String>,
+    pub profile: Option<PathBuf>,
+    pub audit_log: Option<PathBuf>,
+    pub thought_updates: bool,
+    pub eager_connect: bool,
+    pub max_call_depth: Option<usize>,
+    pub run_stats: bool,
+    pub relaxed_signature_matching: bool,
+    pub confirm_destructive: bool,
     pub mode: Mode,
 }
 
 #[derive(Debug, Clone)]
 pub enum Mode {
     Run,
+    Interactive,
     Check,
+    ListFills,
     Acp,
+    ListTools,
+    /// Print the program in canonical layout, or rewrite its file in place.
+    Fmt {
+        write: bool,
+    },
+    Completions(Shell),
 }
 
 #[derive(Debug, Clone)]
@@ -30,10 +59,21 @@ pub enum ProgramSource {
 #[derive(Debug, Clone)]
 pub enum EngineType {
     Print,
-    Gemini {
-        api_key: Option<String>,
-        model: Option<String>,
-    },
+    Gemini(GeminiSettings),
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct GeminiSettings {
+    pub api_key: Option<String>,
+    pub model: Option<String>,
+    pub fallback_models: Vec<String>,
+    pub preset: Option<String>,
+    pub token_command: Option<Vec<String>>,
+    pub token_ttl_secs: Option<u64>,
+    pub message_metadata: bool,
+    pub api_key_in_query: bool,
+    pub deterministic: bool,
+    pub seed: Option<u64>,
 }
 
 #[derive(Debug, Clone)]
@@ -54,19 +94,24 @@ impl Config {
             Command::Run(run_args) => Self::from_run_args(run_args, &file_config),
             Command::Check(check_args) => Self::from_check_args(check_args, &file_config),
             Command::Acp(acp_args) => Self::from_acp_args(acp_args, &file_config),
+            Command::ListTools(list_tools_args) => {
+                Self::from_list_tools_args(list_tools_args, &file_config)
+            }
+            Command::Fmt(fmt_args) => Self::from_fmt_args(fmt_args, &file_config),
+            Command::Completions(completions_args) => Self::from_completions_args(completions_args),
         }
     }
 
     fn from_run_args(args: RunArgs, file_config: &FileConfig) -> Self {
         let program_source = Self::merge_program_source(&args.file, &args.inline, file_config);
         let mcp_servers = Self::merge_mcp_servers(&args.mcp_server, file_config);
-        let gemini_api_key = args
-            .gemini_api_key
-            .or_else(|| file_config.gemini_api_key.clone());
-        let gemini_model = args
-            .gemini_model
-            .or_else(|| file_config.gemini_model.clone());
-        let engine = Self::merge_engine(&args.engine, file_config, gemini_api_key, gemini_model);
+        let eager_connect = args.eager_connect || file_config.eager_connect.unwrap_or(false);
+        let relaxed_signature_matching = args.relaxed_signature_matching
+            || file_config.relaxed_signature_matching.unwrap_or(false);
+        let confirm_destructive =
+            args.confirm_destructive || file_config.confirm_destructive.unwrap_or(false);
+        let gemini = Self::merge_gemini_settings(args.gemini, file_config);
+        let engine = Self::merge_engine(&args.engine, file_config, gemini);
         let with_default_functions =
             args.with_default_functions || file_config.with_default_functions.unwrap_or(false);
         let with_unstable_functions =
@@ -81,19 +126,45 @@ impl Config {
             with_default_functions,
             with_unstable_functions,
             with_acp_functions,
-            mode: Mode::Run,
+            describe_bindings: args.describe_bindings,
+            analysis_stats: false,
+            check_providers: false,
+            disabled_analyzers: Vec::new(),
+            profile: args.profile,
+            audit_log: args.audit_log,
+            thought_updates: false,
+            eager_connect,
+            max_call_depth: args.max_depth.or(file_config.max_depth),
+            run_stats: args.stats,
+            relaxed_signature_matching,
+            confirm_destructive,
+            mode: if args.interactive {
+                Mode::Interactive
+            } else {
+                Mode::Run
+            },
         }
     }
 
     fn from_check_args(args: CheckArgs, file_config: &FileConfig) -> Self {
         let program_source = Self::merge_program_source(&args.file, &args.inline, file_config);
         let mcp_servers = Self::merge_mcp_servers(&args.mcp_server, file_config);
+        let eager_connect = args.eager_connect || file_config.eager_connect.unwrap_or(false);
+        let relaxed_signature_matching = args.relaxed_signature_matching
+            || file_config.relaxed_signature_matching.unwrap_or(false);
+        let confirm_destructive =
+            args.confirm_destructive || file_config.confirm_destructive.unwrap_or(false);
         let with_default_functions =
             args.with_default_functions || file_config.with_default_functions.unwrap_or(false);
         let with_unstable_functions =
             args.with_unstable_functions || file_config.with_unstable_functions.unwrap_or(false);
         let with_acp_functions =
             args.with_acp_functions || file_config.with_acp_functions.unwrap_or(false);
+        let disabled_analyzers = if args.disable_analyzer.is_empty() {
+            file_config.disabled_analyzers.clone().unwrap_or_default()
+        } else {
+            args.disable_analyzer
+        };
 
         Config {
             program_source,
@@ -102,26 +173,43 @@ impl Config {
             with_default_functions,
             with_unstable_functions,
             with_acp_functions,
-            mode: Mode::Check,
+            describe_bindings: args.describe_bindings,
+            analysis_stats: args.analysis_stats,
+            check_providers: args.with_providers,
+            disabled_analyzers,
+            profile: None,
+            audit_log: None,
+            thought_updates: false,
+            eager_connect,
+            max_call_depth: None,
+            run_stats: false,
+            relaxed_signature_matching,
+            confirm_destructive,
+            mode: if args.list_fills {
+                Mode::ListFills
+            } else {
+                Mode::Check
+            },
         }
     }
 
     fn from_acp_args(args: AcpArgs, file_config: &FileConfig) -> Self {
         let program_source = Self::merge_program_source(&args.file, &args.inline, file_config);
         let mcp_servers = Self::merge_mcp_servers(&args.mcp_server, file_config);
-        let gemini_api_key = args
-            .gemini_api_key
-            .or_else(|| file_config.gemini_api_key.clone());
-        let gemini_model = args
-            .gemini_model
-            .or_else(|| file_config.gemini_model.clone());
-        let engine = Self::merge_engine(&args.engine, file_config, gemini_api_key, gemini_model);
+        let eager_connect = args.eager_connect || file_config.eager_connect.unwrap_or(false);
+        let relaxed_signature_matching = args.relaxed_signature_matching
+            || file_config.relaxed_signature_matching.unwrap_or(false);
+        let confirm_destructive =
+            args.confirm_destructive || file_config.confirm_destructive.unwrap_or(false);
+        let gemini = Self::merge_gemini_settings(args.gemini, file_config);
+        let engine = Self::merge_engine(&args.engine, file_config, gemini);
         let with_default_functions =
             args.with_default_functions || file_config.with_default_functions.unwrap_or(false);
         let with_unstable_functions =
             args.with_unstable_functions || file_config.with_unstable_functions.unwrap_or(false);
         let with_acp_functions =
             args.with_acp_functions || file_config.with_acp_functions.unwrap_or(false);
+        let thought_updates = args.thought_updates || file_config.thought_updates.unwrap_or(false);
 
         Config {
             program_source,
@@ -130,10 +218,98 @@ impl Config {
             with_default_functions,
             with_unstable_functions,
             with_acp_functions,
+            describe_bindings: false,
+            analysis_stats: false,
+            check_providers: false,
+            disabled_analyzers: Vec::new(),
+            profile: None,
+            audit_log: None,
+            thought_updates,
+            eager_connect,
+            max_call_depth: args.max_depth.or(file_config.max_depth),
+            run_stats: false,
+            relaxed_signature_matching,
+            confirm_destructive,
             mode: Mode::Acp,
         }
     }
 
+    fn from_list_tools_args(args: ListToolsArgs, file_config: &FileConfig) -> Self {
+        let mcp_servers = Self::merge_mcp_servers(&args.mcp_server, file_config);
+
+        Config {
+            program_source: ProgramSource::Inline(String::new()),
+            mcp_servers,
+            engine: EngineType::Print,
+            with_default_functions: false,
+            with_unstable_functions: false,
+            with_acp_functions: false,
+            describe_bindings: false,
+            analysis_stats: false,
+            check_providers: false,
+            disabled_analyzers: Vec::new(),
+            profile: None,
+            audit_log: None,
+            thought_updates: false,
+            eager_connect: false,
+            max_call_depth: None,
+            run_stats: false,
+            relaxed_signature_matching: false,
+            confirm_destructive: false,
+            mode: Mode::ListTools,
+        }
+    }
+
+    fn from_fmt_args(args: FmtArgs, file_config: &FileConfig) -> Self {
+        let program_source = Self::merge_program_source(&args.file, &args.inline, file_config);
+
+        Config {
+            program_source,
+            mcp_servers: vec![],
+            engine: EngineType::Print,
+            with_default_functions: false,
+            with_unstable_functions: false,
+            with_acp_functions: false,
+            describe_bindings: false,
+            analysis_stats: false,
+            check_providers: false,
+            disabled_analyzers: Vec::new(),
+            profile: None,
+            audit_log: None,
+            thought_updates: false,
+            eager_connect: false,
+            max_call_depth: None,
+            run_stats: false,
+            relaxed_signature_matching: false,
+            confirm_destructive: false,
+            mode: Mode::Fmt { write: args.write },
+        }
+    }
+
+    fn from_completions_args(args: CompletionsArgs) -> Self {
+        Config {
+            program_source: ProgramSource::Inline(String::new()),
+            mcp_servers: vec![],
+            engine: EngineType::Print,
+            with_default_functions: false,
+            with_unstable_functions: false,
+            with_acp_functions: false,
+            describe_bindings: false,
+            analysis_stats: false,
+            check_providers: false,
+            disabled_analyzers: Vec::new(),
+            profile: None,
+            audit_log: None,
+            thought_updates: false,
+            eager_connect: false,
+            max_call_depth: None,
+            run_stats: false,
+            relaxed_signature_matching: false,
+            confirm_destructive: false,
+            mode: Mode::Completions(args.shell),
+        }
+    }
+
     fn load_file_config(path: &std::path::Path) -> FileConfig {
         let absolute_path = path.canonicalize().unwrap_or_else(|e| {
             eprintln!(
@@ -225,12 +401,44 @@ impl Config {
         }
     }
 
-    fn merge_engine(
-        engine: &str,
-        file_config: &FileConfig,
-        api_key: Option<String>,
-        model: Option<String>,
-    ) -> EngineType {
+    fn split_command(command: &str) -> Vec<String> {
+        command.split_whitespace().map(|s| s.to_string()).collect()
+    }
+
+    fn merge_gemini_settings(args: GeminiArgs, file_config: &FileConfig) -> GeminiSettings {
+        GeminiSettings {
+            api_key: args
+                .gemini_api_key
+                .or_else(|| file_config.gemini_api_key.clone()),
+            model: args
+                .gemini_model
+                .or_else(|| file_config.gemini_model.clone()),
+            fallback_models: if args.gemini_fallback_model.is_empty() {
+                file_config
+                    .gemini_fallback_models
+                    .clone()
+                    .unwrap_or_default()
+            } else {
+                args.gemini_fallback_model
+            },
+            preset: args
+                .gemini_preset
+                .or_else(|| file_config.gemini_preset.clone()),
+            token_command: args
+                .gemini_token_command
+                .or_else(|| file_config.gemini_token_command.clone())
+                .map(|command| Self::split_command(&command)),
+            token_ttl_secs: args.gemini_token_ttl.or(file_config.gemini_token_ttl),
+            message_metadata: args.gemini_message_metadata
+                || file_config.gemini_message_metadata.unwrap_or(false),
+            api_key_in_query: args.gemini_api_key_in_query
+                || file_config.gemini_api_key_in_query.unwrap_or(false),
+            deterministic: args.deterministic || file_config.deterministic.unwrap_or(false),
+            seed: args.seed.or(file_config.seed),
+        }
+    }
+
+    fn merge_engine(engine: &str, file_config: &FileConfig, gemini: GeminiSettings) -> EngineType {
         let engine_str = if engine != "print" {
             engine
         } else if let Some(engine) = &file_config.engine {
@@ -240,11 +448,96 @@ impl Config {
         };
 
         match engine_str {
-            "gemini" => EngineType::Gemini { api_key, model },
+            "gemini" => EngineType::Gemini(gemini),
             _ => EngineType::Print,
         }
     }
 
+    /// Checks everything that can be known before the runtime is built, so that
+    /// all problems are reported together rather than one failure at a time.
+    pub fn validate(&self) -> Result<(), Vec<ConfigError>> {
+        self.validate_with(&|name| env::var(name).ok())
+    }
+
+    fn validate_with(&self, vars: &dyn Fn(&str) -> Option<String>) -> Result<(), Vec<ConfigError>> {
+        let mut errors = Vec::new();
+
+        if let EngineType::Gemini(settings) = &self.engine {
+            Self::validate_gemini(settings, vars, &mut errors);
+        }
+
+        for server in &self.mcp_servers {
+            if server.command.trim().is_empty() {
+                errors.push(ConfigError::EmptyMcpCommand);
+            } else if !Self::command_exists(&server.command, vars) {
+                errors.push(ConfigError::McpCommandNotFound(server.command.clone()));
+            }
+        }
+
+        // An inline program from the config file has no file to write back to.
+        if matches!(self.mode, Mode::Fmt { write: true })
+            && matches!(self.program_source, ProgramSource::Inline(_))
+        {
+            errors.push(ConfigError::ConflictingFlags(
+                "--write",
+                "an inline program",
+            ));
+        }
+
+        let known_analyzers = crate::compiler::analyzer_names();
+        for name in &self.disabled_analyzers {
+            if !known_analyzers.contains(name) {
+                errors.push(ConfigError::UnknownAnalyzer(name.clone()));
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    fn validate_gemini(
+        settings: &GeminiSettings,
+        vars: &dyn Fn(&str) -> Option<String>,
+        errors: &mut Vec<ConfigError>,
+    ) {
+        for model in settings.model.iter().chain(&settings.fallback_models) {
+            if model.is_empty() || model.chars().any(char::is_whitespace) {
+                errors.push(ConfigError::InvalidModel(model.clone()));
+            }
+        }
+
+        if let Some(preset) = &settings.preset
+            && crate::gemini::types::Preset::from_name(preset).is_none()
+        {
+            errors.push(ConfigError::UnknownPreset(preset.clone()));
+        }
+
+        let has_api_key = settings.api_key.is_some() || vars("GEMINI_API_KEY").is_some();
+        let token_program = settings
+            .token_command
+            .as_ref()
+            .and_then(|command| command.first())
+            .map(String::as_str)
+            .unwrap_or(DEFAULT_TOKEN_PROGRAM);
+        let has_adc = VERTEX_PROJECT_VARS.iter().any(|var| vars(var).is_some())
+            || Self::command_exists(token_program, vars);
+        if !has_api_key && !has_adc {
+            errors.push(ConfigError::MissingGeminiCredentials);
+        }
+    }
+
+    fn command_exists(command: &str, vars: &dyn Fn(&str) -> Option<String>) -> bool {
+        if command.contains(std::path::MAIN_SEPARATOR) {
+            return Path::new(command).is_file();
+        }
+        vars("PATH").is_some_and(|path| {
+            env::split_paths(&path).any(|directory| directory.join(command).is_file())
+        })
+    }
+
     pub fn describe_source(&self) -> String {
         match &self.program_source {
             ProgramSource::File(path) => format!("Loading program from: {}", path),
@@ -252,3 +545,180 @@ impl Config {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn config(engine: EngineType, mcp_servers: Vec<McpServerConfig>) -> Config {
+        Config {
+            program_source: ProgramSource::Inline(String::new()),
+            mcp_servers,
+            engine,
+            with_default_functions: false,
+            with_unstable_functions: false,
+            with_acp_functions: false,
+            describe_bindings: false,
+            analysis_stats: false,
+            check_providers: false,
+            disabled_analyzers: Vec::new(),
+            profile: None,
+            audit_log: None,
+            thought_updates: false,
+            eager_connect: false,
+            max_call_depth: None,
+            run_stats: false,
+            relaxed_signature_matching: false,
+            confirm_destructive: false,
+            mode: Mode::Run,
+        }
+    }
+
+    fn validate_in(config: &Config, vars: &[(&str, &str)]) -> Result<(), Vec<ConfigError>> {
+        let vars: HashMap<String, String> = vars
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect();
+        config.validate_with(&|name| vars.get(name).cloned())
+    }
+
+    fn empty_command() -> McpServerConfig {
+        McpServerConfig {
+            command: String::new(),
+            args: vec![],
+        }
+    }
+
+    #[test]
+    fn test_gemini_without_key_or_adc_is_an_error() {
+        let config = config(EngineType::Gemini(GeminiSettings::default()), vec![]);
+
+        assert_eq!(
+            validate_in(&config, &[]),
+            Err(vec![ConfigError::MissingGeminiCredentials])
+        );
+    }
+
+    #[test]
+    fn test_gemini_with_key_from_environment_is_valid() {
+        let config = config(EngineType::Gemini(GeminiSettings::default()), vec![]);
+
+        assert_eq!(
+            validate_in(&config, &[("GEMINI_API_KEY", "secret")]),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn test_empty_mcp_command_is_an_error() {
+        let config = config(EngineType::Print, vec![empty_command()]);
+
+        assert_eq!(
+            validate_in(&config, &[]),
+            Err(vec![ConfigError::EmptyMcpCommand])
+        );
+    }
+
+    #[test]
+    fn test_all_problems_are_reported_together() {
+        let settings = GeminiSettings {
+            preset: Some("wild".to_string()),
+            ..GeminiSettings::default()
+        };
+        let config = config(
+            EngineType::Gemini(settings),
+            vec![
+                empty_command(),
+                McpServerConfig {
+                    command: "no-such-mcp-server".to_string(),
+                    args: vec![],
+                },
+            ],
+        );
+
+        assert_eq!(
+            validate_in(&config, &[("PATH", "")]),
+            Err(vec![
+                ConfigError::UnknownPreset("wild".to_string()),
+                ConfigError::MissingGeminiCredentials,
+                ConfigError::EmptyMcpCommand,
+                ConfigError::McpCommandNotFound("no-such-mcp-server".to_string()),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_seed_flag_overrides_config_file() {
+        let file_config = FileConfig {
+            seed: Some(7),
+            ..FileConfig::default()
+        };
+
+        let from_file = Config::merge_gemini_settings(GeminiArgs::default(), &file_config);
+        assert_eq!(from_file.seed, Some(7));
+
+        let args = GeminiArgs {
+            seed: Some(42),
+            ..GeminiArgs::default()
+        };
+        let from_flag = Config::merge_gemini_settings(args, &file_config);
+        assert_eq!(from_flag.seed, Some(42));
+    }
+
+    #[test]
+    fn test_relaxed_signature_matching_from_flag_or_config_file() {
+        use clap::Parser;
+
+        let args = |extra: &[&str]| {
+            RunArgs::parse_from(["run", "--inline", "fn main(): () {}"].iter().chain(extra))
+        };
+        let file_config = FileConfig {
+            relaxed_signature_matching: Some(true),
+            ..FileConfig::default()
+        };
+
+        assert!(
+            !Config::from_run_args(args(&[]), &FileConfig::default()).relaxed_signature_matching
+        );
+        assert!(Config::from_run_args(args(&[]), &file_config).relaxed_signature_matching);
+        assert!(
+            Config::from_run_args(
+                args(&["--relaxed-signature-matching"]),
+                &FileConfig::default()
+            )
+            .relaxed_signature_matching
+        );
+    }
+
+    #[test]
+    fn test_confirm_destructive_from_flag_or_config_file() {
+        use clap::Parser;
+
+        let args = |extra: &[&str]| {
+            AcpArgs::parse_from(["acp", "--inline", "fn main(): () {}"].iter().chain(extra))
+        };
+        let file_config = FileConfig {
+            confirm_destructive: Some(true),
+            ..FileConfig::default()
+        };
+
+        assert!(!Config::from_acp_args(args(&[]), &FileConfig::default()).confirm_destructive);
+        assert!(Config::from_acp_args(args(&[]), &file_config).confirm_destructive);
+        assert!(
+            Config::from_acp_args(args(&["--confirm-destructive"]), &FileConfig::default())
+                .confirm_destructive
+        );
+    }
+
+    #[test]
+    fn test_unknown_disabled_analyzer_is_an_error() {
+        let mut config = config(EngineType::Print, vec![]);
+        config.disabled_analyzers = vec!["variable_shadowing".to_string(), "spelling".to_string()];
+
+        assert_eq!(
+            validate_in(&config, &[]),
+            Err(vec![ConfigError::UnknownAnalyzer("spelling".to_string())])
+        );
+    }
+}
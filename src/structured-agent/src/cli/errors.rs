@@ -7,6 +7,46 @@ pub enum CliError {
     IoError(io::Error),
     McpError(String),
     RuntimeError(String),
+    InvalidConfig(Vec<ConfigError>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfigError {
+    InvalidModel(String),
+    UnknownPreset(String),
+    UnknownAnalyzer(String),
+    MissingGeminiCredentials,
+    EmptyMcpCommand,
+    McpCommandNotFound(String),
+    ConflictingFlags(&'static str, &'static str),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::InvalidModel(name) => write!(f, "Invalid Gemini model name '{}'", name),
+            ConfigError::UnknownPreset(name) => {
+                write!(f, "Unknown generation preset '{}'", name)
+            }
+            ConfigError::UnknownAnalyzer(name) => write!(
+                f,
+                "Unknown analyzer '{}' (known analyzers: {})",
+                name,
+                crate::compiler::analyzer_names().join(", ")
+            ),
+            ConfigError::MissingGeminiCredentials => write!(
+                f,
+                "The Gemini engine needs an API key (--gemini-api-key or GEMINI_API_KEY) or application default credentials"
+            ),
+            ConfigError::EmptyMcpCommand => write!(f, "MCP server command is empty"),
+            ConfigError::McpCommandNotFound(command) => {
+                write!(f, "MCP server command '{}' was not found", command)
+            }
+            ConfigError::ConflictingFlags(first, second) => {
+                write!(f, "{} cannot be combined with {}", first, second)
+            }
+        }
+    }
 }
 
 impl fmt::Display for CliError {
@@ -30,6 +70,13 @@ impl fmt::Display for CliError {
                     write!(f, "Execution error: {}", e)
                 }
             }
+            CliError::InvalidConfig(errors) => {
+                write!(f, "Invalid configuration:")?;
+                for error in errors {
+                    write!(f, "\n  - {}", error)?;
+                }
+                Ok(())
+            }
         }
     }
 }
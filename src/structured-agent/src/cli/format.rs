@@ -0,0 +1,235 @@
+use crate::compiler::{CodespanParser, CompilationUnit};
+use crate::diagnostics::DiagnosticManager;
+
+/// Parses `program` and prints it back through the AST's `Display`, which
+/// fixes indentation and spacing. Only comments above functions, parameters
+/// and statements survive the AST, so a program with a comment anywhere else,
+/// such as a trailing `//` comment, is refused rather than formatted without
+/// it.
+pub fn format_program(program: &CompilationUnit) -> Result<String, String> {
+    let mut diagnostics = DiagnosticManager::new();
+    let file_id = diagnostics.add_file(program.name().to_string(), program.source().to_string());
+    let module = CodespanParser::new().parse(program, file_id, diagnostics.reporter())?;
+
+    let formatted = if module.definitions.is_empty() {
+        String::new()
+    } else {
+        format!("{}\n", module)
+    };
+
+    let mut kept = comments(&formatted);
+    for comment in comments(program.source()) {
+        match kept.iter().position(|other| other.same_as(&comment)) {
+            Some(index) => {
+                kept.remove(index);
+            }
+            None => {
+                return Err(format!(
+                    "Formatting would drop the comment on line {}: {}",
+                    comment.line, comment.text
+                ));
+            }
+        }
+    }
+
+    Ok(formatted)
+}
+
+struct Comment<'a> {
+    line: usize,
+    text: &'a str,
+}
+
+impl Comment<'_> {
+    // The formatter may change the spacing after the marker, as in `#note`
+    // becoming `# note`, but nothing else.
+    fn same_as(&self, other: &Comment) -> bool {
+        fn split(text: &str) -> (&str, &str) {
+            let marker_len = if text.starts_with("//") {
+                2
+            } else {
+                text.len() - text.trim_start_matches('#').len()
+            };
+            (&text[..marker_len], text[marker_len..].trim())
+        }
+        split(self.text) == split(other.text)
+    }
+}
+
+// The `#` and `//` comments of `source`, each running to the end of its line.
+// Comment markers inside string literals are not comments.
+fn comments(source: &str) -> Vec<Comment<'_>> {
+    let mut comments = Vec::new();
+    let mut line = 1;
+    let mut rest = source;
+
+    while let Some(c) = rest.chars().next() {
+        let skipped = if let Some(string) = rest.strip_prefix("'''") {
+            3 + string_length(string, "'''")
+        } else if c == '"' {
+            1 + string_length(&rest[1..], "\"")
+        } else if c == '#' || rest.starts_with("//") {
+            let end = rest.find('\n').unwrap_or(rest.len());
+            comments.push(Comment {
+                line,
+                text: rest[..end].trim_end(),
+            });
+            end
+        } else {
+            c.len_utf8()
+        };
+        line += rest[..skipped].matches('\n').count();
+        rest = &rest[skipped..];
+    }
+
+    comments
+}
+
+// How far into `rest` the string literal closed by `quote` ends, past the
+// quote. An unclosed literal runs to the end of the source.
+fn string_length(rest: &str, quote: &str) -> usize {
+    let mut chars = rest.char_indices();
+    while let Some((index, c)) = chars.next() {
+        if c == '\\' {
+            chars.next();
+        } else if rest[index..].starts_with(quote) {
+            return index + quote.len();
+        }
+    }
+    rest.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn format(source: &str) -> String {
+        format_program(&CompilationUnit::from_string(source.to_string())).unwrap()
+    }
+
+    const CANONICAL: &str = r#"extern fn lookup(key: String): String
+extern const schema: String
+
+# pure
+## Picks a reply.
+fn reply(
+    # What the user asked
+    question: String,
+    tone: String
+): String {
+    # Look it up first
+    let found = lookup(question)
+    if is_some(found) {
+        while false {
+            "waiting"!
+        }
+    } else {
+        '''No answer
+  for this one.'''!
+    }
+    let choice = select {
+        draft(question) as d if true => d,
+        lookup(tone) as t => t
+    }
+    return if true { choice } else { schema }
+}
+"#;
+
+    #[test]
+    fn test_formatting_canonical_program_is_idempotent() {
+        assert_eq!(format(CANONICAL), CANONICAL);
+    }
+
+    #[test]
+    fn test_else_if_chain_stays_flat() {
+        let source = r#"fn pick(a: Boolean, b: Boolean): () {
+    if a {
+        "a"!
+    } else if b {
+        "b"!
+    } else {
+        # Neither matched
+        if a {
+            "never"!
+        }
+    }
+}
+"#;
+
+        assert_eq!(format(source), source);
+    }
+
+    #[test]
+    fn test_messy_whitespace_becomes_canonical() {
+        let messy = r#"
+extern fn lookup(key:String):String
+fn   main( ) :  String{
+      let x=lookup( "a" )
+  if true{
+ x!
+        }else{  "b"!  }
+   x
+}
+"#;
+
+        let formatted = format(messy);
+
+        assert_eq!(
+            formatted,
+            r#"extern fn lookup(key: String): String
+
+fn main(): String {
+    let x = lookup("a")
+    if true {
+        x!
+    } else {
+        "b"!
+    }
+    x
+}
+"#
+        );
+        assert_eq!(format(&formatted), formatted);
+    }
+
+    #[test]
+    fn test_program_with_comments_that_would_be_dropped_is_refused() {
+        let module_comment = r#"fn first(): () {}
+
+# Helpers below
+fn second(): () {}
+"#;
+        let trailing_comment = r#"fn main(): () {
+    "hi"! // greet
+}
+"#;
+
+        let format = |source: &str| format_program(&CompilationUnit::from_string(source.into()));
+
+        assert_eq!(
+            format(module_comment),
+            Err("Formatting would drop the comment on line 3: # Helpers below".to_string())
+        );
+        assert_eq!(
+            format(trailing_comment),
+            Err("Formatting would drop the comment on line 2: // greet".to_string())
+        );
+    }
+
+    #[test]
+    fn test_comment_markers_in_strings_are_kept() {
+        let source = r##"fn main(): () {
+    #tidy
+    "# not a comment // either"!
+    '''# nor
+this'''!
+}
+"##;
+
+        assert_eq!(
+            format(source),
+            source.replace("#tidy", "# tidy"),
+            "only the real comment is respaced"
+        );
+    }
+}
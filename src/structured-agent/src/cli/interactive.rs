@@ -0,0 +1,217 @@
+use crate::acp::agent::PromptMessage;
+use crate::acp::functions::{ReceiveFunction, TryReceiveFunction};
+use crate::cli::config::Config;
+use crate::compiler::CompilationUnit;
+use crate::runtime::{Context, ExpressionValue, Runtime, RuntimeError};
+use std::collections::VecDeque;
+use std::sync::Arc;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt};
+use tokio::sync::{Mutex, mpsc, oneshot};
+use tracing::debug;
+
+pub struct InteractiveSession {
+    runtime: Runtime,
+    prompt_tx: Option<mpsc::UnboundedSender<PromptMessage>>,
+    prompt_rx: Arc<Mutex<mpsc::UnboundedReceiver<PromptMessage>>>,
+    context: Option<Context>,
+}
+
+impl InteractiveSession {
+    pub async fn from_config(program: CompilationUnit, config: &Config) -> Result<Self, String> {
+        let (prompt_tx, prompt_rx) = mpsc::unbounded_channel();
+        let shared_rx = Arc::new(Mutex::new(prompt_rx));
+
+        let runtime = Runtime::builder(program)
+            .with_native_function(Arc::new(ReceiveFunction::new(shared_rx.clone())))
+            .with_native_function(Arc::new(TryReceiveFunction::new(shared_rx.clone())))
+            .from_config(config)
+            .await?;
+
+        Ok(Self {
+            runtime,
+            prompt_tx: Some(prompt_tx),
+            prompt_rx: shared_rx,
+            context: None,
+        })
+    }
+
+    #[cfg(test)]
+    pub fn new(program: CompilationUnit) -> Self {
+        let (prompt_tx, prompt_rx) = mpsc::unbounded_channel();
+        let shared_rx = Arc::new(Mutex::new(prompt_rx));
+
+        let runtime = Runtime::builder(program)
+            .with_native_function(Arc::new(ReceiveFunction::new(shared_rx.clone())))
+            .with_native_function(Arc::new(TryReceiveFunction::new(shared_rx.clone())))
+            .build();
+
+        Self {
+            runtime,
+            prompt_tx: Some(prompt_tx),
+            prompt_rx: shared_rx,
+            context: None,
+        }
+    }
+
+    #[cfg(test)]
+    pub fn context(&self) -> Option<&Context> {
+        self.context.as_ref()
+    }
+
+    // Lines are only read while a turn is blocked, so a line typed during a turn
+    // goes to `receive()` and a line typed between turns starts the next one.
+    pub async fn run<R, F>(&mut self, input: R, mut on_result: F) -> Result<(), RuntimeError>
+    where
+        R: AsyncBufRead + Unpin,
+        F: FnMut(&ExpressionValue),
+    {
+        let mut lines = input.lines();
+        let mut pending = VecDeque::new();
+
+        loop {
+            let runtime = &self.runtime;
+            let context = self.context.take();
+            let turn = async move {
+                match context {
+                    Some(context) => runtime.resume_with_context(context).await,
+                    None => runtime.run_with_context().await,
+                }
+            };
+            tokio::pin!(turn);
+
+            let (result, context) = loop {
+                tokio::select! {
+                    biased;
+                    output = &mut turn => break output?,
+                    line = lines.next_line(), if self.prompt_tx.is_some() => {
+                        match line.map_err(|e| {
+                            RuntimeError::ExecutionError(format!("Failed to read input: {}", e))
+                        })? {
+                            Some(line) => self.send_prompt(line),
+                            None => {
+                                debug!("Input closed while program was running");
+                                self.prompt_tx = None;
+                            }
+                        }
+                    }
+                }
+            };
+
+            on_result(&result);
+            self.context = Some(context);
+            self.take_unread_prompts(&mut pending).await;
+
+            let next_line = match pending.pop_front() {
+                Some(line) => Some(line),
+                None if self.prompt_tx.is_some() => lines.next_line().await.map_err(|e| {
+                    RuntimeError::ExecutionError(format!("Failed to read input: {}", e))
+                })?,
+                None => None,
+            };
+
+            let Some(line) = next_line else {
+                debug!("Input closed, ending interactive session");
+                return Ok(());
+            };
+
+            for unread in pending.drain(..) {
+                self.send_prompt(unread);
+            }
+
+            if let Some(context) = self.context.as_mut() {
+                context.add_event(ExpressionValue::String(line), None, None);
+            }
+        }
+    }
+
+    fn send_prompt(&self, content: String) {
+        if let Some(prompt_tx) = &self.prompt_tx {
+            let (response_tx, _response_rx) = oneshot::channel();
+            let _ = prompt_tx.send(PromptMessage {
+                content,
+                response_tx,
+            });
+        }
+    }
+
+    async fn take_unread_prompts(&self, pending: &mut VecDeque<String>) {
+        let mut rx = self.prompt_rx.lock().await;
+        while let Ok(message) = rx.try_recv() {
+            pending.push_back(message.content);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn events(session: &InteractiveSession) -> Vec<String> {
+        session
+            .context()
+            .expect("session should keep its context")
+            .iter_all_events()
+            .map(|event| event.content.format_for_llm())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_context_carries_over_between_turns() {
+        let program = CompilationUnit::from_string(
+            r#"
+fn main(): String {
+    "Assistant ready"!
+    return "ok"
+}
+"#
+            .to_string(),
+        );
+        let mut session = InteractiveSession::new(program);
+        let mut results = Vec::new();
+
+        session
+            .run(&b"hello\n"[..], |result| results.push(result.clone()))
+            .await
+            .unwrap();
+
+        assert_eq!(results.len(), 2);
+        assert_eq!(
+            events(&session),
+            vec!["Assistant ready", "hello", "Assistant ready"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_receive_reads_scripted_input() {
+        let program = CompilationUnit::from_string(
+            r#"
+extern fn receive(): String
+
+fn main(): String {
+    let message = receive()
+    message!
+    return message
+}
+"#
+            .to_string(),
+        );
+        let mut session = InteractiveSession::new(program);
+        let mut results = Vec::new();
+
+        session
+            .run(&b"first\nsecond\nthird\n"[..], |result| {
+                results.push(result.clone())
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(
+            results,
+            vec![
+                ExpressionValue::String("first".to_string()),
+                ExpressionValue::String("third".to_string()),
+            ]
+        );
+        assert_eq!(events(&session), vec!["first", "second", "third"]);
+    }
+}
@@ -2,6 +2,10 @@ mod app;
 mod args;
 pub mod config;
 mod errors;
+pub mod format;
+pub mod interactive;
+pub mod profile;
+pub mod tools;
 
 pub use app::App;
 pub use args::Args;
@@ -0,0 +1,207 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::span::{Attributes, Id};
+use tracing::{Subscriber, field};
+use tracing_subscriber::Layer;
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::registry::LookupSpan;
+
+/// Records how long each span stack was open, keyed by the folded stack
+/// (`run;call:work;engine:typed`). Wall time is used rather than time spent
+/// polling, since engine and tool calls mostly wait on I/O. Clones share the
+/// recorded timings, so one can be installed as a layer and another kept to
+/// write the report.
+#[derive(Clone, Default)]
+pub struct ProfileLayer {
+    stacks: Arc<Mutex<BTreeMap<String, Duration>>>,
+}
+
+struct SpanTiming {
+    frame: String,
+    opened_at: Instant,
+    children: Duration,
+}
+
+impl ProfileLayer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Time spent in each stack itself, excluding time spent in child spans.
+    pub fn stacks(&self) -> BTreeMap<String, Duration> {
+        self.stacks.lock().unwrap().clone()
+    }
+
+    /// One `stack microseconds` line per stack, as read by flamegraph tools.
+    pub fn folded(&self) -> String {
+        self.stacks()
+            .iter()
+            .map(|(stack, duration)| format!("{} {}\n", stack, duration.as_micros()))
+            .collect()
+    }
+
+    pub fn write_folded(&self, path: &Path) -> std::io::Result<()> {
+        std::fs::write(path, self.folded())
+    }
+}
+
+impl<S> Layer<S> for ProfileLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let mut visitor = FrameVisitor { detail: None };
+        attrs.record(&mut visitor);
+
+        let name = attrs.metadata().name();
+        let frame = match visitor.detail {
+            Some(detail) => format!("{}:{}", name, detail),
+            None => name.to_string(),
+        };
+
+        if let Some(span) = ctx.span(id) {
+            span.extensions_mut().insert(SpanTiming {
+                frame,
+                opened_at: Instant::now(),
+                children: Duration::ZERO,
+            });
+        }
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(&id) else {
+            return;
+        };
+
+        let (total, self_time) = match span.extensions().get::<SpanTiming>() {
+            Some(timing) => {
+                let total = timing.opened_at.elapsed();
+                (total, total.saturating_sub(timing.children))
+            }
+            None => return,
+        };
+
+        let stack = span
+            .scope()
+            .from_root()
+            .filter_map(|frame| {
+                frame
+                    .extensions()
+                    .get::<SpanTiming>()
+                    .map(|timing| timing.frame.clone())
+            })
+            .collect::<Vec<_>>()
+            .join(";");
+
+        if let Some(parent) = span.parent()
+            && let Some(timing) = parent.extensions_mut().get_mut::<SpanTiming>()
+        {
+            timing.children += total;
+        }
+
+        *self.stacks.lock().unwrap().entry(stack).or_default() += self_time;
+    }
+}
+
+// Labels a frame with the span's `function` or `operation` field so calls to
+// different functions get their own stacks.
+struct FrameVisitor {
+    detail: Option<String>,
+}
+
+impl field::Visit for FrameVisitor {
+    fn record_str(&mut self, field: &field::Field, value: &str) {
+        if matches!(field.name(), "function" | "operation") {
+            self.detail = Some(value.to_string());
+        }
+    }
+
+    fn record_debug(&mut self, field: &field::Field, value: &dyn std::fmt::Debug) {
+        if matches!(field.name(), "function" | "operation") {
+            self.detail = Some(format!("{:?}", value));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compiler::CompilationUnit;
+    use crate::runtime::{ExpressionValue, Runtime};
+    use crate::types::{NativeFunction, Parameter, Type};
+    use async_trait::async_trait;
+    use tracing_subscriber::layer::SubscriberExt;
+    use tracing_subscriber::util::SubscriberInitExt;
+
+    #[derive(Debug)]
+    struct SlowFunction {
+        parameters: Vec<Parameter>,
+        return_type: Type,
+    }
+
+    #[async_trait]
+    impl NativeFunction for SlowFunction {
+        fn name(&self) -> &str {
+            "slow"
+        }
+
+        fn parameters(&self) -> &[Parameter] {
+            &self.parameters
+        }
+
+        fn return_type(&self) -> &Type {
+            &self.return_type
+        }
+
+        async fn execute(&self, _args: Vec<ExpressionValue>) -> Result<ExpressionValue, String> {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+            Ok(ExpressionValue::Unit)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_run_records_run_and_call_spans() {
+        let program = r#"
+extern fn slow(): ()
+
+fn work(): () {
+    slow()
+}
+
+fn main(): () {
+    work()
+}
+"#;
+        let runtime = Runtime::builder(CompilationUnit::from_string(program.to_string()))
+            .with_native_function(Arc::new(SlowFunction {
+                parameters: vec![],
+                return_type: Type::unit(),
+            }))
+            .build();
+
+        let profile = ProfileLayer::new();
+        {
+            let _guard = tracing_subscriber::registry()
+                .with(profile.clone())
+                .set_default();
+            runtime.run().await.unwrap();
+        }
+
+        let stacks = profile.stacks();
+        assert!(stacks.contains_key("run"), "stacks: {:?}", stacks);
+        assert!(stacks.contains_key("run;call:work"), "stacks: {:?}", stacks);
+        let slow = stacks
+            .get("run;call:work;call:slow")
+            .unwrap_or_else(|| panic!("no span for slow: {:?}", stacks));
+        assert!(*slow >= Duration::from_millis(5));
+
+        let folded = profile.folded();
+        assert!(
+            folded
+                .lines()
+                .any(|line| line.starts_with("run;call:work;call:slow "))
+        );
+    }
+}
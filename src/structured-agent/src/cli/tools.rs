@@ -0,0 +1,120 @@
+use crate::cli::config::McpServerConfig;
+use crate::mcp::McpClient;
+use crate::types::{ExternalFunctionDefinition, FunctionProvider};
+
+#[derive(Debug)]
+pub struct ServerTools {
+    pub server: McpServerConfig,
+    pub result: Result<Vec<ExternalFunctionDefinition>, String>,
+}
+
+impl ServerTools {
+    pub fn describe_server(&self) -> String {
+        if self.server.args.is_empty() {
+            self.server.command.clone()
+        } else {
+            format!("{} {}", self.server.command, self.server.args.join(" "))
+        }
+    }
+}
+
+pub async fn list_server_tools(servers: &[McpServerConfig]) -> Vec<ServerTools> {
+    let mut results = Vec::new();
+
+    for server in servers {
+        let result = list_tools_for_server(server).await;
+        results.push(ServerTools {
+            server: server.clone(),
+            result,
+        });
+    }
+
+    results
+}
+
+async fn list_tools_for_server(
+    server: &McpServerConfig,
+) -> Result<Vec<ExternalFunctionDefinition>, String> {
+    let client = McpClient::new_stdio(&server.command, server.args.clone())
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let result = client.list_functions().await.map_err(|e| e.to_string());
+    let _ = client.shutdown().await;
+    result
+}
+
+pub fn format_definition(definition: &ExternalFunctionDefinition) -> String {
+    let mut output = String::new();
+
+    if let Some(doc) = &definition.documentation {
+        for line in doc.lines() {
+            output.push_str(&format!("## {}\n", line));
+        }
+    }
+
+    let params = definition
+        .parameters
+        .iter()
+        .map(|p| format!("{}: {}", p.name, p.param_type))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    output.push_str(&format!(
+        "extern fn {}({}): {}",
+        definition.name, params, definition.return_type
+    ));
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{Parameter, Type};
+
+    #[test]
+    fn test_format_definition_with_documentation() {
+        let definition = ExternalFunctionDefinition::new_with_docs(
+            "echo".to_string(),
+            vec![Parameter::new("message".to_string(), Type::string())],
+            Type::string(),
+            Some("Echoes the message back\nUseful for testing".to_string()),
+        );
+
+        assert_eq!(
+            format_definition(&definition),
+            "## Echoes the message back\n## Useful for testing\nextern fn echo(message: String): String"
+        );
+    }
+
+    #[test]
+    fn test_format_definition_without_documentation() {
+        let definition = ExternalFunctionDefinition::new("ping".to_string(), vec![], Type::unit());
+
+        assert_eq!(format_definition(&definition), "extern fn ping(): ()");
+    }
+
+    #[tokio::test]
+    async fn test_list_server_tools_reports_errors_per_server() {
+        let servers = vec![
+            McpServerConfig {
+                command: "nonexistent-mcp-server-a".to_string(),
+                args: vec![],
+            },
+            McpServerConfig {
+                command: "nonexistent-mcp-server-b".to_string(),
+                args: vec!["--flag".to_string()],
+            },
+        ];
+
+        let results = list_server_tools(&servers).await;
+
+        assert_eq!(results.len(), 2);
+        assert!(results.iter().all(|r| r.result.is_err()));
+        assert_eq!(results[0].describe_server(), "nonexistent-mcp-server-a");
+        assert_eq!(
+            results[1].describe_server(),
+            "nonexistent-mcp-server-b --flag"
+        );
+    }
+}
@@ -0,0 +1,36 @@
+use crate::typecheck::TypeError;
+use std::fmt;
+
+/// Why a program or expression failed to compile. Diagnostics for parse and
+/// type errors have already been emitted by the time one of these is returned.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CompileError {
+    Parse(String),
+    Type(TypeError),
+    EmptyProgram,
+    MissingMain,
+    Internal(String),
+}
+
+impl fmt::Display for CompileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CompileError::Parse(message) => write!(f, "{}", message),
+            CompileError::Type(type_error) => write!(f, "Type error: {}", type_error),
+            CompileError::EmptyProgram => write!(
+                f,
+                "No definitions found: the program is empty or contains only comments"
+            ),
+            CompileError::MissingMain => write!(f, "Missing `main` function"),
+            CompileError::Internal(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for CompileError {}
+
+impl From<TypeError> for CompileError {
+    fn from(type_error: TypeError) -> Self {
+        CompileError::Type(type_error)
+    }
+}
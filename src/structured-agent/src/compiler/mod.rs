@@ -1,20 +1,32 @@
+mod error;
 pub mod parser;
 
+pub use error::CompileError;
+
 use crate::analysis::{
-    AnalysisRunner, ConstantConditionAnalyzer, DuplicateInjectionAnalyzer, EmptyBlockAnalyzer,
-    EmptyFunctionAnalyzer, InfiniteLoopAnalyzer, OverwrittenValueAnalyzer,
-    PlaceholderOveruseAnalyzer, ReachabilityAnalyzer, RedundantSelectAnalyzer,
-    UnusedExpressionAnalyzer, UnusedReturnValueAnalyzer, UnusedVariableAnalyzer,
+    AnalysisReport, AnalysisRunner, Analyzer, ArgumentSwapAnalyzer, ConstantConditionAnalyzer,
+    DiscardedReturnAnalyzer, DuplicateInjectionAnalyzer, EmptyBlockAnalyzer, EmptyFunctionAnalyzer,
+    IdenticalBranchAnalyzer, InfiniteLoopAnalyzer, InlineableInjectionAnalyzer,
+    OverwrittenValueAnalyzer, PlaceholderOveruseAnalyzer, PureFunctionAnalyzer,
+    ReachabilityAnalyzer, RedundantSelectAnalyzer, UnusedExpressionAnalyzer,
+    UnusedParameterRebindingAnalyzer, UnusedReturnValueAnalyzer, UnusedVariableAnalyzer,
     VariableShadowingAnalyzer,
 };
-use crate::ast::{Definition, Module};
+use crate::ast::{self, Definition, Expression, Module, Statement};
+use crate::diagnostics::reporter::parse_error_diagnostic;
 use crate::diagnostics::{DiagnosticManager, DiagnosticReporter};
-use crate::typecheck::type_check_module;
-use crate::types::{ExecutableFunction, ExternalFunctionDefinition, FileId, Function};
+use crate::runtime::ExpressionValue;
+use crate::typecheck::{type_check_expression, type_check_module};
+use crate::types::{
+    ExecutableFunction, ExternalConstDefinition, ExternalFunctionDefinition, FileId, Function,
+    Span, Spanned,
+};
 
+use codespan_reporting::diagnostic::Diagnostic;
 use combine::Parser as CombineParser;
 use combine::stream::{easy, position};
-use std::collections::HashMap;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use tracing::{debug, error, warn};
 
 use crate::bytecode::BytecodeCompiler;
@@ -55,6 +67,18 @@ impl CompilationUnit {
     pub fn path(&self) -> Option<&str> {
         self.path.as_deref()
     }
+
+    /// A hash of the source, the same across processes and builds, so two
+    /// units can be told apart when both are named `main`. FNV-1a, 64 bits.
+    pub fn fingerprint(&self) -> String {
+        let hash = self
+            .source
+            .bytes()
+            .fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+                (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3)
+            });
+        format!("{:016x}", hash)
+    }
 }
 
 pub struct CodespanParser {}
@@ -72,50 +96,149 @@ impl CodespanParser {
         file_id: FileId,
         diagnostic_reporter: &DiagnosticReporter,
     ) -> Result<Module, String> {
+        self.parse_collecting(program, file_id)
+            .map_err(|diagnostics| {
+                for diagnostic in &diagnostics {
+                    if let Err(io_err) = diagnostic_reporter.emit_diagnostic(diagnostic) {
+                        eprintln!("Failed to emit diagnostic: {}", io_err);
+                    }
+                }
+                "Parse error".to_string()
+            })
+    }
+
+    /// Like [`CodespanParser::parse`], but returns the diagnostics for a
+    /// failed parse instead of emitting them.
+    pub fn parse_collecting(
+        &self,
+        program: &CompilationUnit,
+        file_id: FileId,
+    ) -> Result<Module, Vec<Diagnostic<FileId>>> {
         debug!("Parsing source code");
         let input = program.source();
+        let module = Self::parse_definitions(input, file_id)?;
+
+        debug!(
+            "Parser succeeded, found {} definitions",
+            module.definitions.len()
+        );
+        Ok(module)
+    }
+
+    // After a failure, parsing starts again at the next definition so that
+    // each broken definition gets its own diagnostic.
+    fn parse_definitions(input: &str, file_id: FileId) -> Result<Module, Vec<Diagnostic<FileId>>> {
+        let mut diagnostics = Vec::new();
+        let mut start = 0;
+
+        loop {
+            let stream = easy::Stream(position::Stream::with_positioner(
+                &input[start..],
+                parser::ByteOffsetPositioner::starting_at(start),
+            ));
+
+            match parser::parse_program(file_id).parse(stream) {
+                Ok((module, _)) if diagnostics.is_empty() => return Ok(module),
+                Ok(_) => return Err(diagnostics),
+                Err(e) => {
+                    diagnostics.push(Self::diagnose_parse_error(
+                        format!("{}", e),
+                        e.position,
+                        input,
+                        file_id,
+                    ));
+                    match parser::next_definition_start(input, start, e.position) {
+                        Some(next) => start = next,
+                        None => return Err(diagnostics),
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn parse_expression(
+        &self,
+        program: &CompilationUnit,
+        file_id: FileId,
+        diagnostic_reporter: &DiagnosticReporter,
+    ) -> Result<Expression, String> {
+        debug!("Parsing standalone expression");
+        let input = program.source();
         let stream = easy::Stream(position::Stream::with_positioner(
             input,
-            position::IndexPositioner::new(),
+            parser::ByteOffsetPositioner::default(),
         ));
 
-        let result = parser::parse_program(file_id).parse(stream);
-
-        match result {
-            Ok((module, _)) => {
-                debug!(
-                    "Parser succeeded, found {} definitions",
-                    module.definitions.len()
-                );
-                Ok(module)
-            }
-            Err(e) => {
-                let error_str = format!("{}", e);
-                let byte_offset = e.position;
-                error!("Parser error at position {}: {}", byte_offset, error_str);
-
-                let clean_message = error_str.lines().skip(1).collect::<Vec<_>>().join("\n");
-
-                if let Err(io_err) = diagnostic_reporter.emit_parse_error(
-                    file_id,
-                    &clean_message,
-                    Some((byte_offset, byte_offset + 1)),
-                ) {
-                    eprintln!("Failed to emit diagnostic: {}", io_err);
-                }
+        match parser::parse_standalone_expression().parse(stream) {
+            Ok((expression, _)) => Ok(expression),
+            Err(e) => Err(Self::report_parse_error(
+                format!("{}", e),
+                e.position,
+                input,
+                file_id,
+                diagnostic_reporter,
+            )),
+        }
+    }
 
-                Err("Parse error".to_string())
-            }
+    fn report_parse_error(
+        error_str: String,
+        byte_offset: usize,
+        source: &str,
+        file_id: FileId,
+        diagnostic_reporter: &DiagnosticReporter,
+    ) -> String {
+        let diagnostic = Self::diagnose_parse_error(error_str, byte_offset, source, file_id);
+        if let Err(io_err) = diagnostic_reporter.emit_diagnostic(&diagnostic) {
+            eprintln!("Failed to emit diagnostic: {}", io_err);
         }
+
+        "Parse error".to_string()
     }
+
+    // The label covers the whole character at the error, so it never ends
+    // inside a multi-byte character.
+    fn diagnose_parse_error(
+        error_str: String,
+        byte_offset: usize,
+        source: &str,
+        file_id: FileId,
+    ) -> Diagnostic<FileId> {
+        error!("Parser error at position {}: {}", byte_offset, error_str);
+
+        let clean_message = error_str.lines().skip(1).collect::<Vec<_>>().join("\n");
+        let width = source
+            .get(byte_offset..)
+            .and_then(|rest| rest.chars().next())
+            .map_or(1, char::len_utf8);
+
+        parse_error_diagnostic(
+            file_id,
+            &clean_message,
+            Some((byte_offset, byte_offset + width)),
+        )
+    }
+}
+
+/// Parses a program into its AST without type checking, analysing or
+/// building a [`Compiler`]. Nothing is printed: a failed parse returns its
+/// diagnostics, whose labels refer to `source` as file 0.
+pub fn parse_source(source: &str) -> Result<Module, Vec<Diagnostic<FileId>>> {
+    let program = CompilationUnit::from_string(source.to_string());
+    let files = crate::types::SourceFiles::new();
+    let file_id = files.add(program.name().to_string(), source.to_string());
+    CodespanParser::new().parse_collecting(&program, file_id)
 }
 
 #[derive(Debug)]
 pub struct CompiledProgram {
-    functions: HashMap<String, Box<dyn ExecutableFunction>>,
-    external_functions: HashMap<String, ExternalFunctionDefinition>,
+    functions: BTreeMap<String, Box<dyn ExecutableFunction>>,
+    external_functions: BTreeMap<String, ExternalFunctionDefinition>,
+    external_constants: BTreeMap<String, ExternalConstDefinition>,
+    constants: BTreeMap<String, ExpressionValue>,
     main_function: Option<String>,
     source_path: Option<String>,
+    analysis_report: Option<AnalysisReport>,
 }
 
 impl Default for CompiledProgram {
@@ -127,10 +250,13 @@ impl Default for CompiledProgram {
 impl CompiledProgram {
     pub fn new() -> Self {
         Self {
-            functions: HashMap::new(),
-            external_functions: HashMap::new(),
+            functions: BTreeMap::new(),
+            external_functions: BTreeMap::new(),
+            external_constants: BTreeMap::new(),
+            constants: BTreeMap::new(),
             main_function: None,
             source_path: None,
+            analysis_report: None,
         }
     }
 
@@ -143,6 +269,17 @@ impl CompiledProgram {
         self.source_path.as_deref()
     }
 
+    pub fn with_analysis_report(mut self, report: Option<AnalysisReport>) -> Self {
+        self.analysis_report = report;
+        self
+    }
+
+    /// Per-analyzer timings, present when compiled with
+    /// [`CompileOptions::with_analysis_stats`].
+    pub fn analysis_report(&self) -> Option<&AnalysisReport> {
+        self.analysis_report.as_ref()
+    }
+
     pub fn add_function(&mut self, function: Box<dyn ExecutableFunction>) {
         let name = Function::name(function.as_ref()).to_string();
         if name == "main" {
@@ -156,19 +293,36 @@ impl CompiledProgram {
         self.external_functions.insert(name, external_function);
     }
 
+    pub fn add_external_constant(&mut self, external_constant: ExternalConstDefinition) {
+        let name = external_constant.name.clone();
+        self.external_constants.insert(name, external_constant);
+    }
+
+    pub fn add_constant(&mut self, name: String, value: ExpressionValue) {
+        self.constants.insert(name, value);
+    }
+
     pub fn main_function(&self) -> Option<&Box<dyn ExecutableFunction>> {
         self.main_function
             .as_ref()
             .and_then(|name| self.functions.get(name))
     }
 
-    pub fn functions(&self) -> &HashMap<String, Box<dyn ExecutableFunction>> {
+    pub fn functions(&self) -> &BTreeMap<String, Box<dyn ExecutableFunction>> {
         &self.functions
     }
 
-    pub fn external_functions(&self) -> &HashMap<String, ExternalFunctionDefinition> {
+    pub fn external_functions(&self) -> &BTreeMap<String, ExternalFunctionDefinition> {
         &self.external_functions
     }
+
+    pub fn external_constants(&self) -> &BTreeMap<String, ExternalConstDefinition> {
+        &self.external_constants
+    }
+
+    pub fn constants(&self) -> &BTreeMap<String, ExpressionValue> {
+        &self.constants
+    }
 }
 
 pub fn compile_external_function(
@@ -187,18 +341,146 @@ pub fn compile_external_function(
     ))
 }
 
+pub fn compile_external_const(
+    ast_ext_const: &crate::ast::ExternalConst,
+) -> ExternalConstDefinition {
+    ExternalConstDefinition::new(
+        ast_ext_const.name.clone(),
+        convert_ast_type_to_type(&ast_ext_const.const_type),
+    )
+}
+
+pub fn compile_constant(ast_constant: &crate::ast::Constant) -> Result<ExpressionValue, String> {
+    match &ast_constant.value {
+        Expression::StringLiteral { value, .. } => Ok(ExpressionValue::String(value.clone())),
+        other => Err(format!(
+            "Constant {} must be a string literal, found {}",
+            ast_constant.name, other
+        )),
+    }
+}
+
 fn convert_ast_type_to_type(ast_type: &crate::ast::Type) -> Type {
-    match ast_type {
-        crate::ast::Type::Unit => Type::unit(),
-        crate::ast::Type::Boolean => Type::boolean(),
-        crate::ast::Type::String => Type::string(),
-        crate::ast::Type::List(inner) => Type::list(convert_ast_type_to_type(inner)),
-        crate::ast::Type::Option(inner) => Type::option(convert_ast_type_to_type(inner)),
+    Type::from(ast_type)
+}
+
+const EXPRESSION_FUNCTION_NAME: &str = "<expression>";
+
+// Standalone expressions are checked against what the runtime already knows,
+// so those signatures are presented to the type checker as extern declarations.
+fn declarations_module(
+    functions: &[ExternalFunctionDefinition],
+    constants: &[ExternalConstDefinition],
+    file_id: FileId,
+) -> Module {
+    let functions = functions.iter().map(|function| {
+        Definition::ExternalFunction(ast::ExternalFunction {
+            name: function.name.clone(),
+            parameters: function
+                .parameters
+                .iter()
+                .map(|p| ast::Parameter {
+                    name: p.name.clone(),
+                    param_type: ast::Type::from(&p.param_type),
+                    type_span: Span::dummy(),
+                    span: Span::dummy(),
+                    comment: None,
+                })
+                .collect(),
+            return_type: ast::Type::from(&function.return_type),
+            return_type_span: Span::dummy(),
+            span: Span::dummy(),
+        })
+    });
+    let constants = constants.iter().map(|constant| {
+        Definition::ExternalConst(ast::ExternalConst {
+            name: constant.name.clone(),
+            const_type: ast::Type::from(&constant.const_type),
+            type_span: Span::dummy(),
+            span: Span::dummy(),
+        })
+    });
+
+    Module {
+        definitions: functions.chain(constants).collect(),
+        span: Span::dummy(),
+        file_id,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CompileOptions {
+    pub run_analysis: bool,
+    pub analysis_stats: bool,
+    pub require_main: bool,
+}
+
+impl Default for CompileOptions {
+    fn default() -> Self {
+        Self {
+            run_analysis: true,
+            analysis_stats: false,
+            require_main: false,
+        }
+    }
+}
+
+impl CompileOptions {
+    pub fn with_analysis(mut self, run_analysis: bool) -> Self {
+        self.run_analysis = run_analysis;
+        self
+    }
+
+    pub fn with_analysis_stats(mut self, analysis_stats: bool) -> Self {
+        self.analysis_stats = analysis_stats;
+        self
+    }
+
+    /// Reject programs that cannot be run: ones with no definitions at all
+    /// and ones without a `main` function.
+    pub fn with_require_main(mut self, require_main: bool) -> Self {
+        self.require_main = require_main;
+        self
     }
 }
 
 pub struct Compiler {
     parser: CodespanParser,
+    analysis_runs: AtomicUsize,
+    disabled_analyzers: Vec<String>,
+}
+
+/// Every analyzer run after type checking, in the order they run.
+fn default_analyzers() -> Vec<Box<dyn Analyzer>> {
+    vec![
+        Box::new(UnusedVariableAnalyzer::new()),
+        Box::new(ReachabilityAnalyzer::new()),
+        Box::new(InfiniteLoopAnalyzer::new()),
+        Box::new(EmptyBlockAnalyzer::new()),
+        Box::new(EmptyFunctionAnalyzer::new()),
+        Box::new(DuplicateInjectionAnalyzer::new()),
+        Box::new(PlaceholderOveruseAnalyzer::new()),
+        Box::new(RedundantSelectAnalyzer::new()),
+        Box::new(ConstantConditionAnalyzer::new()),
+        Box::new(VariableShadowingAnalyzer::new()),
+        Box::new(OverwrittenValueAnalyzer::new()),
+        Box::new(UnusedReturnValueAnalyzer::new()),
+        Box::new(UnusedExpressionAnalyzer::new()),
+        Box::new(UnusedParameterRebindingAnalyzer::new()),
+        Box::new(ArgumentSwapAnalyzer::new()),
+        Box::new(InlineableInjectionAnalyzer::new()),
+        Box::new(PureFunctionAnalyzer::new()),
+        Box::new(IdenticalBranchAnalyzer::new()),
+        Box::new(DiscardedReturnAnalyzer::new()),
+    ]
+}
+
+/// The names analyzers can be disabled by.
+pub fn analyzer_names() -> Vec<String> {
+    default_analyzers()
+        .iter()
+        .map(|analyzer| analyzer.name().to_string())
+        .collect()
 }
 
 impl Default for Compiler {
@@ -210,12 +492,39 @@ impl Default for Compiler {
 impl Compiler {
     pub fn new() -> Self {
         let parser = CodespanParser::new();
-        Self { parser }
+        Self {
+            parser,
+            analysis_runs: AtomicUsize::new(0),
+            disabled_analyzers: Vec::new(),
+        }
+    }
+
+    /// Skips the analyzers with these names. Names that match no analyzer
+    /// are ignored; see [`analyzer_names`] for the ones that exist.
+    pub fn with_disabled_analyzers(mut self, names: Vec<String>) -> Self {
+        self.disabled_analyzers = names;
+        self
+    }
+
+    #[cfg(test)]
+    pub fn analysis_runs(&self) -> usize {
+        self.analysis_runs.load(Ordering::SeqCst)
     }
 }
 
 impl Compiler {
-    pub fn compile_program(&self, program: &CompilationUnit) -> Result<CompiledProgram, String> {
+    pub fn compile_program(
+        &self,
+        program: &CompilationUnit,
+    ) -> Result<CompiledProgram, CompileError> {
+        self.compile_program_with_options(program, CompileOptions::default())
+    }
+
+    pub fn compile_program_with_options(
+        &self,
+        program: &CompilationUnit,
+        options: CompileOptions,
+    ) -> Result<CompiledProgram, CompileError> {
         debug!("Compiling program: {}", program.name());
         debug!("Source length: {} bytes", program.source().len());
 
@@ -234,56 +543,45 @@ impl Compiler {
             }
             Err(e) => {
                 error!("Parsing failed: {}", e);
-                return Err(e);
+                return Err(CompileError::Parse(e));
             }
         };
 
+        if options.require_main
+            && let Err(e) = Self::check_entry_point(&module, &reporter)
+        {
+            error!("{}", e);
+            return Err(e);
+        }
+
         debug!("Starting type checking");
         if let Err(type_error) = type_check_module(&module, file_id) {
             error!("Type checking failed: {}", type_error);
             if let Err(io_err) = reporter.emit_type_error(&type_error) {
                 eprintln!("Failed to emit type error diagnostic: {}", io_err);
             }
-            return Err(format!("Type error: {}", type_error));
+            return Err(CompileError::Type(type_error));
         }
         debug!("Type checking completed successfully");
 
-        let mut runner = AnalysisRunner::new()
-            .with_analyzer(Box::new(UnusedVariableAnalyzer::new()))
-            .with_analyzer(Box::new(ReachabilityAnalyzer::new()))
-            .with_analyzer(Box::new(InfiniteLoopAnalyzer::new()))
-            .with_analyzer(Box::new(EmptyBlockAnalyzer::new()))
-            .with_analyzer(Box::new(EmptyFunctionAnalyzer::new()))
-            .with_analyzer(Box::new(DuplicateInjectionAnalyzer::new()))
-            .with_analyzer(Box::new(PlaceholderOveruseAnalyzer::new()))
-            .with_analyzer(Box::new(RedundantSelectAnalyzer::new()))
-            .with_analyzer(Box::new(ConstantConditionAnalyzer::new()))
-            .with_analyzer(Box::new(VariableShadowingAnalyzer::new()))
-            .with_analyzer(Box::new(OverwrittenValueAnalyzer::new()))
-            .with_analyzer(Box::new(UnusedReturnValueAnalyzer::new()))
-            .with_analyzer(Box::new(UnusedExpressionAnalyzer::new()));
-
-        debug!("Running analysis");
-        let warnings = runner.run(&module, file_id);
-        if !warnings.is_empty() {
-            warn!("Analysis found {} warnings", warnings.len());
-        }
-        for warning in &warnings {
-            debug!("Warning: {:?}", warning);
-            if let Err(io_err) = reporter.emit_diagnostic(&warning.to_diagnostic()) {
-                eprintln!("Failed to emit warning diagnostic: {}", io_err);
-            }
-        }
+        let analysis_report = if options.run_analysis {
+            self.run_analysis(&module, file_id, &reporter, options.analysis_stats)
+        } else {
+            debug!("Skipping analysis");
+            None
+        };
 
-        let mut compiled_program =
-            CompiledProgram::new().with_source_path(program.path().map(String::from));
+        let mut compiled_program = CompiledProgram::new()
+            .with_source_path(program.path().map(String::from))
+            .with_analysis_report(analysis_report);
 
         debug!("Compiling definitions");
         for definition in module.definitions {
             match definition {
                 Definition::Function(ast_function) => {
                     debug!("Compiling function: {}", ast_function.name);
-                    let func_expr = BytecodeCompiler::compile_function(&ast_function)?;
+                    let func_expr = BytecodeCompiler::compile_function(&ast_function)
+                        .map_err(CompileError::Internal)?;
                     compiled_program.add_function(func_expr);
                 }
                 Definition::ExternalFunction(ast_external_function) => {
@@ -300,22 +598,161 @@ impl Compiler {
                                 "Failed to compile external function {}: {}",
                                 ast_external_function.name, e
                             );
-                            return Err(e);
+                            return Err(CompileError::Internal(e));
                         }
                     }
                 }
+                Definition::ExternalConst(ast_external_const) => {
+                    debug!("Compiling external const: {}", ast_external_const.name);
+                    compiled_program
+                        .add_external_constant(compile_external_const(&ast_external_const));
+                }
+                Definition::Constant(ast_constant) => {
+                    debug!("Compiling const: {}", ast_constant.name);
+                    let value = compile_constant(&ast_constant).map_err(CompileError::Internal)?;
+                    compiled_program.add_constant(ast_constant.name, value);
+                }
             }
         }
 
         debug!("Compilation completed successfully");
         Ok(compiled_program)
     }
+
+    pub fn compile_expression(
+        &self,
+        source: &str,
+        functions: &[ExternalFunctionDefinition],
+        constants: &[ExternalConstDefinition],
+    ) -> Result<Box<dyn ExecutableFunction>, CompileError> {
+        debug!("Compiling standalone expression");
+        let program = CompilationUnit::from_string(source.to_string());
+
+        let mut diagnostic_manager = DiagnosticManager::new();
+        let file_id =
+            diagnostic_manager.add_file(program.name().to_string(), program.source().to_string());
+        let reporter = diagnostic_manager.reporter().clone();
+
+        let expression = self
+            .parser
+            .parse_expression(&program, file_id, &reporter)
+            .map_err(CompileError::Parse)?;
+
+        let declarations = declarations_module(functions, constants, file_id);
+        let return_type = match type_check_expression(&declarations, &expression, file_id) {
+            Ok(return_type) => return_type,
+            Err(type_error) => {
+                error!("Type checking failed: {}", type_error);
+                if let Err(io_err) = reporter.emit_type_error(&type_error) {
+                    eprintln!("Failed to emit type error diagnostic: {}", io_err);
+                }
+                return Err(CompileError::Type(type_error));
+            }
+        };
+
+        let span = expression.span();
+        let function = ast::Function {
+            name: EXPRESSION_FUNCTION_NAME.to_string(),
+            parameters: vec![],
+            return_type,
+            return_type_span: Span::dummy(),
+            body: ast::FunctionBody {
+                statements: vec![Statement::Return {
+                    expression,
+                    span,
+                    comment: None,
+                }],
+                span,
+            },
+            documentation: None,
+            pure: false,
+            span,
+        };
+
+        BytecodeCompiler::compile_function(&function).map_err(CompileError::Internal)
+    }
+
+    fn check_entry_point(
+        module: &Module,
+        reporter: &DiagnosticReporter,
+    ) -> Result<(), CompileError> {
+        let (error, diagnostic) = if module.definitions.is_empty() {
+            (
+                CompileError::EmptyProgram,
+                Diagnostic::error()
+                    .with_message("no definitions found")
+                    .with_notes(vec![
+                        "the program is empty or contains only comments".to_string(),
+                    ]),
+            )
+        } else if !module.definitions.iter().any(
+            |definition| matches!(definition, Definition::Function(func) if func.name == "main"),
+        ) {
+            (
+                CompileError::MissingMain,
+                Diagnostic::error()
+                    .with_message("missing `main` function")
+                    .with_notes(vec!["programs start running at `fn main()`".to_string()]),
+            )
+        } else {
+            return Ok(());
+        };
+
+        if let Err(io_err) = reporter.emit_diagnostic(&diagnostic) {
+            eprintln!("Failed to emit diagnostic: {}", io_err);
+        }
+        Err(error)
+    }
+
+    fn run_analysis(
+        &self,
+        module: &Module,
+        file_id: FileId,
+        reporter: &DiagnosticReporter,
+        collect_stats: bool,
+    ) -> Option<AnalysisReport> {
+        self.analysis_runs.fetch_add(1, Ordering::SeqCst);
+
+        let mut runner = AnalysisRunner::new();
+        for analyzer in default_analyzers() {
+            if self
+                .disabled_analyzers
+                .iter()
+                .any(|name| name == analyzer.name())
+            {
+                debug!("Skipping disabled analyzer: {}", analyzer.name());
+                continue;
+            }
+            runner = runner.with_analyzer(analyzer);
+        }
+
+        debug!("Running analysis");
+        let (warnings, report) = if collect_stats {
+            let report = runner.run_with_report(module, file_id);
+            (report.warnings.clone(), Some(report))
+        } else {
+            (runner.run(module, file_id), None)
+        };
+        if !warnings.is_empty() {
+            warn!("Analysis found {} warnings", warnings.len());
+        }
+        for warning in &warnings {
+            debug!("Warning: {:?}", warning);
+            if let Err(io_err) = reporter.emit_diagnostic(&warning.to_diagnostic()) {
+                eprintln!("Failed to emit warning diagnostic: {}", io_err);
+            }
+        }
+        report
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{CompilationUnit, Compiler};
+    use super::{CompilationUnit, CompileError, CompileOptions, Compiler};
+    use crate::analysis::Warning;
     use crate::runtime::{ExpressionValue, Runtime};
+    use crate::typecheck::TypeError;
+    use std::sync::Arc;
 
     async fn run_test_with_compiler(program_source: &str, expected: &str) {
         let program = CompilationUnit::from_string(program_source.to_string());
@@ -347,6 +784,73 @@ fn main(): String {
         run_test_with_compiler(program_source, "Test completed").await;
     }
 
+    #[tokio::test]
+    async fn test_else_if_chain_end_to_end() {
+        let classify = r#"
+fn classify(first: Boolean, second: Boolean): String {
+    if first {
+        return "first"
+    } else if second {
+        return "second"
+    } else {
+        return "neither"
+    }
+}
+"#;
+        for (arguments, expected) in [
+            ("true, true", "first"),
+            ("false, true", "second"),
+            ("false, false", "neither"),
+        ] {
+            let program_source = format!(
+                "{}\nfn main(): String {{\n    return classify({})\n}}\n",
+                classify, arguments
+            );
+            run_test_with_compiler(&program_source, expected).await;
+        }
+    }
+
+    #[tokio::test]
+    async fn test_match_expression_end_to_end() {
+        let program_source = r#"
+fn label(flag: Boolean): String {
+    return match flag {
+        true => "on",
+        false => "off",
+    }
+}
+
+fn main(): String {
+    let on = label(true)
+    return match false {
+        true => on,
+        false => label(false),
+    }
+}
+"#;
+        run_test_with_compiler(program_source, "off").await;
+    }
+
+    #[tokio::test]
+    async fn test_list_literal_end_to_end() {
+        let program_source = r#"
+fn keep(items: List<String>): List<String> {
+    return items
+}
+
+fn main(): List<String> {
+    let empty = keep([])
+    let second = "b"
+    return keep(["a", second])
+}
+"#;
+        let program = CompilationUnit::from_string(program_source.to_string());
+        let result = Runtime::builder(program).build().run().await.unwrap();
+
+        assert!(matches!(result, ExpressionValue::List(_)));
+        assert_eq!(result.to_string(), r#"["a", "b"]"#);
+    }
+
     #[tokio::test]
     async fn test_select_statement_end_to_end() {
         let program_source = r#"
@@ -373,11 +877,30 @@ fn main(): String {
 "#;
         run_test_with_compiler(
             program_source,
-            "<calculator>\n    <param name=\"x\">5</param>\n    <param name=\"y\">3</param>\n    <result>\n    ## calculator\n    </result>\n</calculator>",
+            "<calculator>\n    <param name=\"x\">5</param>\n    <param name=\"y\">3</param>\n    <result>\n    Adding numbers\n    </result>\n</calculator>",
         )
         .await;
     }
 
+    #[tokio::test]
+    async fn test_trailing_expression_is_implicit_return() {
+        let program_source = r#"
+fn pick(flag: Boolean): String {
+    "deciding"!
+    if flag {
+        "inside"!
+    }
+    "picked"
+}
+
+fn main(): String {
+    let value = pick(true)
+    return value
+}
+"#;
+        run_test_with_compiler(program_source, "picked").await;
+    }
+
     #[test]
     fn test_control_flow_analysis_warnings() {
         let program_source = r#"
@@ -474,4 +997,232 @@ fn main(): String {
         )
         .await;
     }
+
+    #[test]
+    fn test_compile_without_analysis_skips_analyzers() {
+        let program = CompilationUnit::from_string(
+            r#"
+fn main(): () {
+    let unused = "value"
+}
+"#
+            .to_string(),
+        );
+        let compiler = Compiler::new();
+
+        let options = CompileOptions::default().with_analysis(false);
+        assert!(
+            compiler
+                .compile_program_with_options(&program, options)
+                .is_ok()
+        );
+        assert_eq!(compiler.analysis_runs(), 0);
+
+        assert!(compiler.compile_program(&program).is_ok());
+        assert_eq!(compiler.analysis_runs(), 1);
+    }
+
+    #[test]
+    fn test_disabled_analyzer_does_not_run() {
+        let program = CompilationUnit::from_string(
+            r#"
+fn helper(): () {
+}
+
+fn main(): () {
+    let unused = "value"
+}
+"#
+            .to_string(),
+        );
+        let compiler =
+            Compiler::new().with_disabled_analyzers(vec!["unused-variables".to_string()]);
+
+        let options = CompileOptions::default().with_analysis_stats(true);
+        let compiled = compiler
+            .compile_program_with_options(&program, options)
+            .unwrap();
+        let report = compiled.analysis_report().unwrap();
+
+        assert!(
+            !report
+                .warnings
+                .iter()
+                .any(|warning| matches!(warning, Warning::UnusedVariable { .. }))
+        );
+        assert!(
+            report
+                .warnings
+                .iter()
+                .any(|warning| matches!(warning, Warning::EmptyFunction { .. }))
+        );
+        assert!(
+            !report
+                .timings
+                .iter()
+                .any(|timing| timing.name == "unused-variables")
+        );
+    }
+
+    #[test]
+    fn test_diagnostic_column_counts_characters_after_multibyte_text() {
+        use codespan_reporting::files::{Files, SimpleFiles};
+
+        let source = "fn main(): () {\n    \"héllo 🎉\"! \"again\"!\n}\n";
+        let program = CompilationUnit::from_string(source.to_string());
+        let mut files = SimpleFiles::new();
+        let file_id = files.add("test.sa".to_string(), source.to_string());
+
+        let diagnostics = super::CodespanParser::new()
+            .parse_collecting(&program, file_id)
+            .unwrap_err();
+
+        let range = diagnostics[0].labels[0].range.clone();
+        assert_eq!(&source[range.clone()], "\"");
+        let location = files.location(file_id, range.start).unwrap();
+        assert_eq!((location.line_number, location.column_number), (2, 16));
+    }
+
+    #[test]
+    fn test_out_of_range_unicode_escape_is_reported_at_the_escape() {
+        let source = "fn main(): () {\n    \"é \\u{110000}\"!\n}\n";
+        let program = CompilationUnit::from_string(source.to_string());
+
+        let diagnostics = super::CodespanParser::new()
+            .parse_collecting(&program, 0)
+            .unwrap_err();
+
+        let label = &diagnostics[0].labels[0];
+        assert!(
+            label.message.contains("not a valid code point"),
+            "{}",
+            label.message
+        );
+        assert_eq!(label.range.start, source.find('\\').unwrap());
+    }
+
+    #[test]
+    fn test_parse_source_reports_unterminated_string_as_diagnostic() {
+        let source = "fn main(): () {\n    \"never closed\n}\n";
+
+        let diagnostics = super::parse_source(source).unwrap_err();
+
+        assert_eq!(diagnostics.len(), 1);
+        let label = &diagnostics[0].labels[0];
+        assert_eq!(label.range.start, source.find('"').unwrap());
+        assert!(
+            label.message.contains("unterminated string literal"),
+            "{}",
+            label.message
+        );
+    }
+
+    #[test]
+    fn test_each_broken_definition_reports_its_own_parse_error() {
+        let source = r#"fn first(: () {
+}
+
+fn fine(): () {
+    "ok"!
+}
+
+fn second(): () {
+    let = "x"
+}
+"#;
+        let program = CompilationUnit::from_string(source.to_string());
+
+        let diagnostics = super::CodespanParser::new()
+            .parse_collecting(&program, 0)
+            .unwrap_err();
+
+        let starts: Vec<usize> = diagnostics
+            .iter()
+            .map(|diagnostic| diagnostic.labels[0].range.start)
+            .collect();
+        assert_eq!(
+            starts,
+            vec![source.find(':').unwrap(), source.find("= ").unwrap()]
+        );
+    }
+
+    #[test]
+    fn test_malformed_program_is_a_parse_error() {
+        let program = CompilationUnit::from_string("fn main(: () {".to_string());
+
+        let result = Compiler::new().compile_program(&program);
+
+        assert!(matches!(result, Err(CompileError::Parse(_))));
+    }
+
+    #[test]
+    fn test_type_mismatch_is_a_type_error() {
+        let program = CompilationUnit::from_string(
+            r#"
+fn main(): String {
+    return true
+}
+"#
+            .to_string(),
+        );
+
+        let result = Compiler::new().compile_program(&program);
+
+        match result {
+            Err(CompileError::Type(TypeError::ReturnTypeMismatch { .. })) => {}
+            other => panic!("Expected a return type mismatch, got {:?}", other.err()),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_run_skips_analysis_and_check_runs_it() {
+        let program = CompilationUnit::from_string(
+            r#"
+fn main(): () {
+    let unused = "value"
+}
+"#
+            .to_string(),
+        );
+        let compiler = Arc::new(Compiler::new());
+        let runtime = Runtime::builder(program)
+            .with_compiler(compiler.clone())
+            .build();
+
+        runtime.run().await.unwrap();
+        assert_eq!(compiler.analysis_runs(), 0);
+
+        runtime.check().unwrap();
+        assert_eq!(compiler.analysis_runs(), 1);
+    }
+
+    async fn run_error(source: &str) -> String {
+        let program = CompilationUnit::from_string(source.to_string());
+        let runtime = Runtime::builder(program).build();
+        runtime.run().await.unwrap_err().to_string()
+    }
+
+    #[tokio::test]
+    async fn test_empty_program_reports_no_definitions() {
+        let error = run_error("").await;
+        assert!(error.contains("No definitions found"), "got: {error}");
+    }
+
+    #[tokio::test]
+    async fn test_comments_only_program_reports_no_definitions() {
+        let error = run_error("# nothing here yet\n\n## not even docs\n").await;
+        assert!(error.contains("No definitions found"), "got: {error}");
+    }
+
+    #[tokio::test]
+    async fn test_program_without_main_reports_missing_main() {
+        let error = run_error(
+            r#"
+fn helper(): () {
+}
+"#,
+        )
+        .await;
+        assert!(error.contains("Missing `main` function"), "got: {error}");
+    }
 }
@@ -1,20 +1,74 @@
 use crate::ast::{
-    Definition, Expression, ExternalFunction, Function, FunctionBody, Module, Parameter,
-    SelectClause, SelectExpression, Statement, Type,
+    Constant, Definition, Expression, ExternalConst, ExternalFunction, Function, FunctionBody,
+    MatchArm, Module, Parameter, SelectClause, SelectExpression, Statement, Type,
 };
 use crate::types::{FileId, Span, Spanned};
-use combine::parser::char::{char, letter, newline, spaces, string};
+use combine::error::{Commit, ParseError, StreamError, Tracked};
+use combine::parser::char::{char, digit, letter, newline, space, spaces, string};
 use combine::parser::choice::choice;
-use combine::parser::repeat::{many, many1, sep_by, skip_many};
+use combine::parser::repeat::{many, many1, sep_by, sep_end_by, skip_many, skip_many1};
 use combine::parser::token::satisfy;
-use combine::{Parser, Stream, attempt, between, optional, position};
+use combine::stream::StreamErrorFor;
+use combine::stream::position::{Positioner, RangePositioner};
+use combine::{Parser, Stream, attempt, between, eof, not_followed_by, optional, position};
+
+/// Positions source as byte offsets, which is what [`Span`]s and the
+/// diagnostics built from them hold. combine's `IndexPositioner` counts
+/// chars, which drifts from the byte offset after any non-ASCII character;
+/// codespan turns byte offsets into character-based columns when reporting.
+#[derive(Debug, Clone, Default)]
+pub struct ByteOffsetPositioner(usize);
+
+impl ByteOffsetPositioner {
+    /// Positions a slice of the source that begins `offset` bytes in.
+    pub fn starting_at(offset: usize) -> Self {
+        Self(offset)
+    }
+}
+
+impl Positioner<char> for ByteOffsetPositioner {
+    type Position = usize;
+    type Checkpoint = Self;
+
+    fn position(&self) -> usize {
+        self.0
+    }
+
+    fn update(&mut self, token: &char) {
+        self.0 += token.len_utf8();
+    }
+
+    fn checkpoint(&self) -> Self {
+        self.clone()
+    }
+
+    fn reset(&mut self, checkpoint: Self) {
+        *self = checkpoint;
+    }
+}
+
+impl<'a> RangePositioner<char, &'a str> for ByteOffsetPositioner {
+    fn update_range(&mut self, range: &&'a str) {
+        self.0 += range.len();
+    }
+}
 
 fn skip_spaces<Input>() -> impl Parser<Input, Output = ()>
 where
     Input: Stream<Token = char, Position = usize>,
     Input::Error: combine::ParseError<Input::Token, Input::Range, Input::Position>,
 {
-    spaces().silent()
+    skip_many(choice((space().map(|_| ()), line_comment()))).silent()
+}
+
+// `//` comments are notes for whoever reads the source. Unlike `#` comments
+// they are never kept as documentation, so they may go anywhere whitespace can.
+fn line_comment<Input>() -> impl Parser<Input, Output = ()>
+where
+    Input: Stream<Token = char, Position = usize>,
+    Input::Error: combine::ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    attempt(string("//")).with(skip_many(satisfy(|c| c != '\n')))
 }
 
 fn lex_char<Input>(c: char) -> impl Parser<Input, Output = char>
@@ -65,28 +119,116 @@ where
     Input: Stream<Token = char, Position = usize>,
     Input::Error: combine::ParseError<Input::Token, Input::Range, Input::Position>,
 {
-    optional(many1(doc_comment_line()))
+    optional(many1(doc_comment_line().skip(skip_spaces())))
         .map(|comments: Option<Vec<String>>| comments.map(|lines| lines.join("\n")))
 }
 
+fn parse_preceding_comments<Input>() -> impl Parser<Input, Output = Option<String>>
+where
+    Input: Stream<Token = char, Position = usize>,
+    Input::Error: combine::ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    many(comment_line().skip(skip_spaces())).map(|comments: Vec<String>| {
+        if comments.is_empty() {
+            None
+        } else {
+            Some(comments.join("\n"))
+        }
+    })
+}
+
 fn skip_spaces_and_comments<Input>() -> impl Parser<Input, Output = ()>
 where
     Input: Stream<Token = char, Position = usize>,
     Input::Error: combine::ParseError<Input::Token, Input::Range, Input::Position>,
 {
-    (skip_many(comment_line().skip(spaces())), spaces()).map(|_| ())
+    (skip_spaces(), skip_many(comment_line().skip(skip_spaces()))).map(|_| ())
+}
+
+// Between definitions `##` lines are left for the function they document to
+// parse. A `##` block that no function follows documents nothing, and is
+// skipped. Returns the text of the plain `#` lines.
+fn plain_comments<Input>() -> impl Parser<Input, Output = Vec<String>>
+where
+    Input: Stream<Token = char, Position = usize>,
+    Input::Error: combine::ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    let plain_comment = attempt(char('#').skip(not_followed_by(char('#'))))
+        .with(many::<String, _, _>(satisfy(|c| c != '\n')))
+        .skip(optional(newline()))
+        .map(|content| Some(content.trim().to_string()));
+    let orphan_doc_comments = attempt(
+        skip_many1(doc_comment_line().skip(skip_spaces())).skip(not_followed_by(keyword("fn"))),
+    )
+    .map(|_| None);
+    skip_spaces()
+        .with(many(
+            choice((plain_comment, orphan_doc_comments)).skip(skip_spaces()),
+        ))
+        .map(|lines: Vec<Option<String>>| lines.into_iter().flatten().collect())
 }
 
+// Each statement keeps the `#` lines written directly above it. Comment lines
+// with no statement after them, at the end of a block, are dropped.
 combine::parser! {
-    fn statement_with_comments[Input]()(Input) -> Statement
+    fn statement_block[Input]()(Input) -> Vec<Statement>
     where [Input: Stream<Token = char, Position = usize>]
     {
-        skip_spaces_and_comments()
-            .with(statement())
-            .skip(skip_spaces_and_comments())
+        many(choice((
+            line_statement().map(Some),
+            (
+                many1(comment_line().skip(skip_spaces())),
+                optional(line_statement()),
+            )
+                .map(|(lines, statement): (Vec<String>, Option<Statement>)| {
+                    statement.map(|statement| statement.with_comment(Some(lines.join("\n"))))
+                }),
+        )))
+        .map(|statements: Vec<Option<Statement>>| statements.into_iter().flatten().collect())
     }
 }
 
+// A statement, which must be the last thing on its line: what follows it is a
+// line break, a `#` comment or the `}` closing its block. Every token skips
+// the whitespace after it, so the stream is wound back once the statement has
+// parsed to look for a line break between its end and the next token.
+fn line_statement<Input>() -> impl Parser<Input, Output = Statement>
+where
+    Input: Stream<Token = char, Position = usize>,
+    Input::Error: combine::ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    combine::parser(|input: &mut Input| {
+        let start = input.checkpoint();
+        let (statement, commit) = statement().parse_stream(input).into_result()?;
+        let next_token = input.position();
+        let stream_error = |error| Commit::Commit(Tracked::from(error));
+
+        input.reset(start).map_err(stream_error)?;
+        let mut line_break = false;
+        while input.position() < next_token {
+            let offset = input.position();
+            let c = input
+                .uncons()
+                .map_err(|e| stream_error(Input::Error::from_error(offset, e)))?;
+            line_break |= offset >= statement.span().end && c == '\n';
+        }
+
+        let at_line_end = line_break || {
+            let next = input.checkpoint();
+            let ends_line = matches!(input.uncons(), Ok('}' | '#') | Err(_));
+            input.reset(next).map_err(stream_error)?;
+            ends_line
+        };
+        if !at_line_end {
+            return Err(stream_error(Input::Error::from_error(
+                next_token,
+                StreamErrorFor::<Input>::expected_static_message("a newline before this statement"),
+            )));
+        }
+        Ok((statement, commit))
+    })
+}
+
 fn identifier_raw<Input>() -> impl Parser<Input, Output = String>
 where
     Input: Stream<Token = char, Position = usize>,
@@ -104,6 +246,18 @@ where
         })
 }
 
+fn keyword<Input>(word: &'static str) -> impl Parser<Input, Output = &'static str>
+where
+    Input: Stream<Token = char, Position = usize>,
+    Input::Error: combine::ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    attempt(string(word).skip(not_followed_by(choice((
+        combine::parser::char::alpha_num(),
+        char('_'),
+    )))))
+    .skip(skip_spaces())
+}
+
 fn identifier<Input>() -> impl Parser<Input, Output = String>
 where
     Input: Stream<Token = char, Position = usize>,
@@ -119,20 +273,83 @@ where
 {
     (
         position(),
-        skip_spaces_and_comments().with(many(
+        plain_comments(),
+        many((
             choice((
                 parse_function_with_docs().map(Definition::Function),
+                attempt(parse_external_const()).map(Definition::ExternalConst),
                 parse_external_function().map(Definition::ExternalFunction),
-            ))
-            .skip(skip_spaces_and_comments()),
+                parse_constant().map(Definition::Constant),
+            )),
+            plain_comments(),
         )),
         position(),
     )
-        .map(move |(start, definitions, end)| Module {
-            definitions,
-            span: Span::new(start, end),
-            file_id,
-        })
+        .map(
+            move |(start, leading, definitions, end): (_, _, Vec<(Definition, Vec<String>)>, _)| {
+                let mut comments_above = leading;
+                let definitions = definitions
+                    .into_iter()
+                    .map(|(mut definition, comments_below)| {
+                        if let Definition::Function(func) = &mut definition {
+                            func.pure = comments_above.iter().any(|line| line == PURE_DIRECTIVE);
+                        }
+                        comments_above = comments_below;
+                        definition
+                    })
+                    .collect();
+                Module {
+                    definitions,
+                    span: Span::new(start, end),
+                    file_id,
+                }
+            },
+        )
+}
+
+/// Comment text (after the `#`) that marks the function below it as pure.
+const PURE_DIRECTIVE: &str = "pure";
+
+/// Keywords that open a top-level definition.
+const DEFINITION_KEYWORDS: &[&str] = &["fn", "extern", "const"];
+
+/// Where parsing resumes after an error at `error_offset` in a parse that
+/// began at `parse_start`: the first definition keyword opening a line, from
+/// the error's line on, that is past `parse_start`.
+pub fn next_definition_start(
+    source: &str,
+    parse_start: usize,
+    error_offset: usize,
+) -> Option<usize> {
+    let before = source.get(..error_offset)?;
+    let mut line_start = before.rfind('\n').map_or(0, |index| index + 1);
+    loop {
+        let line = &source[line_start..];
+        let indent = line.len() - line.trim_start_matches([' ', '\t']).len();
+        let keyword_start = line_start + indent;
+        if keyword_start > parse_start && opens_definition(&source[keyword_start..]) {
+            return Some(keyword_start);
+        }
+        line_start += line.find('\n')? + 1;
+    }
+}
+
+fn opens_definition(text: &str) -> bool {
+    DEFINITION_KEYWORDS.iter().any(|keyword| {
+        text.strip_prefix(keyword)
+            .is_some_and(|rest| rest.starts_with(char::is_whitespace))
+    })
+}
+
+pub fn parse_standalone_expression<Input>() -> impl Parser<Input, Output = Expression>
+where
+    Input: Stream<Token = char, Position = usize>,
+    Input::Error: combine::ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    skip_spaces_and_comments()
+        .with(parse_expression())
+        .skip(skip_spaces_and_comments())
+        .skip(eof())
 }
 
 fn parse_external_function<Input>() -> impl Parser<Input, Output = ExternalFunction>
@@ -151,19 +368,65 @@ where
             sep_by(parse_parameter(), lex_char(',')),
         ),
         lex_char(':'),
-        parse_type(),
+        parse_spanned_type(),
+        position(),
+    )
+        .map(
+            |(start, _, _, name, params, _, (return_type, return_type_span), end)| {
+                ExternalFunction {
+                    name,
+                    parameters: params,
+                    return_type,
+                    return_type_span,
+                    span: Span::new(start, end),
+                }
+            },
+        )
+}
+
+fn parse_external_const<Input>() -> impl Parser<Input, Output = ExternalConst>
+where
+    Input: Stream<Token = char, Position = usize>,
+    Input::Error: combine::ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    (
+        position(),
+        lex_string("extern"),
+        lex_string("const"),
+        identifier(),
+        lex_char(':'),
+        parse_spanned_type(),
         position(),
     )
         .map(
-            |(start, _, _, name, params, _, return_type, end)| ExternalFunction {
+            |(start, _, _, name, _, (const_type, type_span), end)| ExternalConst {
                 name,
-                parameters: params,
-                return_type,
+                const_type,
+                type_span,
                 span: Span::new(start, end),
             },
         )
 }
 
+fn parse_constant<Input>() -> impl Parser<Input, Output = Constant>
+where
+    Input: Stream<Token = char, Position = usize>,
+    Input::Error: combine::ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    (
+        position(),
+        keyword("const"),
+        identifier(),
+        lex_char('='),
+        parse_string_literal(),
+    )
+        .map(|(start, _, name, _, value)| Constant {
+            name,
+            span: Span::new(start, value.span().end),
+            value,
+        })
+}
+
 fn parse_function_with_docs<Input>() -> impl Parser<Input, Output = Function>
 where
     Input: Stream<Token = char, Position = usize>,
@@ -190,17 +453,19 @@ where
             sep_by(parse_parameter(), lex_char(',')),
         ),
         lex_char(':'),
-        parse_type(),
+        parse_spanned_type(),
         between(lex_char('{'), lex_char('}'), parse_function_body()),
         position(),
     )
         .map(
-            |(start, _, name, params, _, return_type, body, end)| Function {
+            |(start, _, name, params, _, (return_type, return_type_span), body, end)| Function {
                 name,
                 parameters: params,
                 return_type,
+                return_type_span,
                 body,
                 documentation: None,
+                pure: false,
                 span: Span::new(start, end),
             },
         )
@@ -212,19 +477,36 @@ where
     Input::Error: combine::ParseError<Input::Token, Input::Range, Input::Position>,
 {
     (
+        parse_preceding_comments(),
         position(),
         identifier(),
         lex_char(':'),
-        parse_type(),
+        parse_spanned_type(),
         position(),
     )
-        .map(|(start, name, _, param_type, end)| Parameter {
-            name,
-            param_type,
-            span: Span::new(start, end),
-        })
+        .map(
+            |(comment, start, name, _, (param_type, type_span), end)| Parameter {
+                name,
+                param_type,
+                type_span,
+                span: Span::new(start, end),
+                comment,
+            },
+        )
 }
 
+fn parse_spanned_type<Input>() -> impl Parser<Input, Output = (Type, Span)>
+where
+    Input: Stream<Token = char, Position = usize>,
+    Input::Error: combine::ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    (position(), parse_type(), position())
+        .skip(skip_spaces())
+        .map(|(start, ty, end)| (ty, Span::new(start, end)))
+}
+
+// Leaves the whitespace after the type unread, so a span taken around it
+// ends at the type's last character.
 combine::parser! {
     fn parse_type[Input]()(Input) -> Type
     where [Input: Stream<Token = char, Position = usize>]
@@ -234,8 +516,8 @@ combine::parser! {
                 (
                     lex_string("List"),
                     lex_char('<'),
-                    parse_type(),
-                    lex_char('>'),
+                    parse_type().skip(skip_spaces()),
+                    char('>'),
                 )
                     .map(|(_, _, inner, _)| Type::List(Box::new(inner))),
             ),
@@ -243,14 +525,19 @@ combine::parser! {
                 (
                     lex_string("Option"),
                     lex_char('<'),
-                    parse_type(),
-                    lex_char('>'),
+                    parse_type().skip(skip_spaces()),
+                    char('>'),
                 )
                     .map(|(_, _, inner, _)| Type::Option(Box::new(inner))),
             ),
-            lex_string("()").map(|_| Type::Unit),
-            lex_string("Boolean").map(|_| Type::Boolean),
-            lex_string("String").map(|_| Type::String),
+            string("()").map(|_| Type::Unit),
+            identifier_raw().map(|name| match name.as_str() {
+                "Boolean" => Type::Boolean,
+                "Number" => Type::Number,
+                "String" => Type::String,
+                "Json" => Type::Json,
+                _ => Type::Custom(name),
+            }),
         ))
     }
 }
@@ -260,11 +547,9 @@ where
     Input: Stream<Token = char, Position = usize>,
     Input::Error: combine::ParseError<Input::Token, Input::Range, Input::Position>,
 {
-    (position(), many(statement_with_comments()), position()).map(|(start, statements, end)| {
-        FunctionBody {
-            statements,
-            span: Span::new(start, end),
-        }
+    (position(), statement_block(), position()).map(|(start, statements, end)| FunctionBody {
+        statements,
+        span: Span::new(start, end),
     })
 }
 
@@ -277,8 +562,9 @@ combine::parser! {
             parse_variable_assignment(),
             attempt(parse_select()),
             attempt(parse_injection()),
-            attempt(parse_if_statement()),
-            attempt(parse_while_statement()),
+            parse_if_statement(),
+            parse_while_statement(),
+            attempt(parse_assert_statement()),
             attempt(parse_return_statement()),
             parse_expression_statement(),
         ))
@@ -292,7 +578,10 @@ where
 {
     parse_expression()
         .skip(lex_char('!'))
-        .map(Statement::Injection)
+        .map(|expression| Statement::Injection {
+            expression,
+            comment: None,
+        })
 }
 
 fn parse_assignment<Input>() -> impl Parser<Input, Output = Statement>
@@ -313,6 +602,7 @@ where
                 variable,
                 expression,
                 span: Span::new(start, end),
+                comment: None,
             }
         })
         .skip(skip_spaces())
@@ -335,6 +625,7 @@ where
                 variable,
                 expression,
                 span: Span::new(start, end),
+                comment: None,
             }
         })
 }
@@ -344,19 +635,49 @@ where
     Input: Stream<Token = char, Position = usize>,
     Input::Error: combine::ParseError<Input::Token, Input::Range, Input::Position>,
 {
-    parse_expression().map(Statement::ExpressionStatement)
+    parse_expression().map(|expression| Statement::ExpressionStatement {
+        expression,
+        comment: None,
+    })
 }
 
 combine::parser! {
     fn parse_simple_expression[Input]()(Input) -> Expression
     where [Input: Stream<Token = char, Position = usize>]
+    {
+        (
+            parse_primary_expression(),
+            many::<Vec<_>, _, _>(
+                (char('.'), identifier_raw(), position()).skip(skip_spaces()),
+            ),
+        )
+            .map(|(object, fields)| {
+                fields
+                    .into_iter()
+                    .fold(object, |object, (_, field, end)| {
+                        let start = object.span().start;
+                        Expression::FieldAccess {
+                            object: Box::new(object),
+                            field,
+                            span: Span::new(start, end),
+                        }
+                    })
+            })
+    }
+}
+
+combine::parser! {
+    fn parse_primary_expression[Input]()(Input) -> Expression
+    where [Input: Stream<Token = char, Position = usize>]
     {
         choice((
+            attempt(parse_option_literal()),
             attempt(parse_call()),
             parse_string_literal(),
             attempt(parse_list_literal()),
             attempt(parse_unit_literal()),
             attempt(parse_boolean_literal()),
+            attempt(parse_number_literal()),
             parse_variable(),
         ))
     }
@@ -369,6 +690,8 @@ combine::parser! {
         choice((
             attempt(parse_select_expression()),
             attempt(parse_if_else_expression()),
+            attempt(parse_match_expression()),
+            parse_return_expression(),
             parse_simple_expression(),
         ))
     }
@@ -384,9 +707,10 @@ combine::parser! {
             parse_simple_expression(),
             between(lex_char('{'), lex_char('}'), parse_expression()),
             lex_string("else"),
-            between(lex_char('{'), lex_char('}'), parse_expression()),
+            between(lex_char('{'), char('}'), parse_expression()),
             position(),
         )
+            .skip(skip_spaces())
             .map(
                 |(start, _, condition, then_expr, _, else_expr, end)| Expression::IfElse {
                     condition: Box::new(condition),
@@ -398,6 +722,20 @@ combine::parser! {
     }
 }
 
+combine::parser! {
+    fn parse_return_expression[Input]()(Input) -> Expression
+    where [Input: Stream<Token = char, Position = usize>]
+    {
+        (position(), keyword("return"), parse_expression()).map(|(start, _, value)| {
+            let end = value.span().end;
+            Expression::Return {
+                value: Box::new(value),
+                span: Span::new(start, end),
+            }
+        })
+    }
+}
+
 fn parse_call<Input>() -> impl Parser<Input, Output = Expression>
 where
     Input: Stream<Token = char, Position = usize>,
@@ -434,10 +772,7 @@ where
     Input: Stream<Token = char, Position = usize>,
     Input::Error: combine::ParseError<Input::Token, Input::Range, Input::Position>,
 {
-    choice((
-        attempt(parse_multiline_string()),
-        parse_single_line_string(),
-    ))
+    choice((parse_multiline_string(), parse_single_line_string()))
 }
 
 fn parse_single_line_string<Input>() -> impl Parser<Input, Output = Expression>
@@ -445,35 +780,82 @@ where
     Input: Stream<Token = char, Position = usize>,
     Input::Error: combine::ParseError<Input::Token, Input::Range, Input::Position>,
 {
-    (
-        position(),
-        between(
-            lex_char('"'),
-            char('"'),
-            many(
-                char('\\')
-                    .with(satisfy(|_| true))
-                    .map(|c| match c {
-                        'n' => '\n',
-                        't' => '\t',
-                        'r' => '\r',
-                        '\\' => '\\',
-                        '\'' => '\'',
-                        '"' => '"',
-                        c => c,
-                    })
-                    .or(satisfy(|c: char| c != '"')),
-            ),
-        ),
-        position(),
+    // A string still open at the end of the source fails at its opening
+    // quote, which is where the reader needs to look.
+    let body = (
+        char('"').skip(spaces().silent()),
+        many(parse_escape().or(satisfy(|c: char| c != '"'))),
+        optional(char('"')),
     )
-        .skip(skip_spaces())
-        .map(
-            |(start, chars, end): (_, Vec<char>, _)| Expression::StringLiteral {
-                value: chars.into_iter().collect(),
-                span: Span::new(start, end),
-            },
-        )
+        .and_then(|(_, chars, close): (_, Vec<char>, _)| match close {
+            Some(_) => Ok(chars),
+            None => Err(StreamErrorFor::<Input>::message_static_message(
+                "unterminated string literal",
+            )),
+        });
+    (position(), body, position()).skip(skip_spaces()).map(
+        |(start, chars, end): (_, Vec<char>, _)| Expression::StringLiteral {
+            value: chars.into_iter().collect(),
+            multiline: false,
+            span: Span::new(start, end),
+        },
+    )
+}
+
+// `\n`, `\t`, `\r`, `\\`, `\'`, `\"` or `\u{XXXX}`. Any other escaped character
+// stands for itself. A bad `\u` escape fails the parse at its backslash.
+fn parse_escape<Input>() -> impl Parser<Input, Output = char>
+where
+    Input: Stream<Token = char, Position = usize>,
+    Input::Error: combine::ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    choice((
+        attempt(string("\\u"))
+            .with((
+                optional(char('{')),
+                many::<String, _, _>(satisfy(|c: char| c.is_ascii_alphanumeric())),
+                optional(char('}')),
+            ))
+            .and_then(|(open, hex, close)| {
+                decode_unicode_escape(open.is_some(), &hex, close.is_some())
+                    .map_err(StreamErrorFor::<Input>::message_format)
+            }),
+        char('\\').with(satisfy(|_| true)).map(|c| match c {
+            'n' => '\n',
+            't' => '\t',
+            'r' => '\r',
+            '\\' => '\\',
+            '\'' => '\'',
+            '"' => '"',
+            c => c,
+        }),
+    ))
+}
+
+fn decode_unicode_escape(open: bool, hex: &str, close: bool) -> Result<char, String> {
+    if !open || !close {
+        return Err(format!(
+            "malformed unicode escape `\\u{}{}{}`: expected `\\u{{XXXX}}`",
+            if open { "{" } else { "" },
+            hex,
+            if close { "}" } else { "" }
+        ));
+    }
+    if hex.is_empty() || hex.len() > 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(format!(
+            "invalid unicode escape `\\u{{{}}}`: expected 1 to 6 hex digits",
+            hex
+        ));
+    }
+    u32::from_str_radix(hex, 16)
+        .ok()
+        .and_then(char::from_u32)
+        .ok_or_else(|| {
+            format!(
+                "invalid unicode escape `\\u{{{}}}`: not a valid code point",
+                hex
+            )
+        })
 }
 
 fn parse_multiline_string<Input>() -> impl Parser<Input, Output = Expression>
@@ -484,22 +866,9 @@ where
     (
         position(),
         between(
-            lex_string("'''"),
+            attempt(string("'''").skip(spaces().silent())),
             string("'''"),
-            many(
-                char('\\')
-                    .with(satisfy(|_| true))
-                    .map(|c| match c {
-                        'n' => '\n',
-                        't' => '\t',
-                        'r' => '\r',
-                        '\\' => '\\',
-                        '\'' => '\'',
-                        '"' => '"',
-                        c => c,
-                    })
-                    .or(satisfy(|c: char| c != '\'')),
-            ),
+            many(parse_escape().or(satisfy(|c: char| c != '\''))),
         ),
         position(),
     )
@@ -507,6 +876,7 @@ where
         .map(
             |(start, chars, end): (_, Vec<char>, _)| Expression::StringLiteral {
                 value: chars.into_iter().collect(),
+                multiline: true,
                 span: Span::new(start, end),
             },
         )
@@ -556,6 +926,38 @@ where
     ))
 }
 
+// `42`, `-7` or `3.14`. A fraction needs digits after the point, so `1.foo`
+// still reads as a field access on `1`.
+fn parse_number_literal<Input>() -> impl Parser<Input, Output = Expression>
+where
+    Input: Stream<Token = char, Position = usize>,
+    Input::Error: combine::ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    (
+        position(),
+        optional(char('-')),
+        many1::<String, _, _>(digit()),
+        optional(attempt(char('.').with(many1::<String, _, _>(digit())))),
+        position(),
+    )
+        .skip(skip_spaces())
+        .map(|(start, sign, whole, fraction, end)| {
+            let mut text = String::new();
+            if sign.is_some() {
+                text.push('-');
+            }
+            text.push_str(&whole);
+            if let Some(fraction) = fraction {
+                text.push('.');
+                text.push_str(&fraction);
+            }
+            Expression::NumberLiteral {
+                value: text.parse().expect("digits always parse as a number"),
+                span: Span::new(start, end),
+            }
+        })
+}
+
 fn parse_unit_literal<Input>() -> impl Parser<Input, Output = Expression>
 where
     Input: Stream<Token = char, Position = usize>,
@@ -568,6 +970,39 @@ where
         })
 }
 
+// Tried before calls, so `Some(x)` is not read as a call to a function
+// named `Some`.
+fn parse_option_literal<Input>() -> impl Parser<Input, Output = Expression>
+where
+    Input: Stream<Token = char, Position = usize>,
+    Input::Error: combine::ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    choice((
+        (
+            position(),
+            keyword("Some"),
+            between(lex_char('('), char(')'), parse_expression()),
+            position(),
+        )
+            .skip(skip_spaces())
+            .map(|(start, _, value, end)| Expression::OptionLiteral {
+                value: Some(Box::new(value)),
+                span: Span::new(start, end),
+            }),
+        (
+            position(),
+            string("None"),
+            not_followed_by(choice((combine::parser::char::alpha_num(), char('_')))),
+            position(),
+        )
+            .skip(skip_spaces())
+            .map(|(start, _, _, end)| Expression::OptionLiteral {
+                value: None,
+                span: Span::new(start, end),
+            }),
+    ))
+}
+
 fn parse_list_literal<Input>() -> impl Parser<Input, Output = Expression>
 where
     Input: Stream<Token = char, Position = usize>,
@@ -597,6 +1032,7 @@ where
     (
         position(),
         lex_string("select").with((
+            optional(attempt(lex_string("ranked")).or(attempt(lex_string("retry")))),
             lex_char('{'),
             skip_spaces_and_comments(),
             sep_by(
@@ -604,16 +1040,22 @@ where
                 lex_char(',').skip(skip_spaces_and_comments()),
             ),
             skip_spaces_and_comments(),
-            lex_char('}'),
+            char('}'),
         )),
         position(),
     )
-        .map(|(start, (_, _, clauses, _, _), end)| {
-            Statement::ExpressionStatement(Expression::Select(SelectExpression {
-                clauses,
-                span: Span::new(start, end),
-            }))
-        })
+        .skip(skip_spaces())
+        .map(
+            |(start, (modifier, _, _, clauses, _, _), end)| Statement::ExpressionStatement {
+                expression: Expression::Select(SelectExpression {
+                    clauses,
+                    ranked: modifier == Some("ranked"),
+                    retry: modifier == Some("retry"),
+                    span: Span::new(start, end),
+                }),
+                comment: None,
+            },
+        )
 }
 
 fn parse_select_expression<Input>() -> impl Parser<Input, Output = Expression>
@@ -624,6 +1066,7 @@ where
     (
         position(),
         lex_string("select").with((
+            optional(attempt(lex_string("ranked")).or(attempt(lex_string("retry")))),
             lex_char('{'),
             skip_spaces_and_comments(),
             sep_by(
@@ -631,74 +1074,142 @@ where
                 lex_char(',').skip(skip_spaces_and_comments()),
             ),
             skip_spaces_and_comments(),
-            lex_char('}'),
+            char('}'),
         )),
         position(),
     )
-        .map(|(start, (_, _, clauses, _, _), end)| {
+        .skip(skip_spaces())
+        .map(|(start, (modifier, _, _, clauses, _, _), end)| {
             Expression::Select(SelectExpression {
                 clauses,
+                ranked: modifier == Some("ranked"),
+                retry: modifier == Some("retry"),
                 span: Span::new(start, end),
             })
         })
 }
 
-fn parse_select_clause<Input>() -> impl Parser<Input, Output = SelectClause>
+// Arms may come in either order; the type checker requires one for each of
+// `true` and `false`.
+fn parse_match_expression<Input>() -> impl Parser<Input, Output = Expression>
 where
     Input: Stream<Token = char, Position = usize>,
     Input::Error: combine::ParseError<Input::Token, Input::Range, Input::Position>,
 {
     (
         position(),
-        parse_call()
-            .skip(skip_spaces())
-            .skip(lex_string("as"))
-            .and(identifier())
-            .skip(lex_string("=>"))
-            .and(parse_expression()),
+        keyword("match"),
+        parse_simple_expression(),
+        lex_char('{'),
+        skip_spaces_and_comments(),
+        sep_end_by(
+            parse_match_arm(),
+            lex_char(',').skip(skip_spaces_and_comments()),
+        ),
+        skip_spaces_and_comments(),
+        char('}'),
         position(),
     )
+        .skip(skip_spaces())
         .map(
-            |(start, ((expression_to_run, result_variable), expression_next), end)| SelectClause {
-                expression_to_run,
-                result_variable,
-                expression_next,
+            |(start, _, scrutinee, _, _, arms, _, _, end)| Expression::Match {
+                scrutinee: Box::new(scrutinee),
+                arms,
                 span: Span::new(start, end),
             },
         )
 }
 
-fn parse_if_statement<Input>() -> impl Parser<Input, Output = Statement>
+fn parse_match_arm<Input>() -> impl Parser<Input, Output = MatchArm>
 where
     Input: Stream<Token = char, Position = usize>,
     Input::Error: combine::ParseError<Input::Token, Input::Range, Input::Position>,
 {
     (
         position(),
-        lex_string("if"),
-        parse_simple_expression(),
-        between(
-            lex_char('{'),
-            lex_char('}'),
-            many(statement_with_comments()),
-        ),
-        optional(lex_string("else").skip(skip_spaces()).with(between(
-            lex_char('{'),
-            lex_char('}'),
-            many(statement_with_comments()),
-        ))),
+        choice((
+            keyword("true").map(|_| true),
+            keyword("false").map(|_| false),
+        )),
+        lex_string("=>"),
+        parse_expression(),
+    )
+        .map(|(start, pattern, _, expression)| MatchArm {
+            pattern,
+            span: Span::new(start, expression.span().end),
+            expression,
+        })
+}
+
+fn parse_select_clause<Input>() -> impl Parser<Input, Output = SelectClause>
+where
+    Input: Stream<Token = char, Position = usize>,
+    Input::Error: combine::ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    (
+        position(),
+        parse_call()
+            .skip(skip_spaces())
+            .skip(lex_string("as"))
+            .and(identifier())
+            .and(optional(keyword("if").with(parse_expression())))
+            .skip(lex_string("=>"))
+            .and(parse_expression()),
         position(),
     )
         .map(
-            |(start, _, condition, body, else_body, end)| Statement::If {
-                condition,
-                body,
-                else_body,
-                span: Span::new(start, end),
+            |(start, (((expression_to_run, result_variable), guard), expression_next), end)| {
+                SelectClause {
+                    expression_to_run,
+                    result_variable,
+                    guard,
+                    expression_next,
+                    span: Span::new(start, end),
+                }
             },
         )
 }
 
+// `else if` is kept as an `else` body holding just the nested `if`.
+combine::parser! {
+    fn parse_if_statement[Input]()(Input) -> Statement
+    where [Input: Stream<Token = char, Position = usize>]
+    {
+        (
+            position(),
+            keyword("if"),
+            parse_simple_expression(),
+            between(lex_char('{'), char('}'), statement_block()),
+            position(),
+            skip_spaces(),
+            optional(keyword("else").with(choice((
+                parse_if_statement().map(|nested| {
+                    let end = nested.span().end;
+                    (vec![nested], end)
+                }),
+                (
+                    between(lex_char('{'), char('}'), statement_block()),
+                    position(),
+                )
+                    .skip(skip_spaces()),
+            )))),
+        )
+            .map(|(start, _, condition, body, body_end, _, else_part)| {
+                let (else_body, end) = match else_part {
+                    Some((else_body, else_end)) => (Some(else_body), else_end),
+                    None => (None, body_end),
+                };
+                Statement::If {
+                    condition,
+                    body,
+                    else_body,
+                    span: Span::new(start, end),
+                    comment: None,
+                }
+            })
+    }
+}
+
 fn parse_while_statement<Input>() -> impl Parser<Input, Output = Statement>
 where
     Input: Stream<Token = char, Position = usize>,
@@ -706,19 +1217,40 @@ where
 {
     (
         position(),
-        lex_string("while"),
+        keyword("while"),
         parse_simple_expression(),
-        between(
-            lex_char('{'),
-            lex_char('}'),
-            many(statement_with_comments()),
-        ),
+        between(lex_char('{'), char('}'), statement_block()),
         position(),
     )
+        .skip(skip_spaces())
         .map(|(start, _, condition, body, end)| Statement::While {
             condition,
             body,
             span: Span::new(start, end),
+            comment: None,
+        })
+}
+
+fn parse_assert_statement<Input>() -> impl Parser<Input, Output = Statement>
+where
+    Input: Stream<Token = char, Position = usize>,
+    Input::Error: combine::ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    (
+        position(),
+        keyword("assert"),
+        parse_expression(),
+        lex_char(','),
+        parse_expression(),
+    )
+        .map(|(start, _, condition, _, message)| {
+            let end = message.span().end;
+            Statement::Assert {
+                condition,
+                message,
+                span: Span::new(start, end),
+                comment: None,
+            }
         })
 }
 
@@ -727,7 +1259,13 @@ where
     Input: Stream<Token = char, Position = usize>,
     Input::Error: combine::ParseError<Input::Token, Input::Range, Input::Position>,
 {
-    (lex_string("return"), parse_expression()).map(|(_, expression)| Statement::Return(expression))
+    (position(), keyword("return"), parse_expression()).map(|(start, _, expression)| {
+        Statement::Return {
+            span: Span::new(start, expression.span().end),
+            expression,
+            comment: None,
+        }
+    })
 }
 
 #[cfg(test)]
@@ -859,6 +1397,70 @@ and multiple lines
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_multiline_string_round_trips_through_display() {
+        let input = r#"
+fn main(): () {
+    '''
+You are reviewing a patch.
+Don\'t guess, quote the "exact" line.
+'''!
+}
+"#;
+
+        let injected_value = |module: &Module| {
+            let Definition::Function(func) = &module.definitions[0] else {
+                panic!("Expected function definition");
+            };
+            match &func.body.statements[0] {
+                Statement::Injection {
+                    expression:
+                        Expression::StringLiteral {
+                            value, multiline, ..
+                        },
+                    ..
+                } => (value.clone(), *multiline),
+                other => panic!("Expected string injection, got: {:?}", other),
+            }
+        };
+
+        let stream = Stream::with_positioner(input, IndexPositioner::default());
+        let (module, _) = parse_program(TEST_FILE_ID).parse(stream).unwrap();
+        let original = injected_value(&module);
+        assert_eq!(
+            original,
+            (
+                "You are reviewing a patch.\nDon't guess, quote the \"exact\" line.\n".to_string(),
+                true
+            )
+        );
+
+        let formatted = module.to_string();
+        assert!(formatted.contains("'''You are reviewing a patch.\n"));
+
+        let stream = Stream::with_positioner(formatted.as_str(), IndexPositioner::default());
+        let (reparsed, _) = parse_program(TEST_FILE_ID).parse(stream).unwrap();
+        assert_eq!(injected_value(&reparsed), original);
+    }
+
+    #[test]
+    fn test_multiline_string_display_keeps_leading_whitespace() {
+        let literal = Expression::StringLiteral {
+            value: "\n  indented\\path".to_string(),
+            multiline: true,
+            span: Span::dummy(),
+        };
+
+        let formatted = literal.to_string();
+        let stream = Stream::with_positioner(formatted.as_str(), IndexPositioner::default());
+        let (reparsed, _) = parse_multiline_string().parse(stream).unwrap();
+
+        match reparsed {
+            Expression::StringLiteral { value, .. } => assert_eq!(value, "\n  indented\\path"),
+            other => panic!("Expected StringLiteral, got: {:?}", other),
+        }
+    }
+
     #[test]
     fn test_parse_single_line_string() {
         let input = r#""test \"string\"""#;
@@ -893,6 +1495,52 @@ and multiple lines
         }
     }
 
+    #[test]
+    fn test_parse_unicode_escapes() {
+        let input = r#""\u{48}\u{49}""#;
+        let stream = Stream::with_positioner(input, IndexPositioner::default());
+
+        let (expr, _) = parse_string_literal().parse(stream).unwrap();
+        match expr {
+            Expression::StringLiteral { value, span, .. } => {
+                assert_eq!(value, "HI");
+                assert_eq!((span.start, span.end), (0, input.len()));
+            }
+            _ => panic!("Expected StringLiteral"),
+        }
+    }
+
+    #[test]
+    fn test_parse_unicode_escape_in_multiline_string() {
+        let input = r#"'''smile \u{1F600}'''"#;
+        let stream = Stream::with_positioner(input, ByteOffsetPositioner::default());
+
+        let (expr, _) = parse_string_literal().parse(stream).unwrap();
+        match expr {
+            Expression::StringLiteral { value, .. } => assert_eq!(value, "smile 😀"),
+            _ => panic!("Expected StringLiteral"),
+        }
+    }
+
+    #[test]
+    fn test_invalid_unicode_escapes_fail_to_parse() {
+        for input in [
+            r#""\u{110000}""#,
+            r#""\u{D800}""#,
+            r#""\u{zz}""#,
+            r#""\u{48""#,
+            r#""\u48""#,
+            r#"'''\u{110000}'''"#,
+        ] {
+            let stream = Stream::with_positioner(input, IndexPositioner::default());
+            assert!(
+                parse_string_literal().parse(stream).is_err(),
+                "{} should not parse",
+                input
+            );
+        }
+    }
+
     #[test]
     fn test_parse_empty_multiline_string_minimal() {
         let input = r#""""""""#;
@@ -987,7 +1635,10 @@ fn main(): () {
         let stream = Stream::with_positioner(input, IndexPositioner::default());
         let (statement, _) = statement().parse(stream).unwrap();
         match statement {
-            Statement::Injection(Expression::StringLiteral { value, .. }) => {
+            Statement::Injection {
+                expression: Expression::StringLiteral { value, .. },
+                ..
+            } => {
                 assert_eq!(value, "Analyze the following code for potential bugs");
             }
             _ => panic!("Expected injection with string literal"),
@@ -1000,7 +1651,10 @@ fn main(): () {
         let stream = Stream::with_positioner(input, IndexPositioner::default());
         let (statement, _) = statement().parse(stream).unwrap();
         match statement {
-            Statement::Injection(Expression::Variable { name, .. }) => {
+            Statement::Injection {
+                expression: Expression::Variable { name, .. },
+                ..
+            } => {
                 assert_eq!(name, "code");
             }
             _ => panic!("Expected injection with variable"),
@@ -1017,6 +1671,7 @@ fn main(): () {
                 variable,
                 expression,
                 span: _,
+                ..
             } => {
                 assert_eq!(variable, "analysis");
                 match expression {
@@ -1116,6 +1771,101 @@ fn calculator(request: String): String {
         assert_eq!(functions[0].name, "calculator");
     }
 
+    fn parse_function_statements(input: &str) -> Vec<Statement> {
+        let stream = Stream::with_positioner(input, IndexPositioner::default());
+        let (module, _) = parse_program(TEST_FILE_ID).parse(stream).unwrap();
+        match module.definitions.into_iter().next() {
+            Some(Definition::Function(func)) => func.body.statements,
+            other => panic!("Expected function definition, got: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_newline_separated_variables_are_separate_statements() {
+        let statements = parse_function_statements(
+            r#"
+fn test(first: String, second: String): () {
+    first
+    second
+}
+"#,
+        );
+
+        let names: Vec<&str> = statements
+            .iter()
+            .map(|statement| match statement {
+                Statement::ExpressionStatement {
+                    expression: Expression::Variable { name, .. },
+                    ..
+                } => name.as_str(),
+                other => panic!("Expected variable expression statement, got: {:?}", other),
+            })
+            .collect();
+        assert_eq!(names, vec!["first", "second"]);
+    }
+
+    #[test]
+    fn test_call_arguments_may_span_lines() {
+        let statements = parse_function_statements(
+            r#"
+fn test(first: String, second: String): () {
+    combine(
+        first,
+        second
+    )
+    first
+}
+"#,
+        );
+
+        assert_eq!(statements.len(), 2);
+        match &statements[0] {
+            Statement::ExpressionStatement {
+                expression:
+                    Expression::Call {
+                        function,
+                        arguments,
+                        ..
+                    },
+                ..
+            } => {
+                assert_eq!(function, "combine");
+                assert_eq!(arguments.len(), 2);
+            }
+            other => panic!("Expected call expression statement, got: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_return_span_includes_keyword() {
+        let input = "fn test(): String {\n    return \"done\"\n}\n";
+        let statements = parse_function_statements(input);
+
+        let span = statements[0].span();
+        assert_eq!(&input[span.start..span.end], "return \"done\"");
+    }
+
+    #[test]
+    fn test_statements_on_one_line_are_rejected() {
+        let input = r#"
+fn test(first: String, second: String): () {
+    if first {
+        first! second!
+    }
+}
+"#;
+        let stream =
+            combine::easy::Stream(Stream::with_positioner(input, IndexPositioner::default()));
+        let error = parse_program(TEST_FILE_ID).parse(stream).unwrap_err();
+
+        assert_eq!(error.position, input.find("second!").unwrap());
+        assert!(
+            error
+                .to_string()
+                .contains("a newline before this statement")
+        );
+    }
+
     #[test]
     fn test_parse_standalone_expression_statement() {
         let input = r#"
@@ -1145,6 +1895,7 @@ fn test_function(): () {
                 variable,
                 expression,
                 span: _,
+                ..
             } => {
                 assert_eq!(variable, "result");
                 match expression {
@@ -1158,14 +1909,20 @@ fn test_function(): () {
         }
 
         match &func.body.statements[1] {
-            Statement::ExpressionStatement(Expression::Variable { name, .. }) => {
+            Statement::ExpressionStatement {
+                expression: Expression::Variable { name, .. },
+                ..
+            } => {
                 assert_eq!(name, "result");
             }
             _ => panic!("Expected expression statement with variable"),
         }
 
         match &func.body.statements[2] {
-            Statement::ExpressionStatement(Expression::Call { function, .. }) => {
+            Statement::ExpressionStatement {
+                expression: Expression::Call { function, .. },
+                ..
+            } => {
                 assert_eq!(function, "another_call");
             }
             _ => panic!("Expected expression statement with call"),
@@ -1203,6 +1960,7 @@ fn calculator_agent(ctx: String, request: String): String {
             variable,
             expression,
             span: _,
+            ..
         } = &func.body.statements[2]
         else {
             panic!("Expected assignment statement");
@@ -1265,6 +2023,7 @@ fn calculator_agent(ctx: String, request: String): String {
             variable,
             expression,
             span: _,
+            ..
         } = &func.body.statements[0]
         else {
             panic!("Expected assignment statement");
@@ -1320,6 +2079,7 @@ fn test_agent(ctx: String): String {
             variable,
             expression,
             span: _,
+            ..
         } = &func.body.statements[0]
         else {
             panic!("Expected assignment statement");
@@ -1364,6 +2124,7 @@ fn test_agent(ctx: String): String {
             variable,
             expression,
             span: _,
+            ..
         } = &func.body.statements[0]
         else {
             panic!("Expected assignment statement");
@@ -1380,6 +2141,100 @@ fn test_agent(ctx: String): String {
 
         let second_clause = &select_stmt.clauses[1];
         assert_eq!(second_clause.result_variable, "diff");
+        assert!(!select_stmt.ranked);
+    }
+
+    #[test]
+    fn test_parse_ranked_select() {
+        let input = r#"
+fn lookup(query: String): Option<String> {
+    let result = select ranked {
+        from_cache(query) as hit => hit,
+        from_index(query) as hit => hit
+    }
+    result
+}
+"#;
+
+        let stream = Stream::with_positioner(input, IndexPositioner::default());
+        let (module, _) = parse_program(TEST_FILE_ID).parse(stream).unwrap();
+
+        let Definition::Function(func) = &module.definitions[0] else {
+            panic!("Expected function definition");
+        };
+        let Statement::Assignment { expression, .. } = &func.body.statements[0] else {
+            panic!("Expected assignment statement");
+        };
+        let Expression::Select(select_stmt) = expression else {
+            panic!("Expected select expression");
+        };
+
+        assert!(select_stmt.ranked);
+        assert_eq!(select_stmt.clauses.len(), 2);
+        assert!(format!("{}", select_stmt).starts_with("select ranked {"));
+    }
+
+    #[test]
+    fn test_parse_retry_select() {
+        let input = r#"
+fn lookup(query: String): String {
+    let result = select retry {
+        from_cache(query) as hit => hit,
+        from_index(query) as hit => hit
+    }
+    result
+}
+"#;
+
+        let stream = Stream::with_positioner(input, IndexPositioner::default());
+        let (module, _) = parse_program(TEST_FILE_ID).parse(stream).unwrap();
+
+        let Definition::Function(func) = &module.definitions[0] else {
+            panic!("Expected function definition");
+        };
+        let Statement::Assignment { expression, .. } = &func.body.statements[0] else {
+            panic!("Expected assignment statement");
+        };
+        let Expression::Select(select_stmt) = expression else {
+            panic!("Expected select expression");
+        };
+
+        assert!(select_stmt.retry);
+        assert!(!select_stmt.ranked);
+        assert!(format!("{}", select_stmt).starts_with("select retry {"));
+    }
+
+    #[test]
+    fn test_parse_select_clause_guard() {
+        let input = r#"
+fn lookup(query: String, cached: Boolean): Option<String> {
+    let result = select {
+        from_cache(query) as hit if cached => hit,
+        from_index(query) as hit => hit
+    }
+    result
+}
+"#;
+
+        let stream = Stream::with_positioner(input, IndexPositioner::default());
+        let (module, _) = parse_program(TEST_FILE_ID).parse(stream).unwrap();
+
+        let Definition::Function(func) = &module.definitions[0] else {
+            panic!("Expected function definition");
+        };
+        let Statement::Assignment { expression, .. } = &func.body.statements[0] else {
+            panic!("Expected assignment statement");
+        };
+        let Expression::Select(select_stmt) = expression else {
+            panic!("Expected select expression");
+        };
+
+        assert!(matches!(
+            &select_stmt.clauses[0].guard,
+            Some(Expression::Variable { name, .. }) if name == "cached"
+        ));
+        assert!(select_stmt.clauses[1].guard.is_none());
+        assert!(format!("{}", select_stmt).contains("as hit if cached => hit"));
     }
 
     #[test]
@@ -1413,6 +2268,43 @@ fn analyze_code(context: String, code: String): String {
         );
     }
 
+    #[test]
+    fn test_docs_after_another_definition_are_kept() {
+        let input = r#"extern fn lookup(key: String): String
+
+# pure
+## Looks the key up
+fn find(key: String): String {
+    lookup(key)
+}
+"#;
+        let stream = Stream::with_positioner(input, IndexPositioner::default());
+        let (module, _) = parse_program(TEST_FILE_ID).parse(stream).unwrap();
+
+        let func = match &module.definitions[1] {
+            Definition::Function(f) => f,
+            _ => panic!("Expected function definition"),
+        };
+        assert_eq!(func.documentation.as_deref(), Some("Looks the key up"));
+    }
+
+    #[test]
+    fn test_docs_before_a_non_function_are_skipped() {
+        let input = r#"## Not attached to anything
+extern fn lookup(key: String): String
+
+## Trailing notes
+"#;
+        let stream = Stream::with_positioner(input, IndexPositioner::default());
+        let (module, _) = parse_program(TEST_FILE_ID).parse(stream).unwrap();
+
+        assert_eq!(module.definitions.len(), 1);
+        assert!(matches!(
+            module.definitions[0],
+            Definition::ExternalFunction(_)
+        ));
+    }
+
     #[test]
     fn test_parse_function_without_comments() {
         let input = r#"
@@ -1437,20 +2329,65 @@ fn simple_function(): () {
     }
 
     #[test]
-    fn test_parse_single_line_comment() {
+    fn test_parse_line_comments_are_ignored() {
         let input = r#"
-## Single line documentation
-fn documented_function(): () {
-    "test"!
+// Not documentation
+fn main(): String {
+    // Before the first statement
+    let greeting = "hi" // after a statement
+    // Between statements
+    greeting!
+    return greeting // the result
+    // After the last statement
 }
 "#;
         let stream = Stream::with_positioner(input, IndexPositioner::default());
 
-        let result = parse_program(TEST_FILE_ID).parse(stream);
-        assert!(result.is_ok());
-
-        let (module, _) = result.unwrap();
-        assert_eq!(module.definitions.len(), 1);
+        let (module, _) = parse_program(TEST_FILE_ID).parse(stream).unwrap();
+        let func = match &module.definitions[0] {
+            Definition::Function(f) => f,
+            _ => panic!("Expected function definition"),
+        };
+        assert!(func.documentation.is_none());
+        assert_eq!(func.body.statements.len(), 3);
+        assert!(matches!(
+            &func.body.statements[0],
+            Statement::Assignment { variable, expression: Expression::StringLiteral { value, .. }, .. }
+                if variable == "greeting" && value == "hi"
+        ));
+    }
+
+    #[test]
+    fn test_parse_line_comment_markers_inside_strings_are_kept() {
+        let input = r#"fn main(): String { return "//not a comment" }"#;
+        let stream = Stream::with_positioner(input, IndexPositioner::default());
+
+        let (module, _) = parse_program(TEST_FILE_ID).parse(stream).unwrap();
+        let func = match &module.definitions[0] {
+            Definition::Function(f) => f,
+            _ => panic!("Expected function definition"),
+        };
+        assert!(matches!(
+            &func.body.statements[0],
+            Statement::Return { expression: Expression::StringLiteral { value, .. }, .. } if value == "//not a comment"
+        ));
+    }
+
+    #[test]
+    fn test_parse_single_line_comment() {
+        let input = r#"
+## Single line documentation
+fn documented_function(): () {
+    "test"!
+}
+"#;
+        let stream = Stream::with_positioner(input, IndexPositioner::default());
+
+        let result = parse_program(TEST_FILE_ID).parse(stream);
+        assert!(result.is_ok());
+
+        let (module, _) = result.unwrap();
+        assert_eq!(module.definitions.len(), 1);
         let func = match &module.definitions[0] {
             Definition::Function(f) => f,
             _ => panic!("Expected function definition"),
@@ -1467,7 +2404,10 @@ fn documented_function(): () {
         let stream = Stream::with_positioner(input, IndexPositioner::default());
         let (statement, _) = statement().parse(stream).unwrap();
         match statement {
-            Statement::Return(Expression::StringLiteral { value, .. }) => {
+            Statement::Return {
+                expression: Expression::StringLiteral { value, .. },
+                ..
+            } => {
                 assert_eq!(value, "hello world");
             }
             _ => panic!("Expected return statement with string literal"),
@@ -1480,7 +2420,10 @@ fn documented_function(): () {
         let stream = Stream::with_positioner(input, IndexPositioner::default());
         let (statement, _) = statement().parse(stream).unwrap();
         match statement {
-            Statement::Return(Expression::UnitLiteral { .. }) => {}
+            Statement::Return {
+                expression: Expression::UnitLiteral { .. },
+                ..
+            } => {}
             _ => panic!("Expected return statement with unit literal"),
         }
     }
@@ -1508,7 +2451,10 @@ fn main(): () {
         assert_eq!(func.body.statements.len(), 1);
 
         match &func.body.statements[0] {
-            Statement::Return(Expression::UnitLiteral { .. }) => {}
+            Statement::Return {
+                expression: Expression::UnitLiteral { .. },
+                ..
+            } => {}
             _ => panic!("Expected return statement with unit literal"),
         }
     }
@@ -1520,7 +2466,10 @@ fn main(): () {
 
         let (stmt, _) = statement().parse(stream).unwrap();
         match stmt {
-            Statement::Return(Expression::Variable { name, .. }) => {
+            Statement::Return {
+                expression: Expression::Variable { name, .. },
+                ..
+            } => {
                 assert_eq!(name, "result");
             }
             _ => panic!("Expected return statement with variable"),
@@ -1557,6 +2506,142 @@ fn multiline_function(
         assert_eq!(func.parameters[2].name, "third_param");
     }
 
+    #[test]
+    fn test_parse_type_spans() {
+        let input = "fn lookup(key: List<Integer>): String {\n    return \"x\"\n}";
+        let stream = Stream::with_positioner(input, IndexPositioner::default());
+
+        let (module, _) = parse_program(TEST_FILE_ID).parse(stream).unwrap();
+        let func = match &module.definitions[0] {
+            Definition::Function(f) => f,
+            _ => panic!("Expected function definition"),
+        };
+
+        let param = &func.parameters[0];
+        assert_eq!(
+            param.param_type,
+            Type::List(Box::new(Type::Custom("Integer".to_string())))
+        );
+        assert_eq!(&input[param.type_span.to_byte_range()], "List<Integer>");
+        assert_eq!(&input[func.return_type_span.to_byte_range()], "String");
+    }
+
+    #[test]
+    fn test_parse_return_keyword_requires_word_boundary() {
+        let input = "fn f(): String {\n    let returned = \"x\"\n    return returned\n}";
+        let stream = Stream::with_positioner(input, IndexPositioner::default());
+
+        let (module, _) = parse_program(TEST_FILE_ID).parse(stream).unwrap();
+        let func = match &module.definitions[0] {
+            Definition::Function(f) => f,
+            _ => panic!("Expected function definition"),
+        };
+
+        assert!(matches!(
+            &func.body.statements[0],
+            Statement::Assignment { variable, .. } if variable == "returned"
+        ));
+        match &func.body.statements[1] {
+            Statement::Return {
+                expression: Expression::Variable { name, .. },
+                ..
+            } => assert_eq!(name, "returned"),
+            other => panic!("Expected return statement, got: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_assert_statement() {
+        let input =
+            "fn f(): () {\n    let asserted = true\n    assert asserted, \"not asserted\"\n}";
+        let stream = Stream::with_positioner(input, IndexPositioner::default());
+
+        let (module, _) = parse_program(TEST_FILE_ID).parse(stream).unwrap();
+        let func = match &module.definitions[0] {
+            Definition::Function(f) => f,
+            _ => panic!("Expected function definition"),
+        };
+
+        assert!(matches!(
+            &func.body.statements[0],
+            Statement::Assignment { variable, .. } if variable == "asserted"
+        ));
+        match &func.body.statements[1] {
+            Statement::Assert {
+                condition: Expression::Variable { name, .. },
+                message: Expression::StringLiteral { value, .. },
+                ..
+            } => {
+                assert_eq!(name, "asserted");
+                assert_eq!(value, "not asserted");
+            }
+            other => panic!("Expected assert statement, got: {:?}", other),
+        }
+        assert_eq!(
+            func.body.statements[1].to_string(),
+            "assert asserted, \"not asserted\""
+        );
+    }
+
+    #[test]
+    fn test_parse_external_const() {
+        let input = r#"
+extern const schema: String
+extern fn lookup(key: String): String
+"#;
+        let stream = Stream::with_positioner(input, IndexPositioner::default());
+
+        let result = parse_program(TEST_FILE_ID).parse(stream);
+        assert!(result.is_ok());
+
+        let (module, _) = result.unwrap();
+        assert_eq!(module.definitions.len(), 2);
+
+        match &module.definitions[0] {
+            Definition::ExternalConst(ext_const) => {
+                assert_eq!(ext_const.name, "schema");
+                assert_eq!(ext_const.const_type, Type::String);
+            }
+            _ => panic!("Expected external const definition"),
+        }
+        assert!(matches!(
+            &module.definitions[1],
+            Definition::ExternalFunction(ef) if ef.name == "lookup"
+        ));
+    }
+
+    #[test]
+    fn test_parse_constant() {
+        let input = r#"
+const GREETING = "Hello"
+const constant_name = '''Multi
+line'''
+"#;
+        let stream = Stream::with_positioner(input, IndexPositioner::default());
+
+        let result = parse_program(TEST_FILE_ID).parse(stream);
+        assert!(result.is_ok());
+
+        let (module, _) = result.unwrap();
+        assert_eq!(module.definitions.len(), 2);
+
+        match &module.definitions[0] {
+            Definition::Constant(constant) => {
+                assert_eq!(constant.name, "GREETING");
+                assert_eq!(
+                    &input[constant.span.start..constant.span.end],
+                    "const GREETING = \"Hello\""
+                );
+                assert_eq!(constant.to_string(), "const GREETING = \"Hello\"");
+            }
+            _ => panic!("Expected constant definition"),
+        }
+        assert!(matches!(
+            &module.definitions[1],
+            Definition::Constant(constant) if constant.name == "constant_name"
+        ));
+    }
+
     #[test]
     fn test_multiline_external_function() {
         let input = r#"
@@ -1584,6 +2669,181 @@ extern fn external_multiline(
         assert_eq!(ext.parameters[1].name, "param2");
     }
 
+    #[test]
+    fn test_parse_number_literals() {
+        let input = r#"
+fn numbers(): Number {
+    let whole = 42
+    let negative = -7
+    let fraction = 2.5
+    return whole
+}
+"#;
+        let stream = Stream::with_positioner(input, IndexPositioner::default());
+
+        let result = parse_program(TEST_FILE_ID).parse(stream);
+        assert!(result.is_ok());
+
+        let (module, _) = result.unwrap();
+        let func = match &module.definitions[0] {
+            Definition::Function(f) => f,
+            _ => panic!("Expected function definition"),
+        };
+        assert_eq!(func.return_type, Type::Number);
+
+        let values: Vec<f64> = func.body.statements[..3]
+            .iter()
+            .map(|statement| match statement {
+                Statement::Assignment {
+                    expression: Expression::NumberLiteral { value, .. },
+                    ..
+                } => *value,
+                other => panic!("Expected number assignment, got {:?}", other),
+            })
+            .collect();
+        assert_eq!(values, vec![42.0, -7.0, 2.5]);
+    }
+
+    #[test]
+    fn test_parse_else_if_chain() {
+        let input = r#"
+fn pick(a: Boolean, b: Boolean): () {
+    if a {
+        "a"!
+    } else if b {
+        "b"!
+    } else {
+        "neither"!
+    }
+}
+"#;
+        let stream = Stream::with_positioner(input, IndexPositioner::default());
+
+        let (module, _) = parse_program(TEST_FILE_ID).parse(stream).unwrap();
+        let func = match &module.definitions[0] {
+            Definition::Function(f) => f,
+            _ => panic!("Expected function definition"),
+        };
+        assert_eq!(func.body.statements.len(), 1);
+
+        let Statement::If {
+            else_body: Some(else_body),
+            ..
+        } = &func.body.statements[0]
+        else {
+            panic!("Expected if statement with an else branch");
+        };
+        match else_body.as_slice() {
+            [
+                Statement::If {
+                    condition: Expression::Variable { name, .. },
+                    body,
+                    else_body: Some(last),
+                    ..
+                },
+            ] => {
+                assert_eq!(name, "b");
+                assert_eq!(body.len(), 1);
+                assert_eq!(last.len(), 1);
+            }
+            other => panic!("Expected a nested else if, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_match_expression() {
+        let input = r#"
+fn label(flag: Boolean): String {
+    let text = match flag {
+        true => "on",
+        false => if flag { "never" } else { "off" },
+    }
+    return text
+}
+"#;
+        let stream = Stream::with_positioner(input, IndexPositioner::default());
+
+        let (module, _) = parse_program(TEST_FILE_ID).parse(stream).unwrap();
+        let func = match &module.definitions[0] {
+            Definition::Function(f) => f,
+            _ => panic!("Expected function definition"),
+        };
+
+        let Statement::Assignment { expression, .. } = &func.body.statements[0] else {
+            panic!("Expected assignment");
+        };
+        let Expression::Match {
+            scrutinee, arms, ..
+        } = expression
+        else {
+            panic!("Expected match, got {:?}", expression);
+        };
+        assert!(matches!(
+            scrutinee.as_ref(),
+            Expression::Variable { name, .. } if name == "flag"
+        ));
+        assert_eq!(
+            arms.iter().map(|arm| arm.pattern).collect::<Vec<_>>(),
+            vec![true, false]
+        );
+        assert!(matches!(arms[1].expression, Expression::IfElse { .. }));
+        assert_eq!(
+            expression.to_string(),
+            "match flag {\n    true => \"on\",\n    false => if flag { \"never\" } else { \"off\" },\n}"
+        );
+    }
+
+    #[test]
+    fn test_parse_option_literals() {
+        let input = r#"
+fn main(): () {
+    let present = Some("x")
+    let absent = None
+    let named = Nonesuch
+}
+"#;
+        let stream = Stream::with_positioner(input, IndexPositioner::default());
+
+        let (module, _) = parse_program(TEST_FILE_ID).parse(stream).unwrap();
+        let func = match &module.definitions[0] {
+            Definition::Function(f) => f,
+            _ => panic!("Expected function definition"),
+        };
+        let values: Vec<&Expression> = func
+            .body
+            .statements
+            .iter()
+            .map(|statement| match statement {
+                Statement::Assignment { expression, .. } => expression,
+                other => panic!("Expected assignment, got {:?}", other),
+            })
+            .collect();
+
+        match values[0] {
+            Expression::OptionLiteral {
+                value: Some(value),
+                span,
+            } => {
+                assert!(matches!(
+                    value.as_ref(),
+                    Expression::StringLiteral { value, .. } if value == "x"
+                ));
+                assert_eq!(&input[span.start..span.end], "Some(\"x\")");
+            }
+            other => panic!("Expected Some, got {:?}", other),
+        }
+        assert!(matches!(
+            values[1],
+            Expression::OptionLiteral { value: None, .. }
+        ));
+        assert!(matches!(
+            values[2],
+            Expression::Variable { name, .. } if name == "Nonesuch"
+        ));
+        assert_eq!(values[0].to_string(), "Some(\"x\")");
+        assert_eq!(values[1].to_string(), "None");
+    }
+
     #[test]
     fn test_parse_if_else_expression() {
         let input = r#"
@@ -1667,7 +2927,10 @@ fn nested(): String {
         };
 
         match &func.body.statements[0] {
-            Statement::Return(Expression::IfElse { then_expr, .. }) => match then_expr.as_ref() {
+            Statement::Return {
+                expression: Expression::IfElse { then_expr, .. },
+                ..
+            } => match then_expr.as_ref() {
                 Expression::IfElse { .. } => {}
                 _ => panic!("Expected nested if-else"),
             },
@@ -1781,7 +3044,10 @@ fn test_if_else_stmt(): () {
 
                 assert_eq!(body.len(), 1);
                 match &body[0] {
-                    Statement::Injection(Expression::StringLiteral { value, .. }) => {
+                    Statement::Injection {
+                        expression: Expression::StringLiteral { value, .. },
+                        ..
+                    } => {
                         assert_eq!(value, "then");
                     }
                     _ => panic!("Expected injection with string literal in then branch"),
@@ -1791,7 +3057,10 @@ fn test_if_else_stmt(): () {
                 let else_body = else_body.as_ref().unwrap();
                 assert_eq!(else_body.len(), 1);
                 match &else_body[0] {
-                    Statement::Injection(Expression::StringLiteral { value, .. }) => {
+                    Statement::Injection {
+                        expression: Expression::StringLiteral { value, .. },
+                        ..
+                    } => {
                         assert_eq!(value, "else");
                     }
                     _ => panic!("Expected injection with string literal in else branch"),
@@ -1861,8 +3130,10 @@ fn test_if_else_stmt(): () {
 
         if let Definition::Function(func) = &module.definitions[0] {
             assert_eq!(func.body.statements.len(), 1);
-            if let Statement::ExpressionStatement(Expression::ListLiteral { elements, .. }) =
-                &func.body.statements[0]
+            if let Statement::ExpressionStatement {
+                expression: Expression::ListLiteral { elements, .. },
+                ..
+            } = &func.body.statements[0]
             {
                 assert_eq!(elements.len(), 0);
             } else {
@@ -1873,6 +3144,41 @@ fn test_if_else_stmt(): () {
         }
     }
 
+    #[test]
+    fn test_parse_list_literal_as_call_argument() {
+        let input = "fn main(): () {\n    join([first, \"b\"], [])\n}";
+        let stream = Stream::with_positioner(input, IndexPositioner::default());
+
+        let (module, _) = parse_program(TEST_FILE_ID).parse(stream).unwrap();
+        let Definition::Function(func) = &module.definitions[0] else {
+            panic!("Expected function definition");
+        };
+        let Statement::ExpressionStatement {
+            expression: Expression::Call { arguments, .. },
+            ..
+        } = &func.body.statements[0]
+        else {
+            panic!("Expected call");
+        };
+
+        match arguments.as_slice() {
+            [
+                Expression::ListLiteral { elements, span },
+                Expression::ListLiteral {
+                    elements: empty, ..
+                },
+            ] => {
+                assert!(matches!(
+                    &elements[0],
+                    Expression::Variable { name, .. } if name == "first"
+                ));
+                assert_eq!(&input[span.start..span.end], "[first, \"b\"]");
+                assert!(empty.is_empty());
+            }
+            other => panic!("Expected two list arguments, got {:?}", other),
+        }
+    }
+
     #[test]
     fn test_parse_option_type() {
         let input = r#"
@@ -1958,4 +3264,163 @@ fn test_function(): () {
             panic!("Expected function definition");
         }
     }
+
+    fn parse_with_statement_comments(input: &str) -> Function {
+        let stream = Stream::with_positioner(input, IndexPositioner::default());
+        let (module, _) = parse_program(TEST_FILE_ID).parse(stream).unwrap();
+
+        match module.definitions.into_iter().next() {
+            Some(Definition::Function(func)) => func,
+            _ => panic!("Expected function definition"),
+        }
+    }
+
+    #[test]
+    fn test_comment_attached_to_following_statement() {
+        let func = parse_with_statement_comments(
+            r#"
+fn test_function(): String {
+    # Greet the user
+    # before doing anything else
+    "hello"!
+    let x = "value"
+    if true {
+        # Only when enabled
+        x!
+    }
+    # Hand the value back
+    return x
+}
+"#,
+        );
+
+        let body = &func.body;
+        assert_eq!(
+            body.statements[0].comment(),
+            Some("Greet the user\nbefore doing anything else")
+        );
+        assert_eq!(body.statements[1].comment(), None);
+
+        let Statement::If { body: if_body, .. } = &body.statements[2] else {
+            panic!("Expected if statement");
+        };
+        assert_eq!(body.statements[2].comment(), None);
+        assert_eq!(if_body[0].comment(), Some("Only when enabled"));
+        assert_eq!(body.statements[3].comment(), Some("Hand the value back"));
+    }
+
+    #[test]
+    fn test_comment_attached_to_following_parameter() {
+        let func = parse_with_statement_comments(
+            r#"
+fn test_function(
+    # What to search for
+    query: String,
+    limit: String
+): () {
+    query!
+    limit!
+}
+"#,
+        );
+
+        assert_eq!(func.parameters.len(), 2);
+        assert_eq!(
+            func.parameters[0].comment.as_deref(),
+            Some("What to search for")
+        );
+        assert_eq!(func.parameters[1].comment, None);
+    }
+
+    #[test]
+    fn test_statement_comments_leave_function_docs_unchanged() {
+        let func = parse_with_statement_comments(
+            r#"
+## Summarises the input
+fn summarise(text: String): () {
+    # Echo the input
+    text!
+}
+"#,
+        );
+
+        assert_eq!(func.documentation.as_deref(), Some("Summarises the input"));
+        assert_eq!(func.parameters[0].comment, None);
+        assert_eq!(func.body.statements[0].comment(), Some("Echo the input"));
+    }
+
+    #[test]
+    fn test_parse_chained_field_access() {
+        let input = r#"
+            fn test(issue: Json): Json {
+                let id = issue.meta.id
+                id
+            }
+        "#;
+
+        let stream = Stream::with_positioner(input, IndexPositioner::default());
+        let module = parse_program(TEST_FILE_ID)
+            .parse(stream)
+            .map(|(module, _)| module)
+            .unwrap();
+
+        let Definition::Function(func) = &module.definitions[0] else {
+            panic!("Expected function definition");
+        };
+        let Statement::Assignment { expression, .. } = &func.body.statements[0] else {
+            panic!("Expected assignment");
+        };
+        let Expression::FieldAccess {
+            object,
+            field,
+            span,
+        } = expression
+        else {
+            panic!("Expected field access, got {:?}", expression);
+        };
+        assert_eq!(field, "id");
+        assert_eq!(&input[span.start..span.end], "issue.meta.id");
+
+        let Expression::FieldAccess { object, field, .. } = object.as_ref() else {
+            panic!("Expected nested field access, got {:?}", object);
+        };
+        assert_eq!(field, "meta");
+        assert!(matches!(
+            object.as_ref(),
+            Expression::Variable { name, .. } if name == "issue"
+        ));
+    }
+
+    #[test]
+    fn test_parse_field_access_on_call_result() {
+        let input = r#"
+            extern fn fetch(): Json
+
+            fn test(): Json {
+                fetch().status
+            }
+        "#;
+
+        let stream = Stream::with_positioner(input, IndexPositioner::default());
+        let module = parse_program(TEST_FILE_ID)
+            .parse(stream)
+            .map(|(module, _)| module)
+            .unwrap();
+
+        let Definition::Function(func) = &module.definitions[1] else {
+            panic!("Expected function definition");
+        };
+        let Statement::ExpressionStatement {
+            expression: Expression::FieldAccess { object, field, .. },
+            ..
+        } = &func.body.statements[0]
+        else {
+            panic!("Expected field access statement");
+        };
+        assert_eq!(field, "status");
+        assert!(matches!(
+            object.as_ref(),
+            Expression::Call { function, .. } if function == "fetch"
+        ));
+    }
 }
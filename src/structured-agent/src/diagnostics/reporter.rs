@@ -29,18 +29,7 @@ impl DiagnosticReporter {
         error: &str,
         span: Option<(usize, usize)>,
     ) -> Result<(), Box<dyn std::error::Error>> {
-        let diagnostic = if let Some((start, end)) = span {
-            Diagnostic::error()
-                .with_message("parse error")
-                .with_labels(vec![
-                    codespan_reporting::diagnostic::Label::primary(file_id, start..end)
-                        .with_message(error),
-                ])
-        } else {
-            Diagnostic::error().with_message(format!("parse error: {}", error))
-        };
-
-        self.emit_diagnostic(&diagnostic)
+        self.emit_diagnostic(&parse_error_diagnostic(file_id, error, span))
     }
 
     pub fn emit_diagnostic(
@@ -59,6 +48,23 @@ impl DiagnosticReporter {
     }
 }
 
+pub fn parse_error_diagnostic(
+    file_id: FileId,
+    error: &str,
+    span: Option<(usize, usize)>,
+) -> Diagnostic<FileId> {
+    if let Some((start, end)) = span {
+        Diagnostic::error()
+            .with_message("parse error")
+            .with_labels(vec![
+                codespan_reporting::diagnostic::Label::primary(file_id, start..end)
+                    .with_message(error),
+            ])
+    } else {
+        Diagnostic::error().with_message(format!("parse error: {}", error))
+    }
+}
+
 impl Default for DiagnosticReporter {
     fn default() -> Self {
         Self::new(SourceFiles::new())
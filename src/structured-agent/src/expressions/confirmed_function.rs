@@ -0,0 +1,85 @@
+use crate::runtime::{Context, ExpressionResult, ExpressionValue};
+use crate::types::{ExecutableFunction, Function, Parameter, Type};
+use async_trait::async_trait;
+use std::any::Any;
+use std::sync::Arc;
+
+/// Asked with the function name and arguments before a destructive tool
+/// runs. Returning false refuses the call.
+pub type ConfirmationHook = Arc<dyn Fn(&str, &[ExpressionValue]) -> bool + Send + Sync>;
+
+/// Wraps a destructive function so every call is confirmed first. Without a
+/// hook, every call is refused.
+#[derive(Clone)]
+pub struct ConfirmedFunctionExpr {
+    inner: Arc<dyn ExecutableFunction>,
+    hook: Option<ConfirmationHook>,
+}
+
+impl ConfirmedFunctionExpr {
+    pub fn new(inner: Arc<dyn ExecutableFunction>, hook: Option<ConfirmationHook>) -> Self {
+        Self { inner, hook }
+    }
+}
+
+impl std::fmt::Debug for ConfirmedFunctionExpr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ConfirmedFunctionExpr")
+            .field("inner", &self.inner)
+            .field("hook", &self.hook.as_ref().map(|_| "ConfirmationHook"))
+            .finish()
+    }
+}
+
+#[async_trait]
+impl Function for ConfirmedFunctionExpr {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn parameters(&self) -> &[Parameter] {
+        self.inner.parameters()
+    }
+
+    fn function_return_type(&self) -> &Type {
+        self.inner.function_return_type()
+    }
+
+    async fn execute(
+        &self,
+        context: Context,
+        args: Vec<ExpressionResult>,
+    ) -> Result<(Context, ExpressionResult), String> {
+        let values: Vec<ExpressionValue> = args.iter().map(|arg| arg.value.clone()).collect();
+        let confirmed = self
+            .hook
+            .as_ref()
+            .is_some_and(|hook| hook(self.name(), &values));
+        if !confirmed {
+            return Err(format!(
+                "Call to destructive tool '{}' was not confirmed",
+                self.name()
+            ));
+        }
+        self.inner.execute(context, args).await
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn clone_box(&self) -> Box<dyn Function> {
+        Box::new(self.clone())
+    }
+
+    fn documentation(&self) -> Option<&str> {
+        self.inner.documentation()
+    }
+}
+
+#[async_trait]
+impl ExecutableFunction for ConfirmedFunctionExpr {
+    fn clone_executable(&self) -> Box<dyn ExecutableFunction> {
+        Box::new(self.clone())
+    }
+}
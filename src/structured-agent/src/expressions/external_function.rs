@@ -1,9 +1,8 @@
 use crate::mcp::McpClient;
 use crate::runtime::{Context, ExpressionResult, ExpressionValue};
 use crate::types::{ExecutableFunction, Function, Parameter, Type};
-use arrow::array::Array;
 use async_trait::async_trait;
-use serde_json::json;
+use serde_json::{Map, Value, json};
 use std::any::Any;
 use std::sync::Arc;
 
@@ -13,6 +12,7 @@ pub struct ExternalFunctionExpr {
     pub return_type: Type,
     pub mcp_client: Arc<McpClient>,
     pub documentation: Option<String>,
+    pub input_schema: Option<Arc<Map<String, Value>>>,
 }
 
 impl std::fmt::Debug for ExternalFunctionExpr {
@@ -23,6 +23,7 @@ impl std::fmt::Debug for ExternalFunctionExpr {
             .field("return_type", &self.return_type)
             .field("mcp_client", &"McpClient")
             .field("documentation", &self.documentation)
+            .field("input_schema", &self.input_schema)
             .finish()
     }
 }
@@ -35,6 +36,7 @@ impl Clone for ExternalFunctionExpr {
             return_type: self.return_type.clone(),
             mcp_client: self.mcp_client.clone(),
             documentation: self.documentation.clone(),
+            input_schema: self.input_schema.clone(),
         }
     }
 }
@@ -53,8 +55,14 @@ impl ExternalFunctionExpr {
             return_type,
             mcp_client,
             documentation,
+            input_schema: None,
         }
     }
+
+    pub fn with_input_schema(mut self, schema: Option<Arc<Map<String, Value>>>) -> Self {
+        self.input_schema = schema;
+        self
+    }
 }
 
 #[async_trait]
@@ -76,52 +84,11 @@ impl Function for ExternalFunctionExpr {
         context: Context,
         args: Vec<ExpressionResult>,
     ) -> Result<(Context, ExpressionResult), String> {
-        let mut arguments = json!({});
-
-        fn expr_result_to_json(value: &ExpressionValue) -> serde_json::Value {
-            match value {
-                ExpressionValue::String(s) => json!(s),
-                ExpressionValue::Unit => json!(null),
-                ExpressionValue::Boolean(b) => json!(b),
-                ExpressionValue::List(list) => {
-                    if list.len() == 0 {
-                        json!([])
-                    } else {
-                        let values = list.value(0);
-                        let mut items = Vec::new();
-                        if let Some(string_array) =
-                            values.as_any().downcast_ref::<arrow::array::StringArray>()
-                        {
-                            for i in 0..string_array.len() {
-                                items.push(json!(string_array.value(i)));
-                            }
-                        }
-                        json!(items)
-                    }
-                }
-                ExpressionValue::Option(opt) => match opt {
-                    Some(inner) => json!({
-                        "some": expr_result_to_json(inner)
-                    }),
-                    None => json!(null),
-                },
-                ExpressionValue::Metadata {
-                    name,
-                    documentation,
-                } => {
-                    json!({
-                        "name": name,
-                        "documentation": documentation
-                    })
-                }
-            }
-        }
-
-        for (i, param) in self.parameters.iter().enumerate() {
-            let json_value = expr_result_to_json(&args[i].value);
-            arguments[&param.name] = json_value;
-        }
+        let values: Vec<ExpressionValue> = args.iter().map(|arg| arg.value.clone()).collect();
+        let arguments = tool_arguments(&self.parameters, self.input_schema.as_deref(), &values)
+            .map_err(|e| format!("Invalid arguments for MCP tool '{}': {}", self.name, e))?;
 
+        context.runtime().stats().record_tool_call();
         let result_raw = self
             .mcp_client
             .call_tool(&self.name, arguments)
@@ -133,28 +100,9 @@ impl Function for ExternalFunctionExpr {
         }
 
         let result = result_raw?;
+        let value = call_result_to_value(&result, &self.return_type)?;
 
-        if result.content.is_empty() {
-            Ok((context, ExpressionResult::new(ExpressionValue::Unit)))
-        } else {
-            if result.content.len() != 1 {
-                return Err(format!("Expected one result, got {}", result.content.len()));
-            }
-
-            match &*result.content[0] {
-                rmcp::model::RawContent::Text(text_content) => Ok((
-                    context,
-                    ExpressionResult::new(ExpressionValue::String(text_content.text.clone())),
-                )),
-                _ => {
-                    let content_str = format!("{:?}", result.content);
-                    Ok((
-                        context,
-                        ExpressionResult::new(ExpressionValue::String(content_str)),
-                    ))
-                }
-            }
-        }
+        Ok((context, ExpressionResult::new(value)))
     }
 
     fn as_any(&self) -> &dyn Any {
@@ -170,6 +118,208 @@ impl Function for ExternalFunctionExpr {
     }
 }
 
+/// Builds the argument object for a tool call. Each value is converted to the
+/// type its property declares in `schema`, so a String can be passed where
+/// the tool takes a number, array or object written as text. Empty Options
+/// and Unit are left out, and every property the schema requires must be
+/// given.
+fn tool_arguments(
+    parameters: &[Parameter],
+    schema: Option<&Map<String, Value>>,
+    args: &[ExpressionValue],
+) -> Result<Value, String> {
+    let properties = schema
+        .and_then(|schema| schema.get("properties"))
+        .and_then(Value::as_object);
+
+    let mut arguments = Map::new();
+    for (param, arg) in parameters.iter().zip(args) {
+        let property = properties.and_then(|properties| properties.get(&param.name));
+        let value = fit_to_schema(arg.to_json(), property)
+            .map_err(|e| format!("argument '{}' {}", param.name, e))?;
+        if !value.is_null() {
+            arguments.insert(param.name.clone(), value);
+        }
+    }
+
+    let required = schema
+        .and_then(|schema| schema.get("required"))
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str);
+    for name in required {
+        if !arguments.contains_key(name) {
+            return Err(format!("missing required argument '{}'", name));
+        }
+    }
+
+    Ok(Value::Object(arguments))
+}
+
+fn fit_to_schema(value: Value, schema: Option<&Value>) -> Result<Value, String> {
+    let Some(schema) = schema else {
+        return Ok(value);
+    };
+    // A nullable property is written `"type": ["string", "null"]`.
+    let expected = match schema.get("type") {
+        Some(Value::String(name)) => name.as_str(),
+        Some(Value::Array(names)) => names
+            .iter()
+            .filter_map(Value::as_str)
+            .find(|name| *name != "null")
+            .unwrap_or("null"),
+        _ => return Ok(value),
+    };
+
+    match (expected, value) {
+        (_, Value::Null) => Ok(Value::Null),
+        ("string", Value::String(text)) => Ok(Value::String(text)),
+        ("string", other) => Ok(Value::String(other.to_string())),
+        ("boolean", Value::Bool(b)) => Ok(Value::Bool(b)),
+        ("boolean", Value::String(text)) => match text.trim() {
+            "true" => Ok(Value::Bool(true)),
+            "false" => Ok(Value::Bool(false)),
+            _ => Err(format!("expects a boolean, got '{}'", text)),
+        },
+        ("integer", Value::Number(n)) if n.is_i64() || n.is_u64() => Ok(Value::Number(n)),
+        ("integer", Value::String(text)) => text
+            .trim()
+            .parse::<i64>()
+            .map(Value::from)
+            .map_err(|_| format!("expects an integer, got '{}'", text)),
+        ("number", Value::Number(n)) => Ok(Value::Number(n)),
+        ("number", Value::String(text)) => text
+            .trim()
+            .parse::<f64>()
+            .ok()
+            .and_then(serde_json::Number::from_f64)
+            .map(Value::Number)
+            .ok_or_else(|| format!("expects a number, got '{}'", text)),
+        ("array" | "object", Value::String(text)) => match serde_json::from_str(&text) {
+            Ok(parsed @ (Value::Array(_) | Value::Object(_))) => {
+                fit_to_schema(parsed, Some(schema))
+            }
+            _ => Err(format!(
+                "expects {} {}, got '{}'",
+                article(expected),
+                expected,
+                text
+            )),
+        },
+        ("array", Value::Array(items)) => items
+            .into_iter()
+            .map(|item| fit_to_schema(item, schema.get("items")))
+            .collect::<Result<Vec<_>, _>>()
+            .map(Value::Array),
+        ("object", Value::Object(fields)) => {
+            let properties = schema.get("properties").and_then(Value::as_object);
+            fields
+                .into_iter()
+                .map(|(name, field)| {
+                    let property = properties.and_then(|properties| properties.get(&name));
+                    fit_to_schema(field, property).map(|field| (name, field))
+                })
+                .collect::<Result<Map<_, _>, _>>()
+                .map(Value::Object)
+        }
+        (_, other @ (Value::Array(_) | Value::Object(_) | Value::Bool(_) | Value::Number(_)))
+            if matches!(
+                expected,
+                "integer" | "number" | "boolean" | "array" | "object"
+            ) =>
+        {
+            Err(format!(
+                "expects {} {}, got {}",
+                article(expected),
+                expected,
+                other
+            ))
+        }
+        (_, other) => Ok(other),
+    }
+}
+
+fn article(type_name: &str) -> &'static str {
+    match type_name {
+        "integer" | "array" | "object" => "an",
+        _ => "a",
+    }
+}
+
+fn call_result_to_value(
+    result: &rmcp::model::CallToolResult,
+    return_type: &Type,
+) -> Result<ExpressionValue, String> {
+    if let (Type::Json, Some(structured)) = (return_type, &result.structured_content) {
+        return Ok(ExpressionValue::Json(structured.clone()));
+    }
+
+    if *return_type == Type::Boolean {
+        return boolean_result(result);
+    }
+
+    if let (Type::Number, Some(structured)) = (return_type, &result.structured_content)
+        && let Some(number) = structured.get("result").and_then(|result| result.as_f64())
+    {
+        return Ok(ExpressionValue::Number(number));
+    }
+
+    if result.content.is_empty() {
+        return Ok(ExpressionValue::Unit);
+    }
+
+    if result.content.len() != 1 {
+        return Err(format!("Expected one result, got {}", result.content.len()));
+    }
+
+    match &*result.content[0] {
+        rmcp::model::RawContent::Text(text_content) => match return_type {
+            Type::Json => Ok(ExpressionValue::Json(
+                serde_json::from_str(&text_content.text)
+                    .unwrap_or_else(|_| json!(text_content.text)),
+            )),
+            Type::Number => text_content
+                .text
+                .trim()
+                .parse()
+                .map(ExpressionValue::Number)
+                .map_err(|_| {
+                    format!(
+                        "Expected a Number result from MCP tool, got '{}'",
+                        text_content.text
+                    )
+                }),
+            _ => Ok(ExpressionValue::String(text_content.text.clone())),
+        },
+        _ => Ok(ExpressionValue::String(format!("{:?}", result.content))),
+    }
+}
+
+// Structured results arrive wrapped as `{"result": true}`; plain text
+// results are accepted when they spell out a boolean.
+fn boolean_result(result: &rmcp::model::CallToolResult) -> Result<ExpressionValue, String> {
+    if let Some(structured) = &result.structured_content {
+        let value = structured.get("result").unwrap_or(structured);
+        if let Some(b) = value.as_bool() {
+            return Ok(ExpressionValue::Boolean(b));
+        }
+    }
+
+    let text = match result.content.first().map(|content| &**content) {
+        Some(rmcp::model::RawContent::Text(text_content)) => text_content.text.trim(),
+        _ => "",
+    };
+    match text.to_ascii_lowercase().as_str() {
+        "true" => Ok(ExpressionValue::Boolean(true)),
+        "false" => Ok(ExpressionValue::Boolean(false)),
+        _ => Err(format!(
+            "Expected a Boolean result from MCP tool, got '{}'",
+            text
+        )),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -186,6 +336,7 @@ mod tests {
             return_type: Type::string(),
             mcp_client: client.clone(),
             documentation: Some("This is a test external function".to_string()),
+            input_schema: None,
         };
 
         assert_eq!(
@@ -199,10 +350,143 @@ mod tests {
             return_type: Type::string(),
             mcp_client: client,
             documentation: None,
+            input_schema: None,
         };
 
         assert_eq!(expr_without_docs.documentation(), None);
     }
+
+    fn issue_schema() -> Map<String, Value> {
+        json!({
+            "type": "object",
+            "properties": {
+                "title": {"type": "string"},
+                "priority": {"type": "integer"},
+                "urgent": {"type": "boolean"},
+                "labels": {"type": "array", "items": {"type": "string"}},
+                "fields": {"type": "object", "properties": {"estimate": {"type": "number"}}},
+                "assignee": {"type": ["string", "null"]}
+            },
+            "required": ["title", "priority"]
+        })
+        .as_object()
+        .unwrap()
+        .clone()
+    }
+
+    fn issue_parameters() -> Vec<Parameter> {
+        [
+            "title", "priority", "urgent", "labels", "fields", "assignee",
+        ]
+        .into_iter()
+        .map(|name| Parameter::new(name.to_string(), Type::string()))
+        .collect()
+    }
+
+    #[test]
+    fn test_arguments_are_converted_to_schema_types() {
+        let args = vec![
+            ExpressionValue::String("Crash on start".to_string()),
+            ExpressionValue::String("2".to_string()),
+            ExpressionValue::Boolean(true),
+            ExpressionValue::from(vec![
+                ExpressionValue::String("bug".to_string()),
+                ExpressionValue::String("startup".to_string()),
+            ]),
+            ExpressionValue::String(r#"{"estimate": "1.5"}"#.to_string()),
+            ExpressionValue::Option(None),
+        ];
+
+        let arguments = tool_arguments(&issue_parameters(), Some(&issue_schema()), &args).unwrap();
+
+        assert_eq!(
+            arguments,
+            json!({
+                "title": "Crash on start",
+                "priority": 2,
+                "urgent": true,
+                "labels": ["bug", "startup"],
+                "fields": {"estimate": 1.5}
+            })
+        );
+    }
+
+    #[test]
+    fn test_missing_required_argument_is_rejected() {
+        let args = vec![
+            ExpressionValue::String("Crash on start".to_string()),
+            ExpressionValue::Option(None),
+            ExpressionValue::Boolean(false),
+            ExpressionValue::from(Vec::new()),
+            ExpressionValue::Json(json!({})),
+            ExpressionValue::Option(None),
+        ];
+
+        assert_eq!(
+            tool_arguments(&issue_parameters(), Some(&issue_schema()), &args),
+            Err("missing required argument 'priority'".to_string())
+        );
+    }
+
+    #[test]
+    fn test_argument_that_cannot_fit_schema_is_rejected() {
+        let args = vec![
+            ExpressionValue::String("Crash on start".to_string()),
+            ExpressionValue::String("high".to_string()),
+        ];
+
+        assert_eq!(
+            tool_arguments(&issue_parameters()[..2], Some(&issue_schema()), &args),
+            Err("argument 'priority' expects an integer, got 'high'".to_string())
+        );
+    }
+
+    #[test]
+    fn test_structured_result_is_preserved_as_json() {
+        let payload = json!({"issue": {"id": 42, "title": "Crash on start"}});
+        let result = rmcp::model::CallToolResult::structured(payload.clone());
+
+        let value = call_result_to_value(&result, &Type::json()).unwrap();
+
+        assert_eq!(value, ExpressionValue::Json(payload));
+    }
+
+    #[test]
+    fn test_text_result_parsed_when_json_declared() {
+        let result = rmcp::model::CallToolResult::success(vec![rmcp::model::Content::text(
+            r#"{"count": 3}"#,
+        )]);
+
+        assert_eq!(
+            call_result_to_value(&result, &Type::json()).unwrap(),
+            ExpressionValue::Json(json!({"count": 3}))
+        );
+        assert_eq!(
+            call_result_to_value(&result, &Type::string()).unwrap(),
+            ExpressionValue::String(r#"{"count": 3}"#.to_string())
+        );
+    }
+
+    #[test]
+    fn test_boolean_result_coerced_from_structured_or_text() {
+        let structured = rmcp::model::CallToolResult::structured(json!({"result": true}));
+        let text =
+            rmcp::model::CallToolResult::success(vec![rmcp::model::Content::text("False\n")]);
+        let other = rmcp::model::CallToolResult::success(vec![rmcp::model::Content::text("maybe")]);
+
+        assert_eq!(
+            call_result_to_value(&structured, &Type::boolean()).unwrap(),
+            ExpressionValue::Boolean(true)
+        );
+        assert_eq!(
+            call_result_to_value(&text, &Type::boolean()).unwrap(),
+            ExpressionValue::Boolean(false)
+        );
+        assert_eq!(
+            call_result_to_value(&other, &Type::boolean()),
+            Err("Expected a Boolean result from MCP tool, got 'maybe'".to_string())
+        );
+    }
 }
 
 #[async_trait]
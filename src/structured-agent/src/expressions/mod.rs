@@ -1,5 +1,7 @@
+pub mod confirmed_function;
 pub mod external_function;
 pub mod native_function;
 
+pub use confirmed_function::{ConfirmationHook, ConfirmedFunctionExpr};
 pub use external_function::ExternalFunctionExpr;
 pub use native_function::NativeFunctionExpr;
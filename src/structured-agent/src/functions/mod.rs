@@ -1,11 +1,15 @@
 pub mod acp_shim;
 pub mod input;
+pub mod parse_int;
 pub mod print;
+pub mod to_string;
 pub mod unstable;
 
 pub use input::InputFunction;
+pub use parse_int::ParseIntFunction;
 pub use print::PrintFunction;
+pub use to_string::ToStringFunction;
 pub use unstable::{
-    HeadFunction, IsSomeFunction, IsSomeListFunction, SomeValueFunction, SomeValueListFunction,
-    TailFunction,
+    HeadFunction, IsSomeFunction, IsSomeListFunction, JsonGetFunction, SomeValueFunction,
+    SomeValueListFunction, TailFunction,
 };
@@ -0,0 +1,92 @@
+use crate::runtime::ExpressionValue;
+use crate::types::{NativeFunction, Parameter, Type};
+use async_trait::async_trait;
+
+#[derive(Debug)]
+pub struct ParseIntFunction {
+    parameters: Vec<Parameter>,
+    return_type: Type,
+}
+
+impl Default for ParseIntFunction {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ParseIntFunction {
+    pub fn new() -> Self {
+        Self {
+            parameters: vec![Parameter::new("text".to_string(), Type::string())],
+            return_type: Type::option(Type::number()),
+        }
+    }
+}
+
+#[async_trait]
+impl NativeFunction for ParseIntFunction {
+    fn name(&self) -> &str {
+        "parse_int"
+    }
+
+    fn parameters(&self) -> &[Parameter] {
+        &self.parameters
+    }
+
+    fn return_type(&self) -> &Type {
+        &self.return_type
+    }
+
+    async fn execute(&self, args: Vec<ExpressionValue>) -> Result<ExpressionValue, String> {
+        if args.len() != 1 {
+            return Err(format!("parse_int expects 1 argument, got {}", args.len()));
+        }
+
+        let text = args[0].as_string()?;
+        let parsed = text
+            .trim()
+            .parse::<i64>()
+            .ok()
+            .map(|n| Box::new(ExpressionValue::Number(n as f64)));
+        Ok(ExpressionValue::Option(parsed))
+    }
+
+    fn documentation(&self) -> Option<&str> {
+        Some("Reads a whole number from text, or returns None if the text is not one")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn parse(text: &str) -> ExpressionValue {
+        ParseIntFunction::new()
+            .execute(vec![ExpressionValue::String(text.to_string())])
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_parse_int_function_properties() {
+        let parse_int = ParseIntFunction::new();
+
+        assert_eq!(parse_int.name(), "parse_int");
+        assert_eq!(parse_int.parameters()[0].param_type.name(), "String");
+        assert_eq!(parse_int.return_type().name(), "Option<Number>");
+    }
+
+    #[tokio::test]
+    async fn test_parse_int_reads_whole_numbers() {
+        assert_eq!(
+            parse(" -42 ").await,
+            ExpressionValue::Option(Some(Box::new(ExpressionValue::Number(-42.0))))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_parse_int_returns_none_for_other_text() {
+        assert_eq!(parse("abc").await, ExpressionValue::Option(None));
+        assert_eq!(parse("4.5").await, ExpressionValue::Option(None));
+    }
+}
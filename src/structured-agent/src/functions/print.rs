@@ -47,6 +47,7 @@ impl NativeFunction for PrintFunction {
             match result {
                 ExpressionValue::String(s) => s.clone(),
                 ExpressionValue::Boolean(b) => b.to_string(),
+                ExpressionValue::Number(n) => n.to_string(),
                 ExpressionValue::Unit => "()".to_string(),
                 ExpressionValue::List(list) => {
                     if list.len() == 0 {
@@ -69,6 +70,7 @@ impl NativeFunction for PrintFunction {
                     Some(inner) => format!("Some({})", format_expr_result(inner)),
                     None => "None".to_string(),
                 },
+                ExpressionValue::Json(value) => value.to_string(),
                 ExpressionValue::Metadata {
                     name,
                     documentation,
@@ -0,0 +1,88 @@
+use crate::runtime::ExpressionValue;
+use crate::types::{NativeFunction, Parameter, Type};
+use async_trait::async_trait;
+
+#[derive(Debug)]
+pub struct ToStringFunction {
+    parameters: Vec<Parameter>,
+    return_type: Type,
+}
+
+impl Default for ToStringFunction {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ToStringFunction {
+    pub fn new() -> Self {
+        Self {
+            parameters: vec![Parameter::new("value".to_string(), Type::number())],
+            return_type: Type::string(),
+        }
+    }
+}
+
+#[async_trait]
+impl NativeFunction for ToStringFunction {
+    fn name(&self) -> &str {
+        "to_string"
+    }
+
+    fn parameters(&self) -> &[Parameter] {
+        &self.parameters
+    }
+
+    fn return_type(&self) -> &Type {
+        &self.return_type
+    }
+
+    async fn execute(&self, args: Vec<ExpressionValue>) -> Result<ExpressionValue, String> {
+        if args.len() != 1 {
+            return Err(format!("to_string expects 1 argument, got {}", args.len()));
+        }
+
+        match &args[0] {
+            ExpressionValue::Number(n) => Ok(ExpressionValue::String(n.to_string())),
+            other => Err(format!(
+                "to_string expects a Number, got {}",
+                other.type_name()
+            )),
+        }
+    }
+
+    fn documentation(&self) -> Option<&str> {
+        Some("Writes a Number as text, without a trailing .0 for whole numbers")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_to_string_function_properties() {
+        let to_string = ToStringFunction::new();
+
+        assert_eq!(to_string.name(), "to_string");
+        assert_eq!(to_string.parameters()[0].param_type.name(), "Number");
+        assert_eq!(to_string.return_type().name(), "String");
+    }
+
+    #[tokio::test]
+    async fn test_to_string_writes_whole_and_fractional_numbers() {
+        let to_string = ToStringFunction::new();
+
+        let result = to_string
+            .execute(vec![ExpressionValue::Number(42.0)])
+            .await
+            .unwrap();
+        assert_eq!(result, ExpressionValue::String("42".to_string()));
+
+        let result = to_string
+            .execute(vec![ExpressionValue::Number(-2.5)])
+            .await
+            .unwrap();
+        assert_eq!(result, ExpressionValue::String("-2.5".to_string()));
+    }
+}
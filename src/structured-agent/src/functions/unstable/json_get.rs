@@ -0,0 +1,143 @@
+use crate::runtime::ExpressionValue;
+use crate::types::{NativeFunction, Parameter, Type};
+use async_trait::async_trait;
+use serde_json::Value;
+
+#[derive(Debug)]
+pub struct JsonGetFunction {
+    parameters: Vec<Parameter>,
+    return_type: Type,
+}
+
+impl Default for JsonGetFunction {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl JsonGetFunction {
+    pub fn new() -> Self {
+        Self {
+            parameters: vec![
+                Parameter::new("value".to_string(), Type::json()),
+                Parameter::new("field".to_string(), Type::string()),
+            ],
+            return_type: Type::json(),
+        }
+    }
+
+    fn lookup<'a>(value: &'a Value, path: &str) -> Option<&'a Value> {
+        path.split('.')
+            .try_fold(value, |current, segment| match current {
+                Value::Object(map) => map.get(segment),
+                Value::Array(items) => segment.parse::<usize>().ok().and_then(|i| items.get(i)),
+                _ => None,
+            })
+    }
+}
+
+#[async_trait]
+impl NativeFunction for JsonGetFunction {
+    fn name(&self) -> &str {
+        "json_get"
+    }
+
+    fn parameters(&self) -> &[Parameter] {
+        &self.parameters
+    }
+
+    fn return_type(&self) -> &Type {
+        &self.return_type
+    }
+
+    async fn execute(&self, args: Vec<ExpressionValue>) -> Result<ExpressionValue, String> {
+        if args.len() != 2 {
+            return Err(format!("json_get expects 2 arguments, got {}", args.len()));
+        }
+
+        let value = match &args[0] {
+            ExpressionValue::Json(value) => value.clone(),
+            ExpressionValue::String(s) => serde_json::from_str(s)
+                .map_err(|e| format!("json_get could not parse value as Json: {}", e))?,
+            _ => return Err("json_get expects a Json value".to_string()),
+        };
+        let field = args[1].as_string()?;
+
+        let found = Self::lookup(&value, field).cloned().unwrap_or(Value::Null);
+        Ok(ExpressionValue::Json(found))
+    }
+
+    fn documentation(&self) -> Option<&str> {
+        Some(
+            "Returns the field at a dot separated path (e.g. \"issue.title\") of a Json value, or null if it is missing",
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn args(value: Value, field: &str) -> Vec<ExpressionValue> {
+        vec![
+            ExpressionValue::Json(value),
+            ExpressionValue::String(field.to_string()),
+        ]
+    }
+
+    #[tokio::test]
+    async fn test_json_get_function_properties() {
+        let json_get = JsonGetFunction::new();
+
+        assert_eq!(json_get.name(), "json_get");
+        assert_eq!(json_get.parameters().len(), 2);
+        assert_eq!(json_get.return_type().name(), "Json");
+    }
+
+    #[tokio::test]
+    async fn test_json_get_nested_field() {
+        let json_get = JsonGetFunction::new();
+        let value = json!({"issue": {"id": 42, "labels": ["bug", "p1"]}});
+
+        let result = json_get
+            .execute(args(value.clone(), "issue.id"))
+            .await
+            .unwrap();
+        assert_eq!(result, ExpressionValue::Json(json!(42)));
+
+        let result = json_get
+            .execute(args(value, "issue.labels.1"))
+            .await
+            .unwrap();
+        assert_eq!(result, ExpressionValue::Json(json!("p1")));
+    }
+
+    #[tokio::test]
+    async fn test_json_get_missing_field_is_null() {
+        let json_get = JsonGetFunction::new();
+
+        let result = json_get
+            .execute(args(json!({"issue": {}}), "issue.title"))
+            .await
+            .unwrap();
+        assert_eq!(result, ExpressionValue::Json(Value::Null));
+    }
+
+    #[tokio::test]
+    async fn test_json_get_wrong_argument_type() {
+        let json_get = JsonGetFunction::new();
+
+        let result = json_get
+            .execute(vec![
+                ExpressionValue::Boolean(true),
+                ExpressionValue::String("field".to_string()),
+            ])
+            .await;
+        assert!(
+            result
+                .unwrap_err()
+                .contains("json_get expects a Json value")
+        );
+    }
+}
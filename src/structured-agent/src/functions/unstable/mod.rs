@@ -1,7 +1,9 @@
 pub mod head;
+pub mod json_get;
 pub mod option;
 pub mod tail;
 
 pub use head::HeadFunction;
+pub use json_get::JsonGetFunction;
 pub use option::{IsSomeFunction, IsSomeListFunction, SomeValueFunction, SomeValueListFunction};
 pub use tail::TailFunction;
@@ -0,0 +1,315 @@
+use crate::gemini::error::{GeminiError, GeminiResult};
+use crate::gemini::types::{GenerationConfig, ModelName, ThinkingConfig};
+
+/// Thinking levels from least to most thinking.
+const THINKING_LEVELS: &[&str] = &["minimal", "low", "medium", "high"];
+
+/// The thinking budgets 2.5 models use for a requested level.
+const LEVEL_BUDGETS: &[(&str, i32)] = &[("low", 1024), ("medium", 8192)];
+
+/// What a model accepts in a [`GenerationConfig`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ModelCapabilities {
+    /// Accepted `thinkingLevel` values, least thinking first. Models without
+    /// levels take a thinking budget instead.
+    pub thinking_levels: &'static [&'static str],
+    /// The smallest and largest thinking budget, for models that take one.
+    pub thinking_budget: Option<(i32, i32)>,
+    /// Whether a thinking budget of 0 turns thinking off.
+    pub can_disable_thinking: bool,
+    pub max_output_tokens: u32,
+}
+
+impl ModelCapabilities {
+    /// The capabilities of a known model. Custom models are not checked.
+    pub fn of(model: &ModelName) -> Option<Self> {
+        let capabilities = match model {
+            ModelName::Gemini25Pro => Self {
+                thinking_levels: &[],
+                thinking_budget: Some((128, 32768)),
+                can_disable_thinking: false,
+                max_output_tokens: 65536,
+            },
+            ModelName::Gemini25Flash => Self {
+                thinking_levels: &[],
+                thinking_budget: Some((1, 24576)),
+                can_disable_thinking: true,
+                max_output_tokens: 65536,
+            },
+            ModelName::Gemini25FlashLite => Self {
+                thinking_levels: &[],
+                thinking_budget: Some((512, 24576)),
+                can_disable_thinking: true,
+                max_output_tokens: 65536,
+            },
+            ModelName::Gemini3FlashPreview => Self {
+                thinking_levels: &["minimal", "low", "medium", "high"],
+                thinking_budget: None,
+                can_disable_thinking: false,
+                max_output_tokens: 65536,
+            },
+            ModelName::Gemini3ProPreview => Self {
+                thinking_levels: &["low", "high"],
+                thinking_budget: None,
+                can_disable_thinking: false,
+                max_output_tokens: 65536,
+            },
+            ModelName::Custom(_) => return None,
+        };
+        Some(capabilities)
+    }
+
+    /// Adjusts `config` to settings the model accepts, returning a
+    /// description of each change. Fails on an unknown thinking level.
+    pub fn fit(
+        &self,
+        mut config: GenerationConfig,
+    ) -> GeminiResult<(GenerationConfig, Vec<String>)> {
+        let mut adjustments = Vec::new();
+
+        if let Some(max_tokens) = config.max_output_tokens
+            && max_tokens > self.max_output_tokens
+        {
+            adjustments.push(format!(
+                "max output tokens {} lowered to {}",
+                max_tokens, self.max_output_tokens
+            ));
+            config.max_output_tokens = Some(self.max_output_tokens);
+        }
+
+        if let Some(thinking) = config.thinking_config.take() {
+            config.thinking_config = Some(self.fit_thinking(thinking, &mut adjustments)?);
+        }
+
+        Ok((config, adjustments))
+    }
+
+    fn fit_thinking(
+        &self,
+        thinking: ThinkingConfig,
+        adjustments: &mut Vec<String>,
+    ) -> GeminiResult<ThinkingConfig> {
+        if let Some(level) = &thinking.thinking_level
+            && !THINKING_LEVELS.contains(&level.as_str())
+        {
+            return Err(GeminiError::InvalidInput(format!(
+                "Unknown thinking level '{}', expected one of: {}",
+                level,
+                THINKING_LEVELS.join(", ")
+            )));
+        }
+
+        let mut fitted = ThinkingConfig {
+            thinking_level: None,
+            thinking_budget: None,
+            include_thoughts: thinking.include_thoughts,
+        };
+
+        match (self.thinking_budget, &thinking.thinking_level) {
+            (Some(range), Some(level)) => {
+                let budget = self.fit_budget(range, level_budget(level, range));
+                adjustments.push(format!(
+                    "thinking level '{}' sent as a budget of {}",
+                    level, budget
+                ));
+                fitted.thinking_budget = Some(budget);
+            }
+            (Some(range), None) => {
+                if let Some(budget) = thinking.thinking_budget {
+                    let fitted_budget = self.fit_budget(range, budget);
+                    if fitted_budget != budget {
+                        adjustments.push(format!(
+                            "thinking budget {} changed to {} to fit {}..={}",
+                            budget, fitted_budget, range.0, range.1
+                        ));
+                    }
+                    fitted.thinking_budget = Some(fitted_budget);
+                }
+            }
+            (None, Some(level)) => {
+                let fitted_level = self.fit_level(level);
+                if fitted_level != level.as_str() {
+                    adjustments.push(format!(
+                        "thinking level '{}' is not supported, using '{}'",
+                        level, fitted_level
+                    ));
+                }
+                fitted.thinking_level = Some(fitted_level.to_string());
+            }
+            (None, None) => {
+                if let Some(budget) = thinking.thinking_budget {
+                    let level = self.fit_level(budget_level(budget));
+                    adjustments.push(format!(
+                        "thinking budget {} sent as thinking level '{}'",
+                        budget, level
+                    ));
+                    fitted.thinking_level = Some(level.to_string());
+                }
+            }
+        }
+
+        Ok(fitted)
+    }
+
+    // A budget of -1 asks the model to decide for itself, which every budget
+    // model accepts.
+    fn fit_budget(&self, (min, max): (i32, i32), budget: i32) -> i32 {
+        if budget == -1 || (budget == 0 && self.can_disable_thinking) {
+            budget
+        } else {
+            budget.clamp(min, max)
+        }
+    }
+
+    // The most thinking the model offers without going past `level`, or its
+    // least thinking when every level it has is above `level`.
+    fn fit_level(&self, level: &str) -> &'static str {
+        let rank = |name: &str| THINKING_LEVELS.iter().position(|known| *known == name);
+        let requested = rank(level);
+        self.thinking_levels
+            .iter()
+            .rev()
+            .find(|supported| rank(supported) <= requested)
+            .or(self.thinking_levels.first())
+            .copied()
+            .unwrap_or("low")
+    }
+}
+
+fn level_budget(level: &str, (min, max): (i32, i32)) -> i32 {
+    match level {
+        "minimal" => 0,
+        "high" => max,
+        level => LEVEL_BUDGETS
+            .iter()
+            .find(|(name, _)| *name == level)
+            .map_or(min, |(_, budget)| *budget),
+    }
+}
+
+fn budget_level(budget: i32) -> &'static str {
+    match budget {
+        0 => "minimal",
+        1..=1024 => "low",
+        1025..=8192 => "medium",
+        // Larger budgets, and -1 asking for dynamic thinking.
+        _ => "high",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn thinking(config: &GenerationConfig) -> (Option<&str>, Option<i32>) {
+        let thinking = config.thinking_config.as_ref().unwrap();
+        (thinking.thinking_level.as_deref(), thinking.thinking_budget)
+    }
+
+    fn capabilities(model: ModelName) -> ModelCapabilities {
+        ModelCapabilities::of(&model).unwrap()
+    }
+
+    #[test]
+    fn test_supported_thinking_level_passes_through() {
+        let config = GenerationConfig::new().with_thinking_config(ThinkingConfig::high());
+
+        let (fitted, adjustments) = capabilities(ModelName::Gemini3ProPreview)
+            .fit(config)
+            .unwrap();
+
+        assert_eq!(thinking(&fitted), (Some("high"), None));
+        assert!(adjustments.is_empty());
+    }
+
+    #[test]
+    fn test_unsupported_thinking_level_is_downgraded() {
+        let config = GenerationConfig::new().with_thinking_config(ThinkingConfig::medium());
+
+        let (fitted, adjustments) = capabilities(ModelName::Gemini3ProPreview)
+            .fit(config)
+            .unwrap();
+
+        assert_eq!(thinking(&fitted), (Some("low"), None));
+        assert_eq!(
+            adjustments,
+            vec!["thinking level 'medium' is not supported, using 'low'"]
+        );
+    }
+
+    #[test]
+    fn test_disabled_thinking_uses_lowest_level_on_models_that_always_think() {
+        let config = GenerationConfig::new().with_minimal_thinking();
+
+        let (fitted, _) = capabilities(ModelName::Gemini3ProPreview)
+            .fit(config.clone())
+            .unwrap();
+        assert_eq!(thinking(&fitted), (Some("low"), None));
+
+        let (fitted, _) = capabilities(ModelName::Gemini3FlashPreview)
+            .fit(config)
+            .unwrap();
+        assert_eq!(thinking(&fitted), (Some("minimal"), None));
+    }
+
+    #[test]
+    fn test_thinking_budget_is_clamped_to_model_range() {
+        let (fitted, adjustments) = capabilities(ModelName::Gemini25Pro)
+            .fit(GenerationConfig::new().with_minimal_thinking())
+            .unwrap();
+        assert_eq!(thinking(&fitted), (None, Some(128)));
+        assert_eq!(adjustments.len(), 1);
+
+        let (fitted, adjustments) = capabilities(ModelName::Gemini25Flash)
+            .fit(GenerationConfig::new().with_minimal_thinking())
+            .unwrap();
+        assert_eq!(thinking(&fitted), (None, Some(0)));
+        assert!(adjustments.is_empty());
+    }
+
+    #[test]
+    fn test_thinking_level_becomes_budget_on_budget_models() {
+        let config = GenerationConfig::new().with_thinking_config(ThinkingConfig::high());
+
+        let (fitted, _) = capabilities(ModelName::Gemini25Flash).fit(config).unwrap();
+
+        assert_eq!(thinking(&fitted), (None, Some(24576)));
+    }
+
+    #[test]
+    fn test_unknown_thinking_level_errors() {
+        let config = GenerationConfig::new().with_thinking_config(ThinkingConfig {
+            thinking_level: Some("extreme".to_string()),
+            thinking_budget: None,
+            include_thoughts: None,
+        });
+
+        let error = capabilities(ModelName::Gemini3ProPreview)
+            .fit(config)
+            .unwrap_err();
+
+        assert!(
+            error
+                .to_string()
+                .contains("Unknown thinking level 'extreme'"),
+            "{}",
+            error
+        );
+    }
+
+    #[test]
+    fn test_max_output_tokens_is_lowered() {
+        let mut config = GenerationConfig::new();
+        config.max_output_tokens = Some(1_000_000);
+
+        let (fitted, adjustments) = capabilities(ModelName::Gemini25Pro).fit(config).unwrap();
+
+        assert_eq!(fitted.max_output_tokens, Some(65536));
+        assert_eq!(adjustments.len(), 1);
+    }
+
+    #[test]
+    fn test_custom_models_are_not_checked() {
+        assert!(ModelCapabilities::of(&ModelName::Custom("my-model".to_string())).is_none());
+    }
+}
@@ -5,12 +5,12 @@ use crate::gemini::{
         ChatMessage, ChatRequest, GeminiApiRequest, GeminiResponse, GenerationConfig, ModelName,
     },
 };
+use crate::types::{Clock, SystemClock};
 use serde_json::Value;
 
 use std::sync::Arc;
 use std::time::{Duration, SystemTime};
 use tokio::sync::RwLock;
-use tokio::time::timeout;
 use url::Url;
 
 const DEFAULT_TIMEOUT_SECS: u64 = 120;
@@ -19,7 +19,6 @@ const MAX_RETRIES: u32 = 3;
 const INITIAL_RETRY_DELAY_MS: u64 = 1000;
 const DEFAULT_API_BASE: &str = "https://generativelanguage.googleapis.com";
 const DEFAULT_VERTEX_BASE: &str = "https://{location}-aiplatform.googleapis.com";
-const GCLOUD_AUTH_COMMAND: &[&str] = &["auth", "print-access-token"];
 
 #[derive(Debug, Clone)]
 struct CachedToken {
@@ -28,8 +27,15 @@ struct CachedToken {
 }
 
 impl CachedToken {
-    fn is_expired(&self) -> bool {
-        SystemTime::now() > self.expires_at
+    fn new(token: String, ttl: Duration, now: SystemTime) -> Self {
+        Self {
+            token,
+            expires_at: now + ttl,
+        }
+    }
+
+    fn is_expired(&self, now: SystemTime) -> bool {
+        now > self.expires_at
     }
 }
 
@@ -41,6 +47,7 @@ pub struct GeminiClient {
     cached_token: Arc<RwLock<Option<CachedToken>>>,
     request_timeout: Duration,
     max_retries: u32,
+    clock: Arc<dyn Clock>,
 }
 
 impl GeminiClient {
@@ -82,6 +89,7 @@ impl GeminiClient {
             cached_token: Arc::new(RwLock::new(None)),
             request_timeout: Duration::from_secs(DEFAULT_REQUEST_TIMEOUT_SECS),
             max_retries: MAX_RETRIES,
+            clock: Arc::new(SystemClock),
         })
     }
 
@@ -105,6 +113,11 @@ impl GeminiClient {
         self
     }
 
+    pub(crate) fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
     pub async fn chat_with_timeout(
         &self,
         request: ChatRequest,
@@ -114,9 +127,13 @@ impl GeminiClient {
         let mut retry_delay = Duration::from_millis(INITIAL_RETRY_DELAY_MS);
 
         for attempt in 0..=self.max_retries {
-            match timeout(timeout_duration, self.chat_internal(request.clone())).await {
-                Ok(Ok(response)) => return Ok(response),
-                Ok(Err(e)) => {
+            let response = tokio::select! {
+                response = self.chat_internal(request.clone()) => Some(response),
+                _ = self.clock.sleep(timeout_duration) => None,
+            };
+            match response {
+                Some(Ok(response)) => return Ok(response),
+                Some(Err(e)) => {
                     let (should_retry, custom_delay) = match &e {
                         GeminiError::RateLimited | GeminiError::RateLimitedWithRetry(_) => {
                             (true, self.extract_retry_delay(&e))
@@ -131,16 +148,16 @@ impl GeminiClient {
                     if should_retry && attempt < self.max_retries {
                         last_error = Some(e);
                         let delay = custom_delay.unwrap_or(retry_delay);
-                        tokio::time::sleep(delay).await;
+                        self.clock.sleep(delay).await;
                         retry_delay *= 2;
                         continue;
                     }
                     return Err(e);
                 }
-                Err(_) => {
+                None => {
                     if attempt < self.max_retries {
                         last_error = Some(GeminiError::Timeout);
-                        tokio::time::sleep(retry_delay).await;
+                        self.clock.sleep(retry_delay).await;
                         retry_delay *= 2;
                         continue;
                     }
@@ -167,7 +184,11 @@ impl GeminiClient {
                     .api_key
                     .as_ref()
                     .ok_or_else(|| GeminiError::Configuration("API key not set".to_string()))?;
-                let builder = self.client.post(&url).query(&[("key", api_key)]);
+                let builder = if self.config.api_key_in_query {
+                    self.client.post(&url).query(&[("key", api_key)])
+                } else {
+                    self.client.post(&url).header("x-goog-api-key", api_key)
+                };
                 (url, builder)
             }
             AuthMethod::ApplicationDefaultCredentials => {
@@ -183,6 +204,7 @@ impl GeminiClient {
 
         let payload = self.build_request_payload(&request)?;
 
+        // Awaited inline rather than spawned so that dropping this future aborts the request.
         let response = request_builder.json(&payload).send().await.map_err(|e| {
             if e.is_timeout() {
                 GeminiError::Timeout
@@ -218,12 +240,7 @@ impl GeminiClient {
             .structured_chat(vec![chat_message], ModelName::default(), None)
             .await?;
 
-        response
-            .first_content()
-            .ok_or_else(|| GeminiError::ApiError {
-                code: 0,
-                message: "No response content received".to_string(),
-            })
+        response.content()
     }
 
     pub async fn structured_chat(
@@ -330,28 +347,39 @@ impl GeminiClient {
         {
             let cached_token = self.cached_token.read().await;
             if let Some(ref token_data) = *cached_token
-                && !token_data.is_expired()
+                && !token_data.is_expired(self.clock.now())
             {
                 return Ok(token_data.token.clone());
             }
         }
 
-        let output = tokio::process::Command::new("gcloud")
-            .args(GCLOUD_AUTH_COMMAND)
+        let (program, args) = self.config.token_command.split_first().ok_or_else(|| {
+            GeminiError::Configuration("Token command cannot be empty".to_string())
+        })?;
+        let command = self.config.token_command.join(" ");
+
+        let output = tokio::process::Command::new(program)
+            .args(args)
+            .kill_on_drop(true)
             .output()
             .await
             .map_err(|e| {
+                let hint = if program == "gcloud" {
+                    ". Make sure gcloud is installed and you've run 'gcloud auth application-default login'"
+                } else {
+                    ""
+                };
                 GeminiError::Authentication(format!(
-                    "Failed to run gcloud command: {}. Make sure gcloud is installed and you've run 'gcloud auth application-default login'",
-                    e
+                    "Failed to run token command '{}': {}{}",
+                    command, e, hint
                 ))
             })?;
 
         if !output.status.success() {
             let stderr = String::from_utf8_lossy(&output.stderr);
             return Err(GeminiError::Authentication(format!(
-                "gcloud auth failed: {}",
-                stderr
+                "Token command '{}' failed: {}",
+                command, stderr
             )));
         }
 
@@ -359,15 +387,14 @@ impl GeminiClient {
         let token = token_str.trim();
 
         if token.is_empty() {
-            return Err(GeminiError::Authentication(
-                "Empty token received from gcloud".to_string(),
-            ));
+            return Err(GeminiError::Authentication(format!(
+                "Empty token received from token command '{}'",
+                command
+            )));
         }
 
-        let cached_token_data = CachedToken {
-            token: token.to_string(),
-            expires_at: SystemTime::now() + Duration::from_secs(55 * 60),
-        };
+        let cached_token_data =
+            CachedToken::new(token.to_string(), self.config.token_ttl, self.clock.now());
 
         {
             let mut cached_token = self.cached_token.write().await;
@@ -392,6 +419,7 @@ impl GeminiClient {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::types::MockClock;
 
     #[test]
     fn test_map_http_error_rate_limit_with_retry_after() {
@@ -400,6 +428,7 @@ mod tests {
             project_id: "test_project".to_string(),
             location: "us-central1".to_string(),
             api_endpoint: None,
+            ..GeminiConfig::default()
         };
 
         let client = GeminiClient {
@@ -410,6 +439,7 @@ mod tests {
             cached_token: Arc::new(RwLock::new(None)),
             request_timeout: Duration::from_secs(DEFAULT_REQUEST_TIMEOUT_SECS),
             max_retries: MAX_RETRIES,
+            clock: Arc::new(SystemClock),
         };
 
         let mut headers = reqwest::header::HeaderMap::new();
@@ -432,6 +462,7 @@ mod tests {
             project_id: "test_project".to_string(),
             location: "us-central1".to_string(),
             api_endpoint: None,
+            ..GeminiConfig::default()
         };
 
         let client = GeminiClient {
@@ -442,6 +473,7 @@ mod tests {
             cached_token: Arc::new(RwLock::new(None)),
             request_timeout: Duration::from_secs(DEFAULT_REQUEST_TIMEOUT_SECS),
             max_retries: MAX_RETRIES,
+            clock: Arc::new(SystemClock),
         };
 
         let mut headers = reqwest::header::HeaderMap::new();
@@ -464,6 +496,7 @@ mod tests {
             project_id: "test_project".to_string(),
             location: "us-central1".to_string(),
             api_endpoint: None,
+            ..GeminiConfig::default()
         };
 
         let client = GeminiClient {
@@ -474,6 +507,7 @@ mod tests {
             cached_token: Arc::new(RwLock::new(None)),
             request_timeout: Duration::from_secs(DEFAULT_REQUEST_TIMEOUT_SECS),
             max_retries: MAX_RETRIES,
+            clock: Arc::new(SystemClock),
         };
 
         let headers = reqwest::header::HeaderMap::new();
@@ -493,6 +527,7 @@ mod tests {
             project_id: "test_project".to_string(),
             location: "us-central1".to_string(),
             api_endpoint: None,
+            ..GeminiConfig::default()
         };
 
         let client = GeminiClient {
@@ -503,6 +538,7 @@ mod tests {
             cached_token: Arc::new(RwLock::new(None)),
             request_timeout: Duration::from_secs(DEFAULT_REQUEST_TIMEOUT_SECS),
             max_retries: MAX_RETRIES,
+            clock: Arc::new(SystemClock),
         };
 
         let error_with_retry = GeminiError::RateLimitedWithRetry(Duration::from_secs(45));
@@ -517,4 +553,307 @@ mod tests {
         let other_error = GeminiError::Timeout;
         assert_eq!(client.extract_retry_delay(&other_error), None);
     }
+
+    #[tokio::test]
+    async fn test_dropping_chat_future_aborts_request() {
+        use std::sync::atomic::{AtomicBool, Ordering};
+        use tokio::io::AsyncReadExt;
+        use tokio::net::TcpListener;
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let request_received = Arc::new(AtomicBool::new(false));
+        let request_completed = Arc::new(AtomicBool::new(false));
+
+        let server = {
+            let request_received = request_received.clone();
+            let request_completed = request_completed.clone();
+            tokio::spawn(async move {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let mut buf = [0u8; 4096];
+                let _ = socket.read(&mut buf).await;
+                request_received.store(true, Ordering::SeqCst);
+
+                let closed = async {
+                    loop {
+                        match socket.read(&mut buf).await {
+                            Ok(0) | Err(_) => break,
+                            Ok(_) => continue,
+                        }
+                    }
+                };
+
+                tokio::select! {
+                    _ = closed => {}
+                    _ = tokio::time::sleep(Duration::from_secs(5)) => {
+                        request_completed.store(true, Ordering::SeqCst);
+                    }
+                }
+            })
+        };
+
+        let config = GeminiConfig {
+            auth_method: AuthMethod::ApiKey("test_key".to_string()),
+            project_id: "test_project".to_string(),
+            location: "us-central1".to_string(),
+            api_endpoint: Some(format!("http://{}", addr)),
+            ..GeminiConfig::default()
+        };
+        let client = GeminiClient::new(config).await.unwrap().with_max_retries(0);
+
+        let request = ChatRequest::new(vec![ChatMessage::user("hello")], ModelName::default());
+        let result = tokio::time::timeout(Duration::from_millis(500), client.chat(request)).await;
+        assert!(result.is_err(), "chat should still be pending when dropped");
+
+        tokio::time::timeout(Duration::from_secs(2), server)
+            .await
+            .expect("server should observe the dropped connection")
+            .unwrap();
+
+        assert!(request_received.load(Ordering::SeqCst));
+        assert!(!request_completed.load(Ordering::SeqCst));
+    }
+
+    async fn capture_api_key_request(config: GeminiConfig) -> String {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        use tokio::net::TcpListener;
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0u8; 4096];
+            while !String::from_utf8_lossy(&request).contains("\r\n\r\n") {
+                let read = socket.read(&mut buf).await.unwrap();
+                if read == 0 {
+                    break;
+                }
+                request.extend_from_slice(&buf[..read]);
+            }
+
+            let body = r#"{"candidates":[{"content":{"parts":[{"text":"ok"}]}}]}"#;
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            socket.write_all(response.as_bytes()).await.unwrap();
+            socket.shutdown().await.unwrap();
+            String::from_utf8_lossy(&request).to_string()
+        });
+
+        let client = GeminiClient::new(config.with_api_endpoint(format!("http://{}", addr)))
+            .await
+            .unwrap()
+            .with_max_retries(0);
+        let request = ChatRequest::new(vec![ChatMessage::user("hello")], ModelName::default());
+        client.chat(request).await.unwrap();
+
+        server.await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_api_key_is_sent_in_header_by_default() {
+        let config = GeminiConfig::with_api_key(
+            "test_project".to_string(),
+            "us-central1".to_string(),
+            "test_key".to_string(),
+        );
+
+        let request = capture_api_key_request(config).await;
+        let request_line = request.lines().next().unwrap_or_default();
+
+        assert!(
+            request
+                .to_ascii_lowercase()
+                .contains("x-goog-api-key: test_key"),
+            "missing api key header: {request}"
+        );
+        assert!(
+            !request_line.contains("key="),
+            "api key leaked into the URL: {request_line}"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_api_key_can_fall_back_to_query_param() {
+        let config = GeminiConfig::with_api_key(
+            "test_project".to_string(),
+            "us-central1".to_string(),
+            "test_key".to_string(),
+        )
+        .with_api_key_in_query(true);
+
+        let request = capture_api_key_request(config).await;
+        let request_line = request.lines().next().unwrap_or_default();
+
+        assert!(request_line.contains("key=test_key"), "{request_line}");
+        assert!(!request.to_ascii_lowercase().contains("x-goog-api-key"));
+    }
+
+    #[tokio::test]
+    async fn test_failing_token_command_is_authentication_error() {
+        let config = GeminiConfig::new("test_project".to_string(), "us-central1".to_string())
+            .with_token_command(vec!["false".to_string()]);
+        let client = GeminiClient::new(config).await.unwrap();
+
+        match client.get_gcloud_token().await {
+            Err(GeminiError::Authentication(message)) => {
+                assert!(message.contains("'false'"), "{message}");
+            }
+            other => panic!("Expected Authentication error, got: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_missing_custom_token_command_is_named_in_error() {
+        let config = GeminiConfig::new("test_project".to_string(), "us-central1".to_string())
+            .with_token_command(vec![
+                "no-such-token-helper".to_string(),
+                "--print".to_string(),
+            ]);
+        let client = GeminiClient::new(config).await.unwrap();
+
+        match client.get_gcloud_token().await {
+            Err(GeminiError::Authentication(message)) => {
+                assert!(
+                    message.contains("'no-such-token-helper --print'"),
+                    "{message}"
+                );
+                assert!(!message.contains("gcloud"), "{message}");
+            }
+            other => panic!("Expected Authentication error, got: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_token_is_cached_for_configured_ttl() {
+        let ttl = Duration::from_secs(600);
+        let config = GeminiConfig::new("test_project".to_string(), "us-central1".to_string())
+            .with_token_command(vec!["echo".to_string(), "test-token".to_string()])
+            .with_token_ttl(ttl);
+        let client = GeminiClient::new(config).await.unwrap();
+
+        assert_eq!(client.get_gcloud_token().await.unwrap(), "test-token");
+
+        let cached = client.cached_token.read().await.clone().unwrap();
+        assert!(!cached.is_expired(SystemTime::now()));
+        assert!(cached.expires_at <= SystemTime::now() + ttl);
+    }
+
+    #[test]
+    fn test_token_expires_only_after_ttl() {
+        let clock = MockClock::default();
+        let ttl = Duration::from_secs(600);
+        let token = CachedToken::new("test-token".to_string(), ttl, clock.now());
+
+        clock.advance(ttl - Duration::from_secs(1));
+        assert!(!token.is_expired(clock.now()));
+
+        clock.advance(Duration::from_secs(1));
+        assert!(!token.is_expired(clock.now()));
+
+        clock.advance(Duration::from_nanos(1));
+        assert!(token.is_expired(clock.now()));
+    }
+
+    #[test]
+    fn test_token_with_zero_ttl_expires() {
+        let clock = MockClock::default();
+        let token = CachedToken::new("test-token".to_string(), Duration::ZERO, clock.now());
+        assert!(!token.is_expired(clock.now()));
+
+        clock.advance(Duration::from_millis(1));
+        assert!(token.is_expired(clock.now()));
+    }
+
+    #[tokio::test]
+    async fn test_expired_token_is_refreshed_from_command() {
+        let clock = Arc::new(MockClock::default());
+        let ttl = Duration::from_secs(60);
+        let config = GeminiConfig::new("test_project".to_string(), "us-central1".to_string())
+            .with_token_command(vec!["echo".to_string(), "test-token".to_string()])
+            .with_token_ttl(ttl);
+        let client = GeminiClient::new(config)
+            .await
+            .unwrap()
+            .with_clock(clock.clone());
+        let expires_at = || async { client.cached_token.read().await.clone().unwrap().expires_at };
+
+        assert_eq!(client.get_gcloud_token().await.unwrap(), "test-token");
+        let first_expiry = expires_at().await;
+        assert_eq!(first_expiry, SystemTime::UNIX_EPOCH + ttl);
+
+        clock.advance(ttl);
+        client.get_gcloud_token().await.unwrap();
+        assert_eq!(expires_at().await, first_expiry);
+
+        clock.advance(Duration::from_secs(1));
+        client.get_gcloud_token().await.unwrap();
+        assert_eq!(expires_at().await, clock.now() + ttl);
+    }
+
+    #[tokio::test]
+    async fn test_request_times_out_on_the_client_clock() {
+        use tokio::io::AsyncReadExt;
+        use tokio::net::TcpListener;
+        use tokio::sync::oneshot;
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (received_tx, received_rx) = oneshot::channel();
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = [0u8; 4096];
+            let _ = socket.read(&mut buf).await;
+            let _ = received_tx.send(());
+            // Hold the connection open without replying.
+            while let Ok(n) = socket.read(&mut buf).await {
+                if n == 0 {
+                    break;
+                }
+            }
+        });
+
+        let clock = Arc::new(MockClock::default());
+        let config = GeminiConfig {
+            auth_method: AuthMethod::ApiKey("test_key".to_string()),
+            api_endpoint: Some(format!("http://{}", addr)),
+            ..GeminiConfig::default()
+        };
+        let client = GeminiClient::new(config)
+            .await
+            .unwrap()
+            .with_max_retries(0)
+            .with_clock(clock.clone());
+
+        let request = ChatRequest::new(vec![ChatMessage::user("hello")], ModelName::default());
+        let chat = tokio::spawn(async move {
+            client
+                .chat_with_timeout(request, Duration::from_secs(30))
+                .await
+        });
+
+        received_rx.await.unwrap();
+        clock.advance(Duration::from_secs(30));
+
+        let result = tokio::time::timeout(Duration::from_secs(5), chat)
+            .await
+            .expect("the request should time out once the clock passes its timeout")
+            .unwrap();
+        assert!(matches!(result, Err(GeminiError::Timeout)));
+    }
+
+    #[test]
+    fn test_empty_token_command_fails_validation() {
+        let config = GeminiConfig::default().with_token_command(vec![]);
+
+        assert_eq!(
+            config.validate(),
+            Err("Token command cannot be empty".to_string())
+        );
+    }
 }
@@ -1,10 +1,24 @@
 use serde::{Deserialize, Serialize};
 use std::env;
+use std::time::Duration;
 
 const DEFAULT_PROJECT_ID: &str = "gemini-api";
 const DEFAULT_LOCATION: &str = "global";
-const DEFAULT_VERTEX_LOCATION: &str = "europe-west9";
+const DEFAULT_VERTEX_LOCATION: &str = "us-central1";
 const DEFAULT_API_ENDPOINT: &str = "https://generativelanguage.googleapis.com";
+const DEFAULT_TOKEN_COMMAND: &[&str] = &["gcloud", "auth", "print-access-token"];
+const DEFAULT_TOKEN_TTL: Duration = Duration::from_secs(55 * 60);
+
+/// Environment variables checked, in order, for the Vertex AI project.
+pub const VERTEX_PROJECT_VARS: &[&str] =
+    &["VERTEX_AI_PROJECT", "GOOGLE_CLOUD_PROJECT", "GCP_PROJECT"];
+/// Environment variables checked, in order, for the Vertex AI location.
+pub const VERTEX_LOCATION_VARS: &[&str] = &[
+    "VERTEX_AI_LOCATION",
+    "GOOGLE_CLOUD_LOCATION",
+    "GOOGLE_CLOUD_REGION",
+    "GCP_REGION",
+];
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum AuthMethod {
@@ -18,6 +32,28 @@ pub struct GeminiConfig {
     pub location: String,
     pub api_endpoint: Option<String>,
     pub auth_method: AuthMethod,
+    pub token_command: Vec<String>,
+    pub token_ttl: Duration,
+    pub api_key_in_query: bool,
+}
+
+fn gcloud_default_project() -> Option<String> {
+    let output = std::process::Command::new("gcloud")
+        .args(["config", "get-value", "project"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let project = String::from_utf8(output.stdout).ok()?.trim().to_string();
+    (!project.is_empty() && project != "(unset)").then_some(project)
+}
+
+fn default_token_command() -> Vec<String> {
+    DEFAULT_TOKEN_COMMAND
+        .iter()
+        .map(|s| s.to_string())
+        .collect()
 }
 
 impl GeminiConfig {
@@ -27,6 +63,9 @@ impl GeminiConfig {
             location,
             api_endpoint: None,
             auth_method: AuthMethod::ApplicationDefaultCredentials,
+            token_command: default_token_command(),
+            token_ttl: DEFAULT_TOKEN_TTL,
+            api_key_in_query: false,
         }
     }
 
@@ -36,6 +75,9 @@ impl GeminiConfig {
             location,
             api_endpoint: None,
             auth_method: AuthMethod::ApiKey(api_key),
+            token_command: default_token_command(),
+            token_ttl: DEFAULT_TOKEN_TTL,
+            api_key_in_query: false,
         }
     }
 
@@ -54,54 +96,71 @@ impl GeminiConfig {
         self
     }
 
+    pub fn with_token_command(mut self, command: Vec<String>) -> Self {
+        self.token_command = command;
+        self
+    }
+
+    pub fn with_token_ttl(mut self, ttl: Duration) -> Self {
+        self.token_ttl = ttl;
+        self
+    }
+
+    /// Send the API key as a `key` query parameter rather than the
+    /// `x-goog-api-key` header, for proxies that only forward the query string.
+    pub fn with_api_key_in_query(mut self, in_query: bool) -> Self {
+        self.api_key_in_query = in_query;
+        self
+    }
+
+    /// Reads credentials from the environment. `GEMINI_API_KEY` selects API
+    /// key auth; otherwise Vertex AI is used with Application Default
+    /// Credentials, taking the project from the first of [`VERTEX_PROJECT_VARS`]
+    /// that is set (falling back to gcloud's default project) and the location
+    /// from [`VERTEX_LOCATION_VARS`], defaulting to `us-central1`.
     pub fn from_env() -> Result<Self, Box<dyn std::error::Error>> {
-        if let Ok(api_key) = env::var("GEMINI_API_KEY") {
-            Ok(Self {
+        Self::from_vars(&|name| env::var(name).ok(), &gcloud_default_project)
+    }
+
+    fn from_vars(
+        vars: &dyn Fn(&str) -> Option<String>,
+        default_project: &dyn Fn() -> Option<String>,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        if let Some(api_key) = vars("GEMINI_API_KEY") {
+            return Ok(Self {
                 project_id: DEFAULT_PROJECT_ID.to_string(),
                 location: DEFAULT_LOCATION.to_string(),
                 api_endpoint: Some(DEFAULT_API_ENDPOINT.to_string()),
                 auth_method: AuthMethod::ApiKey(api_key),
-            })
-        } else {
-            let project_id = env::var("VERTEX_AI_PROJECT")
-                .or_else(|_| env::var("GOOGLE_CLOUD_PROJECT"))
-                .or_else(|_| env::var("GCP_PROJECT"))
-                .or_else(|_| {
-                    std::process::Command::new("gcloud")
-                        .args(["config", "get-value", "project"])
-                        .output()
-                        .ok()
-                        .and_then(|output| {
-                            if output.status.success() {
-                                let project = String::from_utf8(output.stdout)
-                                    .ok()?
-                                    .trim()
-                                    .to_string();
-                                if !project.is_empty() && project != "(unset)" {
-                                    Some(project)
-                                } else {
-                                    None
-                                }
-                            } else {
-                                None
-                            }
-                        })
-                        .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::NotFound, "gcloud config not found"))
-                })
-                .map_err(|_| "For ADC auth, set VERTEX_AI_PROJECT, GOOGLE_CLOUD_PROJECT or GCP_PROJECT environment variable, or ensure gcloud is configured with a default project")?;
-
-            let location = env::var("VERTEX_AI_LOCATION")
-                .or_else(|_| env::var("GOOGLE_CLOUD_REGION"))
-                .or_else(|_| env::var("GCP_REGION"))
-                .unwrap_or_else(|_| DEFAULT_VERTEX_LOCATION.to_string());
-
-            Ok(Self {
-                project_id,
-                location,
-                api_endpoint: None, // Use default Vertex AI endpoint
-                auth_method: AuthMethod::ApplicationDefaultCredentials,
-            })
+                token_command: default_token_command(),
+                token_ttl: DEFAULT_TOKEN_TTL,
+                api_key_in_query: false,
+            });
         }
+
+        let first_set = |names: &[&str]| names.iter().find_map(|name| vars(name));
+
+        let project_id = first_set(VERTEX_PROJECT_VARS)
+            .or_else(default_project)
+            .ok_or_else(|| {
+                format!(
+                    "No Gemini credentials found: set GEMINI_API_KEY, or set {} (or a gcloud default project) to use Vertex AI",
+                    VERTEX_PROJECT_VARS.join(", ")
+                )
+            })?;
+
+        let location =
+            first_set(VERTEX_LOCATION_VARS).unwrap_or_else(|| DEFAULT_VERTEX_LOCATION.to_string());
+
+        Ok(Self {
+            project_id,
+            location,
+            api_endpoint: None, // Use default Vertex AI endpoint
+            auth_method: AuthMethod::ApplicationDefaultCredentials,
+            token_command: default_token_command(),
+            token_ttl: DEFAULT_TOKEN_TTL,
+            api_key_in_query: false,
+        })
     }
 
     pub fn validate(&self) -> Result<(), String> {
@@ -111,6 +170,13 @@ impl GeminiConfig {
         if self.location.is_empty() {
             return Err("Location cannot be empty".to_string());
         }
+        if self
+            .token_command
+            .first()
+            .is_none_or(|command| command.is_empty())
+        {
+            return Err("Token command cannot be empty".to_string());
+        }
         match &self.auth_method {
             AuthMethod::ApiKey(key) if key.is_empty() => {
                 return Err("API key cannot be empty".to_string());
@@ -128,6 +194,82 @@ impl Default for GeminiConfig {
             location: DEFAULT_LOCATION.to_string(),
             api_endpoint: Some(DEFAULT_API_ENDPOINT.to_string()),
             auth_method: AuthMethod::ApplicationDefaultCredentials,
+            token_command: default_token_command(),
+            token_ttl: DEFAULT_TOKEN_TTL,
+            api_key_in_query: false,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn from_vars_in(
+        pairs: &[(&str, &str)],
+        default_project: Option<&str>,
+    ) -> Result<GeminiConfig, String> {
+        GeminiConfig::from_vars(
+            &|name| {
+                pairs
+                    .iter()
+                    .find(|(key, _)| *key == name)
+                    .map(|(_, value)| value.to_string())
+            },
+            &|| default_project.map(str::to_string),
+        )
+        .map_err(|e| e.to_string())
+    }
+
+    #[test]
+    fn test_api_key_selects_api_key_auth() {
+        let config = from_vars_in(
+            &[
+                ("GEMINI_API_KEY", "secret"),
+                ("GOOGLE_CLOUD_PROJECT", "proj"),
+            ],
+            None,
+        )
+        .unwrap();
+
+        assert!(matches!(config.auth_method, AuthMethod::ApiKey(ref key) if key == "secret"));
+        assert_eq!(config.api_endpoint.as_deref(), Some(DEFAULT_API_ENDPOINT));
+    }
+
+    #[test]
+    fn test_vertex_project_and_location_from_env() {
+        let config = from_vars_in(
+            &[
+                ("GOOGLE_CLOUD_PROJECT", "my-project"),
+                ("GOOGLE_CLOUD_LOCATION", "europe-west4"),
+            ],
+            None,
+        )
+        .unwrap();
+
+        assert!(matches!(
+            config.auth_method,
+            AuthMethod::ApplicationDefaultCredentials
+        ));
+        assert_eq!(config.project_id, "my-project");
+        assert_eq!(config.location, "europe-west4");
+        assert_eq!(config.api_endpoint, None);
+    }
+
+    #[test]
+    fn test_vertex_location_defaults_when_unset() {
+        let config = from_vars_in(&[], Some("gcloud-project")).unwrap();
+
+        assert_eq!(config.project_id, "gcloud-project");
+        assert_eq!(config.location, DEFAULT_VERTEX_LOCATION);
+    }
+
+    #[test]
+    fn test_missing_credentials_is_an_error() {
+        let error = from_vars_in(&[("GOOGLE_CLOUD_LOCATION", "europe-west4")], None).unwrap_err();
+
+        assert!(error.contains("No Gemini credentials found"), "{error}");
+        assert!(error.contains("GEMINI_API_KEY"), "{error}");
+        assert!(error.contains("GOOGLE_CLOUD_PROJECT"), "{error}");
+    }
+}
@@ -1,27 +1,55 @@
-use crate::gemini::error::GeminiResult;
+use crate::gemini::capabilities::ModelCapabilities;
+use crate::gemini::error::{GeminiError, GeminiResult};
+use crate::gemini::types::GeminiResponse;
 use crate::gemini::types::GenerationConfig;
 use crate::gemini::types::JsonSchemaBuilder;
+use crate::gemini::types::Preset;
 use crate::gemini::{ChatMessage, GeminiClient, GeminiConfig, ModelName};
 use crate::runtime::Context;
-use crate::runtime::Event;
+use crate::runtime::EventRole;
 use crate::runtime::ExpressionValue;
-use crate::types::LanguageEngine;
+use crate::types::PlaceholderTarget;
 use crate::types::Type;
+use crate::types::{Clock, ENGINE_THOUGHT_TARGET, LanguageEngine};
 use async_trait::async_trait;
 use schemars::schema::SchemaObject;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::{debug, warn};
 
 const DEFAULT_NO_EVENTS_MESSAGE: &str = "No events available.";
-const DEFAULT_NO_RESPONSE_MESSAGE: &str = "No response received";
 
 #[derive(Serialize, Deserialize)]
 struct SelectionResponse {
     selection: u32,
 }
 
+#[derive(Serialize, Deserialize)]
+struct RankingResponse {
+    ranking: Vec<u32>,
+}
+
 pub struct GeminiEngine {
     client: GeminiClient,
     model: ModelName,
+    model_fallback_chain: Vec<ModelName>,
+    served_model: Mutex<Option<ModelName>>,
+    preset: Option<Preset>,
+    message_metadata: bool,
+    deterministic: bool,
+    seed: Option<u64>,
+    session_id: String,
+}
+
+fn new_session_id() -> String {
+    let millis = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or_default();
+    format!("{:x}-{:x}", std::process::id(), millis)
 }
 
 impl GeminiEngine {
@@ -31,6 +59,13 @@ impl GeminiEngine {
         Ok(Self {
             client,
             model: ModelName::default(),
+            model_fallback_chain: Vec::new(),
+            served_model: Mutex::new(None),
+            preset: None,
+            message_metadata: false,
+            deterministic: false,
+            seed: None,
+            session_id: new_session_id(),
         })
     }
 
@@ -40,6 +75,13 @@ impl GeminiEngine {
         Ok(Self {
             client,
             model: ModelName::default(),
+            model_fallback_chain: Vec::new(),
+            served_model: Mutex::new(None),
+            preset: None,
+            message_metadata: false,
+            deterministic: false,
+            seed: None,
+            session_id: new_session_id(),
         })
     }
 
@@ -48,42 +90,250 @@ impl GeminiEngine {
         self
     }
 
+    pub fn with_model_fallback_chain(mut self, models: Vec<ModelName>) -> Self {
+        self.model_fallback_chain = models;
+        self
+    }
+
+    pub fn last_served_model(&self) -> Option<ModelName> {
+        self.served_model.lock().unwrap().clone()
+    }
+
+    /// Samples untyped, typed and fill calls with the preset's temperature
+    /// and top_p instead of the engine's own defaults.
+    pub fn with_preset(mut self, preset: Preset) -> Self {
+        self.preset = Some(preset);
+        self
+    }
+
+    pub fn with_message_metadata(mut self, enabled: bool) -> Self {
+        self.message_metadata = enabled;
+        self
+    }
+
+    /// Overrides the sampling settings of every call, including those chosen
+    /// by a preset, so repeated runs produce the same output.
+    pub fn with_deterministic(mut self, enabled: bool) -> Self {
+        self.deterministic = enabled;
+        self
+    }
+
+    /// Seeds every call whose generation config does not set its own seed.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    pub fn with_session_id(mut self, session_id: impl Into<String>) -> Self {
+        self.session_id = session_id.into();
+        self
+    }
+
+    pub(crate) fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.client = self.client.with_clock(clock);
+        self
+    }
+
+    fn message_metadata(
+        &self,
+        function: Option<&str>,
+        parameter: Option<&str>,
+    ) -> Option<HashMap<String, Value>> {
+        if !self.message_metadata {
+            return None;
+        }
+
+        let mut metadata = HashMap::new();
+        metadata.insert(
+            "session_id".to_string(),
+            Value::String(self.session_id.clone()),
+        );
+        if let Some(function) = function {
+            metadata.insert("function".to_string(), Value::String(function.to_string()));
+        }
+        if let Some(parameter) = parameter {
+            metadata.insert(
+                "parameter".to_string(),
+                Value::String(parameter.to_string()),
+            );
+        }
+        Some(metadata)
+    }
+
+    fn tag_messages(
+        messages: Vec<ChatMessage>,
+        metadata: Option<HashMap<String, Value>>,
+    ) -> Vec<ChatMessage> {
+        match metadata {
+            Some(metadata) => messages
+                .into_iter()
+                .map(|message| message.with_metadata(metadata.clone()))
+                .collect(),
+            None => messages,
+        }
+    }
+
+    fn should_fall_back(error: &GeminiError) -> bool {
+        matches!(
+            error,
+            GeminiError::RateLimited
+                | GeminiError::RateLimitedWithRetry(_)
+                | GeminiError::ModelNotFound(_)
+                | GeminiError::ApiError {
+                    code: 500..=599,
+                    ..
+                }
+        )
+    }
+
+    // The client has already retried each model by the time an error reaches
+    // here, so a failure that would have been retried moves on to the next model.
+    async fn chat(
+        &self,
+        context: &Context,
+        messages: Vec<ChatMessage>,
+        config: GenerationConfig,
+    ) -> GeminiResult<GeminiResponse> {
+        let config = if self.deterministic {
+            debug!("Deterministic mode: temperature 0, top_k 1, candidate_count 1");
+            Self::deterministic_config(config)
+        } else {
+            config
+        };
+        let config = GenerationConfig {
+            seed: config.seed.or(self.seed),
+            ..config
+        };
+
+        for message in &messages {
+            debug!(target: ENGINE_THOUGHT_TARGET, "Prompt: {}", message.content);
+        }
+
+        let chain: Vec<ModelName> = std::iter::once(self.model.clone())
+            .chain(self.model_fallback_chain.iter().cloned())
+            .collect();
+        let mut models = chain.iter().peekable();
+
+        while let Some(model) = models.next() {
+            let config = Self::fit_to_model(model, config.clone())?;
+            match self
+                .client
+                .structured_chat(messages.clone(), model.clone(), Some(config))
+                .await
+            {
+                Ok(response) => {
+                    *self.served_model.lock().unwrap() = Some(model.clone());
+                    if let Some(tokens) = response.token_count() {
+                        context.runtime().stats().record_tokens(u64::from(tokens));
+                    }
+                    if let Some(thoughts) = response.thoughts() {
+                        debug!(target: ENGINE_THOUGHT_TARGET, "Thought: {}", thoughts);
+                    }
+                    if let Some(content) = response.first_content() {
+                        debug!(target: ENGINE_THOUGHT_TARGET, "Response: {}", content);
+                    }
+                    return Ok(response);
+                }
+                Err(e) if Self::should_fall_back(&e) => {
+                    let Some(next) = models.peek() else {
+                        return Err(e);
+                    };
+                    warn!(
+                        "Model {} failed ({}), falling back to {}",
+                        model.as_str(),
+                        e,
+                        next.as_str()
+                    );
+                }
+                Err(e) => return Err(e),
+            }
+        }
+
+        unreachable!("the primary model is always attempted")
+    }
+
+    // Each model in the fallback chain is held to its own limits.
+    fn fit_to_model(model: &ModelName, config: GenerationConfig) -> GeminiResult<GenerationConfig> {
+        let Some(capabilities) = ModelCapabilities::of(model) else {
+            return Ok(config);
+        };
+        let (config, adjustments) = capabilities.fit(config)?;
+        for adjustment in adjustments {
+            debug!("Adjusted request for {}: {}", model.as_str(), adjustment);
+        }
+        Ok(config)
+    }
+
+    fn deterministic_config(config: GenerationConfig) -> GenerationConfig {
+        GenerationConfig {
+            temperature: Some(0.0),
+            top_k: Some(1),
+            candidate_count: Some(1),
+            ..config
+        }
+    }
+
+    fn generation_config(&self, defaults: GenerationConfig) -> GenerationConfig {
+        match self.preset {
+            Some(preset) => defaults.with_preset_sampling(preset),
+            None => defaults,
+        }
+    }
+
+    fn describe_options(options: &[ExpressionValue]) -> String {
+        let mut descriptions = String::new();
+        for (index, option) in options.iter().enumerate() {
+            let description = match option {
+                ExpressionValue::Metadata {
+                    name,
+                    documentation,
+                } => {
+                    if let Some(doc) = documentation {
+                        format!("Function Name: '{}' Documentation: {}", name, doc)
+                    } else {
+                        format!("Function Name: '{}'", name)
+                    }
+                }
+                _ => option.format_for_llm(),
+            };
+            descriptions.push_str(&format!("{}: {}\n", index, description));
+        }
+        descriptions
+    }
+
     fn build_value_schema(value_type: &Type) -> Result<SchemaObject, String> {
         match value_type {
             Type::String => Ok(JsonSchemaBuilder::string()),
             Type::Boolean => Ok(JsonSchemaBuilder::boolean()),
+            Type::Number => Ok(JsonSchemaBuilder::number()),
             Type::List(_) => Ok(JsonSchemaBuilder::array(JsonSchemaBuilder::string())),
             Type::Option(inner_type) => Self::build_value_schema(inner_type),
+            Type::Json => Ok(JsonSchemaBuilder::string()),
             Type::Unit => Err("Unit type cannot be used in schema".to_string()),
             Type::Custom(_) => Err(format!("Unsupported type: {}", value_type.name())),
         }
     }
 
-    fn format_event(event: &Event) -> String {
-        let content = event.content.format_for_llm();
-
-        if let Some(name) = &event.name {
-            let params_xml = if let Some(params) = &event.params {
-                let params_str = params
-                    .iter()
-                    .map(|p| {
-                        let value = p.value.format_for_llm();
-                        format!("    <param name=\"{}\">{}</param>", p.name, value)
-                    })
-                    .collect::<Vec<_>>()
-                    .join("\n");
-                format!("{}\n", params_str)
-            } else {
-                String::new()
-            };
-
-            format!(
-                "<{}>\n{}    <result>\n    {}\n    </result>\n</{}>",
-                name, params_xml, content, name
-            )
-        } else {
-            content
+    fn build_fill_prompt(
+        param_name: &str,
+        param_type: &Type,
+        target: Option<&PlaceholderTarget>,
+    ) -> String {
+        let mut prompt = format!(
+            "Provide a value for '{}' of type '{}'",
+            param_name,
+            param_type.name()
+        );
+        if let Some(target) = target {
+            prompt.push_str(&format!(
+                " as an argument to function '{}'",
+                target.function_name
+            ));
+            if let Some(doc) = &target.documentation {
+                prompt.push_str(&format!(". Function Documentation: {}", doc));
+            }
         }
+        prompt
     }
 
     fn build_context_messages(&self, context: &Context) -> Vec<ChatMessage> {
@@ -94,11 +344,35 @@ impl GeminiEngine {
         } else {
             events
                 .iter()
-                .map(|event| ChatMessage::system(&Self::format_event(event)))
+                .map(|event| {
+                    let content = context.render_event(event);
+                    match event.role {
+                        EventRole::User => ChatMessage::user(content),
+                        EventRole::Model => ChatMessage::model(content),
+                    }
+                })
                 .collect()
         }
     }
 
+    fn build_fill_messages(
+        &self,
+        context: &Context,
+        param_name: &str,
+        param_type: &Type,
+        target: Option<&PlaceholderTarget>,
+    ) -> Vec<ChatMessage> {
+        let mut chat_messages = self.build_context_messages(context);
+        let prompt = Self::build_fill_prompt(param_name, param_type, target);
+        chat_messages.push(ChatMessage::user(prompt));
+
+        let function = target.map(|target| target.function_name.as_str());
+        Self::tag_messages(
+            chat_messages,
+            self.message_metadata(function, Some(param_name)),
+        )
+    }
+
     fn parse_json_value(
         json_value: serde_json::Value,
         value_type: &Type,
@@ -118,6 +392,13 @@ impl GeminiEngine {
                     Err("Expected boolean value".to_string())
                 }
             }
+            Type::Number => {
+                if let Some(n) = json_value.as_f64() {
+                    Ok(ExpressionValue::Number(n))
+                } else {
+                    Err("Expected number value".to_string())
+                }
+            }
             Type::List(_) => {
                 let items: Vec<String> = if json_value.is_array() {
                     json_value
@@ -147,6 +428,12 @@ impl GeminiEngine {
                     Ok(ExpressionValue::Option(Some(Box::new(inner_result))))
                 }
             }
+            Type::Json => match json_value {
+                serde_json::Value::String(s) => Ok(ExpressionValue::Json(
+                    serde_json::from_str(&s).unwrap_or(serde_json::Value::String(s)),
+                )),
+                other => Ok(ExpressionValue::Json(other)),
+            },
             _ => Err(format!("Unsupported type: {}", value_type.name())),
         }
     }
@@ -163,7 +450,7 @@ impl GeminiEngine {
             .ok_or_else(|| "Missing 'value' field in response".to_string())?;
 
         match return_type {
-            Type::String | Type::Boolean | Type::List(_) => {
+            Type::String | Type::Boolean | Type::Number | Type::List(_) | Type::Json => {
                 Self::parse_json_value(value_field.clone(), return_type)
             }
             Type::Option(_) => Self::parse_json_value(value_field.clone(), return_type),
@@ -175,20 +462,19 @@ impl GeminiEngine {
 #[async_trait]
 impl LanguageEngine for GeminiEngine {
     async fn untyped(&self, context: &Context) -> String {
-        let chat_messages = self.build_context_messages(context);
+        let chat_messages = Self::tag_messages(
+            self.build_context_messages(context),
+            self.message_metadata(None, None),
+        );
 
-        let generation_config = GenerationConfig::new()
-            .with_temperature(0.9)
+        let generation_config = self
+            .generation_config(GenerationConfig::new().with_temperature(0.9))
             .with_low_thinking();
 
-        match self
-            .client
-            .structured_chat(chat_messages, self.model.clone(), Some(generation_config))
-            .await
-        {
+        match self.chat(context, chat_messages, generation_config).await {
             Ok(response) => response
-                .first_content()
-                .unwrap_or_else(|| DEFAULT_NO_RESPONSE_MESSAGE.to_string()),
+                .content()
+                .unwrap_or_else(|e| format!("Error communicating with Gemini: {}", e)),
             Err(e) => {
                 format!("Error communicating with Gemini: {}", e)
             }
@@ -219,24 +505,29 @@ impl LanguageEngine for GeminiEngine {
             is_required,
         );
 
-        let chat_messages = self.build_context_messages(context);
+        let chat_messages = Self::tag_messages(
+            self.build_context_messages(context),
+            self.message_metadata(None, None),
+        );
 
-        let generation_config = GenerationConfig::new()
-            .with_temperature(temperature)
-            .with_top_p(0.95)
+        let generation_config = self
+            .generation_config(
+                GenerationConfig::new()
+                    .with_temperature(temperature)
+                    .with_top_p(0.95),
+            )
             .with_response_mime_type("application/json".to_string())
             .with_response_schema(schema)
             .with_minimal_thinking();
 
         let response = self
-            .client
-            .structured_chat(chat_messages, self.model.clone(), Some(generation_config))
+            .chat(context, chat_messages, generation_config)
             .await
             .map_err(|e| format!("Error communicating with Gemini: {}", e))?;
 
         let response_text = response
-            .first_content()
-            .unwrap_or_else(|| DEFAULT_NO_RESPONSE_MESSAGE.to_string());
+            .content()
+            .map_err(|e| format!("Error communicating with Gemini: {}", e))?;
 
         Self::parse_typed_response(&response_text, return_type)
     }
@@ -249,25 +540,11 @@ impl LanguageEngine for GeminiEngine {
         let mut selection_prompt =
             "SELECT: Choose one of the following options by responding with the appropriate number:\n"
                 .to_string();
-        for (index, option) in options.iter().enumerate() {
-            let description = match option {
-                crate::runtime::ExpressionValue::Metadata {
-                    name,
-                    documentation,
-                } => {
-                    if let Some(doc) = documentation {
-                        format!("Function Name: '{}' Documentation: {}", name, doc)
-                    } else {
-                        format!("Function Name: '{}'", name)
-                    }
-                }
-                _ => option.format_for_llm(),
-            };
-            selection_prompt.push_str(&format!("{}: {}\n", index, description));
-        }
+        selection_prompt.push_str(&Self::describe_options(options));
 
         let mut chat_messages = self.build_context_messages(context);
         chat_messages.push(ChatMessage::user(selection_prompt));
+        let chat_messages = Self::tag_messages(chat_messages, self.message_metadata(None, None));
 
         let max_index = if options.is_empty() {
             0
@@ -283,15 +560,11 @@ impl LanguageEngine for GeminiEngine {
             .with_response_schema(schema)
             .with_minimal_thinking();
 
-        match self
-            .client
-            .structured_chat(chat_messages, self.model.clone(), Some(generation_config))
-            .await
-        {
+        match self.chat(context, chat_messages, generation_config).await {
             Ok(response) => {
                 let response_text = response
-                    .first_content()
-                    .unwrap_or_else(|| DEFAULT_NO_RESPONSE_MESSAGE.to_string());
+                    .content()
+                    .map_err(|e| format!("Error communicating with Gemini for selection: {}", e))?;
 
                 let selection_response: SelectionResponse = serde_json::from_str(&response_text)
                     .map_err(|_| {
@@ -319,11 +592,62 @@ impl LanguageEngine for GeminiEngine {
         }
     }
 
+    async fn rank(
+        &self,
+        context: &Context,
+        options: &[crate::runtime::ExpressionValue],
+    ) -> Result<Vec<usize>, String> {
+        let mut ranking_prompt =
+            "RANK: Order all of the following options from most to least suitable, responding with their numbers:\n"
+                .to_string();
+        ranking_prompt.push_str(&Self::describe_options(options));
+
+        let mut chat_messages = self.build_context_messages(context);
+        chat_messages.push(ChatMessage::user(ranking_prompt));
+        let chat_messages = Self::tag_messages(chat_messages, self.message_metadata(None, None));
+
+        let max_index = options.len().saturating_sub(1);
+        let schema = JsonSchemaBuilder::integer_ranking(max_index as u32);
+
+        let generation_config = GenerationConfig::new()
+            .with_temperature(0.0)
+            .with_response_mime_type("application/json".to_string())
+            .with_response_schema(schema)
+            .with_minimal_thinking();
+
+        match self.chat(context, chat_messages, generation_config).await {
+            Ok(response) => {
+                let response_text = response
+                    .content()
+                    .map_err(|e| format!("Error communicating with Gemini for ranking: {}", e))?;
+
+                let ranking_response: RankingResponse = serde_json::from_str(&response_text)
+                    .map_err(|_| {
+                        format!(
+                            "Invalid JSON response from language engine: '{}'",
+                            response_text
+                        )
+                    })?;
+
+                Ok(ranking_response
+                    .ranking
+                    .into_iter()
+                    .map(|index| index as usize)
+                    .collect())
+            }
+            Err(e) => Err(format!(
+                "Error communicating with Gemini for ranking: {}",
+                e
+            )),
+        }
+    }
+
     async fn fill_parameter(
         &self,
         context: &Context,
         param_name: &str,
         param_type: &Type,
+        target: Option<&PlaceholderTarget>,
     ) -> Result<ExpressionValue, String> {
         if matches!(param_type, Type::Unit) {
             return Ok(ExpressionValue::Unit);
@@ -344,30 +668,426 @@ impl LanguageEngine for GeminiEngine {
             is_required,
         );
 
-        let mut chat_messages = self.build_context_messages(context);
-        let prompt = format!(
-            "Provide a value for '{}' of type '{}'",
-            param_name,
-            param_type.name()
-        );
-        chat_messages.push(ChatMessage::user(prompt));
+        let chat_messages = self.build_fill_messages(context, param_name, param_type, target);
 
-        let generation_config = GenerationConfig::new()
-            .with_temperature(temperature)
+        let generation_config = self
+            .generation_config(GenerationConfig::new().with_temperature(temperature))
             .with_response_mime_type("application/json".to_string())
             .with_response_schema(schema)
             .with_minimal_thinking();
 
         let response = self
-            .client
-            .structured_chat(chat_messages, self.model.clone(), Some(generation_config))
+            .chat(context, chat_messages, generation_config)
             .await
             .map_err(|e| format!("Error communicating with Gemini: {}", e))?;
 
         let response_text = response
-            .first_content()
-            .unwrap_or_else(|| DEFAULT_NO_RESPONSE_MESSAGE.to_string());
+            .content()
+            .map_err(|e| format!("Error communicating with Gemini: {}", e))?;
 
         Self::parse_typed_response(&response_text, param_type)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gemini::types::Role;
+
+    #[test]
+    fn test_fill_prompt_includes_function_documentation() {
+        let target = PlaceholderTarget::new(
+            "search".to_string(),
+            Some("Searches the issue tracker".to_string()),
+        );
+
+        let prompt = GeminiEngine::build_fill_prompt("query", &Type::string(), Some(&target));
+
+        assert_eq!(
+            prompt,
+            "Provide a value for 'query' of type 'String' as an argument to function 'search'. Function Documentation: Searches the issue tracker"
+        );
+    }
+
+    #[test]
+    fn test_fill_prompt_without_target() {
+        let prompt = GeminiEngine::build_fill_prompt("placeholder", &Type::string(), None);
+
+        assert_eq!(prompt, "Provide a value for 'placeholder' of type 'String'");
+    }
+
+    async fn test_engine() -> GeminiEngine {
+        let config = crate::gemini::GeminiConfig::with_api_key(
+            "test_project".to_string(),
+            "us-central1".to_string(),
+            "test_key".to_string(),
+        );
+        GeminiEngine::new(config).await.unwrap()
+    }
+
+    fn test_context() -> Context {
+        let program = crate::compiler::CompilationUnit::from_string(String::new());
+        let runtime = crate::runtime::Runtime::builder(program).build();
+        Context::with_runtime(std::sync::Arc::new(runtime))
+    }
+
+    #[tokio::test]
+    async fn test_fill_messages_are_tagged_with_metadata() {
+        let engine = test_engine()
+            .await
+            .with_message_metadata(true)
+            .with_session_id("run-1");
+        let target = PlaceholderTarget::new("search".to_string(), None);
+
+        let messages =
+            engine.build_fill_messages(&test_context(), "query", &Type::string(), Some(&target));
+
+        let expected: HashMap<String, Value> = [
+            ("session_id", "run-1"),
+            ("function", "search"),
+            ("parameter", "query"),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), Value::String(v.to_string())))
+        .collect();
+
+        assert_eq!(messages.len(), 2);
+        for message in &messages {
+            assert_eq!(message.metadata.as_ref(), Some(&expected));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_context_messages_alternate_between_user_and_model() {
+        let engine = test_engine().await;
+        let mut context = test_context();
+        context.add_event(ExpressionValue::String("Hi".to_string()), None, None);
+        context.add_event_with_role(
+            ExpressionValue::String("Hello! How can I help?".to_string()),
+            None,
+            None,
+            EventRole::Model,
+        );
+        context.add_event(
+            ExpressionValue::String("Tell me a joke".to_string()),
+            None,
+            None,
+        );
+
+        let messages = engine.build_context_messages(&context);
+
+        let turns: Vec<_> = messages
+            .iter()
+            .map(|message| (message.role.clone(), message.content.as_str()))
+            .collect();
+        assert!(matches!(
+            turns.as_slice(),
+            [
+                (Role::User, "Hi"),
+                (Role::Model, "Hello! How can I help?"),
+                (Role::User, "Tell me a joke"),
+            ]
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_fill_messages_have_no_metadata_by_default() {
+        let engine = test_engine().await;
+
+        let messages = engine.build_fill_messages(&test_context(), "query", &Type::string(), None);
+
+        assert!(messages.iter().all(|message| message.metadata.is_none()));
+    }
+
+    async fn read_http_request(socket: &mut tokio::net::TcpStream) -> String {
+        use tokio::io::AsyncReadExt;
+
+        let mut request = Vec::new();
+        let mut buf = [0u8; 4096];
+        loop {
+            let read = socket.read(&mut buf).await.unwrap();
+            if read == 0 {
+                break;
+            }
+            request.extend_from_slice(&buf[..read]);
+
+            let text = String::from_utf8_lossy(&request);
+            if let Some(header_end) = text.find("\r\n\r\n") {
+                let content_length = text[..header_end]
+                    .lines()
+                    .find_map(|line| {
+                        let (name, value) = line.split_once(':')?;
+                        if name.eq_ignore_ascii_case("content-length") {
+                            value.trim().parse::<usize>().ok()
+                        } else {
+                            None
+                        }
+                    })
+                    .unwrap_or(0);
+                if request.len() >= header_end + 4 + content_length {
+                    break;
+                }
+            }
+        }
+        String::from_utf8_lossy(&request).to_string()
+    }
+
+    #[tokio::test]
+    async fn test_falls_back_to_next_model_and_records_it() {
+        let (engine, server) = serve_replies(vec![
+            (
+                "404 Not Found",
+                r#"{"error":"model not found"}"#.to_string(),
+            ),
+            ("200 OK", reply_body("from fallback")),
+        ])
+        .await;
+        let engine = engine
+            .with_model(ModelName::Gemini25Pro)
+            .with_model_fallback_chain(vec![ModelName::Gemini25FlashLite]);
+
+        assert!(engine.last_served_model().is_none());
+
+        let response = engine.untyped(&test_context()).await;
+
+        assert_eq!(response, "from fallback");
+        assert_eq!(
+            engine
+                .last_served_model()
+                .map(|model| model.as_str().to_string()),
+            Some("gemini-2.5-flash-lite".to_string())
+        );
+        let requests = server.await.unwrap();
+        let request_lines: Vec<&str> = requests
+            .iter()
+            .map(|request| request.lines().next().unwrap())
+            .collect();
+        assert_eq!(request_lines.len(), 2);
+        assert!(request_lines[0].contains("gemini-2.5-pro:"));
+        assert!(request_lines[1].contains("gemini-2.5-flash-lite:"));
+    }
+
+    #[tokio::test]
+    async fn test_deterministic_mode_overrides_requested_sampling() {
+        let (engine, server) = serve_one_reply("same every time").await;
+        let engine = engine
+            .with_preset(Preset::Creative)
+            .with_deterministic(true);
+
+        // `untyped` asks for temperature 0.9, and the creative preset for 1.2.
+        let response = engine.untyped(&test_context()).await;
+        assert_eq!(response, "same every time");
+
+        let request = server.await.unwrap();
+        let (_, body) = request.split_once("\r\n\r\n").unwrap();
+        let body: Value = serde_json::from_str(body).unwrap();
+        let generation_config = &body["generationConfig"];
+        assert_eq!(generation_config["temperature"], 0.0);
+        assert_eq!(generation_config["topK"], 1);
+        assert_eq!(generation_config["candidateCount"], 1);
+    }
+
+    #[tokio::test]
+    async fn test_preset_replaces_default_sampling() {
+        let (engine, server) = serve_one_reply("precise enough").await;
+        let engine = engine.with_preset(Preset::Precise);
+
+        // `untyped` would otherwise ask for temperature 0.9.
+        assert_eq!(engine.untyped(&test_context()).await, "precise enough");
+
+        let request = server.await.unwrap();
+        let (_, body) = request.split_once("\r\n\r\n").unwrap();
+        let body: Value = serde_json::from_str(body).unwrap();
+        let generation_config = &body["generationConfig"];
+        assert_eq!(generation_config["temperature"].as_f64(), Some(0.0));
+        assert_eq!(generation_config["topP"].as_f64(), Some(0.1_f32 as f64));
+    }
+
+    #[tokio::test]
+    async fn test_seed_is_sent_with_every_call() {
+        let (engine, server) = serve_one_reply("seeded").await;
+        let engine = engine.with_seed(42).with_deterministic(true);
+
+        assert_eq!(engine.untyped(&test_context()).await, "seeded");
+
+        let request = server.await.unwrap();
+        let (_, body) = request.split_once("\r\n\r\n").unwrap();
+        let body: Value = serde_json::from_str(body).unwrap();
+        assert_eq!(body["generationConfig"]["seed"], 42);
+    }
+
+    #[tokio::test]
+    async fn test_call_seed_takes_precedence_over_engine_seed() {
+        let (engine, server) = serve_one_reply("seeded").await;
+        let engine = engine.with_seed(42);
+
+        let response = engine
+            .chat(
+                &test_context(),
+                vec![ChatMessage::user("hello")],
+                GenerationConfig::new().with_seed(7),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.first_content().as_deref(), Some("seeded"));
+
+        let request = server.await.unwrap();
+        let (_, body) = request.split_once("\r\n\r\n").unwrap();
+        let body: Value = serde_json::from_str(body).unwrap();
+        assert_eq!(body["generationConfig"]["seed"], 7);
+    }
+
+    #[tokio::test]
+    async fn test_thinking_config_is_fitted_to_the_model() {
+        let (engine, server) = serve_one_reply("fitted").await;
+        let engine = engine.with_model(ModelName::Gemini3ProPreview);
+
+        engine
+            .chat(
+                &test_context(),
+                vec![ChatMessage::user("hello")],
+                GenerationConfig::new().with_minimal_thinking(),
+            )
+            .await
+            .unwrap();
+
+        let request = server.await.unwrap();
+        let (_, body) = request.split_once("\r\n\r\n").unwrap();
+        let body: Value = serde_json::from_str(body).unwrap();
+        let thinking = &body["generationConfig"]["thinkingConfig"];
+        assert_eq!(thinking["thinkingLevel"], "low");
+        assert!(thinking.get("thinkingBudget").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_rank_requests_a_ranking_schema() {
+        let (engine, server) = serve_one_reply(r#"{"ranking":[1,0]}"#).await;
+
+        let ranking = engine
+            .rank(&test_context(), &clause_options())
+            .await
+            .unwrap();
+        assert_eq!(ranking, vec![1, 0]);
+
+        let request = server.await.unwrap();
+        let (_, body) = request.split_once("\r\n\r\n").unwrap();
+        let body: Value = serde_json::from_str(body).unwrap();
+        let schema = &body["generationConfig"]["responseSchema"];
+        assert_eq!(schema["properties"]["ranking"]["type"], "array");
+        assert_eq!(schema["properties"]["ranking"]["items"]["maximum"], 1.0);
+    }
+
+    // Answers one request per reply, in order, with the reply's status and
+    // body, and hands back the raw requests once they have all been served.
+    async fn serve_replies(
+        replies: Vec<(&'static str, String)>,
+    ) -> (GeminiEngine, tokio::task::JoinHandle<Vec<String>>) {
+        use tokio::io::AsyncWriteExt;
+        use tokio::net::TcpListener;
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let server = tokio::spawn(async move {
+            let mut requests = Vec::new();
+            for (status, body) in replies {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                requests.push(read_http_request(&mut socket).await);
+                let response = format!(
+                    "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                );
+                socket.write_all(response.as_bytes()).await.unwrap();
+                socket.shutdown().await.unwrap();
+            }
+            requests
+        });
+
+        let config = crate::gemini::GeminiConfig::with_api_key(
+            "test_project".to_string(),
+            "us-central1".to_string(),
+            "test_key".to_string(),
+        )
+        .with_api_endpoint(format!("http://{}", addr));
+        (GeminiEngine::new(config).await.unwrap(), server)
+    }
+
+    // Answers a single request with `text` as the model's reply and hands
+    // back the raw request once it has been served.
+    async fn serve_one_reply(text: &str) -> (GeminiEngine, tokio::task::JoinHandle<String>) {
+        let (engine, server) = serve_replies(vec![("200 OK", reply_body(text))]).await;
+        let request = tokio::spawn(async move { server.await.unwrap().remove(0) });
+        (engine, request)
+    }
+
+    fn reply_body(text: &str) -> String {
+        serde_json::json!({
+            "candidates": [{"content": {"parts": [{"text": text}]}}]
+        })
+        .to_string()
+    }
+
+    fn clause_options() -> Vec<ExpressionValue> {
+        ["from_cache", "from_index"]
+            .into_iter()
+            .map(|name| ExpressionValue::Metadata {
+                name: name.to_string(),
+                documentation: None,
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_select_requests_an_integer_selection_schema() {
+        let (engine, server) = serve_one_reply(r#"{"selection": 1}"#).await;
+
+        let selected = engine
+            .select(&test_context(), &clause_options())
+            .await
+            .unwrap();
+        assert_eq!(selected, 1);
+
+        let request = server.await.unwrap();
+        let (_, body) = request.split_once("\r\n\r\n").unwrap();
+        let body: Value = serde_json::from_str(body).unwrap();
+        let generation_config = &body["generationConfig"];
+        assert_eq!(generation_config["responseMimeType"], "application/json");
+        let selection = &generation_config["responseSchema"]["properties"]["selection"];
+        assert_eq!(selection["type"], "integer");
+        assert_eq!(selection["minimum"], 0.0);
+        assert_eq!(selection["maximum"], 1.0);
+    }
+
+    #[tokio::test]
+    async fn test_select_rejects_out_of_range_selection() {
+        let (engine, server) = serve_one_reply(r#"{"selection": 2}"#).await;
+
+        let error = engine
+            .select(&test_context(), &clause_options())
+            .await
+            .unwrap_err();
+
+        assert_eq!(error, "Language engine selected invalid option index: 2");
+        server.await.unwrap();
+    }
+
+    #[test]
+    fn test_only_unavailable_model_errors_fall_back() {
+        assert!(GeminiEngine::should_fall_back(&GeminiError::RateLimited));
+        assert!(GeminiEngine::should_fall_back(&GeminiError::ModelNotFound(
+            "gone".to_string()
+        )));
+        assert!(GeminiEngine::should_fall_back(&GeminiError::ApiError {
+            code: 503,
+            message: "unavailable".to_string(),
+        }));
+        assert!(!GeminiEngine::should_fall_back(&GeminiError::InvalidInput(
+            "bad request".to_string()
+        )));
+        assert!(!GeminiEngine::should_fall_back(
+            &GeminiError::Authentication("denied".to_string())
+        ));
+    }
+}
@@ -6,7 +6,10 @@ pub enum GeminiError {
     Configuration(String),
     Authentication(String),
     Network(String),
-    ApiError { code: u32, message: String },
+    ApiError {
+        code: u32,
+        message: String,
+    },
     InvalidInput(String),
     Timeout,
     RateLimited,
@@ -14,6 +17,10 @@ pub enum GeminiError {
     QuotaExceeded,
     ModelNotFound(String),
     Serialization(String),
+    /// The prompt was blocked before any candidate was generated.
+    PromptBlocked(String),
+    /// A response arrived but held no answer text.
+    EmptyResponse(String),
     Unknown(String),
 }
 
@@ -39,6 +46,8 @@ impl fmt::Display for GeminiError {
             GeminiError::QuotaExceeded => write!(f, "Quota exceeded"),
             GeminiError::ModelNotFound(model) => write!(f, "Model not found: {}", model),
             GeminiError::Serialization(msg) => write!(f, "Serialization error: {}", msg),
+            GeminiError::PromptBlocked(reason) => write!(f, "Prompt blocked: {}", reason),
+            GeminiError::EmptyResponse(details) => write!(f, "Empty response: {}", details),
             GeminiError::Unknown(msg) => write!(f, "Unknown error: {}", msg),
         }
     }
@@ -1,3 +1,4 @@
+pub mod capabilities;
 pub mod client;
 pub mod config;
 pub mod engine;
@@ -1,3 +1,4 @@
+use crate::gemini::error::{GeminiError, GeminiResult};
 use schemars::schema::{InstanceType, Schema, SchemaObject, SingleOrVec};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
@@ -14,6 +15,17 @@ pub enum ModelName {
 }
 
 impl ModelName {
+    pub fn from_name(name: &str) -> Self {
+        match name {
+            "gemini-2.5-pro" => Self::Gemini25Pro,
+            "gemini-2.5-flash" => Self::Gemini25Flash,
+            "gemini-2.5-flash-lite" => Self::Gemini25FlashLite,
+            "gemini-3-flash-preview" => Self::Gemini3FlashPreview,
+            "gemini-3-pro-preview" => Self::Gemini3ProPreview,
+            custom => Self::Custom(custom.to_string()),
+        }
+    }
+
     pub fn as_str(&self) -> &str {
         match self {
             Self::Gemini25Pro => "gemini-2.5-pro",
@@ -175,6 +187,8 @@ pub struct GenerationConfig {
     pub response_schema: Option<JsonSchema>,
     #[serde(skip_serializing_if = "Option::is_none", rename = "thinkingConfig")]
     pub thinking_config: Option<ThinkingConfig>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
 }
 
 impl GenerationConfig {
@@ -189,6 +203,26 @@ impl GenerationConfig {
             response_mime_type: None,
             response_schema: None,
             thinking_config: None,
+            seed: None,
+        }
+    }
+
+    pub fn preset(preset: Preset) -> Self {
+        match preset {
+            Preset::Creative => Self::new().with_temperature(1.2).with_top_p(0.98),
+            Preset::Precise => Self::new().with_temperature(0.0).with_top_p(0.1),
+            Preset::Balanced => Self::new().with_temperature(0.7).with_top_p(0.95),
+        }
+    }
+
+    /// Replaces the sampling set here (temperature and top_p) with the
+    /// preset's. Every other field is kept.
+    pub fn with_preset_sampling(self, preset: Preset) -> Self {
+        let preset = Self::preset(preset);
+        Self {
+            temperature: preset.temperature,
+            top_p: preset.top_p,
+            ..self
         }
     }
 
@@ -227,6 +261,13 @@ impl GenerationConfig {
         self
     }
 
+    /// Asks the model to sample with a fixed seed, so repeated requests can
+    /// return the same output. Not every model version honours it.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
     pub fn with_thinking_config(mut self, thinking_config: ThinkingConfig) -> Self {
         self.thinking_config = Some(thinking_config);
         self
@@ -254,6 +295,25 @@ impl Default for GenerationConfig {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Preset {
+    Creative,
+    Precise,
+    #[default]
+    Balanced,
+}
+
+impl Preset {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "creative" => Some(Self::Creative),
+            "precise" => Some(Self::Precise),
+            "balanced" => Some(Self::Balanced),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChatRequest {
     pub messages: Vec<ChatMessage>,
@@ -304,13 +364,16 @@ pub struct UsageMetadata {
     pub thoughts_token_count: Option<u32>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
 pub struct ResponseContent {
+    #[serde(default)]
     pub parts: Vec<Part>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Candidate {
+    // A candidate stopped for safety can arrive without any content.
+    #[serde(default)]
     pub content: ResponseContent,
     #[serde(skip_serializing_if = "Option::is_none", rename = "finishReason")]
     pub finish_reason: Option<String>,
@@ -322,6 +385,8 @@ pub struct Candidate {
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct GeminiResponse {
+    // Left out entirely when the prompt itself was blocked.
+    #[serde(default)]
     pub candidates: Vec<Candidate>,
     #[serde(skip_serializing_if = "Option::is_none", rename = "usageMetadata")]
     pub usage_metadata: Option<UsageMetadata>,
@@ -336,12 +401,60 @@ impl GeminiResponse {
                 .content
                 .parts
                 .iter()
+                .filter(|part| !part.is_thought())
                 .map(|part| part.text.as_str())
                 .collect::<Vec<_>>()
                 .join("")
         })
     }
 
+    /// The text of the first candidate, or why there is none: a prompt
+    /// blocked before any candidate was generated, or a candidate that came
+    /// back without text.
+    pub fn content(&self) -> GeminiResult<String> {
+        let Some(candidate) = self.candidates.first() else {
+            return Err(match self.block_reason() {
+                Some(reason) => GeminiError::PromptBlocked(reason),
+                None => GeminiError::EmptyResponse("no candidates were returned".to_string()),
+            });
+        };
+
+        match self.first_content() {
+            Some(content) if !content.is_empty() => Ok(content),
+            _ => Err(GeminiError::EmptyResponse(format!(
+                "the candidate has no text (finish reason: {})",
+                candidate.finish_reason.as_deref().unwrap_or("unknown")
+            ))),
+        }
+    }
+
+    /// Why the prompt was blocked, from `promptFeedback`, with the API's
+    /// explanation when it gives one.
+    pub fn block_reason(&self) -> Option<String> {
+        let feedback = self.prompt_feedback.as_ref()?;
+        let reason = feedback.get("blockReason")?.as_str()?;
+        match feedback.get("blockReasonMessage").and_then(Value::as_str) {
+            Some(message) => Some(format!("{}: {}", reason, message)),
+            None => Some(reason.to_string()),
+        }
+    }
+
+    /// Thought summaries returned alongside the answer when `include_thoughts`
+    /// is enabled.
+    pub fn thoughts(&self) -> Option<String> {
+        let thoughts = self
+            .candidates
+            .first()?
+            .content
+            .parts
+            .iter()
+            .filter(|part| part.is_thought())
+            .map(|part| part.text.as_str())
+            .collect::<Vec<_>>();
+
+        (!thoughts.is_empty()).then(|| thoughts.join(""))
+    }
+
     pub fn is_blocked(&self) -> bool {
         self.candidates.iter().any(|candidate| {
             candidate
@@ -369,6 +482,14 @@ pub struct StreamingResponse {
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Part {
     pub text: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thought: Option<bool>,
+}
+
+impl Part {
+    pub fn is_thought(&self) -> bool {
+        self.thought.unwrap_or(false)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -383,12 +504,10 @@ pub struct JsonSchemaBuilder;
 
 impl JsonSchemaBuilder {
     pub fn integer_selection(max_value: u32) -> SchemaObject {
-        let mut schema = SchemaObject::default();
-        schema.instance_type = Some(SingleOrVec::Single(Box::new(InstanceType::Object)));
+        let mut schema = Self::object();
 
         let mut properties = BTreeMap::new();
-        let mut selection_schema = SchemaObject::default();
-        selection_schema.instance_type = Some(SingleOrVec::Single(Box::new(InstanceType::Integer)));
+        let mut selection_schema = Self::of_type(InstanceType::Integer);
         let number_validation = selection_schema.number();
         number_validation.minimum = Some(0.0);
         number_validation.maximum = Some(max_value as f64);
@@ -400,10 +519,17 @@ impl JsonSchemaBuilder {
         schema
     }
 
+    pub fn integer_ranking(max_value: u32) -> SchemaObject {
+        let mut index_schema = Self::of_type(InstanceType::Integer);
+        let number_validation = index_schema.number();
+        number_validation.minimum = Some(0.0);
+        number_validation.maximum = Some(max_value as f64);
+
+        Self::with_property(Self::object(), "ranking", Self::array(index_schema), true)
+    }
+
     pub fn object() -> SchemaObject {
-        let mut schema = SchemaObject::default();
-        schema.instance_type = Some(SingleOrVec::Single(Box::new(InstanceType::Object)));
-        schema
+        Self::of_type(InstanceType::Object)
     }
 
     pub fn with_property(
@@ -423,24 +549,30 @@ impl JsonSchemaBuilder {
     }
 
     pub fn string() -> SchemaObject {
-        let mut schema = SchemaObject::default();
-        schema.instance_type = Some(SingleOrVec::Single(Box::new(InstanceType::String)));
-        schema
+        Self::of_type(InstanceType::String)
     }
 
     pub fn boolean() -> SchemaObject {
-        let mut schema = SchemaObject::default();
-        schema.instance_type = Some(SingleOrVec::Single(Box::new(InstanceType::Boolean)));
-        schema
+        Self::of_type(InstanceType::Boolean)
+    }
+
+    pub fn number() -> SchemaObject {
+        Self::of_type(InstanceType::Number)
     }
 
     pub fn array(items: SchemaObject) -> SchemaObject {
-        let mut schema = SchemaObject::default();
-        schema.instance_type = Some(SingleOrVec::Single(Box::new(InstanceType::Array)));
+        let mut schema = Self::of_type(InstanceType::Array);
         let array_validation = schema.array();
         array_validation.items = Some(SingleOrVec::Single(Box::new(Schema::Object(items))));
         schema
     }
+
+    fn of_type(instance_type: InstanceType) -> SchemaObject {
+        SchemaObject {
+            instance_type: Some(SingleOrVec::Single(Box::new(instance_type))),
+            ..Default::default()
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -473,6 +605,7 @@ impl From<&ChatRequest> for GeminiApiRequest {
                     role: role.to_string(),
                     parts: vec![Part {
                         text: msg.content.clone(),
+                        thought: None,
                     }],
                 }
             })
@@ -485,6 +618,7 @@ impl From<&ChatRequest> for GeminiApiRequest {
                 .map(|instruction| SystemInstruction {
                     parts: vec![Part {
                         text: instruction.clone(),
+                        thought: None,
                     }],
                 });
 
@@ -513,6 +647,7 @@ mod tests {
             response_mime_type: None,
             response_schema: None,
             thinking_config: None,
+            seed: None,
         };
 
         let serialized = serde_json::to_value(&config).unwrap();
@@ -541,6 +676,7 @@ mod tests {
             response_mime_type: None,
             response_schema: None,
             thinking_config: None,
+            seed: None,
         };
 
         let serialized = serde_json::to_value(&config).unwrap();
@@ -558,6 +694,61 @@ mod tests {
         assert!((top_p - 0.8).abs() < 0.001);
     }
 
+    #[test]
+    fn test_generation_config_serializes_seed() {
+        let serialized = serde_json::to_value(GenerationConfig::new().with_seed(42)).unwrap();
+        assert_eq!(serialized["seed"], json!(42));
+
+        let serialized = serde_json::to_value(GenerationConfig::new()).unwrap();
+        assert!(serialized.get("seed").is_none());
+    }
+
+    #[test]
+    fn test_generation_config_presets() {
+        let creative = GenerationConfig::preset(Preset::Creative);
+        assert_eq!(creative.temperature, Some(1.2));
+        assert_eq!(creative.top_p, Some(0.98));
+
+        let precise = GenerationConfig::preset(Preset::Precise);
+        assert_eq!(precise.temperature, Some(0.0));
+        assert_eq!(precise.top_p, Some(0.1));
+
+        let balanced = GenerationConfig::preset(Preset::Balanced);
+        assert_eq!(balanced.temperature, Some(0.7));
+        assert_eq!(balanced.top_p, Some(0.95));
+        assert!(balanced.top_k.is_none());
+        assert!(balanced.thinking_config.is_none());
+    }
+
+    #[test]
+    fn test_explicit_temperature_overrides_preset() {
+        let config = GenerationConfig::preset(Preset::Creative).with_temperature(0.3);
+
+        assert_eq!(config.temperature, Some(0.3));
+        assert_eq!(config.top_p, Some(0.98));
+    }
+
+    #[test]
+    fn test_preset_sampling_replaces_config_sampling() {
+        let config = GenerationConfig::new()
+            .with_temperature(0.9)
+            .with_top_p(0.95)
+            .with_seed(7)
+            .with_preset_sampling(Preset::Precise);
+
+        assert_eq!(config.temperature, Some(0.0));
+        assert_eq!(config.top_p, Some(0.1));
+        assert_eq!(config.seed, Some(7));
+    }
+
+    #[test]
+    fn test_preset_from_name() {
+        assert_eq!(Preset::from_name("creative"), Some(Preset::Creative));
+        assert_eq!(Preset::from_name("precise"), Some(Preset::Precise));
+        assert_eq!(Preset::from_name("balanced"), Some(Preset::Balanced));
+        assert_eq!(Preset::from_name("wild"), None);
+    }
+
     #[test]
     fn test_gemini_api_request_serialization() {
         let messages = vec![
@@ -575,6 +766,7 @@ mod tests {
             response_mime_type: None,
             response_schema: None,
             thinking_config: None,
+            seed: None,
         };
 
         let request = ChatRequest {
@@ -715,9 +907,11 @@ mod tests {
                     parts: vec![
                         Part {
                             text: "Hello ".to_string(),
+                            thought: None,
                         },
                         Part {
                             text: "world!".to_string(),
+                            thought: None,
                         },
                     ],
                 },
@@ -742,4 +936,64 @@ mod tests {
 
         assert_eq!(response.first_content(), None);
     }
+
+    #[test]
+    fn test_empty_candidates_report_prompt_block_reason() {
+        let response: GeminiResponse = serde_json::from_str(
+            r#"{
+                "promptFeedback": {"blockReason": "SAFETY"},
+                "usageMetadata": {"promptTokenCount": 12, "totalTokenCount": 12}
+            }"#,
+        )
+        .unwrap();
+
+        assert!(response.candidates.is_empty());
+        match response.content() {
+            Err(GeminiError::PromptBlocked(reason)) => assert_eq!(reason, "SAFETY"),
+            other => panic!("Expected a blocked prompt, got: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_candidate_without_text_is_an_empty_response() {
+        let response: GeminiResponse = serde_json::from_str(
+            r#"{
+                "candidates": [{
+                    "content": {"parts": [{"text": ""}]},
+                    "finishReason": "MAX_TOKENS"
+                }]
+            }"#,
+        )
+        .unwrap();
+
+        match response.content() {
+            Err(GeminiError::EmptyResponse(details)) => {
+                assert!(details.contains("MAX_TOKENS"), "{}", details)
+            }
+            other => panic!("Expected an empty response, got: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_thought_parts_are_separated_from_content() {
+        let response: GeminiResponse = serde_json::from_str(
+            r#"{
+                "candidates": [{
+                    "content": {
+                        "parts": [
+                            {"text": "Considering the options.", "thought": true},
+                            {"text": "The answer"}
+                        ]
+                    }
+                }]
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(response.first_content(), Some("The answer".to_string()));
+        assert_eq!(
+            response.thoughts(),
+            Some("Considering the options.".to_string())
+        );
+    }
 }
@@ -13,5 +13,10 @@ pub mod runtime;
 pub mod typecheck;
 pub mod types;
 
+pub use compiler::parse_source;
+
 #[cfg(test)]
 mod test_doc;
+
+#[cfg(test)]
+pub(crate) mod test_support;
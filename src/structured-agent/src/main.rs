@@ -14,21 +14,44 @@ mod typecheck;
 mod types;
 
 use clap::Parser;
+use cli::profile::ProfileLayer;
 use cli::{App, Args, Config};
 use std::process;
-use tracing_subscriber::{EnvFilter, layer::SubscriberExt, util::SubscriberInitExt};
+use tracing::Level;
+use tracing_subscriber::filter::Targets;
+use tracing_subscriber::{EnvFilter, Layer, layer::SubscriberExt, util::SubscriberInitExt};
 
 #[tokio::main]
 async fn main() {
+    let args = Args::parse();
+    let config = Config::from_args(args);
+
+    // The profile layer sees the runtime's debug spans even when the log
+    // filter hides them, so the filter applies to the fmt layer only.
+    let profile = config
+        .profile
+        .clone()
+        .map(|path| (path, ProfileLayer::new()));
     tracing_subscriber::registry()
-        .with(EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")))
-        .with(tracing_subscriber::fmt::layer())
+        .with(tracing_subscriber::fmt::layer().with_filter(
+            EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")),
+        ))
+        .with(profile.as_ref().map(|(_, layer)| {
+            layer
+                .clone()
+                .with_filter(Targets::new().with_target("structured_agent", Level::DEBUG))
+        }))
         .init();
 
-    let args = Args::parse();
-    let config = Config::from_args(args);
+    let result = App::run(config).await;
+
+    if let Some((path, layer)) = &profile
+        && let Err(e) = layer.write_folded(path)
+    {
+        eprintln!("Failed to write profile to {}: {}", path.display(), e);
+    }
 
-    if let Err(e) = App::run(config).await {
+    if let Err(e) = result {
         eprintln!("Error: {}", e);
         process::exit(1);
     }
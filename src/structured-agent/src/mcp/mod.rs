@@ -1,19 +1,27 @@
 use crate::expressions::ExternalFunctionExpr;
+use crate::runtime::ExpressionValue;
 use crate::runtime::RuntimeError;
 use crate::types::{
-    ExecutableFunction, ExternalFunctionDefinition, FunctionProvider, Parameter, Type,
+    ExecutableFunction, ExternalConstDefinition, ExternalFunctionDefinition, FunctionAnnotations,
+    FunctionProvider, Parameter, Type,
 };
 use async_trait::async_trait;
-use rmcp::model::{CallToolRequestParams, Tool};
+use rmcp::model::{
+    CallToolRequestParams, ReadResourceRequestParams, ResourceContents, Tool, ToolAnnotations,
+};
 use rmcp::{RoleClient, ServiceError, ServiceExt};
 use serde_json::Value;
 use std::error::Error;
 use std::fmt;
+use std::future::Future;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::RwLock;
 
 type RmcpClient = rmcp::service::RunningService<RoleClient, ()>;
 
+pub const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(30);
+
 #[derive(Debug)]
 pub enum McpError {
     ConnectionError(String),
@@ -47,10 +55,59 @@ impl From<std::io::Error> for McpError {
     }
 }
 
+/// How often a client tries to start its server before giving up. Each retry
+/// waits `backoff_factor` times longer than the one before it. A negative or
+/// NaN factor is treated as zero, and delays too long to represent saturate.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    pub initial_delay: Duration,
+    pub backoff_factor: f64,
+}
+
+/// The live session shared by every clone of a client. Dropping the last
+/// clone shuts the server down.
+struct Connection {
+    service: RwLock<Option<RmcpClient>>,
+    process_id: std::sync::Mutex<Option<u32>>,
+}
+
+impl Connection {
+    fn new() -> Self {
+        Self {
+            service: RwLock::new(None),
+            process_id: std::sync::Mutex::new(None),
+        }
+    }
+}
+
+impl Drop for Connection {
+    fn drop(&mut self) {
+        let Some(service) = self.service.get_mut().take() else {
+            return;
+        };
+        // Drop can't await the cancel, so hand it to the runtime when there is
+        // one; otherwise kill_on_drop takes the child down with the transport.
+        if let Ok(handle) = tokio::runtime::Handle::try_current() {
+            handle.spawn(async move {
+                let _ = service.cancel().await;
+            });
+        }
+    }
+}
+
 pub struct McpClient {
-    client: Arc<RwLock<Option<RmcpClient>>>,
+    client: Arc<Connection>,
     command: String,
     args: Vec<String>,
+    connect_timeout: Duration,
+    retry_policy: Option<RetryPolicy>,
+}
+
+fn next_retry_delay(delay: Duration, backoff_factor: f64) -> Duration {
+    // f64::max drops a NaN in favour of the other operand.
+    Duration::try_from_secs_f64(delay.as_secs_f64() * backoff_factor.max(0.0))
+        .unwrap_or(Duration::MAX)
 }
 
 impl McpClient {
@@ -59,14 +116,30 @@ impl McpClient {
         args: Vec<String>,
     ) -> std::result::Result<Self, McpError> {
         Ok(Self {
-            client: Arc::new(RwLock::new(None)),
+            client: Arc::new(Connection::new()),
             command: command.to_string(),
             args,
+            connect_timeout: DEFAULT_CONNECT_TIMEOUT,
+            retry_policy: None,
         })
     }
 
-    async fn ensure_connected(&self) -> std::result::Result<(), McpError> {
-        let client_lock = self.client.read().await;
+    pub fn with_connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = timeout;
+        self
+    }
+
+    /// Retries a server that fails to start or to complete the handshake.
+    /// Without a policy the client makes a single attempt.
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = Some(policy);
+        self
+    }
+
+    /// Starts the server and completes the MCP handshake if that has not
+    /// happened yet. Other calls do this on first use.
+    pub async fn ensure_connected(&self) -> std::result::Result<(), McpError> {
+        let client_lock = self.client.service.read().await;
         if client_lock.is_none() {
             drop(client_lock);
             self.connect().await?;
@@ -75,6 +148,32 @@ impl McpClient {
     }
 
     async fn connect(&self) -> std::result::Result<(), McpError> {
+        let Some(policy) = self.retry_policy else {
+            return self.connect_once().await;
+        };
+
+        let max_attempts = policy.max_attempts.max(1);
+        let mut retry_delay = policy.initial_delay;
+        let mut attempt = 1;
+        loop {
+            match self.connect_once().await {
+                Ok(()) => return Ok(()),
+                Err(e) if attempt >= max_attempts => {
+                    return Err(McpError::ConnectionError(format!(
+                        "gave up after {} attempts: {}",
+                        attempt, e
+                    )));
+                }
+                Err(_) => {
+                    tokio::time::sleep(retry_delay).await;
+                    retry_delay = next_retry_delay(retry_delay, policy.backoff_factor);
+                    attempt += 1;
+                }
+            }
+        }
+    }
+
+    async fn connect_once(&self) -> std::result::Result<(), McpError> {
         use rmcp::transport::{ConfigureCommandExt, TokioChildProcess};
         use tokio::process::Command;
 
@@ -82,15 +181,19 @@ impl McpClient {
             for arg in &self.args {
                 cmd.arg(arg);
             }
+            // The child is owned by the handshake future, so a timed out
+            // connect drops it and takes the process down with it.
+            cmd.kill_on_drop(true);
         }))?;
+        let process_id = transport.id();
 
-        let service = ()
-            .serve(transport)
-            .await
+        let service = connect_with_timeout(self.connect_timeout, ().serve(transport))
+            .await?
             .map_err(|e| McpError::ConnectionError(format!("Failed to start client: {}", e)))?;
 
-        let mut client_lock = self.client.write().await;
+        let mut client_lock = self.client.service.write().await;
         *client_lock = Some(service);
+        *self.client.process_id.lock().unwrap() = process_id;
 
         Ok(())
     }
@@ -98,7 +201,7 @@ impl McpClient {
     pub async fn list_tools(&self) -> std::result::Result<Vec<Tool>, McpError> {
         self.ensure_connected().await?;
 
-        let client_lock = self.client.read().await;
+        let client_lock = self.client.service.read().await;
         let client = client_lock
             .as_ref()
             .ok_or_else(|| McpError::ConnectionError("No client available".to_string()))?;
@@ -118,7 +221,7 @@ impl McpClient {
     ) -> std::result::Result<rmcp::model::CallToolResult, McpError> {
         self.ensure_connected().await?;
 
-        let client_lock = self.client.read().await;
+        let client_lock = self.client.service.read().await;
         let client = client_lock
             .as_ref()
             .ok_or_else(|| McpError::ConnectionError("No client available".to_string()))?;
@@ -144,8 +247,62 @@ impl McpClient {
         Ok(response)
     }
 
+    /// Text of the resource called `name`, or None when the server has no
+    /// resource by that name or does not offer resources at all.
+    pub async fn read_resource(&self, name: &str) -> std::result::Result<Option<String>, McpError> {
+        self.ensure_connected().await?;
+
+        let client_lock = self.client.service.read().await;
+        let client = client_lock
+            .as_ref()
+            .ok_or_else(|| McpError::ConnectionError("No client available".to_string()))?;
+
+        let offers_resources = client
+            .peer_info()
+            .is_some_and(|info| info.capabilities.resources.is_some());
+        if !offers_resources {
+            return Ok(None);
+        }
+
+        let resources = client
+            .list_all_resources()
+            .await
+            .map_err(|e| McpError::ProtocolError(format!("Failed to list resources: {}", e)))?;
+        let Some(resource) = resources.into_iter().find(|resource| resource.name == name) else {
+            return Ok(None);
+        };
+
+        let response = client
+            .read_resource(ReadResourceRequestParams {
+                meta: None,
+                uri: resource.uri.clone(),
+            })
+            .await
+            .map_err(|e| McpError::ProtocolError(format!("Failed to read resource: {}", e)))?;
+
+        let mut text = String::new();
+        for contents in response.contents {
+            match contents {
+                ResourceContents::TextResourceContents { text: part, .. } => text.push_str(&part),
+                ResourceContents::BlobResourceContents { uri, .. } => {
+                    return Err(McpError::ProtocolError(format!(
+                        "Resource '{}' is binary and cannot be read as text",
+                        uri
+                    )));
+                }
+            }
+        }
+        Ok(Some(text))
+    }
+
+    /// Process id of the running server, once connected.
+    pub fn process_id(&self) -> Option<u32> {
+        *self.client.process_id.lock().unwrap()
+    }
+
     pub async fn shutdown(&self) -> std::result::Result<(), McpError> {
-        let mut client_lock = self.client.write().await;
+        let mut client_lock = self.client.service.write().await;
+        *self.client.process_id.lock().unwrap() = None;
         if let Some(client) = client_lock.take() {
             client
                 .cancel()
@@ -158,6 +315,14 @@ impl McpClient {
 
 #[async_trait]
 impl FunctionProvider for McpClient {
+    fn name(&self) -> String {
+        if self.args.is_empty() {
+            format!("mcp: {}", self.command)
+        } else {
+            format!("mcp: {} {}", self.command, self.args.join(" "))
+        }
+    }
+
     async fn list_functions(&self) -> Result<Vec<ExternalFunctionDefinition>, RuntimeError> {
         let tools = self.list_tools().await.map_err(|e| {
             RuntimeError::ExecutionError(format!("Failed to list MCP tools: {}", e))
@@ -180,9 +345,11 @@ impl FunctionProvider for McpClient {
                 ExternalFunctionDefinition::new_with_docs(
                     tool.name.to_string(),
                     parameters,
-                    Type::string(),
+                    return_type_from_output_schema(tool.output_schema.as_deref()),
                     tool.description.map(|d| d.to_string()),
                 )
+                .with_annotations(annotations_from_tool(tool.annotations.as_ref()))
+                .with_input_schema(tool.input_schema)
             })
             .collect();
 
@@ -199,9 +366,86 @@ impl FunctionProvider for McpClient {
             definition.return_type.clone(),
             Arc::new(self.clone()),
             definition.documentation.clone(),
-        );
+        )
+        .with_input_schema(definition.input_schema.clone());
         Ok(Arc::new(expr))
     }
+
+    /// Extern consts are read from the server's resource of the same name.
+    async fn resolve_constant(
+        &self,
+        definition: &ExternalConstDefinition,
+    ) -> Result<Option<ExpressionValue>, RuntimeError> {
+        let text = self.read_resource(&definition.name).await.map_err(|e| {
+            RuntimeError::ExecutionError(format!("Failed to read MCP resource: {}", e))
+        })?;
+        Ok(text.map(|text| constant_from_resource(text, &definition.const_type)))
+    }
+}
+
+/// Resources are text, so a const declared with another type is parsed from
+/// it. Text that does not parse stays a String, and binding reports that it
+/// does not match the declared type.
+fn constant_from_resource(text: String, const_type: &Type) -> ExpressionValue {
+    match const_type {
+        Type::Boolean => match text.trim() {
+            "true" => ExpressionValue::Boolean(true),
+            "false" => ExpressionValue::Boolean(false),
+            _ => ExpressionValue::String(text),
+        },
+        Type::Number => text
+            .trim()
+            .parse()
+            .map_or(ExpressionValue::String(text), ExpressionValue::Number),
+        Type::Json => serde_json::from_str(&text).map_or_else(
+            |_| ExpressionValue::Json(Value::String(text.clone())),
+            ExpressionValue::Json,
+        ),
+        _ => ExpressionValue::String(text),
+    }
+}
+
+/// Tools whose output schema wraps a single boolean `result` property (as
+/// FastMCP does for `-> bool` tools) return Boolean, and those wrapping a
+/// `number` or `integer` one return Number. Everything else is treated as
+/// returning String, which Json declarations can also bind to.
+fn return_type_from_output_schema(schema: Option<&serde_json::Map<String, Value>>) -> Type {
+    let result_type = schema
+        .and_then(|schema| schema.get("properties"))
+        .and_then(|properties| properties.as_object())
+        .filter(|properties| properties.len() == 1)
+        .and_then(|properties| properties.get("result"))
+        .and_then(|result| result.get("type"))
+        .and_then(|result_type| result_type.as_str());
+
+    match result_type {
+        Some("boolean") => Type::boolean(),
+        Some("number" | "integer") => Type::number(),
+        _ => Type::string(),
+    }
+}
+
+/// Follows the MCP defaults: a tool that is not read-only is destructive
+/// unless it says otherwise. Tools with no annotations at all are left
+/// unmarked rather than all being treated as destructive.
+fn annotations_from_tool(annotations: Option<&ToolAnnotations>) -> FunctionAnnotations {
+    let Some(annotations) = annotations else {
+        return FunctionAnnotations::default();
+    };
+    let read_only = annotations.read_only_hint.unwrap_or(false);
+    FunctionAnnotations {
+        read_only,
+        destructive: !read_only && annotations.destructive_hint.unwrap_or(true),
+    }
+}
+
+async fn connect_with_timeout<F, T>(timeout: Duration, connect: F) -> Result<T, McpError>
+where
+    F: Future<Output = T>,
+{
+    tokio::time::timeout(timeout, connect)
+        .await
+        .map_err(|_| McpError::ConnectionError("connect timed out".to_string()))
 }
 
 impl Clone for McpClient {
@@ -210,6 +454,8 @@ impl Clone for McpClient {
             client: self.client.clone(),
             command: self.command.clone(),
             args: self.args.clone(),
+            connect_timeout: self.connect_timeout,
+            retry_policy: self.retry_policy,
         }
     }
 }
@@ -250,6 +496,191 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[tokio::test]
+    async fn test_connect_timeout_expires_when_handshake_never_resolves() {
+        let result = connect_with_timeout(
+            Duration::from_millis(10),
+            std::future::pending::<Result<(), McpError>>(),
+        )
+        .await;
+
+        match result {
+            Err(McpError::ConnectionError(msg)) => assert_eq!(msg, "connect timed out"),
+            other => panic!("Expected connect timeout, got: {:?}", other.map(|_| ())),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_connect_timeout_passes_through_completed_handshake() {
+        let result = connect_with_timeout(Duration::from_secs(1), async { 42 }).await;
+        assert_eq!(result.unwrap(), 42);
+    }
+
+    #[tokio::test]
+    async fn test_with_connect_timeout_overrides_default() {
+        let client = McpClient::new_stdio("echo", vec![]).await.unwrap();
+        assert_eq!(client.connect_timeout, DEFAULT_CONNECT_TIMEOUT);
+
+        let client = client.with_connect_timeout(Duration::from_millis(250));
+        assert_eq!(client.connect_timeout, Duration::from_millis(250));
+    }
+
+    #[tokio::test]
+    async fn test_retry_policy_reports_attempts_once_exhausted() {
+        let client = McpClient::new_stdio("structured-agent-missing-mcp-server", vec![])
+            .await
+            .unwrap()
+            .with_retry_policy(RetryPolicy {
+                max_attempts: 3,
+                initial_delay: Duration::from_millis(1),
+                backoff_factor: 2.0,
+            });
+
+        match client.ensure_connected().await {
+            Err(McpError::ConnectionError(msg)) => {
+                assert!(msg.starts_with("gave up after 3 attempts: "), "{}", msg)
+            }
+            other => panic!("Expected connection error, got: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_next_retry_delay_grows_by_backoff_factor() {
+        assert_eq!(
+            next_retry_delay(Duration::from_millis(100), 2.0),
+            Duration::from_millis(200)
+        );
+    }
+
+    #[test]
+    fn test_next_retry_delay_tolerates_bad_backoff_factors() {
+        let delay = Duration::from_millis(100);
+        assert_eq!(next_retry_delay(delay, -1.0), Duration::ZERO);
+        assert_eq!(next_retry_delay(delay, f64::NAN), Duration::ZERO);
+        assert_eq!(next_retry_delay(delay, f64::INFINITY), Duration::MAX);
+        assert_eq!(next_retry_delay(Duration::MAX, 2.0), Duration::MAX);
+    }
+
+    fn schema(value: Value) -> serde_json::Map<String, Value> {
+        value.as_object().unwrap().clone()
+    }
+
+    #[test]
+    fn test_wrapped_boolean_output_schema_returns_boolean() {
+        let output_schema = schema(json!({
+            "type": "object",
+            "properties": {"result": {"type": "boolean"}},
+            "required": ["result"],
+        }));
+
+        assert_eq!(
+            return_type_from_output_schema(Some(&output_schema)),
+            Type::boolean()
+        );
+    }
+
+    #[test]
+    fn test_wrapped_numeric_output_schemas_return_number() {
+        for result_type in ["number", "integer"] {
+            let output_schema = schema(json!({
+                "type": "object",
+                "properties": {"result": {"type": result_type}},
+                "required": ["result"],
+            }));
+
+            assert_eq!(
+                return_type_from_output_schema(Some(&output_schema)),
+                Type::number(),
+                "{}",
+                result_type
+            );
+        }
+    }
+
+    #[test]
+    fn test_other_output_schemas_return_string() {
+        let wrapped_string = schema(json!({
+            "type": "object",
+            "properties": {"result": {"type": "string"}},
+        }));
+        let object = schema(json!({
+            "type": "object",
+            "properties": {"ok": {"type": "boolean"}, "result": {"type": "boolean"}},
+        }));
+
+        assert_eq!(return_type_from_output_schema(None), Type::string());
+        assert_eq!(
+            return_type_from_output_schema(Some(&wrapped_string)),
+            Type::string()
+        );
+        assert_eq!(
+            return_type_from_output_schema(Some(&object)),
+            Type::string()
+        );
+    }
+
+    fn tool_annotations(value: Value) -> ToolAnnotations {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn test_tool_annotations_mark_read_only_and_destructive_tools() {
+        let read_only = tool_annotations(json!({"readOnlyHint": true}));
+        let destructive = tool_annotations(json!({"destructiveHint": true}));
+        let additive = tool_annotations(json!({"destructiveHint": false}));
+
+        assert_eq!(
+            annotations_from_tool(Some(&read_only)),
+            FunctionAnnotations {
+                read_only: true,
+                destructive: false,
+            }
+        );
+        assert_eq!(
+            annotations_from_tool(Some(&destructive)),
+            FunctionAnnotations {
+                read_only: false,
+                destructive: true,
+            }
+        );
+        assert_eq!(
+            annotations_from_tool(Some(&additive)),
+            FunctionAnnotations::default()
+        );
+    }
+
+    #[test]
+    fn test_tool_annotation_defaults() {
+        assert_eq!(annotations_from_tool(None), FunctionAnnotations::default());
+        assert_eq!(
+            annotations_from_tool(Some(&tool_annotations(json!({"title": "Write"})))),
+            FunctionAnnotations {
+                read_only: false,
+                destructive: true,
+            }
+        );
+    }
+
+    #[test]
+    fn test_constants_are_parsed_from_resource_text_by_declared_type() {
+        assert_eq!(
+            constant_from_resource("true\n".to_string(), &Type::boolean()),
+            ExpressionValue::Boolean(true)
+        );
+        assert_eq!(
+            constant_from_resource("42".to_string(), &Type::number()),
+            ExpressionValue::Number(42.0)
+        );
+        assert_eq!(
+            constant_from_resource(r#"{"tables": []}"#.to_string(), &Type::json()),
+            ExpressionValue::Json(json!({"tables": []}))
+        );
+        assert_eq!(
+            constant_from_resource("maybe".to_string(), &Type::boolean()),
+            ExpressionValue::String("maybe".to_string())
+        );
+    }
+
     #[tokio::test]
     async fn test_call_tool_with_invalid_server() {
         let client = McpClient::new_stdio("echo", vec![]).await.unwrap();
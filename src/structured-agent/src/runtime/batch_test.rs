@@ -0,0 +1,33 @@
+use super::*;
+use crate::compiler::CompilationUnit;
+use crate::types::PrintEngine;
+use std::sync::Arc;
+
+// With no body, `main` is filled by the engine, and the print engine answers
+// with the last event: the input the run was seeded with.
+const PROGRAM: &str = r#"
+fn main(): String {
+}
+"#;
+
+#[tokio::test]
+async fn test_run_batch_runs_each_input_in_its_own_context() {
+    let runtime = Runtime::builder(CompilationUnit::from_string(PROGRAM.to_string()))
+        .with_language_engine(Arc::new(PrintEngine {}))
+        .build();
+    let inputs = ["first", "second", "third"]
+        .into_iter()
+        .map(|input| InitialContext::new().with_event(input.to_string()))
+        .collect();
+
+    let results = runtime.run_batch(inputs, 2).await;
+
+    assert_eq!(
+        results,
+        vec![
+            Ok(ExpressionValue::String("first".to_string())),
+            Ok(ExpressionValue::String("second".to_string())),
+            Ok(ExpressionValue::String("third".to_string())),
+        ]
+    );
+}
@@ -0,0 +1,57 @@
+use super::*;
+use crate::compiler::CompilationUnit;
+
+const PROGRAM: &str = r#"
+fn fourth(): String {
+    return "done"
+}
+
+fn third(): String {
+    return fourth()
+}
+
+fn second(): String {
+    return third()
+}
+
+fn first(): String {
+    return second()
+}
+
+fn main(): String {
+    return first()
+}
+"#;
+
+fn runtime(max_depth: usize) -> Runtime {
+    Runtime::builder(CompilationUnit::from_string(PROGRAM.to_string()))
+        .with_max_call_depth(max_depth)
+        .build()
+}
+
+#[tokio::test]
+async fn test_call_chain_within_limit_runs() {
+    let result = runtime(4).run().await;
+
+    assert_eq!(result, Ok(ExpressionValue::String("done".to_string())));
+}
+
+#[tokio::test]
+async fn test_call_chain_beyond_limit_names_offending_function() {
+    let result = runtime(3).run().await;
+
+    let Err(RuntimeError::ExecutionError(message)) = result else {
+        panic!("Expected an execution error, got: {:?}", result);
+    };
+    assert!(
+        message.contains("maximum call depth exceeded"),
+        "{}",
+        message
+    );
+    assert!(message.contains("calling 'fourth'"), "{}", message);
+    assert!(
+        message.contains("first -> second -> third -> fourth"),
+        "{}",
+        message
+    );
+}
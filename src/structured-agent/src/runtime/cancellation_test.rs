@@ -0,0 +1,87 @@
+use super::*;
+use crate::compiler::CompilationUnit;
+use crate::runtime::ExpressionValue;
+use crate::types::{NativeFunction, Parameter, Type};
+use async_trait::async_trait;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio_util::sync::CancellationToken;
+
+const PROGRAM: &str = r#"
+extern fn wait(): ()
+
+fn main(): () {
+    wait()
+}
+"#;
+
+#[derive(Debug)]
+struct WaitForeverFunction {
+    parameters: Vec<Parameter>,
+    return_type: Type,
+}
+
+impl WaitForeverFunction {
+    fn new() -> Self {
+        Self {
+            parameters: vec![],
+            return_type: Type::unit(),
+        }
+    }
+}
+
+#[async_trait]
+impl NativeFunction for WaitForeverFunction {
+    fn name(&self) -> &str {
+        "wait"
+    }
+
+    fn parameters(&self) -> &[Parameter] {
+        &self.parameters
+    }
+
+    fn return_type(&self) -> &Type {
+        &self.return_type
+    }
+
+    async fn execute(&self, _args: Vec<ExpressionValue>) -> Result<ExpressionValue, String> {
+        std::future::pending().await
+    }
+}
+
+fn runtime() -> Runtime {
+    Runtime::builder(CompilationUnit::from_string(PROGRAM.to_string()))
+        .with_native_function(Arc::new(WaitForeverFunction::new()))
+        .build()
+}
+
+#[tokio::test]
+async fn test_deadline_in_the_past_fails_immediately() {
+    let result = runtime().run_with_deadline(Instant::now()).await;
+
+    assert_eq!(
+        result,
+        Err(RuntimeError::ExecutionError(
+            "Execution deadline exceeded".to_string()
+        ))
+    );
+}
+
+#[tokio::test]
+async fn test_cancelling_mid_run_stops_execution() {
+    let token = CancellationToken::new();
+    let canceller = token.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        canceller.cancel();
+    });
+
+    let result = runtime().run_with_cancellation(token).await;
+
+    assert_eq!(
+        result,
+        Err(RuntimeError::ExecutionError(
+            "Execution cancelled".to_string()
+        ))
+    );
+}
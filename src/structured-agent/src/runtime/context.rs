@@ -1,15 +1,210 @@
 use crate::runtime::Runtime;
-use crate::runtime::types::{ExpressionParameter, ExpressionResult, ExpressionValue};
-use std::collections::HashMap;
+use crate::runtime::types::{EventRole, ExpressionParameter, ExpressionResult, ExpressionValue};
+use serde::{Serialize, Serializer};
+use std::collections::{BTreeMap, HashMap};
+use std::io::Write;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 #[derive(Debug, Clone)]
 pub struct Event {
     pub content: ExpressionValue,
     pub name: Option<String>,
     pub params: Option<Vec<ExpressionParameter>>,
+    pub seq: u64,
+    pub timestamp: SystemTime,
+    pub role: EventRole,
 }
 
+impl Event {
+    /// One audit log record. Content of any type is written as JSON, with
+    /// `type` naming the original value type.
+    pub fn to_json(&self) -> serde_json::Value {
+        let timestamp_ms = self
+            .timestamp
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_millis() as u64);
+        let params = self.params.as_ref().map(|params| {
+            params
+                .iter()
+                .map(|param| {
+                    serde_json::json!({
+                        "name": param.name,
+                        "type": param.value.type_name(),
+                        "value": param.value.to_json(),
+                    })
+                })
+                .collect::<Vec<_>>()
+        });
+
+        serde_json::json!({
+            "seq": self.seq,
+            "timestamp_ms": timestamp_ms,
+            "role": match self.role {
+                EventRole::User => "user",
+                EventRole::Model => "model",
+            },
+            "function": self.name,
+            "params": params,
+            "type": self.content.type_name(),
+            "content": self.content.to_json(),
+        })
+    }
+
+    /// Reads back a record written by [`Event::to_json`].
+    pub fn from_json(record: &serde_json::Value) -> Result<Self, String> {
+        let field = |name: &str| {
+            record
+                .get(name)
+                .ok_or_else(|| format!("Event record has no '{}' field", name))
+        };
+
+        let type_name = field("type")?
+            .as_str()
+            .ok_or("Event record 'type' is not a string")?;
+        let params = match field("params")? {
+            serde_json::Value::Null => None,
+            params => Some(
+                params
+                    .as_array()
+                    .ok_or("Event record 'params' is not a list")?
+                    .iter()
+                    .map(|param| {
+                        let name = param["name"]
+                            .as_str()
+                            .ok_or("Event parameter has no name")?;
+                        let type_name = param["type"]
+                            .as_str()
+                            .ok_or("Event parameter has no type")?;
+                        let value = ExpressionValue::from_json(type_name, &param["value"])?;
+                        Ok(ExpressionParameter::new(name.to_string(), value))
+                    })
+                    .collect::<Result<Vec<_>, String>>()?,
+            ),
+        };
+
+        Ok(Event {
+            content: ExpressionValue::from_json(type_name, field("content")?)?,
+            name: field("function")?.as_str().map(str::to_string),
+            params,
+            seq: field("seq")?
+                .as_u64()
+                .ok_or("Event record 'seq' is not a number")?,
+            timestamp: UNIX_EPOCH
+                + Duration::from_millis(field("timestamp_ms")?.as_u64().unwrap_or_default()),
+            role: match field("role")?.as_str() {
+                Some("model") => EventRole::Model,
+                _ => EventRole::User,
+            },
+        })
+    }
+}
+
+impl Serialize for Event {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.to_json().serialize(serializer)
+    }
+}
+
+/// The events and root variables of a [`Context`], detached from its
+/// runtime so they can be written to disk and resumed later with
+/// [`Runtime::run_from`]. A saved context belongs to the program it was
+/// taken from, identified by the [`CompilationUnit::fingerprint`] of its
+/// source.
+///
+/// [`CompilationUnit`]: crate::compiler::CompilationUnit
+#[derive(Debug, Clone)]
+pub struct SavedContext {
+    pub program: String,
+    pub events: Vec<Event>,
+    pub variables: BTreeMap<String, ExpressionValue>,
+}
+
+impl SavedContext {
+    pub fn to_json(&self) -> serde_json::Value {
+        let variables: serde_json::Map<String, serde_json::Value> = self
+            .variables
+            .iter()
+            .map(|(name, value)| {
+                (
+                    name.clone(),
+                    serde_json::json!({ "type": value.type_name(), "content": value.to_json() }),
+                )
+            })
+            .collect();
+
+        serde_json::json!({
+            "program": self.program,
+            "events": self.events.iter().map(Event::to_json).collect::<Vec<_>>(),
+            "variables": variables,
+        })
+    }
+
+    pub fn from_json(saved: &serde_json::Value) -> Result<Self, String> {
+        let program = saved["program"]
+            .as_str()
+            .ok_or("Saved context has no program")?
+            .to_string();
+        let events = saved["events"]
+            .as_array()
+            .ok_or("Saved context has no events")?
+            .iter()
+            .map(Event::from_json)
+            .collect::<Result<Vec<_>, String>>()?;
+        let variables = match &saved["variables"] {
+            serde_json::Value::Null => BTreeMap::new(),
+            variables => variables
+                .as_object()
+                .ok_or("Saved context 'variables' is not an object")?
+                .iter()
+                .map(|(name, variable)| {
+                    let type_name = variable["type"]
+                        .as_str()
+                        .ok_or_else(|| format!("Saved variable '{}' has no type", name))?;
+                    let value = ExpressionValue::from_json(type_name, &variable["content"])?;
+                    Ok((name.clone(), value))
+                })
+                .collect::<Result<BTreeMap<_, _>, String>>()?,
+        };
+
+        Ok(Self {
+            program,
+            events,
+            variables,
+        })
+    }
+}
+
+/// What a run of [`Runtime::run_batch`] starts from: events the engine sees
+/// before `main` runs, and variables placed in the root scope.
+#[derive(Debug, Clone, Default)]
+pub struct InitialContext {
+    pub events: Vec<ExpressionValue>,
+    pub variables: BTreeMap<String, ExpressionValue>,
+}
+
+impl InitialContext {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_event(mut self, content: impl Into<ExpressionValue>) -> Self {
+        self.events.push(content.into());
+        self
+    }
+
+    pub fn with_variable(
+        mut self,
+        name: impl Into<String>,
+        value: impl Into<ExpressionValue>,
+    ) -> Self {
+        self.variables.insert(name.into(), value.into());
+        self
+    }
+}
+
+#[derive(Clone)]
 pub struct Context {
     parent: Option<Box<Context>>,
     events: Vec<Event>,
@@ -17,6 +212,10 @@ pub struct Context {
     is_scope_boundary: bool,
     return_value: Option<ExpressionResult>,
     runtime: Arc<Runtime>,
+    event_sequence: Arc<AtomicU64>,
+    // The functions called to reach this context, outermost first. `main`
+    // is not included.
+    call_chain: Arc<[String]>,
 }
 
 impl Context {
@@ -28,6 +227,58 @@ impl Context {
             is_scope_boundary: true,
             return_value: None,
             runtime,
+            event_sequence: Arc::new(AtomicU64::new(0)),
+            call_chain: Arc::from([]),
+        }
+    }
+
+    /// A root context preloaded with `saved`. Event numbering carries on
+    /// from the last saved event.
+    pub fn restore(runtime: Arc<Runtime>, saved: SavedContext) -> Self {
+        let next_seq = saved.events.iter().map(|event| event.seq + 1).max();
+        let variables = saved
+            .variables
+            .into_iter()
+            .map(|(name, value)| (name, ExpressionResult::new(value)))
+            .collect();
+
+        Self {
+            events: saved.events,
+            variables,
+            event_sequence: Arc::new(AtomicU64::new(next_seq.unwrap_or_default())),
+            ..Self::with_runtime(runtime)
+        }
+    }
+
+    /// A root context holding the events and variables of `initial`, added
+    /// as user events in order.
+    pub fn seeded(runtime: Arc<Runtime>, initial: InitialContext) -> Self {
+        let mut context = Self::with_runtime(runtime);
+        for content in initial.events {
+            context.add_event(content, None, None);
+        }
+        for (name, value) in initial.variables {
+            context.declare_variable(name, ExpressionResult::new(value));
+        }
+        context
+    }
+
+    /// Every event visible from this context and the variables of its root
+    /// scope, as the context of a finished run holds them.
+    pub fn save(&self) -> SavedContext {
+        let mut root = self;
+        while let Some(parent) = root.parent.as_deref() {
+            root = parent;
+        }
+
+        SavedContext {
+            program: self.runtime.program().fingerprint(),
+            events: self.iter_all_events().collect(),
+            variables: root
+                .variables
+                .iter()
+                .map(|(name, result)| (name.clone(), result.value.clone()))
+                .collect(),
         }
     }
 
@@ -37,13 +288,44 @@ impl Context {
         name: Option<String>,
         params: Option<Vec<ExpressionParameter>>,
     ) {
+        self.add_event_with_role(content, name, params, EventRole::User);
+    }
+
+    pub fn add_event_with_role(
+        &mut self,
+        content: ExpressionValue,
+        name: Option<String>,
+        params: Option<Vec<ExpressionParameter>>,
+        role: EventRole,
+    ) {
+        let seq = self.event_sequence.fetch_add(1, Ordering::SeqCst);
         self.events.push(Event {
             content,
             name,
             params,
+            seq,
+            timestamp: self.runtime.clock().now(),
+            role,
         });
     }
 
+    /// Starts an empty model event that a streamed response is appended to
+    /// chunk by chunk. The event is part of the context from the start.
+    pub fn begin_event(&mut self) -> EventHandle<'_> {
+        self.add_event_with_role(
+            ExpressionValue::String(String::new()),
+            None,
+            None,
+            EventRole::Model,
+        );
+        let index = self.events.len() - 1;
+        EventHandle {
+            context: self,
+            index,
+            observers: Vec::new(),
+        }
+    }
+
     pub fn iter_all_events(&self) -> impl Iterator<Item = Event> + '_ {
         let mut all_events = Vec::new();
         let mut current_context = Some(self);
@@ -61,6 +343,37 @@ impl Context {
         all_events.into_iter()
     }
 
+    /// Every event visible from this context as a JSON array, oldest first,
+    /// one [`Event::to_json`] object each.
+    pub fn events_as_json(&self) -> serde_json::Value {
+        self.iter_all_events()
+            .map(|event| event.to_json())
+            .collect()
+    }
+
+    /// Writes every event visible from this context as JSON Lines, oldest
+    /// first, one [`Event::to_json`] object per line.
+    pub fn export_jsonl(&self, writer: &mut impl Write) -> std::io::Result<()> {
+        for event in self.iter_all_events() {
+            serde_json::to_writer(&mut *writer, &event.to_json())?;
+            writer.write_all(b"\n")?;
+        }
+        writer.flush()
+    }
+
+    pub fn render_event(&self, event: &Event) -> String {
+        self.runtime.injection_template().render(event)
+    }
+
+    /// The events visible from this context, rendered with the runtime's
+    /// injection template and joined by newlines, as the engine sees them.
+    pub fn render_prompt(&self) -> String {
+        self.iter_all_events()
+            .map(|event| self.render_event(&event))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
     pub fn events_count(&self) -> usize {
         self.events.len()
     }
@@ -123,18 +436,29 @@ impl Context {
         self.variables.remove(name);
     }
 
-    pub fn create_child(self, is_scope_boundary: bool) -> Self {
-        let runtime = self.runtime.clone();
-        Self {
-            parent: Some(Box::new(self)),
-            events: Vec::new(),
-            variables: HashMap::new(),
-            is_scope_boundary,
-            return_value: None,
-            runtime,
+    pub fn child(self) -> ContextBuilder {
+        ContextBuilder {
+            parent: self,
+            is_scope_boundary: false,
+            called_function: None,
         }
     }
 
+    /// How many function calls deep this context is, counting from `main`.
+    pub fn call_depth(&self) -> usize {
+        self.call_chain.len()
+    }
+
+    /// The functions called to reach this context, outermost first.
+    pub fn call_chain(&self) -> &[String] {
+        &self.call_chain
+    }
+
+    #[deprecated(note = "use `Context::child` with `function_scope` or `block_scope`")]
+    pub fn create_child(self, is_scope_boundary: bool) -> Self {
+        self.child().scope_boundary(is_scope_boundary).build()
+    }
+
     pub fn restore_parent(self) -> Result<Self, String> {
         self.parent
             .map(|p| *p)
@@ -149,6 +473,11 @@ impl Context {
         self.runtime.clone()
     }
 
+    #[cfg(test)]
+    pub fn next_event_seq(&self) -> u64 {
+        self.event_sequence.load(Ordering::SeqCst)
+    }
+
     pub fn set_return_value(&mut self, result: ExpressionResult) {
         if self.is_scope_boundary {
             self.return_value = Some(result);
@@ -178,6 +507,99 @@ impl Context {
     }
 }
 
+type EventObserver = Box<dyn Fn(&Event) + Send + Sync>;
+
+/// An event being built from a stream. Finishing consumes the handle, so the
+/// event cannot change afterwards.
+pub struct EventHandle<'a> {
+    context: &'a mut Context,
+    index: usize,
+    observers: Vec<EventObserver>,
+}
+
+impl EventHandle<'_> {
+    /// Registers a callback that sees the partial event after every chunk.
+    pub fn with_observer(mut self, observer: impl Fn(&Event) + Send + Sync + 'static) -> Self {
+        self.observers.push(Box::new(observer));
+        self
+    }
+
+    pub fn push_chunk(&mut self, chunk: &str) {
+        let event = &mut self.context.events[self.index];
+        if let ExpressionValue::String(content) = &mut event.content {
+            content.push_str(chunk);
+        }
+        for observer in &self.observers {
+            observer(event);
+        }
+    }
+
+    pub fn event(&self) -> &Event {
+        &self.context.events[self.index]
+    }
+
+    pub fn finish(self) -> Event {
+        self.context.events[self.index].clone()
+    }
+}
+
+pub struct ContextBuilder {
+    parent: Context,
+    is_scope_boundary: bool,
+    called_function: Option<String>,
+}
+
+impl ContextBuilder {
+    /// A function scope hides the parent's variables and holds its own
+    /// return value.
+    pub fn function_scope(self) -> Self {
+        self.scope_boundary(true)
+    }
+
+    /// A block scope sees and assigns the parent's variables and forwards
+    /// return values to the enclosing function scope.
+    pub fn block_scope(self) -> Self {
+        self.scope_boundary(false)
+    }
+
+    pub fn scope_boundary(mut self, is_scope_boundary: bool) -> Self {
+        self.is_scope_boundary = is_scope_boundary;
+        self
+    }
+
+    /// Records the child as the body of a call to `function`, one level
+    /// deeper in the call chain than its parent.
+    pub fn calling(mut self, function: &str) -> Self {
+        self.called_function = Some(function.to_string());
+        self
+    }
+
+    pub fn build(self) -> Context {
+        let runtime = self.parent.runtime.clone();
+        let event_sequence = self.parent.event_sequence.clone();
+        let call_chain = match self.called_function {
+            Some(function) => self
+                .parent
+                .call_chain
+                .iter()
+                .cloned()
+                .chain(std::iter::once(function))
+                .collect(),
+            None => self.parent.call_chain.clone(),
+        };
+        Context {
+            parent: Some(Box::new(self.parent)),
+            events: Vec::new(),
+            variables: HashMap::new(),
+            is_scope_boundary: self.is_scope_boundary,
+            return_value: None,
+            runtime,
+            event_sequence,
+            call_chain,
+        }
+    }
+}
+
 impl std::fmt::Debug for Context {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("Context")
@@ -479,3 +479,66 @@ fn main(): () {
     assert!(error_message.contains("Type error"));
     assert_eq!(logger.messages_vec(), Vec::<String>::new());
 }
+
+#[tokio::test]
+async fn test_passing_assert_continues() {
+    let logger = Arc::new(LoggingFunction::new());
+
+    let program_source = r#"
+extern fn log(message: String): ()
+extern fn get_bool(): Boolean
+
+fn main(): () {
+    assert get_bool(), "tool reported failure"
+    log("after assert")
+}
+"#;
+
+    let runtime = Runtime::builder(program(program_source))
+        .with_native_function(logger.clone())
+        .with_native_function(Arc::new(BooleanFunction::new(true)))
+        .build();
+
+    let result = runtime.run().await.unwrap();
+
+    assert_eq!(logger.messages_vec(), vec!["after assert"]);
+    assert_eq!(result, ExpressionValue::Unit);
+}
+
+#[tokio::test]
+async fn test_failing_assert_stops_with_message() {
+    let logger = Arc::new(LoggingFunction::new());
+
+    let program_source = r#"
+extern fn log(message: String): ()
+extern fn get_bool(): Boolean
+
+fn main(): () {
+    log("before assert")
+    assert get_bool(), "tool reported failure"
+    log("after assert")
+}
+"#;
+
+    let runtime = Runtime::builder(program(program_source))
+        .with_native_function(logger.clone())
+        .with_native_function(Arc::new(BooleanFunction::new(false)))
+        .build();
+
+    let result = runtime.run().await;
+
+    match result {
+        Err(RuntimeError::ExecutionError(message)) => {
+            assert!(
+                message.contains("Assertion failed at"),
+                "unexpected error: {message}"
+            );
+            assert!(
+                message.contains("tool reported failure"),
+                "unexpected error: {message}"
+            );
+        }
+        other => panic!("Expected an assertion failure, got {:?}", other),
+    }
+    assert_eq!(logger.messages_vec(), vec!["before assert"]);
+}
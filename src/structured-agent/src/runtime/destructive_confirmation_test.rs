@@ -0,0 +1,116 @@
+use super::*;
+use crate::compiler::CompilationUnit;
+use crate::types::{
+    ExecutableFunction, ExternalFunctionDefinition, FunctionAnnotations, FunctionProvider,
+    Parameter, Type,
+};
+use async_trait::async_trait;
+use std::sync::{Arc, Mutex};
+
+const PROGRAM: &str = r#"
+extern fn delete_note(title: String): String
+
+fn main(): String {
+    return delete_note("draft")
+}
+"#;
+
+/// Offers `delete_note`, marked destructive as an MCP tool would be.
+struct NotesProvider {
+    inner: NativeFunctionProvider,
+}
+
+impl NotesProvider {
+    fn new() -> Self {
+        let mut inner = NativeFunctionProvider::new();
+        inner.add_function(Arc::new(ClosureFunction::new(
+            "delete_note",
+            vec![Parameter::new("title".to_string(), Type::string())],
+            Type::string(),
+            |_args| Ok(ExpressionValue::String("deleted".to_string())),
+        )));
+        Self { inner }
+    }
+}
+
+#[async_trait]
+impl FunctionProvider for NotesProvider {
+    async fn list_functions(&self) -> Result<Vec<ExternalFunctionDefinition>, RuntimeError> {
+        let definitions = self.inner.list_functions().await?;
+        Ok(definitions
+            .into_iter()
+            .map(|definition| {
+                definition.with_annotations(FunctionAnnotations {
+                    read_only: false,
+                    destructive: true,
+                })
+            })
+            .collect())
+    }
+
+    async fn create_expression(
+        &self,
+        definition: &ExternalFunctionDefinition,
+    ) -> Result<Arc<dyn ExecutableFunction>, RuntimeError> {
+        self.inner.create_expression(definition).await
+    }
+}
+
+fn builder() -> RuntimeBuilder {
+    Runtime::builder(CompilationUnit::from_string(PROGRAM.to_string()))
+        .with_provider(Arc::new(NotesProvider::new()))
+}
+
+#[tokio::test]
+async fn test_destructive_call_asks_the_confirmation_hook() {
+    let asked = Arc::new(Mutex::new(Vec::new()));
+    let recorded = asked.clone();
+    let runtime = builder()
+        .with_confirm_destructive(true)
+        .with_confirmation_hook(move |name, args| {
+            recorded
+                .lock()
+                .unwrap()
+                .push((name.to_string(), args.to_vec()));
+            true
+        })
+        .build();
+
+    let result = runtime.run().await;
+
+    assert_eq!(result, Ok(ExpressionValue::String("deleted".to_string())));
+    assert_eq!(
+        *asked.lock().unwrap(),
+        vec![(
+            "delete_note".to_string(),
+            vec![ExpressionValue::String("draft".to_string())]
+        )]
+    );
+}
+
+#[tokio::test]
+async fn test_refused_destructive_call_fails() {
+    let runtime = builder()
+        .with_confirm_destructive(true)
+        .with_confirmation_hook(|_, _| false)
+        .build();
+
+    let error = runtime.run().await.unwrap_err();
+
+    assert!(
+        error
+            .to_string()
+            .contains("Call to destructive tool 'delete_note' was not confirmed"),
+        "{}",
+        error
+    );
+}
+
+#[tokio::test]
+async fn test_destructive_calls_run_without_the_policy() {
+    let runtime = builder().with_confirmation_hook(|_, _| false).build();
+
+    let result = runtime.run().await;
+
+    assert_eq!(result, Ok(ExpressionValue::String("deleted".to_string())));
+}
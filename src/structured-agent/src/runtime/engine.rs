@@ -1,27 +1,72 @@
+use crate::analysis::AnalysisReport;
+use crate::ast::Definition;
 use crate::cli::config::{Config, EngineType, McpServerConfig, ProgramSource};
-use crate::compiler::{CompilationUnit, Compiler};
+use crate::compiler::{
+    CodespanParser, CompilationUnit, CompileOptions, CompiledProgram, Compiler,
+    compile_external_function,
+};
+use crate::diagnostics::DiagnosticManager;
+use crate::expressions::{ConfirmationHook, ConfirmedFunctionExpr};
 use crate::functions::{
-    HeadFunction, InputFunction, IsSomeFunction, IsSomeListFunction, PrintFunction,
-    SomeValueFunction, SomeValueListFunction, TailFunction, acp_shim,
+    HeadFunction, InputFunction, IsSomeFunction, IsSomeListFunction, JsonGetFunction,
+    ParseIntFunction, PrintFunction, SomeValueFunction, SomeValueListFunction, TailFunction,
+    ToStringFunction, acp_shim,
 };
-use crate::gemini::{GeminiConfig, GeminiEngine};
+use crate::gemini::{GeminiConfig, GeminiEngine, ModelName};
 use crate::mcp::McpClient;
-use crate::runtime::{Context, ExpressionValue, NativeFunctionProvider};
+use crate::runtime::{
+    ClosureFunction, Context, Event, ExpressionResult, ExpressionValue, InitialContext,
+    InjectionTemplate, NativeFunctionProvider, RunStats, SavedContext,
+};
 use crate::types::{
-    ExecutableFunction, ExternalFunctionDefinition, Function, FunctionProvider, LanguageEngine,
-    NativeFunction,
+    Clock, ExecutableFunction, ExternalConstDefinition, ExternalFunctionDefinition, FileId,
+    Function, FunctionProvider, LanguageEngine, NativeFunction, Parameter, SystemClock, Type,
 };
-use std::collections::HashMap;
+use codespan_reporting::diagnostic::{Diagnostic, Label};
+use std::collections::BTreeMap;
 use std::sync::Arc;
-use tracing::{debug, error};
+use std::time::Instant;
+use tokio::sync::Semaphore;
+use tokio::task::LocalSet;
+use tokio_util::sync::CancellationToken;
+use tracing::{Instrument, debug, debug_span, error};
+
+/// How deeply calls may nest unless [`RuntimeBuilder::with_max_call_depth`]
+/// says otherwise.
+pub const DEFAULT_MAX_CALL_DEPTH: usize = 256;
+
+type ProviderMatch = (ExternalFunctionDefinition, Arc<dyn FunctionProvider>);
+type BoundFunctions = Vec<(String, Arc<dyn ExecutableFunction>)>;
+
+enum RunStart {
+    Fresh,
+    Context(Context),
+    Saved(SavedContext),
+    Initial(InitialContext),
+}
 
 pub struct Runtime {
-    function_registry: HashMap<String, Arc<dyn ExecutableFunction>>,
-    external_function_registry: HashMap<String, ExternalFunctionDefinition>,
+    function_registry: BTreeMap<String, Arc<dyn ExecutableFunction>>,
+    external_function_registry: BTreeMap<String, ExternalFunctionDefinition>,
+    external_const_registry: BTreeMap<String, ExternalConstDefinition>,
+    constants: BTreeMap<String, ExpressionValue>,
     language_engine: Arc<dyn LanguageEngine>,
     compiler: Arc<Compiler>,
     providers: Vec<Arc<dyn FunctionProvider>>,
     compiled_program: CompilationUnit,
+    injection_template: InjectionTemplate,
+    clock: Arc<dyn Clock>,
+    cache_provider_bindings: bool,
+    relaxed_signature_matching: bool,
+    confirm_destructive: bool,
+    confirmation_hook: Option<ConfirmationHook>,
+    max_call_depth: usize,
+    // Shared by every clone, including the per-run copy made in `run_main`.
+    provider_bindings: Arc<tokio::sync::Mutex<Option<BoundFunctions>>>,
+    // Shared by every clone too, so a run's calls are counted in one place.
+    // Reset as each run starts, or each batch of runs.
+    stats: Arc<RunStats>,
+    resumed_context: Option<SavedContext>,
 }
 
 pub struct RuntimeBuilder {
@@ -30,6 +75,14 @@ pub struct RuntimeBuilder {
     language_engine: Option<Arc<dyn LanguageEngine>>,
     compiler: Option<Arc<Compiler>>,
     program_source: CompilationUnit,
+    injection_template: InjectionTemplate,
+    clock: Arc<dyn Clock>,
+    cache_provider_bindings: bool,
+    relaxed_signature_matching: bool,
+    confirm_destructive: bool,
+    confirmation_hook: Option<ConfirmationHook>,
+    max_call_depth: usize,
+    resumed_context: Option<SavedContext>,
 }
 
 #[derive(Debug, PartialEq)]
@@ -37,6 +90,10 @@ pub enum RuntimeError {
     FunctionNotFound(String),
     InvalidArguments(String),
     ExecutionError(String),
+    NoProvider { function: String },
+    SignatureMismatch { function: String, details: String },
+    ToolFailed { function: String, cause: String },
+    BindingFailed { function: String, cause: String },
 }
 
 impl std::fmt::Display for RuntimeError {
@@ -45,12 +102,54 @@ impl std::fmt::Display for RuntimeError {
             RuntimeError::FunctionNotFound(name) => write!(f, "Function not found: {}", name),
             RuntimeError::InvalidArguments(msg) => write!(f, "Invalid arguments: {}", msg),
             RuntimeError::ExecutionError(msg) => write!(f, "Execution error: {}", msg),
+            RuntimeError::NoProvider { function } => {
+                write!(f, "No provider found for extern function '{}'", function)
+            }
+            RuntimeError::SignatureMismatch { details, .. } => write!(f, "{}", details),
+            RuntimeError::ToolFailed { function, cause } => {
+                write!(f, "Tool call failed for function '{}': {}", function, cause)
+            }
+            RuntimeError::BindingFailed { function, cause } => {
+                write!(
+                    f,
+                    "Failed to bind extern function '{}': {}",
+                    function, cause
+                )
+            }
         }
     }
 }
 
 impl std::error::Error for RuntimeError {}
 
+#[derive(Debug, Clone, PartialEq)]
+pub struct FunctionBinding {
+    pub function: String,
+    pub signature: String,
+    pub outcome: BindingOutcome,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum BindingOutcome {
+    Bound { provider: String, signature: String },
+    Unbound { reason: String },
+}
+
+impl std::fmt::Display for FunctionBinding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{}", self.signature)?;
+        match &self.outcome {
+            BindingOutcome::Bound {
+                provider,
+                signature,
+            } => write!(f, "  bound to {}: {}", provider, signature),
+            BindingOutcome::Unbound { reason } => {
+                write!(f, "  unbound: {}", reason.replace('\n', "\n  "))
+            }
+        }
+    }
+}
+
 impl RuntimeBuilder {
     pub fn new(program: CompilationUnit) -> Self {
         Self {
@@ -59,6 +158,14 @@ impl RuntimeBuilder {
             language_engine: None,
             compiler: None,
             program_source: program,
+            injection_template: InjectionTemplate::default(),
+            clock: Arc::new(SystemClock),
+            cache_provider_bindings: false,
+            relaxed_signature_matching: false,
+            confirm_destructive: false,
+            confirmation_hook: None,
+            max_call_depth: DEFAULT_MAX_CALL_DEPTH,
+            resumed_context: None,
         }
     }
 
@@ -72,6 +179,61 @@ impl RuntimeBuilder {
         self
     }
 
+    pub fn with_injection_template(mut self, template: InjectionTemplate) -> Self {
+        self.injection_template = template;
+        self
+    }
+
+    pub(crate) fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Bind extern functions to providers once and reuse the bindings on later
+    /// runs, instead of listing every provider's functions per run.
+    pub fn with_provider_caching(mut self, enabled: bool) -> Self {
+        self.cache_provider_bindings = enabled;
+        self
+    }
+
+    /// When no provider offers an extern function with the declared parameter
+    /// names, accept one whose parameter types match by position instead.
+    /// Arguments are then passed under the provider's names.
+    pub fn with_relaxed_signature_matching(mut self, enabled: bool) -> Self {
+        self.relaxed_signature_matching = enabled;
+        self
+    }
+
+    /// Ask before each call to a function its provider marks destructive, as
+    /// MCP tools do with `destructiveHint`. Calls are refused unless
+    /// [`Self::with_confirmation_hook`] approves them.
+    pub fn with_confirm_destructive(mut self, enabled: bool) -> Self {
+        self.confirm_destructive = enabled;
+        self
+    }
+
+    pub fn with_confirmation_hook(
+        mut self,
+        hook: impl Fn(&str, &[ExpressionValue]) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        self.confirmation_hook = Some(Arc::new(hook));
+        self
+    }
+
+    /// Fail a call that would nest more than `depth` calls below `main`,
+    /// instead of letting a runaway chain of calls grow without bound.
+    pub fn with_max_call_depth(mut self, depth: usize) -> Self {
+        self.max_call_depth = depth;
+        self
+    }
+
+    /// Start every run from `saved` instead of an empty context. See
+    /// [`Runtime::run_from`].
+    pub fn with_resumed_context(mut self, saved: SavedContext) -> Self {
+        self.resumed_context = Some(saved);
+        self
+    }
+
     pub fn with_provider(mut self, provider: Arc<dyn FunctionProvider>) -> Self {
         self.providers.push(provider);
         self
@@ -85,6 +247,26 @@ impl RuntimeBuilder {
         self
     }
 
+    /// Registers `function` as the native function `name`, for embedding
+    /// without writing a [`NativeFunction`] impl.
+    pub fn with_native_fn<F>(
+        self,
+        name: &str,
+        parameters: Vec<Parameter>,
+        return_type: Type,
+        function: F,
+    ) -> Self
+    where
+        F: Fn(Vec<ExpressionValue>) -> Result<ExpressionValue, String> + Send + Sync + 'static,
+    {
+        self.with_native_function(Arc::new(ClosureFunction::new(
+            name,
+            parameters,
+            return_type,
+            function,
+        )))
+    }
+
     pub fn with_mcp_client(mut self, client: McpClient) -> Self {
         self.providers.push(Arc::new(client));
         self
@@ -122,48 +304,106 @@ impl RuntimeBuilder {
         Ok(self)
     }
 
+    /// Like [`Self::with_mcp_server_configs`], but starts every server now so
+    /// a broken one is reported before the program runs. Each server that
+    /// fails to connect gets its own line in the error.
+    pub async fn with_connected_mcp_server_configs(
+        mut self,
+        configs: &[McpServerConfig],
+    ) -> Result<Self, String> {
+        let mut errors = Vec::new();
+        for config in configs {
+            let connected = match McpClient::new_stdio(&config.command, config.args.clone()).await {
+                Ok(client) => client.ensure_connected().await.map(|()| client),
+                Err(e) => Err(e),
+            };
+            match connected {
+                Ok(client) => self.providers.push(Arc::new(client)),
+                Err(e) => errors.push(format!(
+                    "Failed to connect to MCP server '{}': {}",
+                    config.command, e
+                )),
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(self)
+        } else {
+            Err(errors.join("\n"))
+        }
+    }
+
     pub fn with_native_provider(mut self, provider: NativeFunctionProvider) -> Self {
         self.providers.push(Arc::new(provider));
         self
     }
 
     pub async fn from_config(mut self, config: &Config) -> Result<Runtime, String> {
-        self = self.with_mcp_server_configs(&config.mcp_servers).await?;
+        self = if config.eager_connect {
+            self.with_connected_mcp_server_configs(&config.mcp_servers)
+                .await?
+        } else {
+            self.with_mcp_server_configs(&config.mcp_servers).await?
+        };
 
         let engine: Arc<dyn LanguageEngine> = match &config.engine {
             EngineType::Print => Arc::new(crate::types::PrintEngine {}),
-            EngineType::Gemini { api_key, model } => {
-                let gemini_config = if let Some(key) = api_key {
+            EngineType::Gemini(settings) => {
+                let mut gemini_config = if let Some(key) = &settings.api_key {
                     GeminiConfig::default().with_api_key_auth(key.clone())
                 } else {
                     GeminiConfig::from_env().map_err(|e| {
                         format!("Failed to load Gemini config from environment: {}", e)
                     })?
                 };
+                if let Some(command) = &settings.token_command {
+                    gemini_config = gemini_config.with_token_command(command.clone());
+                }
+                if let Some(secs) = settings.token_ttl_secs {
+                    gemini_config =
+                        gemini_config.with_token_ttl(std::time::Duration::from_secs(secs));
+                }
+                if settings.api_key_in_query {
+                    gemini_config = gemini_config.with_api_key_in_query(true);
+                }
 
                 let mut gemini = match GeminiEngine::new(gemini_config).await {
-                    Ok(gemini) => gemini,
+                    Ok(gemini) => gemini.with_clock(self.clock.clone()),
                     Err(e) => {
                         return Err(format!("Failed to initialize Gemini engine: {}", e));
                     }
                 };
 
-                if let Some(model_name) = model {
-                    let model_enum = match model_name.as_str() {
-                        "gemini-2.5-pro" => crate::gemini::types::ModelName::Gemini25Pro,
-                        "gemini-2.5-flash" => crate::gemini::types::ModelName::Gemini25Flash,
-                        "gemini-2.5-flash-lite" => {
-                            crate::gemini::types::ModelName::Gemini25FlashLite
-                        }
-                        "gemini-3-flash-preview" => {
-                            crate::gemini::types::ModelName::Gemini3FlashPreview
-                        }
-                        "gemini-3-pro-preview" => {
-                            crate::gemini::types::ModelName::Gemini3ProPreview
-                        }
-                        custom => crate::gemini::types::ModelName::Custom(custom.to_string()),
-                    };
-                    gemini = gemini.with_model(model_enum);
+                if let Some(model_name) = &settings.model {
+                    gemini = gemini.with_model(ModelName::from_name(model_name));
+                }
+
+                if !settings.fallback_models.is_empty() {
+                    gemini = gemini.with_model_fallback_chain(
+                        settings
+                            .fallback_models
+                            .iter()
+                            .map(|name| ModelName::from_name(name))
+                            .collect(),
+                    );
+                }
+
+                if let Some(preset_name) = &settings.preset {
+                    let preset = crate::gemini::types::Preset::from_name(preset_name)
+                        .ok_or_else(|| format!("Unknown generation preset: {}", preset_name))?;
+                    gemini = gemini.with_preset(preset);
+                }
+
+                if settings.message_metadata {
+                    gemini = gemini.with_message_metadata(true);
+                }
+
+                if settings.deterministic {
+                    gemini = gemini.with_deterministic(true);
+                }
+
+                if let Some(seed) = settings.seed {
+                    gemini = gemini.with_seed(seed);
                 }
 
                 Arc::new(gemini)
@@ -172,10 +412,30 @@ impl RuntimeBuilder {
 
         self = self.with_language_engine(engine);
 
+        if !config.disabled_analyzers.is_empty() {
+            self = self.with_compiler(Arc::new(
+                Compiler::new().with_disabled_analyzers(config.disabled_analyzers.clone()),
+            ));
+        }
+
+        if let Some(depth) = config.max_call_depth {
+            self = self.with_max_call_depth(depth);
+        }
+
+        if config.relaxed_signature_matching {
+            self = self.with_relaxed_signature_matching(true);
+        }
+
+        if config.confirm_destructive {
+            self = self.with_confirm_destructive(true);
+        }
+
         if config.with_default_functions {
             self = self
                 .with_native_function(Arc::new(InputFunction::new()))
-                .with_native_function(Arc::new(PrintFunction::new()));
+                .with_native_function(Arc::new(PrintFunction::new()))
+                .with_native_function(Arc::new(ToStringFunction::new()))
+                .with_native_function(Arc::new(ParseIntFunction::new()));
         }
 
         if config.with_unstable_functions {
@@ -185,7 +445,8 @@ impl RuntimeBuilder {
                 .with_native_function(Arc::new(IsSomeFunction::new()))
                 .with_native_function(Arc::new(SomeValueFunction::new()))
                 .with_native_function(Arc::new(IsSomeListFunction::new()))
-                .with_native_function(Arc::new(SomeValueListFunction::new()));
+                .with_native_function(Arc::new(SomeValueListFunction::new()))
+                .with_native_function(Arc::new(JsonGetFunction::new()));
         }
 
         if config.with_acp_functions {
@@ -206,13 +467,25 @@ impl RuntimeBuilder {
 
         Runtime {
             function_registry,
-            external_function_registry: HashMap::new(),
+            external_function_registry: BTreeMap::new(),
+            external_const_registry: BTreeMap::new(),
+            constants: BTreeMap::new(),
             language_engine: self
                 .language_engine
                 .unwrap_or_else(|| Arc::new(crate::types::PrintEngine {})),
             compiler: self.compiler.unwrap_or_else(|| Arc::new(Compiler::new())),
             providers,
             compiled_program: self.program_source,
+            injection_template: self.injection_template,
+            clock: self.clock,
+            cache_provider_bindings: self.cache_provider_bindings,
+            relaxed_signature_matching: self.relaxed_signature_matching,
+            confirm_destructive: self.confirm_destructive,
+            confirmation_hook: self.confirmation_hook.clone(),
+            max_call_depth: self.max_call_depth,
+            provider_bindings: Arc::new(tokio::sync::Mutex::new(None)),
+            stats: Arc::new(RunStats::new()),
+            resumed_context: self.resumed_context,
         }
     }
 }
@@ -244,10 +517,41 @@ impl Runtime {
         self.external_function_registry.get(name)
     }
 
+    pub fn register_external_constant(&mut self, constant: ExternalConstDefinition) {
+        self.external_const_registry
+            .insert(constant.name.clone(), constant);
+    }
+
+    pub fn register_constant(&mut self, name: String, value: ExpressionValue) {
+        self.constants.insert(name, value);
+    }
+
+    pub fn get_constant(&self, name: &str) -> Option<&ExpressionValue> {
+        self.constants.get(name)
+    }
+
     pub fn list_functions(&self) -> Vec<&str> {
         self.function_registry.keys().map(|s| s.as_str()).collect()
     }
 
+    pub fn injection_template(&self) -> InjectionTemplate {
+        self.injection_template
+    }
+
+    pub(crate) fn clock(&self) -> &dyn Clock {
+        self.clock.as_ref()
+    }
+
+    pub fn max_call_depth(&self) -> usize {
+        self.max_call_depth
+    }
+
+    /// What the most recent run asked of the engine and tools. A batch run
+    /// counts all of its runs together.
+    pub fn stats(&self) -> &RunStats {
+        &self.stats
+    }
+
     pub fn engine(&self) -> &dyn LanguageEngine {
         self.language_engine.as_ref()
     }
@@ -256,6 +560,10 @@ impl Runtime {
         &self.compiler
     }
 
+    pub fn program(&self) -> &CompilationUnit {
+        &self.compiled_program
+    }
+
     pub fn check(&self) -> Result<(), RuntimeError> {
         debug!("Starting program check");
         match self.compiler.compile_program(&self.compiled_program) {
@@ -265,15 +573,217 @@ impl Runtime {
             }
             Err(e) => {
                 error!("Program check failed: {}", e);
-                Err(RuntimeError::ExecutionError(e))
+                Err(RuntimeError::ExecutionError(e.to_string()))
             }
         }
     }
 
+    /// Like [`Runtime::check`], but also reports how long each analyzer took.
+    pub fn check_with_report(&self) -> Result<AnalysisReport, RuntimeError> {
+        debug!("Starting program check with analysis stats");
+        let options = CompileOptions::default().with_analysis_stats(true);
+        let program = self
+            .compiler
+            .compile_program_with_options(&self.compiled_program, options)
+            .map_err(|e| {
+                error!("Program check failed: {}", e);
+                RuntimeError::ExecutionError(e.to_string())
+            })?;
+        program.analysis_report().cloned().ok_or_else(|| {
+            RuntimeError::ExecutionError("Analysis did not produce a report".to_string())
+        })
+    }
+
     pub async fn run(&self) -> Result<ExpressionValue, RuntimeError> {
+        self.run_with_context().await.map(|(value, _context)| value)
+    }
+
+    /// Runs the program, abandoning it once `deadline` passes. Pending
+    /// engine and tool calls are dropped at their await points.
+    pub async fn run_with_deadline(
+        &self,
+        deadline: Instant,
+    ) -> Result<ExpressionValue, RuntimeError> {
+        tokio::select! {
+            biased;
+            _ = tokio::time::sleep_until(deadline.into()) => Err(RuntimeError::ExecutionError(
+                "Execution deadline exceeded".to_string(),
+            )),
+            result = self.run() => result,
+        }
+    }
+
+    /// Runs the program until it finishes or `token` is cancelled.
+    pub async fn run_with_cancellation(
+        &self,
+        token: CancellationToken,
+    ) -> Result<ExpressionValue, RuntimeError> {
+        tokio::select! {
+            biased;
+            _ = token.cancelled() => Err(RuntimeError::ExecutionError(
+                "Execution cancelled".to_string(),
+            )),
+            result = self.run() => result,
+        }
+    }
+
+    pub async fn run_with_context(&self) -> Result<(ExpressionValue, Context), RuntimeError> {
+        self.stats.reset();
+        let start = match &self.resumed_context {
+            Some(saved) => RunStart::Saved(saved.clone()),
+            None => RunStart::Fresh,
+        };
+        self.run_main(start).instrument(debug_span!("run")).await
+    }
+
+    /// Runs the program and returns its result with every event of the
+    /// finished run, oldest first.
+    pub async fn run_collecting(&self) -> Result<(ExpressionValue, Vec<Event>), RuntimeError> {
+        let (value, context) = self.run_with_context().await?;
+        Ok((value, context.iter_all_events().collect()))
+    }
+
+    /// Runs the program once per input, with at most `concurrency` runs in
+    /// flight. Each run starts from a fresh context seeded by its input, and
+    /// results come back in input order.
+    pub async fn run_batch(
+        &self,
+        inputs: Vec<InitialContext>,
+        concurrency: usize,
+    ) -> Vec<Result<ExpressionValue, RuntimeError>> {
+        self.stats.reset();
+        let runtime = Arc::new(self.create_runtime_ref());
+        let permits = Arc::new(Semaphore::new(concurrency.max(1)));
+        let local = LocalSet::new();
+
+        let handles: Vec<_> = inputs
+            .into_iter()
+            .map(|input| {
+                let runtime = runtime.clone();
+                let permits = permits.clone();
+                local.spawn_local(async move {
+                    let _permit = permits
+                        .acquire()
+                        .await
+                        .expect("batch semaphore is never closed");
+                    runtime
+                        .run_main(RunStart::Initial(input))
+                        .instrument(debug_span!("run"))
+                        .await
+                        .map(|(value, _context)| value)
+                })
+            })
+            .collect();
+
+        local
+            .run_until(async move {
+                let mut results = Vec::with_capacity(handles.len());
+                for handle in handles {
+                    results.push(handle.await.unwrap_or_else(|e| {
+                        Err(RuntimeError::ExecutionError(format!(
+                            "Batched run panicked: {}",
+                            e
+                        )))
+                    }));
+                }
+                results
+            })
+            .await
+    }
+
+    pub async fn resume_with_context(
+        &self,
+        context: Context,
+    ) -> Result<(ExpressionValue, Context), RuntimeError> {
+        self.stats.reset();
+        self.run_main(RunStart::Context(context))
+            .instrument(debug_span!("run"))
+            .await
+    }
+
+    /// Runs the program with a context restored from `saved`, as
+    /// [`Runtime::resume_with_context`] does with a live one: `main` starts
+    /// again from the top, seeing the saved events, and the saved variables
+    /// are back in the root scope. The context must have been saved from a
+    /// run of the same program source, by [`CompilationUnit::fingerprint`].
+    pub async fn run_from(
+        &self,
+        saved: SavedContext,
+    ) -> Result<(ExpressionValue, Context), RuntimeError> {
+        self.stats.reset();
+        self.run_main(RunStart::Saved(saved))
+            .instrument(debug_span!("run"))
+            .await
+    }
+
+    async fn run_main(&self, start: RunStart) -> Result<(ExpressionValue, Context), RuntimeError> {
         debug!("Starting program execution");
 
-        let compiled_program = match self.compiler.compile_program(&self.compiled_program) {
+        if let RunStart::Saved(saved) = &start
+            && saved.program != self.compiled_program.fingerprint()
+        {
+            return Err(RuntimeError::ExecutionError(format!(
+                "Saved context belongs to program '{}', not '{}'",
+                saved.program,
+                self.compiled_program.fingerprint()
+            )));
+        }
+
+        let options = CompileOptions::default()
+            .with_analysis(false)
+            .with_require_main(true);
+        let (runtime, compiled_program) = self.prepare(options).await?;
+
+        if let Some(main_function) = compiled_program.main_function() {
+            debug!("Executing main function");
+            let main_function = main_function.as_ref() as &dyn crate::types::Function;
+            let execution = match start {
+                RunStart::Fresh => runtime.run_expression_with_context(main_function).await,
+                RunStart::Context(context) => {
+                    runtime
+                        .run_expression_in_context(main_function, context)
+                        .await
+                }
+                RunStart::Saved(saved) => {
+                    let context = Context::restore(Arc::new(runtime.create_runtime_ref()), saved);
+                    runtime
+                        .run_expression_in_context(main_function, context)
+                        .await
+                }
+                RunStart::Initial(initial) => {
+                    let context = Context::seeded(Arc::new(runtime.create_runtime_ref()), initial);
+                    runtime
+                        .run_expression_in_context(main_function, context)
+                        .await
+                }
+            };
+            match execution {
+                Ok((result, context)) => {
+                    debug!("Program execution completed successfully");
+                    debug!("Result type: {}", result.type_name());
+                    Ok((result, context))
+                }
+                Err(e) => {
+                    error!("Runtime execution failed: {:?}", e);
+                    Err(e)
+                }
+            }
+        } else {
+            error!("No main function found in program");
+            Err(RuntimeError::FunctionNotFound("main".to_string()))
+        }
+    }
+
+    // Compiles the program and returns a runtime with its functions,
+    // constants and provider bindings registered, ready to call into.
+    async fn prepare(
+        &self,
+        options: CompileOptions,
+    ) -> Result<(Runtime, CompiledProgram), RuntimeError> {
+        let compiled_program = match self
+            .compiler
+            .compile_program_with_options(&self.compiled_program, options)
+        {
             Ok(program) => {
                 debug!("Program compiled successfully");
                 debug!(
@@ -288,17 +798,29 @@ impl Runtime {
             }
             Err(e) => {
                 error!("Compilation failed: {}", e);
-                return Err(RuntimeError::ExecutionError(e));
+                return Err(RuntimeError::ExecutionError(e.to_string()));
             }
         };
 
         let mut runtime = Runtime {
             function_registry: self.function_registry.clone(),
             external_function_registry: self.external_function_registry.clone(),
+            external_const_registry: self.external_const_registry.clone(),
+            constants: self.constants.clone(),
             language_engine: self.language_engine.clone(),
             compiler: self.compiler.clone(),
             providers: self.providers.clone(),
             compiled_program: self.compiled_program.clone(),
+            injection_template: self.injection_template,
+            clock: self.clock.clone(),
+            cache_provider_bindings: self.cache_provider_bindings,
+            relaxed_signature_matching: self.relaxed_signature_matching,
+            confirm_destructive: self.confirm_destructive,
+            confirmation_hook: self.confirmation_hook.clone(),
+            max_call_depth: self.max_call_depth,
+            provider_bindings: self.provider_bindings.clone(),
+            stats: self.stats.clone(),
+            resumed_context: self.resumed_context.clone(),
         };
 
         for function in compiled_program.functions().values() {
@@ -312,44 +834,149 @@ impl Runtime {
             debug!("Registering external function: {}", external_function.name);
             runtime.register_external_function(external_function.clone());
         }
+        for external_constant in compiled_program.external_constants().values() {
+            debug!("Registering external constant: {}", external_constant.name);
+            runtime.register_external_constant(external_constant.clone());
+        }
+        for (name, value) in compiled_program.constants() {
+            debug!("Registering constant: {}", name);
+            runtime.register_constant(name.clone(), value.clone());
+        }
 
         if let Err(e) = runtime.map_providers_to_functions().await {
             error!("Failed to map providers to functions: {:?}", e);
             return Err(e);
         }
 
-        if let Some(main_function) = compiled_program.main_function() {
-            debug!("Executing main function");
-            match runtime
-                .run_expression(main_function.as_ref() as &dyn crate::types::Function)
-                .await
-            {
-                Ok(result) => {
-                    debug!("Program execution completed successfully");
-                    debug!("Result type: {}", result.type_name());
-                    Ok(result)
-                }
-                Err(e) => {
-                    error!("Runtime execution failed: {:?}", e);
-                    Err(e)
-                }
-            }
+        if let Err(e) = runtime.resolve_external_constants().await {
+            error!("Failed to resolve external constants: {:?}", e);
+            return Err(e);
+        }
+
+        Ok((runtime, compiled_program))
+    }
+
+    /// Runs the top-level function `name` with `args` in a fresh context,
+    /// the way [`Runtime::run`] runs `main`. The program needs no `main`.
+    /// `name` may also be an extern, which calls the function bound to it;
+    /// an error from that call is reported as [`RuntimeError::ToolFailed`].
+    pub async fn run_function(
+        &self,
+        name: &str,
+        args: Vec<ExpressionValue>,
+    ) -> Result<ExpressionValue, RuntimeError> {
+        self.stats.reset();
+        let options = CompileOptions::default().with_analysis(false);
+        let (runtime, compiled_program) = self.prepare(options).await?;
+        let is_extern = compiled_program.external_functions().contains_key(name);
+        let function = if is_extern {
+            runtime.get_function(name)
         } else {
-            error!("No main function found in program");
-            Err(RuntimeError::FunctionNotFound("main".to_string()))
+            compiled_program.functions().get(name).map(|f| f.as_ref())
+        }
+        .ok_or_else(|| RuntimeError::FunctionNotFound(name.to_string()))?;
+
+        let parameters = function.parameters();
+        if args.len() != parameters.len() {
+            return Err(RuntimeError::InvalidArguments(format!(
+                "{} expects {} arguments, got {}",
+                name,
+                parameters.len(),
+                args.len()
+            )));
+        }
+        for (arg, param) in args.iter().zip(parameters) {
+            if !Self::value_matches_type(arg, &param.param_type) {
+                return Err(RuntimeError::InvalidArguments(format!(
+                    "{} expects {} to be {}, got {}",
+                    name,
+                    param.name,
+                    param.param_type.name(),
+                    arg.type_name()
+                )));
+            }
+        }
+
+        let context = Context::with_runtime(Arc::new(runtime.create_runtime_ref()));
+        let args = args.into_iter().map(ExpressionResult::new).collect();
+        match function.execute(context, args).await {
+            Ok((_context, result)) => Ok(result.value),
+            Err(e) if is_extern => {
+                error!("Tool call {} failed: {}", name, e);
+                Err(RuntimeError::ToolFailed {
+                    function: name.to_string(),
+                    cause: e,
+                })
+            }
+            Err(e) => {
+                error!("Function {} failed: {}", name, e);
+                Err(RuntimeError::ExecutionError(e))
+            }
+        }
+    }
+
+    pub async fn eval_expression(&self, source: &str) -> Result<ExpressionValue, RuntimeError> {
+        let mut functions = BTreeMap::new();
+        for (name, definition) in &self.external_function_registry {
+            functions.insert(name.clone(), definition.clone());
         }
+        for (name, function) in &self.function_registry {
+            functions.insert(
+                name.clone(),
+                ExternalFunctionDefinition::new(
+                    name.clone(),
+                    function.parameters().to_vec(),
+                    function.function_return_type().clone(),
+                ),
+            );
+        }
+        let functions = functions.into_values().collect::<Vec<_>>();
+        let constants = self
+            .external_const_registry
+            .values()
+            .cloned()
+            .collect::<Vec<_>>();
+
+        let expression = self
+            .compiler
+            .compile_expression(source, &functions, &constants)
+            .map_err(|e| {
+                error!("Expression compilation failed: {}", e);
+                RuntimeError::ExecutionError(e.to_string())
+            })?;
+
+        self.run_expression(expression.as_ref() as &dyn crate::types::Function)
+            .await
     }
 
     pub async fn run_expression(
         &self,
         program: &dyn crate::types::Function,
     ) -> Result<ExpressionValue, RuntimeError> {
-        debug!("Running expression");
+        self.run_expression_with_context(program)
+            .await
+            .map(|(value, _context)| value)
+    }
+
+    pub async fn run_expression_with_context(
+        &self,
+        program: &dyn crate::types::Function,
+    ) -> Result<(ExpressionValue, Context), RuntimeError> {
         let initial_context = Context::with_runtime(Arc::new(self.create_runtime_ref()));
-        match program.execute(initial_context, vec![]).await {
-            Ok((_context, result)) => {
+        self.run_expression_in_context(program, initial_context)
+            .await
+    }
+
+    pub async fn run_expression_in_context(
+        &self,
+        program: &dyn crate::types::Function,
+        context: Context,
+    ) -> Result<(ExpressionValue, Context), RuntimeError> {
+        debug!("Running expression");
+        match program.execute(context, vec![]).await {
+            Ok((context, result)) => {
                 debug!("Expression evaluated successfully");
-                Ok(result.value)
+                Ok((result.value, context))
             }
             Err(e) => {
                 error!("Expression evaluation failed: {}", e);
@@ -362,10 +989,22 @@ impl Runtime {
         Runtime {
             function_registry: self.function_registry.clone(),
             external_function_registry: self.external_function_registry.clone(),
+            external_const_registry: self.external_const_registry.clone(),
+            constants: self.constants.clone(),
             language_engine: self.language_engine.clone(),
             compiler: self.compiler.clone(),
             providers: self.providers.clone(),
             compiled_program: self.compiled_program.clone(),
+            injection_template: self.injection_template,
+            clock: self.clock.clone(),
+            cache_provider_bindings: self.cache_provider_bindings,
+            relaxed_signature_matching: self.relaxed_signature_matching,
+            confirm_destructive: self.confirm_destructive,
+            confirmation_hook: self.confirmation_hook.clone(),
+            max_call_depth: self.max_call_depth,
+            provider_bindings: self.provider_bindings.clone(),
+            stats: self.stats.clone(),
+            resumed_context: self.resumed_context.clone(),
         }
     }
 
@@ -373,11 +1012,9 @@ impl Runtime {
         provider_def: &ExternalFunctionDefinition,
         definition: &ExternalFunctionDefinition,
     ) -> bool {
-        if provider_def.parameters.len() != definition.parameters.len() {
-            return false;
-        }
-
-        if provider_def.return_type != definition.return_type {
+        if provider_def.parameters.len() != definition.parameters.len()
+            || !Self::return_types_match(provider_def, definition)
+        {
             return false;
         }
 
@@ -398,46 +1035,94 @@ impl Runtime {
         true
     }
 
+    fn signatures_match_by_position(
+        provider_def: &ExternalFunctionDefinition,
+        definition: &ExternalFunctionDefinition,
+    ) -> bool {
+        provider_def.parameters.len() == definition.parameters.len()
+            && Self::return_types_match(provider_def, definition)
+            && provider_def
+                .parameters
+                .iter()
+                .zip(&definition.parameters)
+                .all(|(provider_param, extern_param)| {
+                    provider_param.param_type == extern_param.param_type
+                })
+    }
+
+    fn return_types_match(
+        provider_def: &ExternalFunctionDefinition,
+        definition: &ExternalFunctionDefinition,
+    ) -> bool {
+        // Providers that only know about strings (MCP tools) can back a Json
+        // declaration; the result is parsed when the call returns.
+        let json_from_string =
+            definition.return_type == Type::Json && provider_def.return_type == Type::String;
+        provider_def.return_type == definition.return_type || json_from_string
+    }
+
+    fn describe_signature(name: &str, definition: &ExternalFunctionDefinition) -> String {
+        let params = definition
+            .parameters
+            .iter()
+            .map(|p| format!("{}: {:?}", p.name, p.param_type))
+            .collect::<Vec<_>>()
+            .join(", ");
+        format!("fn {}({}) -> {:?}", name, params, definition.return_type)
+    }
+
     fn find_matching_provider<'a>(
-        matches: &'a [(ExternalFunctionDefinition, Arc<dyn FunctionProvider>)],
+        matches: &'a [ProviderMatch],
         definition: &ExternalFunctionDefinition,
         name: &str,
-    ) -> Result<&'a Arc<dyn FunctionProvider>, RuntimeError> {
-        matches
+        relaxed: bool,
+    ) -> Result<&'a ProviderMatch, RuntimeError> {
+        let exact = matches
             .iter()
-            .find(|(provider_def, _)| Self::signatures_match(provider_def, definition))
-            .map(|(_, provider)| provider)
-            .ok_or_else(|| {
-                let expected_params = definition
-                    .parameters
-                    .iter()
-                    .map(|p| format!("{}: {:?}", p.name, p.param_type))
-                    .collect::<Vec<_>>()
-                    .join(", ");
+            .find(|(provider_def, _)| Self::signatures_match(provider_def, definition));
+        let relaxed_match = || {
+            let found = matches.iter().find(|(provider_def, _)| {
+                Self::signatures_match_by_position(provider_def, definition)
+            });
+            if let Some((provider_def, provider)) = found {
+                debug!(
+                    "Binding extern function '{}' to {} by parameter position: declared {}, provider offers {}",
+                    name,
+                    provider.name(),
+                    Self::describe_signature(name, definition),
+                    Self::describe_signature(name, provider_def)
+                );
+            }
+            found
+        };
 
+        exact
+            .or_else(|| if relaxed { relaxed_match() } else { None })
+            .ok_or_else(|| {
                 let available_sigs = matches
                     .iter()
                     .map(|(provider_def, _)| {
-                        let params = provider_def
-                            .parameters
-                            .iter()
-                            .map(|p| format!("{}: {:?}", p.name, p.param_type))
-                            .collect::<Vec<_>>()
-                            .join(", ");
-                        format!("  - fn {}({}) -> {:?}", name, params, provider_def.return_type)
+                        format!("  - {}", Self::describe_signature(name, provider_def))
                     })
                     .collect::<Vec<_>>()
                     .join("\n");
 
-                RuntimeError::ExecutionError(format!(
-                    "No matching provider found for extern function '{}'.\n\nExpected signature:\n  fn {}({}) -> {:?}\n\nAvailable signatures from providers:\n{}",
-                    name, name, expected_params, definition.return_type, available_sigs
-                ))
+                RuntimeError::SignatureMismatch {
+                    function: name.to_string(),
+                    details: format!(
+                        "No matching provider found for extern function '{}'.\n\nExpected signature:\n  {}\n\nAvailable signatures from providers:\n{}",
+                        name,
+                        Self::describe_signature(name, definition),
+                        available_sigs
+                    ),
+                }
             })
     }
 
-    async fn map_providers_to_functions(&mut self) -> Result<(), RuntimeError> {
-        let mut provider_functions = HashMap::new();
+    async fn collect_provider_functions(
+        &self,
+    ) -> Result<BTreeMap<String, Vec<ProviderMatch>>, RuntimeError> {
+        let mut provider_functions = BTreeMap::new();
 
         for provider in &self.providers {
             let available_functions = provider.list_functions().await?;
@@ -450,28 +1135,281 @@ impl Runtime {
             }
         }
 
+        Ok(provider_functions)
+    }
+
+    /// Reports which provider would back each extern function in the program,
+    /// without creating any of the bound expressions.
+    pub async fn describe_bindings(&self) -> Result<Vec<FunctionBinding>, RuntimeError> {
+        let options = CompileOptions::default().with_analysis(false);
+        let compiled_program = self
+            .compiler
+            .compile_program_with_options(&self.compiled_program, options)
+            .map_err(|e| RuntimeError::ExecutionError(e.to_string()))?;
+
+        let provider_functions = self.collect_provider_functions().await?;
+
+        let bindings = compiled_program
+            .external_functions()
+            .iter()
+            .map(|(name, definition)| FunctionBinding {
+                function: name.clone(),
+                signature: Self::describe_signature(name, definition),
+                outcome: self.binding_outcome(&provider_functions, name, definition),
+            })
+            .collect();
+
+        Ok(bindings)
+    }
+
+    fn binding_outcome(
+        &self,
+        provider_functions: &BTreeMap<String, Vec<ProviderMatch>>,
+        name: &str,
+        definition: &ExternalFunctionDefinition,
+    ) -> BindingOutcome {
+        match provider_functions.get(name) {
+            Some(matches) => match Self::find_matching_provider(
+                matches,
+                definition,
+                name,
+                self.relaxed_signature_matching,
+            ) {
+                Ok((provider_def, provider)) => BindingOutcome::Bound {
+                    provider: provider.name(),
+                    signature: Self::describe_signature(name, provider_def),
+                },
+                Err(e) => BindingOutcome::Unbound {
+                    reason: e.to_string(),
+                },
+            },
+            None => BindingOutcome::Unbound {
+                reason: RuntimeError::NoProvider {
+                    function: name.to_string(),
+                }
+                .to_string(),
+            },
+        }
+    }
+
+    /// Lists the configured providers and returns an error diagnostic, labelled
+    /// at the declaration, for every extern function none of them can supply.
+    pub async fn provider_diagnostics(&self) -> Result<Vec<Diagnostic<FileId>>, RuntimeError> {
+        let (manager, file_id) = self.program_diagnostics();
+        self.provider_diagnostics_for_file(&manager, file_id).await
+    }
+
+    /// Like [`Runtime::check`], but fails when an extern function has no
+    /// compatible provider instead of waiting for the first run to find out.
+    pub async fn check_providers(&self) -> Result<(), RuntimeError> {
+        debug!("Checking extern functions against providers");
+        let (manager, file_id) = self.program_diagnostics();
+        let diagnostics = self
+            .provider_diagnostics_for_file(&manager, file_id)
+            .await?;
+        for diagnostic in &diagnostics {
+            if let Err(io_err) = manager.reporter().emit_diagnostic(diagnostic) {
+                eprintln!("Failed to emit provider diagnostic: {}", io_err);
+            }
+        }
+
+        if diagnostics.is_empty() {
+            Ok(())
+        } else {
+            Err(RuntimeError::ExecutionError(format!(
+                "{} extern function(s) have no compatible provider",
+                diagnostics.len()
+            )))
+        }
+    }
+
+    fn program_diagnostics(&self) -> (DiagnosticManager, FileId) {
+        let mut manager = DiagnosticManager::new();
+        let file_id = manager.add_file(
+            self.compiled_program.name().to_string(),
+            self.compiled_program.source().to_string(),
+        );
+        (manager, file_id)
+    }
+
+    async fn provider_diagnostics_for_file(
+        &self,
+        manager: &DiagnosticManager,
+        file_id: FileId,
+    ) -> Result<Vec<Diagnostic<FileId>>, RuntimeError> {
+        let module = CodespanParser::new()
+            .parse(&self.compiled_program, file_id, manager.reporter())
+            .map_err(RuntimeError::ExecutionError)?;
+
+        let provider_functions = self.collect_provider_functions().await?;
+
+        let mut diagnostics = Vec::new();
+        for definition in &module.definitions {
+            let Definition::ExternalFunction(ast_function) = definition else {
+                continue;
+            };
+            let compiled =
+                compile_external_function(ast_function).map_err(RuntimeError::ExecutionError)?;
+
+            if let BindingOutcome::Unbound { reason } =
+                self.binding_outcome(&provider_functions, &ast_function.name, &compiled)
+            {
+                diagnostics.push(
+                    Diagnostic::error()
+                        .with_message(format!(
+                            "no provider can supply extern function `{}`",
+                            ast_function.name
+                        ))
+                        .with_labels(vec![
+                            Label::primary(file_id, ast_function.span.to_byte_range())
+                                .with_message("declared here"),
+                        ])
+                        .with_notes(vec![reason]),
+                );
+            }
+        }
+
+        Ok(diagnostics)
+    }
+
+    async fn map_providers_to_functions(&mut self) -> Result<(), RuntimeError> {
+        let bound = if self.cache_provider_bindings {
+            // Held across the binding so concurrent first runs bind only once.
+            let mut cached = self.provider_bindings.lock().await;
+            if cached.is_none() {
+                *cached = Some(self.bind_external_functions().await?);
+            }
+            cached.clone().unwrap_or_default()
+        } else {
+            self.bind_external_functions().await?
+        };
+
+        for (name, expr) in bound {
+            self.register_expression(name, expr);
+        }
+
+        Ok(())
+    }
+
+    /// Drops cached provider bindings so the next run lists providers again.
+    pub async fn invalidate_provider_bindings(&self) {
+        *self.provider_bindings.lock().await = None;
+    }
+
+    async fn bind_external_functions(&self) -> Result<BoundFunctions, RuntimeError> {
+        let provider_functions = self.collect_provider_functions().await?;
+
         let mut functions_to_register = Vec::new();
 
         for (name, definition) in &self.external_function_registry {
-            let matches = provider_functions.get(name).ok_or_else(|| {
-                RuntimeError::ExecutionError(format!(
-                    "No provider found for extern function '{}'",
-                    name
-                ))
+            let matches = provider_functions
+                .get(name)
+                .ok_or_else(|| RuntimeError::NoProvider {
+                    function: name.clone(),
+                })?;
+
+            let (provider_def, provider) = Self::find_matching_provider(
+                matches,
+                definition,
+                name,
+                self.relaxed_signature_matching,
+            )?;
+            let mut definition = if Self::signatures_match(provider_def, definition) {
+                definition.clone()
+            } else {
+                Self::with_provider_parameter_names(definition, provider_def)
+            };
+            definition.input_schema = provider_def.input_schema.clone();
+            // An extern declared without a doc comment is described to the
+            // engine by the provider's own description, such as an MCP tool's.
+            if definition.documentation.is_none() {
+                definition.documentation = provider_def.documentation.clone();
+            }
+            let mut expr = provider.create_expression(&definition).await.map_err(|e| {
+                RuntimeError::BindingFailed {
+                    function: name.clone(),
+                    cause: e.to_string(),
+                }
             })?;
-
-            let provider = Self::find_matching_provider(matches, definition, name)?;
-            let expr = provider.create_expression(definition).await?;
+            if self.confirm_destructive && provider_def.annotations.destructive {
+                expr = Arc::new(ConfirmedFunctionExpr::new(
+                    expr,
+                    self.confirmation_hook.clone(),
+                ));
+            }
             functions_to_register.push((name.clone(), expr));
         }
 
-        for (name, expr) in functions_to_register {
-            self.register_expression(name, expr);
+        Ok(functions_to_register)
+    }
+
+    // A relaxed binding matched by position, so the provider must receive
+    // each argument under the name it advertised for that position.
+    fn with_provider_parameter_names(
+        definition: &ExternalFunctionDefinition,
+        provider_def: &ExternalFunctionDefinition,
+    ) -> ExternalFunctionDefinition {
+        let mut renamed = definition.clone();
+        for (param, provider_param) in renamed.parameters.iter_mut().zip(&provider_def.parameters) {
+            param.name = provider_param.name.clone();
+        }
+        renamed
+    }
+
+    async fn resolve_external_constants(&mut self) -> Result<(), RuntimeError> {
+        let mut resolved = Vec::new();
+
+        for (name, definition) in &self.external_const_registry {
+            let mut value = None;
+            for provider in &self.providers {
+                if let Some(v) = provider.resolve_constant(definition).await? {
+                    value = Some(v);
+                    break;
+                }
+            }
+
+            let value = value.ok_or_else(|| RuntimeError::NoProvider {
+                function: name.clone(),
+            })?;
+
+            if !Self::value_matches_type(&value, &definition.const_type) {
+                return Err(RuntimeError::SignatureMismatch {
+                    function: name.clone(),
+                    details: format!(
+                        "Extern const '{}' declared as {} but provider supplied {}",
+                        name,
+                        definition.const_type,
+                        value.type_name()
+                    ),
+                });
+            }
+
+            resolved.push((name.clone(), value));
+        }
+
+        for (name, value) in resolved {
+            self.constants.insert(name, value);
         }
 
         Ok(())
     }
 
+    fn value_matches_type(value: &ExpressionValue, expected: &Type) -> bool {
+        match (value, expected) {
+            (ExpressionValue::String(_), Type::String) => true,
+            (ExpressionValue::Boolean(_), Type::Boolean) => true,
+            (ExpressionValue::Number(_), Type::Number) => true,
+            (ExpressionValue::Unit, Type::Unit) => true,
+            (ExpressionValue::List(_), Type::List(_)) => true,
+            (ExpressionValue::Json(_), Type::Json) => true,
+            (ExpressionValue::Option(None), Type::Option(_)) => true,
+            (ExpressionValue::Option(Some(inner)), Type::Option(inner_type)) => {
+                Self::value_matches_type(inner, inner_type)
+            }
+            _ => false,
+        }
+    }
+
     #[cfg(test)]
     pub fn providers_count(&self) -> usize {
         self.providers.len()
@@ -488,10 +1426,22 @@ impl Clone for Runtime {
         Self {
             function_registry: self.function_registry.clone(),
             external_function_registry: self.external_function_registry.clone(),
+            external_const_registry: self.external_const_registry.clone(),
+            constants: self.constants.clone(),
             language_engine: self.language_engine.clone(),
             compiler: self.compiler.clone(),
             providers: self.providers.clone(),
             compiled_program: self.compiled_program.clone(),
+            injection_template: self.injection_template,
+            clock: self.clock.clone(),
+            cache_provider_bindings: self.cache_provider_bindings,
+            relaxed_signature_matching: self.relaxed_signature_matching,
+            confirm_destructive: self.confirm_destructive,
+            confirmation_hook: self.confirmation_hook.clone(),
+            max_call_depth: self.max_call_depth,
+            provider_bindings: self.provider_bindings.clone(),
+            stats: self.stats.clone(),
+            resumed_context: self.resumed_context.clone(),
         }
     }
 }
@@ -639,4 +1589,55 @@ mod tests {
 
         assert!(!Runtime::signatures_match(&provider_def, &extern_def));
     }
+
+    #[test]
+    fn test_signatures_match_json_declared_over_string_provider() {
+        let provider_def = ExternalFunctionDefinition::new(
+            "lookup".to_string(),
+            vec![Parameter::new("id".to_string(), Type::string())],
+            Type::string(),
+        );
+
+        let extern_def = ExternalFunctionDefinition::new(
+            "lookup".to_string(),
+            vec![Parameter::new("id".to_string(), Type::string())],
+            Type::json(),
+        );
+
+        assert!(Runtime::signatures_match(&provider_def, &extern_def));
+        assert!(!Runtime::signatures_match(&extern_def, &provider_def));
+    }
+
+    #[test]
+    fn test_list_functions_is_sorted() {
+        let program_source = r#"
+fn zeta(): String {
+    return "z"
+}
+
+fn alpha(): String {
+    return "a"
+}
+
+fn middle(): String {
+    return "m"
+}
+
+fn main(): () {
+    alpha()!
+}
+"#;
+
+        let program = CompilationUnit::from_string(program_source.to_string());
+        let compiled_program = Compiler::new().compile_program(&program).unwrap();
+        let mut runtime = Runtime::builder(program).build();
+        for function in compiled_program.functions().values() {
+            runtime.register_function(function.clone_executable());
+        }
+
+        assert_eq!(
+            runtime.list_functions(),
+            vec!["alpha", "main", "middle", "zeta"]
+        );
+    }
 }
@@ -0,0 +1,82 @@
+use crate::compiler::{CompilationUnit, Compiler};
+use crate::runtime::{ExpressionValue, Runtime, RuntimeError};
+
+fn empty_runtime() -> Runtime {
+    Runtime::builder(CompilationUnit::from_string(String::new())).build()
+}
+
+#[tokio::test]
+async fn test_eval_string_literal() {
+    let runtime = empty_runtime();
+
+    let result = runtime.eval_expression(r#""hello""#).await.unwrap();
+
+    assert_eq!(result, ExpressionValue::String("hello".to_string()));
+}
+
+#[tokio::test]
+async fn test_eval_boolean_literal() {
+    let runtime = empty_runtime();
+
+    let result = runtime.eval_expression("true").await.unwrap();
+
+    assert_eq!(result, ExpressionValue::Boolean(true));
+}
+
+#[tokio::test]
+async fn test_eval_call_to_registered_function() {
+    let program = CompilationUnit::from_string(
+        r#"
+fn greet(name: String): String {
+    return name
+}
+"#
+        .to_string(),
+    );
+    let compiled_program = Compiler::new().compile_program(&program).unwrap();
+    let mut runtime = Runtime::builder(program).build();
+    for function in compiled_program.functions().values() {
+        runtime.register_function(function.clone_executable());
+    }
+
+    let result = runtime.eval_expression(r#"greet("world")"#).await.unwrap();
+
+    assert_eq!(result, ExpressionValue::String("world".to_string()));
+}
+
+#[tokio::test]
+async fn test_eval_unknown_function_is_rejected() {
+    let runtime = empty_runtime();
+
+    let result = runtime.eval_expression("missing()").await;
+
+    assert_eq!(
+        result,
+        Err(RuntimeError::ExecutionError(
+            "Type error: Unknown function: missing".to_string()
+        ))
+    );
+}
+
+#[tokio::test]
+async fn test_eval_unknown_variable_is_rejected() {
+    let runtime = empty_runtime();
+
+    let result = runtime.eval_expression("value").await;
+
+    assert_eq!(
+        result,
+        Err(RuntimeError::ExecutionError(
+            "Type error: Unknown variable: value".to_string()
+        ))
+    );
+}
+
+#[tokio::test]
+async fn test_eval_rejects_trailing_input() {
+    let runtime = empty_runtime();
+
+    let result = runtime.eval_expression(r#""a" "b""#).await;
+
+    assert!(result.is_err());
+}
@@ -0,0 +1,125 @@
+use super::*;
+use crate::compiler::CompilationUnit;
+use crate::runtime::ExpressionValue;
+use crate::types::{NativeFunction, Parameter, Type};
+use async_trait::async_trait;
+use serde_json::json;
+use std::sync::Arc;
+
+fn program(source: &str) -> CompilationUnit {
+    CompilationUnit::from_string(source.to_string())
+}
+
+#[derive(Debug)]
+struct FetchIssueFunction {
+    parameters: Vec<Parameter>,
+    return_type: Type,
+}
+
+impl FetchIssueFunction {
+    fn new() -> Self {
+        Self {
+            parameters: vec![],
+            return_type: Type::json(),
+        }
+    }
+}
+
+#[async_trait]
+impl NativeFunction for FetchIssueFunction {
+    fn name(&self) -> &str {
+        "fetch_issue"
+    }
+
+    fn parameters(&self) -> &[Parameter] {
+        &self.parameters
+    }
+
+    fn return_type(&self) -> &Type {
+        &self.return_type
+    }
+
+    async fn execute(&self, _args: Vec<ExpressionValue>) -> Result<ExpressionValue, String> {
+        Ok(ExpressionValue::Json(json!({
+            "title": "Crash on startup",
+            "meta": { "id": 7 }
+        })))
+    }
+}
+
+async fn run_with_issue(source: &str) -> Result<ExpressionValue, RuntimeError> {
+    let runtime = Runtime::builder(program(source))
+        .with_native_function(Arc::new(FetchIssueFunction::new()))
+        .build();
+
+    runtime.run().await
+}
+
+#[tokio::test]
+async fn test_field_access_reads_json_field() {
+    let result = run_with_issue(
+        r#"
+extern fn fetch_issue(): Json
+
+fn main(): Json {
+    let issue = fetch_issue()
+    return issue.title
+}
+"#,
+    )
+    .await;
+
+    assert_eq!(result, Ok(ExpressionValue::Json(json!("Crash on startup"))));
+}
+
+#[tokio::test]
+async fn test_field_access_chains_through_nested_objects() {
+    let result = run_with_issue(
+        r#"
+extern fn fetch_issue(): Json
+
+fn main(): Json {
+    return fetch_issue().meta.id
+}
+"#,
+    )
+    .await;
+
+    assert_eq!(result, Ok(ExpressionValue::Json(json!(7))));
+}
+
+#[tokio::test]
+async fn test_missing_field_reads_as_null() {
+    let result = run_with_issue(
+        r#"
+extern fn fetch_issue(): Json
+
+fn main(): Json {
+    let issue = fetch_issue()
+    return issue.assignee.name
+}
+"#,
+    )
+    .await;
+
+    assert_eq!(result, Ok(ExpressionValue::Json(serde_json::Value::Null)));
+}
+
+#[tokio::test]
+async fn test_field_access_on_string_is_rejected() {
+    let result = run_with_issue(
+        r#"
+fn main(): Json {
+    let name = "issue"
+    return name.title
+}
+"#,
+    )
+    .await;
+
+    let error = result.unwrap_err().to_string();
+    assert!(
+        error.contains("Cannot access field title on type String"),
+        "unexpected error: {error}"
+    );
+}
@@ -129,3 +129,489 @@ fn main(): () {
     assert!(result.is_ok());
     assert_eq!(extern_fn.get_call_count(), 1);
 }
+
+#[derive(Debug)]
+struct DocumentedSearch {
+    parameters: Vec<Parameter>,
+    return_type: Type,
+}
+
+#[async_trait]
+impl NativeFunction for DocumentedSearch {
+    fn name(&self) -> &str {
+        "search"
+    }
+
+    fn parameters(&self) -> &[Parameter] {
+        &self.parameters
+    }
+
+    fn return_type(&self) -> &Type {
+        &self.return_type
+    }
+
+    async fn execute(&self, args: Vec<ExpressionValue>) -> Result<ExpressionValue, String> {
+        Ok(args[0].clone())
+    }
+
+    fn documentation(&self) -> Option<&str> {
+        Some("Searches the issue tracker for matching tickets")
+    }
+}
+
+type FillRequest = (String, Type, Option<crate::types::PlaceholderTarget>);
+
+#[derive(Default)]
+struct RecordingEngine {
+    fill_requests: std::sync::Mutex<Vec<FillRequest>>,
+    select_options: std::sync::Mutex<Vec<Vec<ExpressionValue>>>,
+}
+
+#[async_trait]
+impl crate::types::LanguageEngine for RecordingEngine {
+    async fn untyped(&self, _context: &Context) -> String {
+        String::new()
+    }
+
+    async fn typed(
+        &self,
+        _context: &Context,
+        _return_type: &Type,
+    ) -> Result<ExpressionValue, String> {
+        Ok(ExpressionValue::Unit)
+    }
+
+    async fn select(
+        &self,
+        _context: &Context,
+        options: &[ExpressionValue],
+    ) -> Result<usize, String> {
+        self.select_options.lock().unwrap().push(options.to_vec());
+        Ok(0)
+    }
+
+    async fn fill_parameter(
+        &self,
+        _context: &Context,
+        param_name: &str,
+        param_type: &Type,
+        target: Option<&crate::types::PlaceholderTarget>,
+    ) -> Result<ExpressionValue, String> {
+        self.fill_requests.lock().unwrap().push((
+            param_name.to_string(),
+            param_type.clone(),
+            target.cloned(),
+        ));
+        Ok(ExpressionValue::String("filled".to_string()))
+    }
+}
+
+#[tokio::test]
+async fn test_placeholder_fill_receives_external_function_documentation() {
+    let search = Arc::new(DocumentedSearch {
+        parameters: vec![Parameter::new("query".to_string(), Type::string())],
+        return_type: Type::string(),
+    });
+    let engine = Arc::new(RecordingEngine::default());
+
+    let program_source = r#"
+extern fn search(query: String): String
+
+fn main(): String {
+    return search(_)
+}
+"#;
+
+    let runtime = Runtime::builder(program(program_source))
+        .with_native_function(search)
+        .with_language_engine(engine.clone())
+        .build();
+
+    let result = runtime.run().await.unwrap();
+    assert_eq!(result, ExpressionValue::String("filled".to_string()));
+
+    let requests = engine.fill_requests.lock().unwrap();
+    assert_eq!(requests.len(), 1);
+    let (param_name, param_type, target) = &requests[0];
+    assert_eq!(param_name, "query");
+    assert_eq!(param_type, &Type::string());
+    let target = target
+        .as_ref()
+        .expect("placeholder should know its target function");
+    assert_eq!(target.function_name, "search");
+    assert_eq!(
+        target.documentation.as_deref(),
+        Some("Searches the issue tracker for matching tickets")
+    );
+}
+
+/// Builds each function from the definition it binds, as `McpClient` does,
+/// so the function's documentation is whatever the binding passed along.
+#[derive(Debug)]
+struct DefinedFunction {
+    definition: crate::types::ExternalFunctionDefinition,
+}
+
+#[async_trait]
+impl NativeFunction for DefinedFunction {
+    fn name(&self) -> &str {
+        &self.definition.name
+    }
+
+    fn parameters(&self) -> &[Parameter] {
+        &self.definition.parameters
+    }
+
+    fn return_type(&self) -> &Type {
+        &self.definition.return_type
+    }
+
+    async fn execute(&self, _args: Vec<ExpressionValue>) -> Result<ExpressionValue, String> {
+        Ok(ExpressionValue::String(self.definition.name.clone()))
+    }
+
+    fn documentation(&self) -> Option<&str> {
+        self.definition.documentation.as_deref()
+    }
+}
+
+/// Describes its tools the way an MCP server does, separately from the
+/// program's extern declarations.
+struct DescribedToolProvider;
+
+#[async_trait]
+impl crate::types::FunctionProvider for DescribedToolProvider {
+    async fn list_functions(
+        &self,
+    ) -> Result<Vec<crate::types::ExternalFunctionDefinition>, RuntimeError> {
+        Ok(vec![
+            crate::types::ExternalFunctionDefinition::new_with_docs(
+                "search".to_string(),
+                vec![],
+                Type::string(),
+                Some("Searches the issue tracker for matching tickets".to_string()),
+            ),
+            crate::types::ExternalFunctionDefinition::new(
+                "browse".to_string(),
+                vec![],
+                Type::string(),
+            ),
+        ])
+    }
+
+    async fn create_expression(
+        &self,
+        definition: &crate::types::ExternalFunctionDefinition,
+    ) -> Result<Arc<dyn crate::types::ExecutableFunction>, RuntimeError> {
+        let function = Arc::new(DefinedFunction {
+            definition: definition.clone(),
+        });
+        Ok(Arc::new(crate::expressions::NativeFunctionExpr::new(
+            function,
+        )))
+    }
+}
+
+#[tokio::test]
+async fn test_select_options_carry_provider_documentation() {
+    let engine = Arc::new(RecordingEngine::default());
+
+    let program_source = r#"
+extern fn search(): String
+extern fn browse(): String
+
+fn main(): String {
+    return select {
+        search() as found => found,
+        browse() as page => page
+    }
+}
+"#;
+
+    let runtime = Runtime::builder(program(program_source))
+        .with_provider(Arc::new(DescribedToolProvider))
+        .with_language_engine(engine.clone())
+        .build();
+
+    let result = runtime.run().await.unwrap();
+    assert_eq!(result, ExpressionValue::String("search".to_string()));
+
+    let offers = engine.select_options.lock().unwrap();
+    assert_eq!(
+        *offers,
+        vec![vec![
+            ExpressionValue::Metadata {
+                name: "search".to_string(),
+                documentation: Some("Searches the issue tracker for matching tickets".to_string()),
+            },
+            ExpressionValue::Metadata {
+                name: "browse".to_string(),
+                documentation: None,
+            },
+        ]]
+    );
+}
+
+#[derive(Debug)]
+struct RecordingCompose {
+    calls: std::sync::Mutex<Vec<Vec<ExpressionValue>>>,
+    parameters: Vec<Parameter>,
+    return_type: Type,
+}
+
+#[async_trait]
+impl NativeFunction for RecordingCompose {
+    fn name(&self) -> &str {
+        "compose"
+    }
+
+    fn parameters(&self) -> &[Parameter] {
+        &self.parameters
+    }
+
+    fn return_type(&self) -> &Type {
+        &self.return_type
+    }
+
+    async fn execute(&self, args: Vec<ExpressionValue>) -> Result<ExpressionValue, String> {
+        self.calls.lock().unwrap().push(args);
+        Ok(ExpressionValue::Unit)
+    }
+}
+
+#[tokio::test]
+async fn test_mixed_call_only_fills_placeholder_positions() {
+    let compose = Arc::new(RecordingCompose {
+        calls: std::sync::Mutex::new(Vec::new()),
+        parameters: vec![
+            Parameter::new("greeting".to_string(), Type::string()),
+            Parameter::new("name".to_string(), Type::string()),
+            Parameter::new("loud".to_string(), Type::boolean()),
+        ],
+        return_type: Type::unit(),
+    });
+    let engine = Arc::new(RecordingEngine::default());
+
+    let program_source = r#"
+extern fn compose(greeting: String, name: String, loud: Boolean): ()
+
+fn main(): () {
+    let greeting = "Hello"
+    compose(greeting, _, true)
+}
+"#;
+
+    let runtime = Runtime::builder(program(program_source))
+        .with_native_function(compose.clone())
+        .with_language_engine(engine.clone())
+        .build();
+
+    runtime.run().await.unwrap();
+
+    let requests = engine.fill_requests.lock().unwrap();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].0, "name");
+    assert_eq!(requests[0].1, Type::string());
+
+    let calls = compose.calls.lock().unwrap();
+    assert_eq!(
+        *calls,
+        vec![vec![
+            ExpressionValue::String("Hello".to_string()),
+            ExpressionValue::String("filled".to_string()),
+            ExpressionValue::Boolean(true),
+        ]]
+    );
+}
+
+type Turn = (EventRole, ExpressionValue);
+
+#[derive(Default)]
+struct ConversationEngine {
+    seen_turns: std::sync::Mutex<Vec<Vec<Turn>>>,
+}
+
+#[async_trait]
+impl crate::types::LanguageEngine for ConversationEngine {
+    async fn untyped(&self, _context: &Context) -> String {
+        String::new()
+    }
+
+    async fn typed(
+        &self,
+        context: &Context,
+        _return_type: &Type,
+    ) -> Result<ExpressionValue, String> {
+        let turns = context
+            .iter_all_events()
+            .map(|event| (event.role, event.content))
+            .collect();
+        self.seen_turns.lock().unwrap().push(turns);
+        Ok(ExpressionValue::String("Sure, here you go".to_string()))
+    }
+
+    async fn select(
+        &self,
+        _context: &Context,
+        _options: &[ExpressionValue],
+    ) -> Result<usize, String> {
+        Ok(0)
+    }
+
+    async fn fill_parameter(
+        &self,
+        _context: &Context,
+        _param_name: &str,
+        _param_type: &Type,
+        _target: Option<&crate::types::PlaceholderTarget>,
+    ) -> Result<ExpressionValue, String> {
+        Ok(ExpressionValue::Unit)
+    }
+}
+
+#[tokio::test]
+async fn test_injected_engine_results_are_model_turns() {
+    let engine = Arc::new(ConversationEngine::default());
+
+    let program_source = r#"
+fn reply(): String {
+}
+
+fn main(): () {
+    "Can you help?"!
+    let first = reply()
+    first!
+    "Tell me more"!
+    let second = reply()
+}
+"#;
+
+    let runtime = Runtime::builder(program(program_source))
+        .with_language_engine(engine.clone())
+        .build();
+
+    runtime.run().await.unwrap();
+
+    let seen_turns = engine.seen_turns.lock().unwrap();
+    assert_eq!(seen_turns.len(), 2);
+    let text = |value: &str| ExpressionValue::String(value.to_string());
+    assert_eq!(
+        seen_turns[1],
+        vec![
+            (EventRole::User, text("Can you help?")),
+            (EventRole::Model, text("Sure, here you go")),
+            (EventRole::User, text("Tell me more")),
+            (EventRole::User, text("## reply")),
+        ]
+    );
+}
+
+struct ConstantProvider {
+    value: ExpressionValue,
+}
+
+#[async_trait]
+impl crate::types::FunctionProvider for ConstantProvider {
+    async fn list_functions(
+        &self,
+    ) -> Result<Vec<crate::types::ExternalFunctionDefinition>, RuntimeError> {
+        Ok(vec![])
+    }
+
+    async fn create_expression(
+        &self,
+        definition: &crate::types::ExternalFunctionDefinition,
+    ) -> Result<Arc<dyn crate::types::ExecutableFunction>, RuntimeError> {
+        Err(RuntimeError::FunctionNotFound(definition.name.clone()))
+    }
+
+    async fn resolve_constant(
+        &self,
+        definition: &crate::types::ExternalConstDefinition,
+    ) -> Result<Option<ExpressionValue>, RuntimeError> {
+        if definition.name == "schema" {
+            Ok(Some(self.value.clone()))
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_extern_const_resolved_from_provider() {
+    let program_source = r#"
+extern const schema: String
+
+fn describe(): String {
+    return schema
+}
+
+fn main(): String {
+    schema!
+    return describe()
+}
+"#;
+
+    let runtime = Runtime::builder(program(program_source))
+        .with_provider(Arc::new(ConstantProvider {
+            value: ExpressionValue::String("{\"tables\": []}".to_string()),
+        }))
+        .build();
+
+    let (result, context) = runtime.run_with_context().await.unwrap();
+    assert_eq!(
+        result,
+        ExpressionValue::String("{\"tables\": []}".to_string())
+    );
+
+    let events: Vec<String> = context
+        .iter_all_events()
+        .map(|event| event.content.format_for_llm())
+        .collect();
+    assert_eq!(events, vec!["{\"tables\": []}".to_string()]);
+}
+
+#[tokio::test]
+async fn test_extern_const_type_mismatch_is_rejected() {
+    let program_source = r#"
+extern const schema: String
+
+fn main(): String {
+    return schema
+}
+"#;
+
+    let runtime = Runtime::builder(program(program_source))
+        .with_provider(Arc::new(ConstantProvider {
+            value: ExpressionValue::Boolean(true),
+        }))
+        .build();
+
+    match runtime.run().await.unwrap_err() {
+        RuntimeError::SignatureMismatch { function, details } => {
+            assert_eq!(function, "schema");
+            assert!(details.contains("declared as String"));
+        }
+        other => panic!("Expected SignatureMismatch, got: {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_extern_const_without_provider_fails() {
+    let program_source = r#"
+extern const schema: String
+
+fn main(): String {
+    return schema
+}
+"#;
+
+    let runtime = Runtime::builder(program(program_source)).build();
+
+    assert_eq!(
+        runtime.run().await.unwrap_err(),
+        RuntimeError::NoProvider {
+            function: "schema".to_string()
+        }
+    );
+}
@@ -0,0 +1,255 @@
+use super::*;
+use crate::compiler::CompilationUnit;
+use crate::types::{NativeFunction, Parameter, Type};
+use arrow::array::{BooleanBuilder, ListBuilder, StringBuilder};
+use async_trait::async_trait;
+use std::sync::Arc;
+
+#[derive(Debug)]
+struct ConstantFunction {
+    name: &'static str,
+    value: ExpressionValue,
+    return_type: Type,
+}
+
+#[async_trait]
+impl NativeFunction for ConstantFunction {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn parameters(&self) -> &[Parameter] {
+        &[]
+    }
+
+    fn return_type(&self) -> &Type {
+        &self.return_type
+    }
+
+    async fn execute(&self, _args: Vec<ExpressionValue>) -> Result<ExpressionValue, String> {
+        Ok(self.value.clone())
+    }
+}
+
+fn string_list(items: &[&str]) -> ExpressionValue {
+    let mut builder = ListBuilder::new(StringBuilder::new());
+    for item in items {
+        builder.values().append_value(item);
+    }
+    builder.append(true);
+    ExpressionValue::List(Arc::new(builder.finish()))
+}
+
+fn boolean_list(items: &[bool]) -> ExpressionValue {
+    let mut builder = ListBuilder::new(BooleanBuilder::new());
+    for item in items {
+        builder.values().append_value(*item);
+    }
+    builder.append(true);
+    ExpressionValue::List(Arc::new(builder.finish()))
+}
+
+async fn injected_events(source: &str, functions: Vec<ConstantFunction>) -> Vec<String> {
+    let mut builder = Runtime::builder(CompilationUnit::from_string(source.to_string()));
+    for function in functions {
+        builder = builder.with_native_function(Arc::new(function));
+    }
+
+    let (_, context) = builder.build().run_with_context().await.unwrap();
+    context
+        .iter_all_events()
+        .map(|event| event.content.format_for_llm())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_injected_booleans_render_bare() {
+    let events = injected_events(
+        r#"
+extern fn is_ready(): Boolean
+
+fn main(): () {
+    true!
+    is_ready()!
+}
+"#,
+        vec![ConstantFunction {
+            name: "is_ready",
+            value: ExpressionValue::Boolean(false),
+            return_type: Type::boolean(),
+        }],
+    )
+    .await;
+
+    assert_eq!(events, vec!["true", "false"]);
+}
+
+#[tokio::test]
+async fn test_injected_lists_render_their_items() {
+    let events = injected_events(
+        r#"
+extern fn labels(): List<String>
+extern fn checks(): List<Boolean>
+
+fn main(): () {
+    labels()!
+    checks()!
+}
+"#,
+        vec![
+            ConstantFunction {
+                name: "labels",
+                value: string_list(&["bug", "ui"]),
+                return_type: Type::list(Type::string()),
+            },
+            ConstantFunction {
+                name: "checks",
+                value: boolean_list(&[true, false]),
+                return_type: Type::list(Type::boolean()),
+            },
+        ],
+    )
+    .await;
+
+    assert_eq!(events, vec!["[\"bug\", \"ui\"]", "[true, false]"]);
+}
+
+#[tokio::test]
+async fn test_injecting_an_unrenderable_value_is_an_error() {
+    let source = r#"
+extern fn groups(): List<List<String>>
+
+fn main(): () {
+    groups()!
+}
+"#;
+    let mut builder = ListBuilder::new(ListBuilder::new(StringBuilder::new()));
+    builder.values().values().append_value("bug");
+    builder.values().append(true);
+    builder.append(true);
+
+    let runtime = Runtime::builder(CompilationUnit::from_string(source.to_string()))
+        .with_native_function(Arc::new(ConstantFunction {
+            name: "groups",
+            value: ExpressionValue::List(Arc::new(builder.finish())),
+            return_type: Type::list(Type::list(Type::string())),
+        }))
+        .build();
+
+    let start = source.find("groups()!").unwrap();
+    let span = format!("{}..{}", start, start + "groups()".len());
+    match runtime.run().await {
+        Err(RuntimeError::ExecutionError(message)) => {
+            assert!(
+                message.contains("Cannot inject List value"),
+                "unexpected error: {message}"
+            );
+            assert!(message.contains(&span), "unexpected error: {message}");
+        }
+        other => panic!("Expected an injection error, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_list_display_matches_prompt_rendering() {
+    let list = string_list(&["a", "b"]);
+
+    assert_eq!(list.to_string(), "[\"a\", \"b\"]");
+    assert_eq!(list.to_string(), list.format_for_llm());
+    assert_eq!(string_list(&[]).to_string(), "[]");
+}
+
+#[tokio::test]
+async fn test_export_jsonl_writes_one_record_per_event() {
+    let source = r#"
+extern fn labels(): List<String>
+
+fn main(): () {
+    "hello"!
+    true!
+    labels()!
+}
+"#;
+    let runtime = Runtime::builder(CompilationUnit::from_string(source.to_string()))
+        .with_native_function(Arc::new(ConstantFunction {
+            name: "labels",
+            value: string_list(&["bug", "ui"]),
+            return_type: Type::list(Type::string()),
+        }))
+        .build();
+    let (_, context) = runtime.run_with_context().await.unwrap();
+
+    let mut output = Vec::new();
+    context.export_jsonl(&mut output).unwrap();
+
+    let records: Vec<serde_json::Value> = String::from_utf8(output)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(records.len(), 3);
+    // Sequence numbers are shared with events in nested calls, so they
+    // increase but may skip the numbers of events that were not kept.
+    let seqs: Vec<u64> = records
+        .iter()
+        .map(|record| record["seq"].as_u64().unwrap())
+        .collect();
+    assert!(seqs.windows(2).all(|pair| pair[0] < pair[1]), "{seqs:?}");
+    for record in &records {
+        assert_eq!(record["role"], "user");
+        for field in ["timestamp_ms", "function", "params"] {
+            assert!(record.get(field).is_some(), "missing {field}: {record}");
+        }
+    }
+    let contents: Vec<(&str, &serde_json::Value)> = records
+        .iter()
+        .map(|record| (record["type"].as_str().unwrap(), &record["content"]))
+        .collect();
+    assert_eq!(
+        contents,
+        vec![
+            ("String", &serde_json::json!("hello")),
+            ("Boolean", &serde_json::json!(true)),
+            ("List", &serde_json::json!(["bug", "ui"])),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_events_as_json_round_trips_the_event_log() {
+    let source = r#"
+extern fn labels(): List<String>
+
+fn main(): () {
+    "hello"!
+    labels()!
+}
+"#;
+    let runtime = Runtime::builder(CompilationUnit::from_string(source.to_string()))
+        .with_native_function(Arc::new(ConstantFunction {
+            name: "labels",
+            value: string_list(&["bug", "ui"]),
+            return_type: Type::list(Type::string()),
+        }))
+        .build();
+    let (_, context) = runtime.run_with_context().await.unwrap();
+
+    let json = context.events_as_json();
+
+    let records = json.as_array().unwrap();
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[1]["content"], serde_json::json!(["bug", "ui"]));
+    let events: Vec<Event> = records
+        .iter()
+        .map(|record| Event::from_json(record).unwrap())
+        .collect();
+    assert_eq!(serde_json::to_value(&events).unwrap(), json);
+    assert_eq!(
+        serde_json::to_value(context.iter_all_events().collect::<Vec<_>>()).unwrap(),
+        json
+    );
+    assert_eq!(
+        serde_json::to_value(string_list(&["a"])).unwrap(),
+        serde_json::json!(["a"])
+    );
+}
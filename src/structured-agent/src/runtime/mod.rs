@@ -1,6 +1,8 @@
 mod context;
 mod engine;
 mod native_provider;
+mod stats;
+mod template;
 mod types;
 
 #[cfg(test)]
@@ -18,7 +20,59 @@ mod control_flow_test;
 #[cfg(test)]
 mod signature_mismatch_test;
 
-pub use context::{Context, Event};
-pub use engine::{Runtime, RuntimeBuilder, RuntimeError, load_program};
-pub use native_provider::NativeFunctionProvider;
-pub use types::{ExpressionParameter, ExpressionResult, ExpressionValue};
+#[cfg(test)]
+mod template_test;
+
+#[cfg(test)]
+mod eval_expression_test;
+
+#[cfg(test)]
+mod field_access_test;
+
+#[cfg(test)]
+mod ranked_select_test;
+
+#[cfg(test)]
+mod retry_select_test;
+
+#[cfg(test)]
+mod value_conversion_test;
+
+#[cfg(test)]
+mod provider_cache_test;
+
+#[cfg(test)]
+mod injection_rendering_test;
+
+#[cfg(test)]
+mod number_test;
+
+#[cfg(test)]
+mod cancellation_test;
+
+#[cfg(test)]
+mod saved_context_test;
+
+#[cfg(test)]
+mod batch_test;
+
+#[cfg(test)]
+mod destructive_confirmation_test;
+
+#[cfg(test)]
+mod call_depth_test;
+
+#[cfg(test)]
+mod run_stats_test;
+
+#[cfg(test)]
+mod run_function_test;
+
+pub use context::{Context, ContextBuilder, Event, EventHandle, InitialContext, SavedContext};
+pub use engine::{
+    BindingOutcome, FunctionBinding, Runtime, RuntimeBuilder, RuntimeError, load_program,
+};
+pub use native_provider::{ClosureFunction, NativeFunctionProvider};
+pub use stats::RunStats;
+pub use template::InjectionTemplate;
+pub use types::{EventRole, ExpressionParameter, ExpressionResult, ExpressionValue};
@@ -1,20 +1,21 @@
 use crate::expressions::NativeFunctionExpr;
-use crate::runtime::RuntimeError;
+use crate::runtime::{ExpressionValue, RuntimeError};
 use crate::types::{
-    ExecutableFunction, ExternalFunctionDefinition, FunctionProvider, NativeFunction,
+    ExecutableFunction, ExternalFunctionDefinition, FunctionProvider, NativeFunction, Parameter,
+    Type,
 };
 use async_trait::async_trait;
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 use std::sync::Arc;
 
 pub struct NativeFunctionProvider {
-    pub(crate) native_functions: HashMap<String, Arc<dyn ExecutableFunction>>,
+    pub(crate) native_functions: BTreeMap<String, Arc<dyn ExecutableFunction>>,
 }
 
 impl NativeFunctionProvider {
     pub fn new() -> Self {
         Self {
-            native_functions: HashMap::new(),
+            native_functions: BTreeMap::new(),
         }
     }
 
@@ -33,6 +34,10 @@ impl Default for NativeFunctionProvider {
 
 #[async_trait]
 impl FunctionProvider for NativeFunctionProvider {
+    fn name(&self) -> String {
+        "native".to_string()
+    }
+
     async fn list_functions(&self) -> Result<Vec<ExternalFunctionDefinition>, RuntimeError> {
         let definitions = self
             .native_functions
@@ -64,6 +69,60 @@ impl FunctionProvider for NativeFunctionProvider {
     }
 }
 
+type NativeFn = dyn Fn(Vec<ExpressionValue>) -> Result<ExpressionValue, String> + Send + Sync;
+
+/// A native function backed by a closure, as registered by
+/// [`crate::runtime::RuntimeBuilder::with_native_fn`].
+pub struct ClosureFunction {
+    name: String,
+    parameters: Vec<Parameter>,
+    return_type: Type,
+    function: Box<NativeFn>,
+}
+
+impl ClosureFunction {
+    pub fn new<F>(name: &str, parameters: Vec<Parameter>, return_type: Type, function: F) -> Self
+    where
+        F: Fn(Vec<ExpressionValue>) -> Result<ExpressionValue, String> + Send + Sync + 'static,
+    {
+        Self {
+            name: name.to_string(),
+            parameters,
+            return_type,
+            function: Box::new(function),
+        }
+    }
+}
+
+impl std::fmt::Debug for ClosureFunction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ClosureFunction")
+            .field("name", &self.name)
+            .field("parameters", &self.parameters)
+            .field("return_type", &self.return_type)
+            .finish_non_exhaustive()
+    }
+}
+
+#[async_trait]
+impl NativeFunction for ClosureFunction {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn parameters(&self) -> &[Parameter] {
+        &self.parameters
+    }
+
+    fn return_type(&self) -> &Type {
+        &self.return_type
+    }
+
+    async fn execute(&self, args: Vec<ExpressionValue>) -> Result<ExpressionValue, String> {
+        (self.function)(args)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,100 @@
+use super::*;
+use crate::compiler::CompilationUnit;
+use crate::functions::{ParseIntFunction, ToStringFunction};
+use std::sync::Arc;
+
+fn runtime(source: &str) -> Runtime {
+    Runtime::builder(CompilationUnit::from_string(source.to_string())).build()
+}
+
+fn runtime_with_conversions(source: &str) -> Runtime {
+    Runtime::builder(CompilationUnit::from_string(source.to_string()))
+        .with_native_function(Arc::new(ToStringFunction::new()))
+        .with_native_function(Arc::new(ParseIntFunction::new()))
+        .build()
+}
+
+#[tokio::test]
+async fn test_number_literals_flow_through_calls_and_branches() {
+    let program = r#"
+fn pick(high: Boolean, low: Number): Number {
+    return if high { 2.5 } else { low }
+}
+
+fn main(): Number {
+    let low = -7
+    return pick(false, low)
+}
+"#;
+
+    let result = runtime(program).run().await;
+
+    assert_eq!(result, Ok(ExpressionValue::Number(-7.0)));
+}
+
+#[test]
+fn test_number_values_render_without_trailing_zeros() {
+    assert_eq!(ExpressionValue::Number(42.0).value_string(), "42");
+    assert_eq!(ExpressionValue::Number(2.5).value_string(), "2.5");
+    assert_eq!(
+        ExpressionValue::Number(42.0).to_json(),
+        serde_json::json!(42)
+    );
+}
+
+#[tokio::test]
+async fn test_parse_int_reads_a_whole_number() {
+    let program = r#"
+extern fn parse_int(text: String): Option<Number>
+
+fn main(): Option<Number> {
+    return parse_int("42")
+}
+"#;
+
+    let result = runtime_with_conversions(program).run().await;
+
+    assert_eq!(
+        result,
+        Ok(ExpressionValue::Option(Some(Box::new(
+            ExpressionValue::Number(42.0)
+        ))))
+    );
+}
+
+#[tokio::test]
+async fn test_to_string_and_parse_int_round_trip() {
+    let program = r#"
+extern fn to_string(value: Number): String
+extern fn parse_int(text: String): Option<Number>
+
+fn main(): Option<Number> {
+    let text = to_string(42)
+    return parse_int(text)
+}
+"#;
+
+    let result = runtime_with_conversions(program).run().await;
+
+    assert_eq!(
+        result,
+        Ok(ExpressionValue::Option(Some(Box::new(
+            ExpressionValue::Number(42.0)
+        ))))
+    );
+}
+
+#[tokio::test]
+async fn test_parse_int_of_text_is_none() {
+    let program = r#"
+extern fn parse_int(text: String): Option<Number>
+
+fn main(): Option<Number> {
+    return parse_int("abc")
+}
+"#;
+
+    let result = runtime_with_conversions(program).run().await;
+
+    assert_eq!(result, Ok(ExpressionValue::Option(None)));
+}
@@ -0,0 +1,128 @@
+use super::*;
+use crate::compiler::CompilationUnit;
+use crate::types::{
+    ExecutableFunction, ExternalFunctionDefinition, FunctionProvider, NativeFunction, Parameter,
+    Type,
+};
+use async_trait::async_trait;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+const PROGRAM: &str = r#"
+extern fn greet(): String
+
+fn main(): String {
+    return greet()
+}
+"#;
+
+#[derive(Debug)]
+struct GreetFunction {
+    return_type: Type,
+}
+
+#[async_trait]
+impl NativeFunction for GreetFunction {
+    fn name(&self) -> &str {
+        "greet"
+    }
+
+    fn parameters(&self) -> &[Parameter] {
+        &[]
+    }
+
+    fn return_type(&self) -> &Type {
+        &self.return_type
+    }
+
+    async fn execute(&self, _args: Vec<ExpressionValue>) -> Result<ExpressionValue, String> {
+        Ok(ExpressionValue::String("hello".to_string()))
+    }
+}
+
+struct CountingProvider {
+    inner: NativeFunctionProvider,
+    list_calls: Arc<AtomicUsize>,
+}
+
+impl CountingProvider {
+    fn new(list_calls: Arc<AtomicUsize>) -> Self {
+        let mut inner = NativeFunctionProvider::new();
+        inner.add_function(Arc::new(GreetFunction {
+            return_type: Type::string(),
+        }));
+        Self { inner, list_calls }
+    }
+}
+
+#[async_trait]
+impl FunctionProvider for CountingProvider {
+    fn name(&self) -> String {
+        "counting".to_string()
+    }
+
+    async fn list_functions(&self) -> Result<Vec<ExternalFunctionDefinition>, RuntimeError> {
+        self.list_calls.fetch_add(1, Ordering::SeqCst);
+        self.inner.list_functions().await
+    }
+
+    async fn create_expression(
+        &self,
+        definition: &ExternalFunctionDefinition,
+    ) -> Result<Arc<dyn ExecutableFunction>, RuntimeError> {
+        self.inner.create_expression(definition).await
+    }
+}
+
+fn counting_runtime(caching: bool) -> (Runtime, Arc<AtomicUsize>) {
+    let list_calls = Arc::new(AtomicUsize::new(0));
+    let runtime = Runtime::builder(CompilationUnit::from_string(PROGRAM.to_string()))
+        .with_provider(Arc::new(CountingProvider::new(list_calls.clone())))
+        .with_provider_caching(caching)
+        .build();
+    (runtime, list_calls)
+}
+
+#[tokio::test]
+async fn test_cached_bindings_list_functions_once_across_runs() {
+    let (runtime, list_calls) = counting_runtime(true);
+
+    for _ in 0..2 {
+        let result = runtime.run().await;
+        assert_eq!(result, Ok(ExpressionValue::String("hello".to_string())));
+    }
+
+    assert_eq!(list_calls.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn test_uncached_bindings_list_functions_every_run() {
+    let (runtime, list_calls) = counting_runtime(false);
+
+    runtime.run().await.unwrap();
+    runtime.run().await.unwrap();
+
+    assert_eq!(list_calls.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn test_invalidated_bindings_list_functions_again() {
+    let (runtime, list_calls) = counting_runtime(true);
+
+    runtime.run().await.unwrap();
+    runtime.invalidate_provider_bindings().await;
+    runtime.run().await.unwrap();
+
+    assert_eq!(list_calls.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn test_concurrent_first_runs_bind_once() {
+    let (runtime, list_calls) = counting_runtime(true);
+
+    let (first, second) = tokio::join!(runtime.run(), runtime.run());
+    first.unwrap();
+    second.unwrap();
+
+    assert_eq!(list_calls.load(Ordering::SeqCst), 1);
+}
@@ -0,0 +1,316 @@
+use super::*;
+use crate::compiler::CompilationUnit;
+use crate::runtime::ExpressionValue;
+use crate::types::{LanguageEngine, NativeFunction, Parameter, Type};
+use async_trait::async_trait;
+use std::sync::{Arc, Mutex};
+
+fn program(source: &str) -> CompilationUnit {
+    CompilationUnit::from_string(source.to_string())
+}
+
+#[derive(Debug)]
+struct LookupFunction {
+    name: String,
+    result: Option<String>,
+    calls: Arc<Mutex<Vec<String>>>,
+    parameters: Vec<Parameter>,
+    return_type: Type,
+}
+
+impl LookupFunction {
+    fn new(name: &str, result: Option<&str>, calls: Arc<Mutex<Vec<String>>>) -> Arc<Self> {
+        Arc::new(Self {
+            name: name.to_string(),
+            result: result.map(|value| value.to_string()),
+            calls,
+            parameters: vec![],
+            return_type: Type::option(Type::string()),
+        })
+    }
+}
+
+#[async_trait]
+impl NativeFunction for LookupFunction {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn parameters(&self) -> &[Parameter] {
+        &self.parameters
+    }
+
+    fn return_type(&self) -> &Type {
+        &self.return_type
+    }
+
+    async fn execute(&self, _args: Vec<ExpressionValue>) -> Result<ExpressionValue, String> {
+        self.calls.lock().unwrap().push(self.name.clone());
+        Ok(ExpressionValue::Option(
+            self.result
+                .clone()
+                .map(|value| Box::new(ExpressionValue::String(value))),
+        ))
+    }
+}
+
+struct RankingEngine {
+    ranking: Vec<usize>,
+    rank_requests: Mutex<Vec<Vec<String>>>,
+}
+
+impl RankingEngine {
+    fn new(ranking: Vec<usize>) -> Arc<Self> {
+        Arc::new(Self {
+            ranking,
+            rank_requests: Mutex::new(Vec::new()),
+        })
+    }
+}
+
+#[async_trait]
+impl LanguageEngine for RankingEngine {
+    async fn untyped(&self, _context: &Context) -> String {
+        String::new()
+    }
+
+    async fn typed(
+        &self,
+        _context: &Context,
+        _return_type: &Type,
+    ) -> Result<ExpressionValue, String> {
+        Ok(ExpressionValue::Unit)
+    }
+
+    async fn select(
+        &self,
+        _context: &Context,
+        _options: &[ExpressionValue],
+    ) -> Result<usize, String> {
+        Ok(1)
+    }
+
+    async fn rank(
+        &self,
+        _context: &Context,
+        options: &[ExpressionValue],
+    ) -> Result<Vec<usize>, String> {
+        let names = options
+            .iter()
+            .map(|option| match option {
+                ExpressionValue::Metadata { name, .. } => name.clone(),
+                other => other.format_for_llm(),
+            })
+            .collect();
+        self.rank_requests.lock().unwrap().push(names);
+        Ok(self.ranking.clone())
+    }
+
+    async fn fill_parameter(
+        &self,
+        _context: &Context,
+        _param_name: &str,
+        _param_type: &Type,
+        _target: Option<&crate::types::PlaceholderTarget>,
+    ) -> Result<ExpressionValue, String> {
+        Ok(ExpressionValue::Unit)
+    }
+}
+
+const LOOKUP_PROGRAM: &str = r#"
+extern fn from_cache(): Option<String>
+extern fn from_index(): Option<String>
+extern fn from_web(): Option<String>
+
+fn main(): Option<String> {
+    return select ranked {
+        from_cache() as hit => hit,
+        from_index() as hit => hit,
+        from_web() as hit => hit
+    }
+}
+"#;
+
+async fn run_lookup(
+    engine: Arc<RankingEngine>,
+    results: [Option<&str>; 3],
+) -> (Result<ExpressionValue, RuntimeError>, Vec<String>) {
+    let calls = Arc::new(Mutex::new(Vec::new()));
+    let runtime = Runtime::builder(program(LOOKUP_PROGRAM))
+        .with_native_function(LookupFunction::new("from_cache", results[0], calls.clone()))
+        .with_native_function(LookupFunction::new("from_index", results[1], calls.clone()))
+        .with_native_function(LookupFunction::new("from_web", results[2], calls.clone()))
+        .with_language_engine(engine)
+        .build();
+
+    let result = runtime.run().await;
+    let calls = calls.lock().unwrap().clone();
+    (result, calls)
+}
+
+#[tokio::test]
+async fn test_ranked_select_tries_clauses_in_engine_order() {
+    let engine = RankingEngine::new(vec![2, 0, 1]);
+
+    let (result, calls) = run_lookup(engine.clone(), [Some("cached"), Some("indexed"), None]).await;
+
+    assert_eq!(
+        result,
+        Ok(ExpressionValue::Option(Some(Box::new(
+            ExpressionValue::String("cached".to_string())
+        ))))
+    );
+    assert_eq!(calls, vec!["from_web", "from_cache"]);
+    assert_eq!(
+        engine.rank_requests.lock().unwrap().clone(),
+        vec![vec!["from_cache", "from_index", "from_web"]]
+    );
+}
+
+#[tokio::test]
+async fn test_ranked_select_appends_unranked_clauses_and_returns_none_when_all_are_empty() {
+    let engine = RankingEngine::new(vec![1]);
+
+    let (result, calls) = run_lookup(engine, [None, None, None]).await;
+
+    assert_eq!(result, Ok(ExpressionValue::Option(None)));
+    assert_eq!(calls, vec!["from_index", "from_cache", "from_web"]);
+}
+
+#[tokio::test]
+async fn test_ranked_select_rejects_out_of_range_ranking() {
+    let engine = RankingEngine::new(vec![3]);
+
+    let (result, calls) = run_lookup(engine, [Some("cached"), None, None]).await;
+
+    let error = result.unwrap_err().to_string();
+    assert!(
+        error.contains("ranked invalid option index: 3"),
+        "unexpected error: {error}"
+    );
+    assert!(calls.is_empty());
+}
+
+#[tokio::test]
+async fn test_plain_select_still_uses_single_pick() {
+    let engine = RankingEngine::new(vec![2, 0, 1]);
+    let calls = Arc::new(Mutex::new(Vec::new()));
+    let source = LOOKUP_PROGRAM.replace("select ranked", "select");
+    let runtime = Runtime::builder(program(&source))
+        .with_native_function(LookupFunction::new("from_cache", None, calls.clone()))
+        .with_native_function(LookupFunction::new(
+            "from_index",
+            Some("indexed"),
+            calls.clone(),
+        ))
+        .with_native_function(LookupFunction::new(
+            "from_web",
+            Some("fetched"),
+            calls.clone(),
+        ))
+        .with_language_engine(engine.clone())
+        .build();
+
+    let result = runtime.run().await;
+
+    assert_eq!(
+        result,
+        Ok(ExpressionValue::Option(Some(Box::new(
+            ExpressionValue::String("indexed".to_string())
+        ))))
+    );
+    assert_eq!(calls.lock().unwrap().clone(), vec!["from_index"]);
+    assert!(engine.rank_requests.lock().unwrap().is_empty());
+}
+
+const GUARDED_PROGRAM: &str = r#"
+extern fn from_cache(): Option<String>
+extern fn from_index(): Option<String>
+extern fn from_web(): Option<String>
+
+fn main(): Option<String> {
+    let use_cache = false
+    return select ranked {
+        from_cache() as hit if use_cache => hit,
+        from_index() as hit => hit,
+        from_web() as hit => hit
+    }
+}
+"#;
+
+fn guarded_runtime(
+    source: &str,
+    engine: Arc<RankingEngine>,
+    calls: Arc<Mutex<Vec<String>>>,
+) -> Runtime {
+    Runtime::builder(program(source))
+        .with_native_function(LookupFunction::new(
+            "from_cache",
+            Some("cached"),
+            calls.clone(),
+        ))
+        .with_native_function(LookupFunction::new("from_index", None, calls.clone()))
+        .with_native_function(LookupFunction::new("from_web", Some("fetched"), calls))
+        .with_language_engine(engine)
+        .build()
+}
+
+#[tokio::test]
+async fn test_ranked_select_does_not_offer_clauses_with_false_guards() {
+    let engine = RankingEngine::new(vec![1, 0]);
+    let calls = Arc::new(Mutex::new(Vec::new()));
+
+    let result = guarded_runtime(GUARDED_PROGRAM, engine.clone(), calls.clone())
+        .run()
+        .await;
+
+    assert_eq!(
+        result,
+        Ok(ExpressionValue::Option(Some(Box::new(
+            ExpressionValue::String("fetched".to_string())
+        ))))
+    );
+    assert_eq!(calls.lock().unwrap().clone(), vec!["from_web"]);
+    assert_eq!(
+        engine.rank_requests.lock().unwrap().clone(),
+        vec![vec!["from_index", "from_web"]]
+    );
+}
+
+#[tokio::test]
+async fn test_plain_select_maps_pick_back_to_eligible_clause() {
+    let engine = RankingEngine::new(vec![]);
+    let calls = Arc::new(Mutex::new(Vec::new()));
+    let source = GUARDED_PROGRAM.replace("select ranked", "select");
+
+    let result = guarded_runtime(&source, engine, calls.clone()).run().await;
+
+    assert_eq!(
+        result,
+        Ok(ExpressionValue::Option(Some(Box::new(
+            ExpressionValue::String("fetched".to_string())
+        ))))
+    );
+    assert_eq!(calls.lock().unwrap().clone(), vec!["from_web"]);
+}
+
+#[tokio::test]
+async fn test_select_fails_when_every_guard_is_false() {
+    let engine = RankingEngine::new(vec![]);
+    let calls = Arc::new(Mutex::new(Vec::new()));
+    let source = GUARDED_PROGRAM
+        .replace(
+            "from_index() as hit =>",
+            "from_index() as hit if use_cache =>",
+        )
+        .replace("from_web() as hit =>", "from_web() as hit if use_cache =>");
+
+    let result = guarded_runtime(&source, engine, calls.clone()).run().await;
+
+    let error = result.unwrap_err().to_string();
+    assert!(
+        error.contains("No select clause is eligible"),
+        "unexpected error: {error}"
+    );
+    assert!(calls.lock().unwrap().is_empty());
+}
@@ -0,0 +1,171 @@
+use super::*;
+use crate::compiler::CompilationUnit;
+use crate::runtime::ExpressionValue;
+use crate::types::{LanguageEngine, NativeFunction, Parameter, Type};
+use async_trait::async_trait;
+use std::sync::{Arc, Mutex};
+
+#[derive(Debug)]
+struct FetchFunction {
+    name: String,
+    result: Result<String, String>,
+    parameters: Vec<Parameter>,
+    return_type: Type,
+}
+
+impl FetchFunction {
+    fn new(name: &str, result: Result<&str, &str>) -> Arc<Self> {
+        Arc::new(Self {
+            name: name.to_string(),
+            result: result.map(str::to_string).map_err(str::to_string),
+            parameters: vec![],
+            return_type: Type::string(),
+        })
+    }
+}
+
+#[async_trait]
+impl NativeFunction for FetchFunction {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn parameters(&self) -> &[Parameter] {
+        &self.parameters
+    }
+
+    fn return_type(&self) -> &Type {
+        &self.return_type
+    }
+
+    async fn execute(&self, _args: Vec<ExpressionValue>) -> Result<ExpressionValue, String> {
+        self.result.clone().map(ExpressionValue::String)
+    }
+}
+
+/// Always picks the first clause it is offered, recording each offer.
+struct FirstChoiceEngine {
+    offers: Mutex<Vec<Vec<String>>>,
+}
+
+#[async_trait]
+impl LanguageEngine for FirstChoiceEngine {
+    async fn untyped(&self, _context: &Context) -> String {
+        String::new()
+    }
+
+    async fn typed(
+        &self,
+        _context: &Context,
+        _return_type: &Type,
+    ) -> Result<ExpressionValue, String> {
+        Ok(ExpressionValue::Unit)
+    }
+
+    async fn select(
+        &self,
+        _context: &Context,
+        options: &[ExpressionValue],
+    ) -> Result<usize, String> {
+        let names = options
+            .iter()
+            .map(|option| match option {
+                ExpressionValue::Metadata { name, .. } => name.clone(),
+                other => other.format_for_llm(),
+            })
+            .collect();
+        self.offers.lock().unwrap().push(names);
+        Ok(0)
+    }
+
+    async fn fill_parameter(
+        &self,
+        _context: &Context,
+        _param_name: &str,
+        _param_type: &Type,
+        _target: Option<&crate::types::PlaceholderTarget>,
+    ) -> Result<ExpressionValue, String> {
+        Ok(ExpressionValue::Unit)
+    }
+}
+
+const FETCH_PROGRAM: &str = r#"
+extern fn from_cache(): String
+extern fn from_index(): String
+extern fn from_web(): String
+
+fn main(): String {
+    return select retry {
+        from_cache() as page => page,
+        from_index() as page => page,
+        from_web() as page => page
+    }
+}
+"#;
+
+async fn run_fetch(
+    source: &str,
+    results: [Result<&str, &str>; 3],
+) -> (Result<ExpressionValue, RuntimeError>, Vec<Vec<String>>) {
+    let engine = Arc::new(FirstChoiceEngine {
+        offers: Mutex::new(Vec::new()),
+    });
+    let runtime = Runtime::builder(CompilationUnit::from_string(source.to_string()))
+        .with_native_function(FetchFunction::new("from_cache", results[0]))
+        .with_native_function(FetchFunction::new("from_index", results[1]))
+        .with_native_function(FetchFunction::new("from_web", results[2]))
+        .with_language_engine(engine.clone())
+        .build();
+
+    let result = runtime.run().await;
+    let offers = engine.offers.lock().unwrap().clone();
+    (result, offers)
+}
+
+#[tokio::test]
+async fn test_retry_select_excludes_failed_clause_and_chooses_again() {
+    let (result, offers) = run_fetch(
+        FETCH_PROGRAM,
+        [Err("cache is down"), Ok("indexed"), Ok("fetched")],
+    )
+    .await;
+
+    assert_eq!(result, Ok(ExpressionValue::String("indexed".to_string())));
+    assert_eq!(
+        offers,
+        vec![
+            vec!["from_cache", "from_index", "from_web"],
+            vec!["from_index", "from_web"],
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_retry_select_fails_once_every_clause_has_failed() {
+    let (result, offers) = run_fetch(
+        FETCH_PROGRAM,
+        [Err("cache is down"), Err("index is down"), Err("offline")],
+    )
+    .await;
+
+    let error = result.unwrap_err().to_string();
+    assert!(
+        error.contains("Every select clause failed") && error.contains("offline"),
+        "unexpected error: {error}"
+    );
+    assert_eq!(offers.len(), 3);
+}
+
+#[tokio::test]
+async fn test_plain_select_does_not_retry() {
+    let source = FETCH_PROGRAM.replace("select retry", "select");
+
+    let (result, offers) = run_fetch(
+        &source,
+        [Err("cache is down"), Ok("indexed"), Ok("fetched")],
+    )
+    .await;
+
+    assert!(result.unwrap_err().to_string().contains("cache is down"));
+    assert_eq!(offers.len(), 1);
+}
@@ -0,0 +1,85 @@
+use crate::compiler::CompilationUnit;
+use crate::runtime::{ExpressionValue, Runtime, RuntimeError};
+
+// No `main`: each function is run on its own.
+const PROGRAM: &str = r#"
+fn pick(flag: Boolean, yes: String, no: String): String {
+    return if flag { yes } else { no }
+}
+
+fn greeting(): String {
+    return "hello"
+}
+"#;
+
+fn runtime() -> Runtime {
+    Runtime::builder(CompilationUnit::from_string(PROGRAM.to_string())).build()
+}
+
+#[tokio::test]
+async fn test_run_function_passes_arguments_to_parameters() {
+    let result = runtime()
+        .run_function(
+            "pick",
+            vec![
+                ExpressionValue::Boolean(false),
+                ExpressionValue::String("a".to_string()),
+                ExpressionValue::String("b".to_string()),
+            ],
+        )
+        .await;
+
+    assert_eq!(result, Ok(ExpressionValue::String("b".to_string())));
+}
+
+#[tokio::test]
+async fn test_run_function_without_parameters() {
+    let result = runtime().run_function("greeting", vec![]).await;
+
+    assert_eq!(result, Ok(ExpressionValue::String("hello".to_string())));
+}
+
+#[tokio::test]
+async fn test_run_function_reports_unknown_function() {
+    let result = runtime().run_function("missing", vec![]).await;
+
+    assert_eq!(
+        result,
+        Err(RuntimeError::FunctionNotFound("missing".to_string()))
+    );
+}
+
+#[tokio::test]
+async fn test_run_function_rejects_wrong_argument_count() {
+    let result = runtime()
+        .run_function("greeting", vec![ExpressionValue::Unit])
+        .await;
+
+    assert_eq!(
+        result,
+        Err(RuntimeError::InvalidArguments(
+            "greeting expects 0 arguments, got 1".to_string()
+        ))
+    );
+}
+
+#[tokio::test]
+async fn test_run_function_rejects_wrong_argument_type() {
+    let result = runtime()
+        .run_function(
+            "pick",
+            vec![
+                ExpressionValue::String("true".to_string()),
+                ExpressionValue::String("a".to_string()),
+                ExpressionValue::String("b".to_string()),
+            ],
+        )
+        .await;
+
+    assert_eq!(
+        result,
+        Err(RuntimeError::InvalidArguments(
+            "pick expects flag to be Boolean, got String".to_string()
+        ))
+    );
+}
@@ -0,0 +1,108 @@
+use super::*;
+use crate::compiler::CompilationUnit;
+use crate::types::{LanguageEngine, PlaceholderTarget, Type};
+use async_trait::async_trait;
+use std::sync::Arc;
+
+/// Answers every typed request, reporting `tokens_per_call` tokens for each
+/// the way a real engine reports a response's usage metadata.
+struct TokenReportingEngine {
+    tokens_per_call: u64,
+}
+
+#[async_trait]
+impl LanguageEngine for TokenReportingEngine {
+    async fn untyped(&self, _context: &Context) -> String {
+        String::new()
+    }
+
+    async fn typed(
+        &self,
+        context: &Context,
+        _return_type: &Type,
+    ) -> Result<ExpressionValue, String> {
+        context
+            .runtime()
+            .stats()
+            .record_tokens(self.tokens_per_call);
+        Ok(ExpressionValue::String("summary".to_string()))
+    }
+
+    async fn select(
+        &self,
+        _context: &Context,
+        _options: &[ExpressionValue],
+    ) -> Result<usize, String> {
+        Ok(0)
+    }
+
+    async fn fill_parameter(
+        &self,
+        _context: &Context,
+        _param_name: &str,
+        _param_type: &Type,
+        _target: Option<&PlaceholderTarget>,
+    ) -> Result<ExpressionValue, String> {
+        Ok(ExpressionValue::Unit)
+    }
+}
+
+#[tokio::test]
+async fn test_stats_count_engine_calls_and_reported_tokens() {
+    let program = r#"
+fn summarize(topic: String): String {}
+
+fn main(): String {
+    let first = summarize("cats")
+    return summarize(first)
+}
+"#;
+    let runtime = Runtime::builder(CompilationUnit::from_string(program.to_string()))
+        .with_language_engine(Arc::new(TokenReportingEngine {
+            tokens_per_call: 21,
+        }))
+        .build();
+
+    runtime.run().await.unwrap();
+
+    assert_eq!(runtime.stats().engine_calls(), 2);
+    assert_eq!(runtime.stats().tool_calls(), 0);
+    assert_eq!(runtime.stats().tokens(), 42);
+    assert_eq!(
+        runtime.stats().summary(),
+        "2 engine calls, 0 tool calls, 42 tokens"
+    );
+}
+
+#[tokio::test]
+async fn test_stats_start_from_zero_for_each_run() {
+    let program = r#"
+fn summarize(topic: String): String {}
+
+fn main(): String {
+    return summarize("cats")
+}
+"#;
+    let runtime = Runtime::builder(CompilationUnit::from_string(program.to_string()))
+        .with_language_engine(Arc::new(TokenReportingEngine {
+            tokens_per_call: 10,
+        }))
+        .build();
+
+    runtime.run().await.unwrap();
+    runtime.run().await.unwrap();
+
+    assert_eq!(
+        runtime.stats().summary(),
+        "1 engine calls, 0 tool calls, 10 tokens"
+    );
+
+    runtime
+        .run_batch(vec![InitialContext::new(), InitialContext::new()], 2)
+        .await;
+
+    assert_eq!(
+        runtime.stats().summary(),
+        "2 engine calls, 0 tool calls, 20 tokens"
+    );
+}
@@ -0,0 +1,92 @@
+use super::*;
+use crate::compiler::CompilationUnit;
+
+const PROGRAM: &str = r#"
+fn main(): () {
+    "first"!
+    true!
+}
+"#;
+
+fn runtime(program: CompilationUnit) -> Runtime {
+    Runtime::builder(program).build()
+}
+
+async fn saved_after_one_run() -> SavedContext {
+    let (_, context) = runtime(CompilationUnit::from_string(PROGRAM.to_string()))
+        .run_with_context()
+        .await
+        .unwrap();
+    let text = context.save().to_json().to_string();
+    SavedContext::from_json(&serde_json::from_str(&text).unwrap()).unwrap()
+}
+
+#[tokio::test]
+async fn test_resumed_run_sees_saved_events() {
+    let saved = saved_after_one_run().await;
+    assert_eq!(
+        saved.program,
+        CompilationUnit::from_string(PROGRAM.to_string()).fingerprint()
+    );
+    assert_eq!(saved.events.len(), 2);
+
+    let (_, context) = runtime(CompilationUnit::from_string(PROGRAM.to_string()))
+        .run_from(saved)
+        .await
+        .unwrap();
+
+    let events: Vec<(u64, String)> = context
+        .iter_all_events()
+        .map(|event| (event.seq, event.content.format_for_llm()))
+        .collect();
+    assert_eq!(
+        events,
+        vec![
+            (0, "first".to_string()),
+            (1, "true".to_string()),
+            (2, "first".to_string()),
+            (3, "true".to_string()),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_builder_resumed_context_applies_to_every_run() {
+    let saved = saved_after_one_run().await;
+    let runtime = Runtime::builder(CompilationUnit::from_string(PROGRAM.to_string()))
+        .with_resumed_context(saved)
+        .build();
+
+    for _ in 0..2 {
+        let (_, context) = runtime.run_with_context().await.unwrap();
+        assert_eq!(context.iter_all_events().count(), 4);
+    }
+}
+
+#[tokio::test]
+async fn test_saved_context_from_another_program_is_rejected() {
+    let saved = saved_after_one_run().await;
+    let other = CompilationUnit::from_string(PROGRAM.replace("first", "second"));
+    let expected = format!(
+        "Saved context belongs to program '{}', not '{}'",
+        saved.program,
+        other.fingerprint()
+    );
+
+    let result = runtime(other).run_from(saved).await;
+
+    assert_eq!(
+        result.map(|(value, _)| value),
+        Err(RuntimeError::ExecutionError(expected))
+    );
+}
+
+#[tokio::test]
+async fn test_saved_context_resumes_the_same_source_from_a_file() {
+    let saved = saved_after_one_run().await;
+    let same = CompilationUnit::from_file("same.sa".to_string(), PROGRAM.to_string());
+
+    let (_, context) = runtime(same).run_from(saved).await.unwrap();
+
+    assert_eq!(context.iter_all_events().count(), 4);
+}
@@ -253,3 +253,184 @@ async fn test_context_assign_variable_directly() {
         ExpressionValue::String("modified".to_string())
     );
 }
+
+#[tokio::test]
+async fn test_event_sequence_strictly_increasing_across_nested_contexts() {
+    let runtime = Arc::new(test_runtime());
+    let mut context = Context::with_runtime(runtime);
+
+    context.add_event(ExpressionValue::String("root".to_string()), None, None);
+
+    let mut child = context.child().function_scope().build();
+    child.add_event(ExpressionValue::String("child".to_string()), None, None);
+
+    let mut grandchild = child.child().block_scope().build();
+    grandchild.add_event(
+        ExpressionValue::String("grandchild".to_string()),
+        None,
+        None,
+    );
+
+    let nested_seqs: Vec<u64> = grandchild.iter_all_events().map(|e| e.seq).collect();
+    assert_eq!(nested_seqs, vec![0, 1, 2]);
+
+    let child = grandchild.restore_parent().unwrap();
+    let mut context = child.restore_parent().unwrap();
+    context.add_event(ExpressionValue::String("after".to_string()), None, None);
+
+    let seqs: Vec<u64> = context.iter_all_events().map(|e| e.seq).collect();
+    assert_eq!(seqs, vec![0, 3]);
+    assert_eq!(context.next_event_seq(), 4);
+
+    let events: Vec<_> = context.iter_all_events().collect();
+    assert!(events[0].timestamp <= events[1].timestamp);
+}
+
+#[tokio::test]
+async fn test_event_timestamps_come_from_runtime_clock() {
+    use crate::types::MockClock;
+    use std::time::{Duration, SystemTime};
+
+    let clock = Arc::new(MockClock::new(SystemTime::UNIX_EPOCH));
+    let runtime = Runtime::builder(program("fn main(): () {}"))
+        .with_clock(clock.clone())
+        .build();
+    let mut context = Context::with_runtime(Arc::new(runtime));
+
+    context.add_event(ExpressionValue::String("first".to_string()), None, None);
+    clock.advance(Duration::from_secs(30));
+    context.add_event(ExpressionValue::String("second".to_string()), None, None);
+
+    let timestamps: Vec<SystemTime> = context.iter_all_events().map(|e| e.timestamp).collect();
+    assert_eq!(
+        timestamps,
+        vec![
+            SystemTime::UNIX_EPOCH,
+            SystemTime::UNIX_EPOCH + Duration::from_secs(30)
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_function_scope_builder_matches_create_child_boundary() {
+    let runtime = Arc::new(test_runtime());
+    let mut context = Context::with_runtime(runtime);
+    context.declare_variable(
+        "outer".to_string(),
+        ExpressionResult::new(ExpressionValue::String("visible".to_string())),
+    );
+
+    let mut built = context.child().function_scope().build();
+    assert!(built.get_variable("outer").is_none());
+    built.set_return_value(ExpressionResult::new(ExpressionValue::String(
+        "result".to_string(),
+    )));
+    assert!(built.has_return_value());
+
+    let context = built.restore_parent().unwrap();
+    assert!(!context.has_return_value());
+
+    #[allow(deprecated)]
+    let legacy = context.create_child(true);
+    assert!(legacy.get_variable("outer").is_none());
+    let context = legacy.restore_parent().unwrap();
+    assert!(context.get_variable("outer").is_some());
+}
+
+#[tokio::test]
+async fn test_block_scope_builder_shares_parent_variables() {
+    let runtime = Arc::new(test_runtime());
+    let mut context = Context::with_runtime(runtime);
+    context.declare_variable(
+        "outer".to_string(),
+        ExpressionResult::new(ExpressionValue::String("initial".to_string())),
+    );
+
+    let mut block = context.child().block_scope().build();
+    assert!(block.get_variable("outer").is_some());
+    block
+        .assign_variable(
+            "outer".to_string(),
+            ExpressionResult::new(ExpressionValue::String("changed".to_string())),
+        )
+        .unwrap();
+
+    let context = block.restore_parent().unwrap();
+    assert_eq!(
+        context.get_variable("outer").unwrap().value,
+        ExpressionValue::String("changed".to_string())
+    );
+}
+
+#[tokio::test]
+async fn test_streamed_event_accumulates_chunks_into_one_event() {
+    let runtime = Arc::new(test_runtime());
+    let mut context = Context::with_runtime(runtime);
+
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let observed = seen.clone();
+    let mut handle = context.begin_event().with_observer(move |event| {
+        observed.lock().unwrap().push(event.content.clone());
+    });
+    handle.push_chunk("Hello");
+    handle.push_chunk(", ");
+    handle.push_chunk("world");
+    let finished = handle.finish();
+
+    let expected = ExpressionValue::String("Hello, world".to_string());
+    assert_eq!(finished.content, expected);
+    assert_eq!(finished.role, EventRole::Model);
+    assert_eq!(context.events_count(), 1);
+    assert_eq!(context.last_event().unwrap().content, expected);
+    assert_eq!(
+        *seen.lock().unwrap(),
+        vec![
+            ExpressionValue::String("Hello".to_string()),
+            ExpressionValue::String("Hello, ".to_string()),
+            ExpressionValue::String("Hello, world".to_string()),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_module_constants_are_visible_in_every_function() {
+    let source = r#"
+const GREETING = "Hello"
+const NAME = '''World'''
+
+fn greet(): String {
+    return GREETING
+}
+
+fn main(): String {
+    return if true { greet() } else { NAME }
+}
+"#;
+    let runtime = Runtime::builder(program(source)).build();
+
+    let result = runtime.run().await.unwrap();
+
+    assert_eq!(result, ExpressionValue::String("Hello".to_string()));
+}
+
+#[tokio::test]
+async fn test_local_shadowing_leaves_constant_for_other_functions() {
+    let source = r#"
+const GREETING = "Hello"
+
+fn greet(): String {
+    return GREETING
+}
+
+fn main(): String {
+    let GREETING = "Hi"
+    GREETING = "Hey"
+    return greet()
+}
+"#;
+    let runtime = Runtime::builder(program(source)).build();
+
+    let result = runtime.run().await.unwrap();
+
+    assert_eq!(result, ExpressionValue::String("Hello".to_string()));
+}
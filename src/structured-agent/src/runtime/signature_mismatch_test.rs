@@ -1,6 +1,9 @@
 use super::*;
 use crate::compiler::CompilationUnit;
-use crate::types::{NativeFunction, Parameter, Type};
+use crate::types::{
+    ExecutableFunction, ExternalFunctionDefinition, FunctionProvider, NativeFunction, Parameter,
+    Type,
+};
 use async_trait::async_trait;
 use std::sync::Arc;
 
@@ -189,3 +192,297 @@ fn main(): () {
     assert!(error_msg.contains("-> Unit"));
     assert!(error_msg.contains("-> String"));
 }
+
+#[tokio::test]
+async fn test_missing_provider_produces_no_provider_error() {
+    let program_source = r#"
+extern fn log(message: String): ()
+
+fn main(): () {
+    log("test")!
+}
+"#;
+
+    let runtime =
+        Runtime::builder(CompilationUnit::from_string(program_source.to_string())).build();
+
+    let result = runtime.run().await;
+
+    assert_eq!(
+        result.unwrap_err(),
+        RuntimeError::NoProvider {
+            function: "log".to_string()
+        }
+    );
+}
+
+#[tokio::test]
+async fn test_signature_mismatch_produces_signature_mismatch_error() {
+    let program_source = r#"
+extern fn log(message: String): ()
+
+fn main(): () {
+    log("test")!
+}
+"#;
+
+    let runtime = Runtime::builder(CompilationUnit::from_string(program_source.to_string()))
+        .with_native_function(Arc::new(WrongSignatureFunction::new()))
+        .build();
+
+    let result = runtime.run().await;
+
+    match result.unwrap_err() {
+        RuntimeError::SignatureMismatch { function, details } => {
+            assert_eq!(function, "log");
+            assert!(details.contains("wrong_param_name: String"));
+        }
+        other => panic!("Expected SignatureMismatch, got: {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_failing_binding_produces_binding_failed_error() {
+    use crate::types::{ExecutableFunction, ExternalFunctionDefinition, FunctionProvider};
+
+    struct FailingProvider;
+
+    #[async_trait]
+    impl FunctionProvider for FailingProvider {
+        async fn list_functions(&self) -> Result<Vec<ExternalFunctionDefinition>, RuntimeError> {
+            Ok(vec![ExternalFunctionDefinition::new(
+                "log".to_string(),
+                vec![Parameter::new("message".to_string(), Type::string())],
+                Type::unit(),
+            )])
+        }
+
+        async fn create_expression(
+            &self,
+            _definition: &ExternalFunctionDefinition,
+        ) -> Result<Arc<dyn ExecutableFunction>, RuntimeError> {
+            Err(RuntimeError::ExecutionError("connection lost".to_string()))
+        }
+    }
+
+    let program_source = r#"
+extern fn log(message: String): ()
+
+fn main(): () {
+    log("test")!
+}
+"#;
+
+    let runtime = Runtime::builder(CompilationUnit::from_string(program_source.to_string()))
+        .with_provider(Arc::new(FailingProvider))
+        .build();
+
+    let result = runtime.run().await;
+
+    match result.unwrap_err() {
+        RuntimeError::BindingFailed { function, cause } => {
+            assert_eq!(function, "log");
+            assert!(cause.contains("connection lost"));
+        }
+        other => panic!("Expected BindingFailed, got: {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_failing_tool_call_produces_tool_failed_error() {
+    #[derive(Debug)]
+    struct FailingFunction {
+        parameters: Vec<Parameter>,
+        return_type: Type,
+    }
+
+    #[async_trait]
+    impl NativeFunction for FailingFunction {
+        fn name(&self) -> &str {
+            "log"
+        }
+
+        fn parameters(&self) -> &[Parameter] {
+            &self.parameters
+        }
+
+        fn return_type(&self) -> &Type {
+            &self.return_type
+        }
+
+        async fn execute(&self, _args: Vec<ExpressionValue>) -> Result<ExpressionValue, String> {
+            Err("connection lost".to_string())
+        }
+    }
+
+    let program_source = r#"
+extern fn log(message: String): ()
+"#;
+
+    let runtime = Runtime::builder(CompilationUnit::from_string(program_source.to_string()))
+        .with_native_function(Arc::new(FailingFunction {
+            parameters: vec![Parameter::new("message".to_string(), Type::string())],
+            return_type: Type::unit(),
+        }))
+        .build();
+
+    let result = runtime
+        .run_function("log", vec![ExpressionValue::String("test".to_string())])
+        .await;
+
+    match result.unwrap_err() {
+        RuntimeError::ToolFailed { function, cause } => {
+            assert_eq!(function, "log");
+            assert!(cause.contains("connection lost"));
+        }
+        other => panic!("Expected ToolFailed, got: {:?}", other),
+    }
+}
+
+struct MockProvider;
+
+#[async_trait]
+impl FunctionProvider for MockProvider {
+    fn name(&self) -> String {
+        "mock-tools".to_string()
+    }
+
+    async fn list_functions(&self) -> Result<Vec<ExternalFunctionDefinition>, RuntimeError> {
+        Ok(vec![ExternalFunctionDefinition::new(
+            "log".to_string(),
+            vec![Parameter::new("message".to_string(), Type::string())],
+            Type::unit(),
+        )])
+    }
+
+    async fn create_expression(
+        &self,
+        _definition: &ExternalFunctionDefinition,
+    ) -> Result<Arc<dyn ExecutableFunction>, RuntimeError> {
+        panic!("binding checks must not create expressions");
+    }
+}
+
+#[tokio::test]
+async fn test_describe_bindings_names_provider_and_signature() {
+    use crate::runtime::BindingOutcome;
+
+    let program_source = r#"
+extern fn log(message: String): ()
+extern fn missing(): String
+
+fn main(): () {
+    log("test")!
+}
+"#;
+
+    let runtime = Runtime::builder(CompilationUnit::from_string(program_source.to_string()))
+        .with_provider(Arc::new(MockProvider))
+        .build();
+
+    let bindings = runtime.describe_bindings().await.unwrap();
+
+    assert_eq!(bindings.len(), 2);
+
+    assert_eq!(bindings[0].function, "log");
+    assert_eq!(bindings[0].signature, "fn log(message: String) -> Unit");
+    assert_eq!(
+        bindings[0].outcome,
+        BindingOutcome::Bound {
+            provider: "mock-tools".to_string(),
+            signature: "fn log(message: String) -> Unit".to_string(),
+        }
+    );
+    assert!(
+        bindings[0]
+            .to_string()
+            .contains("bound to mock-tools: fn log(message: String) -> Unit")
+    );
+
+    assert_eq!(bindings[1].function, "missing");
+    match &bindings[1].outcome {
+        BindingOutcome::Unbound { reason } => {
+            assert!(reason.contains("No provider found for extern function 'missing'"))
+        }
+        other => panic!("Expected unbound function, got: {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_provider_check_reports_extern_without_provider() {
+    let program_source = r#"
+extern fn log(message: String): ()
+extern fn missing(): String
+
+fn main(): () {
+    log("test")!
+}
+"#;
+
+    let runtime = Runtime::builder(CompilationUnit::from_string(program_source.to_string()))
+        .with_provider(Arc::new(MockProvider))
+        .build();
+
+    let diagnostics = runtime.provider_diagnostics().await.unwrap();
+
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(
+        diagnostics[0].message,
+        "no provider can supply extern function `missing`"
+    );
+    let declaration_start = program_source.find("extern fn missing").unwrap();
+    assert_eq!(diagnostics[0].labels[0].range.start, declaration_start);
+    assert!(diagnostics[0].notes[0].contains("No provider found for extern function 'missing'"));
+
+    let checked = runtime.check_providers().await;
+    assert_eq!(
+        checked,
+        Err(RuntimeError::ExecutionError(
+            "1 extern function(s) have no compatible provider".to_string()
+        ))
+    );
+}
+
+#[tokio::test]
+async fn test_exact_parameter_names_bind() {
+    let program_source = r#"
+extern fn log(message: String): ()
+
+fn main(): () {
+    log("test")!
+}
+"#;
+
+    let runtime = Runtime::builder(CompilationUnit::from_string(program_source.to_string()))
+        .with_provider(Arc::new(MockProvider))
+        .build();
+
+    let bindings = runtime.describe_bindings().await.unwrap();
+
+    assert!(matches!(bindings[0].outcome, BindingOutcome::Bound { .. }));
+}
+
+#[tokio::test]
+async fn test_relaxed_matching_binds_parameter_name_mismatch() {
+    let program_source = r#"
+extern fn log(message: String): ()
+
+fn main(): () {
+    log("test")
+}
+"#;
+
+    let strict = Runtime::builder(CompilationUnit::from_string(program_source.to_string()))
+        .with_native_function(Arc::new(WrongSignatureFunction::new()))
+        .build();
+    assert!(matches!(
+        strict.run().await,
+        Err(RuntimeError::SignatureMismatch { .. })
+    ));
+
+    let relaxed = Runtime::builder(CompilationUnit::from_string(program_source.to_string()))
+        .with_native_function(Arc::new(WrongSignatureFunction::new()))
+        .with_relaxed_signature_matching(true)
+        .build();
+    assert_eq!(relaxed.run().await, Ok(ExpressionValue::Unit));
+}
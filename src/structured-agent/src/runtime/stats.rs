@@ -0,0 +1,55 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Counts what a run asked of the language engine and of MCP tools. Engines
+/// add the tokens each response reports through [`RunStats::record_tokens`].
+#[derive(Debug, Default)]
+pub struct RunStats {
+    engine_calls: AtomicU64,
+    tool_calls: AtomicU64,
+    tokens: AtomicU64,
+}
+
+impl RunStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn reset(&self) {
+        self.engine_calls.store(0, Ordering::Relaxed);
+        self.tool_calls.store(0, Ordering::Relaxed);
+        self.tokens.store(0, Ordering::Relaxed);
+    }
+
+    pub fn record_engine_call(&self) {
+        self.engine_calls.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_tool_call(&self) {
+        self.tool_calls.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_tokens(&self, tokens: u64) {
+        self.tokens.fetch_add(tokens, Ordering::Relaxed);
+    }
+
+    pub fn engine_calls(&self) -> u64 {
+        self.engine_calls.load(Ordering::Relaxed)
+    }
+
+    pub fn tool_calls(&self) -> u64 {
+        self.tool_calls.load(Ordering::Relaxed)
+    }
+
+    pub fn tokens(&self) -> u64 {
+        self.tokens.load(Ordering::Relaxed)
+    }
+
+    pub fn summary(&self) -> String {
+        format!(
+            "{} engine calls, {} tool calls, {} tokens",
+            self.engine_calls(),
+            self.tool_calls(),
+            self.tokens()
+        )
+    }
+}
@@ -0,0 +1,65 @@
+use crate::runtime::Event;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum InjectionTemplate {
+    #[default]
+    Tags,
+    Markdown,
+    Plain,
+}
+
+impl InjectionTemplate {
+    pub fn render(&self, event: &Event) -> String {
+        let content = event.content.format_for_llm();
+
+        let Some(name) = &event.name else {
+            return content;
+        };
+
+        let params: Vec<(&str, String)> = event
+            .params
+            .iter()
+            .flatten()
+            .map(|p| (p.name.as_str(), p.value.format_for_llm()))
+            .collect();
+
+        match self {
+            InjectionTemplate::Tags => {
+                let params_xml = if event.params.is_some() {
+                    let params_str = params
+                        .iter()
+                        .map(|(param, value)| {
+                            format!("    <param name=\"{}\">{}</param>", param, value)
+                        })
+                        .collect::<Vec<_>>()
+                        .join("\n");
+                    format!("{}\n", params_str)
+                } else {
+                    String::new()
+                };
+
+                format!(
+                    "<{}>\n{}    <result>\n    {}\n    </result>\n</{}>",
+                    name, params_xml, content, name
+                )
+            }
+            InjectionTemplate::Markdown => {
+                let params_md: String = params
+                    .iter()
+                    .map(|(param, value)| format!("- `{}`: {}\n", param, value))
+                    .collect();
+
+                format!("### {}\n{}\n{}", name, params_md, content)
+            }
+            InjectionTemplate::Plain => {
+                let params_plain = params
+                    .iter()
+                    .map(|(param, value)| format!("{}: {}", param, value))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+
+                format!("{}({}) = {}", name, params_plain, content)
+            }
+        }
+    }
+}
@@ -0,0 +1,113 @@
+use super::*;
+use crate::compiler::CompilationUnit;
+use crate::types::PrintEngine;
+use std::sync::Arc;
+use std::time::SystemTime;
+
+const PROGRAM: &str = r#"
+fn greet(name: String): String {
+    return "Hello"
+}
+
+fn main(): String {
+    let greeting = greet("World")
+    greeting!
+}
+"#;
+
+fn named_event() -> Event {
+    Event {
+        content: ExpressionValue::String("Hello".to_string()),
+        name: Some("greet".to_string()),
+        params: Some(vec![ExpressionParameter::new(
+            "name".to_string(),
+            ExpressionValue::String("World".to_string()),
+        )]),
+        seq: 0,
+        timestamp: SystemTime::now(),
+        role: EventRole::User,
+    }
+}
+
+async fn run_with_template(template: Option<InjectionTemplate>) -> ExpressionValue {
+    let mut builder = Runtime::builder(CompilationUnit::from_string(PROGRAM.to_string()))
+        .with_language_engine(Arc::new(PrintEngine {}));
+    if let Some(template) = template {
+        builder = builder.with_injection_template(template);
+    }
+    builder.build().run().await.unwrap()
+}
+
+#[test]
+fn test_unnamed_event_renders_content_in_every_template() {
+    let event = Event {
+        name: None,
+        params: None,
+        ..named_event()
+    };
+
+    for template in [
+        InjectionTemplate::Tags,
+        InjectionTemplate::Markdown,
+        InjectionTemplate::Plain,
+    ] {
+        assert_eq!(template.render(&event), "Hello");
+    }
+}
+
+#[test]
+fn test_markdown_template() {
+    assert_eq!(
+        InjectionTemplate::Markdown.render(&named_event()),
+        "### greet\n- `name`: World\n\nHello"
+    );
+}
+
+#[tokio::test]
+async fn test_default_template_keeps_tags() {
+    let result = run_with_template(None).await;
+
+    assert_eq!(
+        result,
+        ExpressionValue::String(
+            "<greet>\n    <param name=\"name\">World</param>\n    <result>\n    Hello\n    </result>\n</greet>"
+                .to_string()
+        )
+    );
+}
+
+#[tokio::test]
+async fn test_plain_template_omits_tags() {
+    let result = run_with_template(Some(InjectionTemplate::Plain)).await;
+
+    assert_eq!(
+        result,
+        ExpressionValue::String("greet(name: World) = Hello".to_string())
+    );
+}
+
+#[test]
+fn test_render_prompt_applies_template_to_every_event() {
+    let runtime = Runtime::builder(CompilationUnit::from_string(PROGRAM.to_string())).build();
+    let mut context = Context::with_runtime(Arc::new(runtime));
+    context.add_event(
+        ExpressionValue::String("Greet the user".to_string()),
+        None,
+        None,
+    );
+    let event = named_event();
+    context.add_event(event.content, event.name, event.params);
+
+    assert_eq!(
+        context.render_prompt(),
+        "Greet the user\n<greet>\n    <param name=\"name\">World</param>\n    <result>\n    Hello\n    </result>\n</greet>"
+    );
+}
+
+#[test]
+fn test_render_prompt_is_empty_without_events() {
+    let runtime = Runtime::builder(CompilationUnit::from_string(String::new())).build();
+    let context = Context::with_runtime(Arc::new(runtime));
+
+    assert_eq!(context.render_prompt(), "");
+}
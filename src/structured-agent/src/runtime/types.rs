@@ -1,4 +1,5 @@
 use arrow::array::{Array, ListArray};
+use serde::{Serialize, Serializer};
 use std::sync::Arc;
 
 #[derive(Debug, Clone, PartialEq)]
@@ -6,6 +7,16 @@ pub struct ExpressionResult {
     pub name: Option<String>,
     pub params: Option<Vec<ExpressionParameter>>,
     pub value: ExpressionValue,
+    pub role: EventRole,
+}
+
+/// Who a value, and the event it becomes when injected, came from: the
+/// program itself or the language engine.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum EventRole {
+    #[default]
+    User,
+    Model,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -26,8 +37,10 @@ pub enum ExpressionValue {
     Unit,
     String(String),
     Boolean(bool),
+    Number(f64),
     List(Arc<ListArray>),
     Option(Option<Box<ExpressionValue>>),
+    Json(serde_json::Value),
     Metadata {
         name: String,
         documentation: Option<String>,
@@ -40,6 +53,7 @@ impl ExpressionResult {
             name: None,
             params: None,
             value,
+            role: EventRole::User,
         }
     }
 
@@ -48,6 +62,7 @@ impl ExpressionResult {
             name: None,
             params: Some(params),
             value,
+            role: EventRole::User,
         }
     }
 
@@ -56,6 +71,7 @@ impl ExpressionResult {
             name: Some(name),
             params: None,
             value,
+            role: EventRole::User,
         }
     }
 
@@ -68,8 +84,14 @@ impl ExpressionResult {
             name: Some(name),
             params: Some(params),
             value,
+            role: EventRole::User,
         }
     }
+
+    pub fn with_role(mut self, role: EventRole) -> Self {
+        self.role = role;
+        self
+    }
 }
 
 impl ExpressionValue {
@@ -87,6 +109,13 @@ impl ExpressionValue {
         }
     }
 
+    pub fn as_number(&self) -> Result<f64, String> {
+        match self {
+            ExpressionValue::Number(n) => Ok(*n),
+            _ => Err("Expected number result".to_string()),
+        }
+    }
+
     pub fn as_list(&self) -> Result<&Arc<ListArray>, String> {
         match self {
             ExpressionValue::List(list) => Ok(list),
@@ -99,8 +128,10 @@ impl ExpressionValue {
             ExpressionValue::Unit => "Unit",
             ExpressionValue::String(_) => "String",
             ExpressionValue::Boolean(_) => "Boolean",
+            ExpressionValue::Number(_) => "Number",
             ExpressionValue::List(_) => "List",
             ExpressionValue::Option(_) => "Option",
+            ExpressionValue::Json(_) => "Json",
             ExpressionValue::Metadata { .. } => "Metadata",
         }
     }
@@ -110,11 +141,13 @@ impl ExpressionValue {
             ExpressionValue::Unit => "()".to_string(),
             ExpressionValue::String(s) => s.clone(),
             ExpressionValue::Boolean(b) => b.to_string(),
-            ExpressionValue::List(list) => format!("{:?}", list),
+            ExpressionValue::Number(n) => n.to_string(),
+            ExpressionValue::List(list) => render_list(list),
             ExpressionValue::Option(opt) => match opt {
                 Some(value) => format!("Some({})", value.value_string()),
                 None => "None".to_string(),
             },
+            ExpressionValue::Json(value) => value.to_string(),
             ExpressionValue::Metadata {
                 name,
                 documentation,
@@ -128,32 +161,37 @@ impl ExpressionValue {
         }
     }
 
-    pub fn format_for_llm(&self) -> String {
+    /// Whether [`ExpressionValue::format_for_llm`] renders the value without
+    /// losing anything. Lists of anything but strings, booleans or numbers
+    /// do not.
+    pub fn is_renderable(&self) -> bool {
         match self {
-            ExpressionValue::String(s) => s.clone(),
-            ExpressionValue::Unit => "()".to_string(),
-            ExpressionValue::Boolean(b) => b.to_string(),
             ExpressionValue::List(list) => {
-                if list.len() == 0 {
-                    "[]".to_string()
-                } else {
+                list.is_empty() || {
                     let values = list.value(0);
-                    if let Some(string_array) =
-                        values.as_any().downcast_ref::<arrow::array::StringArray>()
-                    {
-                        let items: Vec<String> = (0..string_array.len())
-                            .map(|i| format!("\"{}\"", string_array.value(i)))
-                            .collect();
-                        format!("[{}]", items.join(", "))
-                    } else {
-                        "[]".to_string()
-                    }
+                    let values = values.as_any();
+                    values.is::<arrow::array::StringArray>()
+                        || values.is::<arrow::array::BooleanArray>()
+                        || values.is::<arrow::array::Float64Array>()
                 }
             }
+            ExpressionValue::Option(Some(inner)) => inner.is_renderable(),
+            _ => true,
+        }
+    }
+
+    pub fn format_for_llm(&self) -> String {
+        match self {
+            ExpressionValue::String(s) => s.clone(),
+            ExpressionValue::Unit => "()".to_string(),
+            ExpressionValue::Boolean(b) => b.to_string(),
+            ExpressionValue::Number(n) => n.to_string(),
+            ExpressionValue::List(list) => render_list(list),
             ExpressionValue::Option(opt) => match opt {
                 Some(inner) => format!("Some({})", inner.format_for_llm()),
                 None => "None".to_string(),
             },
+            ExpressionValue::Json(value) => value.to_string(),
             ExpressionValue::Metadata {
                 name,
                 documentation,
@@ -168,6 +206,287 @@ impl ExpressionValue {
     }
 }
 
+impl ExpressionValue {
+    /// The value as JSON, for logs and exports. Strings, booleans, numbers
+    /// and lists map directly; `Unit` and `None` become null; metadata becomes an
+    /// object with its name and documentation.
+    pub fn to_json(&self) -> serde_json::Value {
+        match self {
+            ExpressionValue::Unit => serde_json::Value::Null,
+            ExpressionValue::String(s) => serde_json::Value::String(s.clone()),
+            ExpressionValue::Boolean(b) => serde_json::Value::Bool(*b),
+            ExpressionValue::Number(n) => number_json(*n),
+            ExpressionValue::List(list) => {
+                serde_json::Value::Array(list_items(list).iter().map(Self::to_json).collect())
+            }
+            ExpressionValue::Option(opt) => opt
+                .as_ref()
+                .map_or(serde_json::Value::Null, |value| value.to_json()),
+            ExpressionValue::Json(value) => value.clone(),
+            ExpressionValue::Metadata {
+                name,
+                documentation,
+            } => serde_json::json!({
+                "name": name,
+                "documentation": documentation,
+            }),
+        }
+    }
+
+    /// Reads back a value written by [`ExpressionValue::to_json`], given the
+    /// [`ExpressionValue::type_name`] it was written with. An `Option`'s
+    /// inner type is not recorded, so it is taken from the JSON itself.
+    pub fn from_json(type_name: &str, value: &serde_json::Value) -> Result<Self, String> {
+        use serde_json::Value;
+
+        match (type_name, value) {
+            ("Unit", Value::Null) => Ok(ExpressionValue::Unit),
+            ("String", Value::String(s)) => Ok(ExpressionValue::String(s.clone())),
+            ("Boolean", Value::Bool(b)) => Ok(ExpressionValue::Boolean(*b)),
+            ("Number", Value::Number(n)) => n
+                .as_f64()
+                .map(ExpressionValue::Number)
+                .ok_or_else(|| format!("Cannot read Number value from {}", n)),
+            ("List", Value::Array(items)) => list_from_json(items),
+            ("Option", Value::Null) => Ok(ExpressionValue::Option(None)),
+            ("Option", inner) => {
+                let inner_type = match inner {
+                    Value::String(_) => "String",
+                    Value::Bool(_) => "Boolean",
+                    Value::Number(_) => "Number",
+                    Value::Array(_) => "List",
+                    _ => "Json",
+                };
+                Ok(ExpressionValue::Option(Some(Box::new(Self::from_json(
+                    inner_type, inner,
+                )?))))
+            }
+            ("Json", value) => Ok(ExpressionValue::Json(value.clone())),
+            ("Metadata", Value::Object(fields)) => Ok(ExpressionValue::Metadata {
+                name: fields
+                    .get("name")
+                    .and_then(Value::as_str)
+                    .ok_or("Metadata value has no name")?
+                    .to_string(),
+                documentation: fields
+                    .get("documentation")
+                    .and_then(Value::as_str)
+                    .map(str::to_string),
+            }),
+            (type_name, value) => Err(format!("Cannot read {} value from {}", type_name, value)),
+        }
+    }
+}
+
+impl From<String> for ExpressionValue {
+    fn from(value: String) -> Self {
+        ExpressionValue::String(value)
+    }
+}
+
+impl From<bool> for ExpressionValue {
+    fn from(value: bool) -> Self {
+        ExpressionValue::Boolean(value)
+    }
+}
+
+impl From<f64> for ExpressionValue {
+    fn from(value: f64) -> Self {
+        ExpressionValue::Number(value)
+    }
+}
+
+/// The language has no integer type, so integers are carried as Json numbers.
+impl From<i64> for ExpressionValue {
+    fn from(value: i64) -> Self {
+        ExpressionValue::Number(value as f64)
+    }
+}
+
+/// Serializes as [`ExpressionValue::to_json`].
+impl Serialize for ExpressionValue {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.to_json().serialize(serializer)
+    }
+}
+
+/// All-String, all-Boolean or all-Number items become a List. Lists can't
+/// hold other items, so anything else becomes a Json array of their
+/// [`ExpressionValue::to_json`] forms.
+impl From<Vec<ExpressionValue>> for ExpressionValue {
+    fn from(items: Vec<ExpressionValue>) -> Self {
+        let json: Vec<serde_json::Value> = items.iter().map(ExpressionValue::to_json).collect();
+        let listable = items
+            .iter()
+            .all(|item| matches!(item, ExpressionValue::String(_)))
+            || items
+                .iter()
+                .all(|item| matches!(item, ExpressionValue::Boolean(_)))
+            || items
+                .iter()
+                .all(|item| matches!(item, ExpressionValue::Number(_)));
+
+        if listable && let Ok(list) = list_from_json(&json) {
+            return list;
+        }
+        ExpressionValue::Json(json.into())
+    }
+}
+
+impl TryFrom<ExpressionValue> for String {
+    type Error = String;
+
+    fn try_from(value: ExpressionValue) -> Result<Self, Self::Error> {
+        match value {
+            ExpressionValue::String(s) => Ok(s),
+            other => Err(format!(
+                "Expected a String value, got {}",
+                other.type_name()
+            )),
+        }
+    }
+}
+
+impl TryFrom<ExpressionValue> for bool {
+    type Error = String;
+
+    fn try_from(value: ExpressionValue) -> Result<Self, Self::Error> {
+        match value {
+            ExpressionValue::Boolean(b) => Ok(b),
+            other => Err(format!(
+                "Expected a Boolean value, got {}",
+                other.type_name()
+            )),
+        }
+    }
+}
+
+impl TryFrom<ExpressionValue> for f64 {
+    type Error = String;
+
+    fn try_from(value: ExpressionValue) -> Result<Self, Self::Error> {
+        match value {
+            ExpressionValue::Number(n) => Ok(n),
+            other => Err(format!(
+                "Expected a Number value, got {}",
+                other.type_name()
+            )),
+        }
+    }
+}
+
+impl TryFrom<ExpressionValue> for i64 {
+    type Error = String;
+
+    fn try_from(value: ExpressionValue) -> Result<Self, Self::Error> {
+        match value {
+            ExpressionValue::Number(n) if n.fract() == 0.0 && n.abs() <= i64::MAX as f64 => {
+                Ok(n as i64)
+            }
+            ExpressionValue::Number(n) => Err(format!("Expected an integer, got Number {}", n)),
+            other => Err(format!(
+                "Expected a Number value, got {}",
+                other.type_name()
+            )),
+        }
+    }
+}
+
+/// List items come back as String, Boolean or Number values and Json array
+/// items as Json values.
+impl TryFrom<ExpressionValue> for Vec<ExpressionValue> {
+    type Error = String;
+
+    fn try_from(value: ExpressionValue) -> Result<Self, Self::Error> {
+        match value {
+            ExpressionValue::List(list) => Ok(list_items(&list)),
+            ExpressionValue::Json(serde_json::Value::Array(items)) => {
+                Ok(items.into_iter().map(ExpressionValue::Json).collect())
+            }
+            other => Err(format!("Expected a List value, got {}", other.type_name())),
+        }
+    }
+}
+
+// Whole numbers are written as JSON integers, so tools taking an integer
+// accept them; NaN and infinities have no JSON form and become null.
+fn number_json(n: f64) -> serde_json::Value {
+    if n.fract() == 0.0 && n.abs() <= i64::MAX as f64 {
+        serde_json::Value::from(n as i64)
+    } else {
+        serde_json::Number::from_f64(n).map_or(serde_json::Value::Null, serde_json::Value::Number)
+    }
+}
+
+fn list_from_json(items: &[serde_json::Value]) -> Result<ExpressionValue, String> {
+    let list = if !items.is_empty() && items.iter().all(serde_json::Value::is_boolean) {
+        let mut builder = arrow::array::ListBuilder::new(arrow::array::BooleanBuilder::new());
+        for item in items {
+            builder
+                .values()
+                .append_value(item.as_bool().unwrap_or_default());
+        }
+        builder.append(true);
+        builder.finish()
+    } else if !items.is_empty() && items.iter().all(serde_json::Value::is_number) {
+        let mut builder = arrow::array::ListBuilder::new(arrow::array::Float64Builder::new());
+        for item in items {
+            builder
+                .values()
+                .append_value(item.as_f64().unwrap_or_default());
+        }
+        builder.append(true);
+        builder.finish()
+    } else {
+        let mut builder = arrow::array::ListBuilder::new(arrow::array::StringBuilder::new());
+        for item in items {
+            let item = item
+                .as_str()
+                .ok_or_else(|| format!("Cannot read list item {}", item))?;
+            builder.values().append_value(item);
+        }
+        builder.append(true);
+        builder.finish()
+    };
+    Ok(ExpressionValue::List(Arc::new(list)))
+}
+
+// The items of a list, which holds strings, booleans or numbers.
+fn list_items(list: &ListArray) -> Vec<ExpressionValue> {
+    if list.is_empty() {
+        return Vec::new();
+    }
+    let values = list.value(0);
+    if let Some(strings) = values.as_any().downcast_ref::<arrow::array::StringArray>() {
+        (0..strings.len())
+            .map(|i| ExpressionValue::String(strings.value(i).to_string()))
+            .collect()
+    } else if let Some(booleans) = values.as_any().downcast_ref::<arrow::array::BooleanArray>() {
+        (0..booleans.len())
+            .map(|i| ExpressionValue::Boolean(booleans.value(i)))
+            .collect()
+    } else if let Some(numbers) = values.as_any().downcast_ref::<arrow::array::Float64Array>() {
+        (0..numbers.len())
+            .map(|i| ExpressionValue::Number(numbers.value(i)))
+            .collect()
+    } else {
+        Vec::new()
+    }
+}
+
+// Lists render the same way in prompts and in Display: strings quoted,
+// booleans and numbers bare, e.g. `["a", "b"]` or `[1, 2.5]`.
+fn render_list(list: &ListArray) -> String {
+    let items: Vec<String> = list_items(list)
+        .iter()
+        .map(|item| match item {
+            ExpressionValue::String(s) => format!("\"{}\"", s),
+            other => other.value_string(),
+        })
+        .collect();
+    format!("[{}]", items.join(", "))
+}
+
 impl std::fmt::Display for ExpressionValue {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.value_string())
@@ -0,0 +1,106 @@
+use super::*;
+use serde_json::json;
+
+#[test]
+fn test_string_round_trips() {
+    let value = ExpressionValue::from("hello".to_string());
+
+    assert_eq!(value, ExpressionValue::String("hello".to_string()));
+    assert_eq!(String::try_from(value), Ok("hello".to_string()));
+}
+
+#[test]
+fn test_bool_round_trips() {
+    let value = ExpressionValue::from(true);
+
+    assert_eq!(value, ExpressionValue::Boolean(true));
+    assert_eq!(bool::try_from(value), Ok(true));
+}
+
+#[test]
+fn test_i64_round_trips_as_number() {
+    let value = ExpressionValue::from(-42i64);
+
+    assert_eq!(value, ExpressionValue::Number(-42.0));
+    assert_eq!(i64::try_from(value), Ok(-42));
+}
+
+#[test]
+fn test_string_items_round_trip_as_list() {
+    let items = vec![
+        ExpressionValue::from("a".to_string()),
+        ExpressionValue::from("b".to_string()),
+    ];
+
+    let value = ExpressionValue::from(items.clone());
+
+    assert!(matches!(value, ExpressionValue::List(_)));
+    assert_eq!(value.to_string(), r#"["a", "b"]"#);
+    assert_eq!(Vec::<ExpressionValue>::try_from(value), Ok(items));
+}
+
+#[test]
+fn test_boolean_items_round_trip_as_list() {
+    let items = vec![ExpressionValue::from(true), ExpressionValue::from(false)];
+
+    let value = ExpressionValue::from(items.clone());
+
+    assert!(matches!(value, ExpressionValue::List(_)));
+    assert_eq!(Vec::<ExpressionValue>::try_from(value), Ok(items));
+}
+
+#[test]
+fn test_number_items_round_trip_as_list() {
+    let items = vec![ExpressionValue::from(1.0), ExpressionValue::from(2.5)];
+
+    let value = ExpressionValue::from(items.clone());
+
+    assert!(matches!(value, ExpressionValue::List(_)));
+    assert_eq!(value.to_json(), json!([1, 2.5]));
+    assert_eq!(
+        ExpressionValue::from_json("List", &value.to_json()),
+        Ok(value.clone())
+    );
+    assert_eq!(Vec::<ExpressionValue>::try_from(value), Ok(items));
+}
+
+#[test]
+fn test_mixed_items_become_json_array() {
+    let value = ExpressionValue::from(vec![
+        ExpressionValue::from("a".to_string()),
+        ExpressionValue::from(1i64),
+    ]);
+
+    assert_eq!(value, ExpressionValue::Json(json!(["a", 1])));
+    assert_eq!(
+        Vec::<ExpressionValue>::try_from(value),
+        Ok(vec![
+            ExpressionValue::Json(json!("a")),
+            ExpressionValue::Json(json!(1)),
+        ])
+    );
+}
+
+#[test]
+fn test_try_from_wrong_type_names_both_types() {
+    assert_eq!(
+        String::try_from(ExpressionValue::Boolean(true)),
+        Err("Expected a String value, got Boolean".to_string())
+    );
+    assert_eq!(
+        bool::try_from(ExpressionValue::Unit),
+        Err("Expected a Boolean value, got Unit".to_string())
+    );
+    assert_eq!(
+        i64::try_from(ExpressionValue::String("7".to_string())),
+        Err("Expected a Number value, got String".to_string())
+    );
+    assert_eq!(
+        i64::try_from(ExpressionValue::Number(1.5)),
+        Err("Expected an integer, got Number 1.5".to_string())
+    );
+    assert_eq!(
+        Vec::<ExpressionValue>::try_from(ExpressionValue::Option(None)),
+        Err("Expected a List value, got Option".to_string())
+    );
+}
@@ -0,0 +1,117 @@
+use crate::compiler::CompilationUnit;
+use crate::runtime::{ExpressionValue, Runtime};
+use crate::types::LanguageEngine;
+use std::sync::Arc;
+
+pub async fn assert_program_output(
+    source: &str,
+    engine: Arc<dyn LanguageEngine>,
+    expected_result: ExpressionValue,
+    expected_events: &[&str],
+) {
+    let program = CompilationUnit::from_string(source.to_string());
+    let runtime = Runtime::builder(program)
+        .with_language_engine(engine)
+        .build();
+
+    let (result, context) = match runtime.run_with_context().await {
+        Ok(output) => output,
+        Err(e) => panic!("Program failed to run: {}\n\nSource:\n{}", e, source),
+    };
+
+    let actual_events: Vec<String> = context
+        .iter_all_events()
+        .map(|event| event.content.format_for_llm())
+        .collect();
+
+    let expected_events: Vec<String> = expected_events.iter().map(|e| e.to_string()).collect();
+
+    if result != expected_result || actual_events != expected_events {
+        panic!(
+            "Program output mismatch\n\nResult:\n  expected: {:?}\n  actual:   {:?}\n\nEvents (- expected, + actual):\n{}",
+            expected_result,
+            result,
+            diff_lines(&expected_events, &actual_events)
+        );
+    }
+}
+
+fn diff_lines(expected: &[String], actual: &[String]) -> String {
+    let mut output = Vec::new();
+
+    for i in 0..expected.len().max(actual.len()) {
+        match (expected.get(i), actual.get(i)) {
+            (Some(e), Some(a)) if e == a => output.push(format!("  {}: {:?}", i, e)),
+            (Some(e), Some(a)) => {
+                output.push(format!("- {}: {:?}", i, e));
+                output.push(format!("+ {}: {:?}", i, a));
+            }
+            (Some(e), None) => output.push(format!("- {}: {:?}", i, e)),
+            (None, Some(a)) => output.push(format!("+ {}: {:?}", i, a)),
+            (None, None) => {}
+        }
+    }
+
+    output.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::PrintEngine;
+
+    #[tokio::test]
+    async fn test_assert_program_output_with_print_engine() {
+        let source = r#"
+fn greet(name: String): String {
+    return name
+}
+
+fn main(): String {
+    "Hello"!
+    let greeting = greet("World")
+    greeting!
+    return "done"
+}
+"#;
+
+        assert_program_output(
+            source,
+            Arc::new(PrintEngine {}),
+            ExpressionValue::String("done".to_string()),
+            &["Hello", "World"],
+        )
+        .await;
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "- 1: \"Goodbye\"\n+ 1: \"World\"")]
+    async fn test_assert_program_output_reports_event_diff() {
+        let source = r#"
+fn main(): String {
+    "Hello"!
+    "World"!
+    return "done"
+}
+"#;
+
+        assert_program_output(
+            source,
+            Arc::new(PrintEngine {}),
+            ExpressionValue::String("done".to_string()),
+            &["Hello", "Goodbye"],
+        )
+        .await;
+    }
+
+    #[test]
+    fn test_diff_lines_marks_missing_and_extra_events() {
+        let expected = vec!["a".to_string(), "b".to_string()];
+        let actual = vec!["a".to_string(), "c".to_string(), "d".to_string()];
+
+        assert_eq!(
+            diff_lines(&expected, &actual),
+            "  0: \"a\"\n- 1: \"b\"\n+ 1: \"c\"\n+ 2: \"d\""
+        );
+    }
+}
@@ -1,4 +1,6 @@
-use crate::ast::{Definition, Expression, Function, Module, Parameter, Statement, Type as AstType};
+use crate::ast::{
+    Definition, Expression, Function, Module, Parameter, SelectClause, Statement, Type as AstType,
+};
 use crate::typecheck::error::TypeError;
 use crate::types::{FileId, Span, Spanned};
 use std::collections::HashMap;
@@ -6,6 +8,7 @@ use std::collections::HashMap;
 #[derive(Debug)]
 pub struct TypeChecker {
     function_signatures: HashMap<String, FunctionSignature>,
+    constants: HashMap<String, (AstType, Span)>,
 }
 
 #[derive(Debug, Clone)]
@@ -30,6 +33,7 @@ impl TypeChecker {
     pub fn new() -> Self {
         Self {
             function_signatures: HashMap::new(),
+            constants: HashMap::new(),
         }
     }
 
@@ -39,6 +43,22 @@ impl TypeChecker {
         Ok(())
     }
 
+    pub fn check_standalone_expression(
+        &mut self,
+        declarations: &Module,
+        expression: &Expression,
+        file_id: FileId,
+    ) -> Result<AstType, TypeError> {
+        self.collect_function_signatures(declarations, file_id)?;
+
+        let mut env = TypeEnvironment::new();
+        for (name, (const_type, span)) in &self.constants {
+            env.declare_variable(name.clone(), const_type.clone(), *span);
+        }
+
+        self.check_expression(expression, &env, file_id)
+    }
+
     fn collect_function_signatures(
         &mut self,
         module: &Module,
@@ -47,9 +67,9 @@ impl TypeChecker {
         for definition in &module.definitions {
             match definition {
                 Definition::Function(func) => {
-                    self.validate_type(&func.return_type, func.span, file_id)?;
+                    self.validate_type(&func.return_type, func.return_type_span, file_id)?;
                     for param in &func.parameters {
-                        self.validate_type(&param.param_type, param.span, file_id)?;
+                        self.validate_type(&param.param_type, param.type_span, file_id)?;
                     }
 
                     let signature = FunctionSignature {
@@ -60,9 +80,9 @@ impl TypeChecker {
                         .insert(func.name.clone(), signature);
                 }
                 Definition::ExternalFunction(ext_func) => {
-                    self.validate_type(&ext_func.return_type, ext_func.span, file_id)?;
+                    self.validate_type(&ext_func.return_type, ext_func.return_type_span, file_id)?;
                     for param in &ext_func.parameters {
-                        self.validate_type(&param.param_type, param.span, file_id)?;
+                        self.validate_type(&param.param_type, param.type_span, file_id)?;
                     }
 
                     let signature = FunctionSignature {
@@ -72,11 +92,64 @@ impl TypeChecker {
                     self.function_signatures
                         .insert(ext_func.name.clone(), signature);
                 }
+                Definition::ExternalConst(ext_const) => {
+                    self.validate_type(&ext_const.const_type, ext_const.type_span, file_id)?;
+                    self.declare_constant(
+                        &ext_const.name,
+                        ext_const.const_type.clone(),
+                        ext_const.span,
+                        file_id,
+                    )?;
+                }
+                Definition::Constant(constant) => {
+                    let const_type =
+                        self.check_expression(&constant.value, &TypeEnvironment::new(), file_id)?;
+                    self.declare_constant(&constant.name, const_type, constant.span, file_id)?;
+                }
             }
         }
         Ok(())
     }
 
+    // Select clauses and match arms must all produce the first branch's type.
+    fn branch_type_mismatch(
+        &self,
+        (first_type, first_span): (&AstType, Span),
+        (index, branch_type, span): (usize, &AstType, Span),
+        file_id: FileId,
+    ) -> Option<TypeError> {
+        if self.types_equal(first_type, branch_type) {
+            return None;
+        }
+        Some(TypeError::SelectBranchTypeMismatch {
+            expected: format!("{}", first_type),
+            found: format!("{}", branch_type),
+            branch_index: index,
+            span,
+            first_branch_span: first_span,
+            file_id,
+        })
+    }
+
+    fn declare_constant(
+        &mut self,
+        name: &str,
+        const_type: AstType,
+        span: Span,
+        file_id: FileId,
+    ) -> Result<(), TypeError> {
+        if let Some((_, previous_span)) = self.constants.get(name) {
+            return Err(TypeError::DuplicateConstant {
+                name: name.to_string(),
+                span,
+                previous_span: *previous_span,
+                file_id,
+            });
+        }
+        self.constants.insert(name.to_string(), (const_type, span));
+        Ok(())
+    }
+
     fn validate_type(
         &self,
         ast_type: &AstType,
@@ -84,9 +157,18 @@ impl TypeChecker {
         file_id: FileId,
     ) -> Result<(), TypeError> {
         match ast_type {
-            AstType::Unit | AstType::Boolean | AstType::String => Ok(()),
+            AstType::Unit
+            | AstType::Boolean
+            | AstType::Number
+            | AstType::String
+            | AstType::Json => Ok(()),
             AstType::List(inner) => self.validate_type(inner, span, file_id),
             AstType::Option(inner) => self.validate_type(inner, span, file_id),
+            AstType::Custom(name) => Err(TypeError::UnsupportedType {
+                type_name: name.clone(),
+                span,
+                file_id,
+            }),
         }
     }
 
@@ -102,6 +184,10 @@ impl TypeChecker {
     fn check_function(&self, func: &Function, file_id: FileId) -> Result<(), TypeError> {
         let mut env = TypeEnvironment::new();
 
+        for (name, (const_type, span)) in &self.constants {
+            env.declare_variable(name.clone(), const_type.clone(), *span);
+        }
+
         for param in &func.parameters {
             env.declare_variable(param.name.clone(), param.param_type.clone(), param.span);
         }
@@ -110,6 +196,20 @@ impl TypeChecker {
             env = self.check_statement(statement, env, &func.name, file_id)?;
         }
 
+        if let Some(expr) = func.implicit_return() {
+            let return_type =
+                self.check_expression_expecting(expr, &func.return_type, &env, file_id)?;
+            if return_type != func.return_type {
+                return Err(TypeError::ReturnTypeMismatch {
+                    function: func.name.clone(),
+                    expected: format!("{}", func.return_type),
+                    found: format!("{}", return_type),
+                    span: expr.span(),
+                    file_id,
+                });
+            }
+        }
+
         Ok(())
     }
 
@@ -121,7 +221,9 @@ impl TypeChecker {
         file_id: FileId,
     ) -> Result<TypeEnvironment, TypeError> {
         match statement {
-            Statement::Injection(expr) => {
+            Statement::Injection {
+                expression: expr, ..
+            } => {
                 self.check_expression(expr, &env, file_id)?;
                 Ok(env)
             }
@@ -129,6 +231,7 @@ impl TypeChecker {
                 variable,
                 expression,
                 span: _,
+                ..
             } => {
                 let expr_type = self.check_expression(expression, &env, file_id)?;
                 env.declare_variable(variable.clone(), expr_type, expression.span());
@@ -138,8 +241,8 @@ impl TypeChecker {
                 variable,
                 expression,
                 span,
+                ..
             } => {
-                let expr_type = self.check_expression(expression, &env, file_id)?;
                 let (existing_type, declaration_span) = env
                     .lookup_variable_with_span(variable)
                     .ok_or_else(|| TypeError::UnknownVariable {
@@ -147,6 +250,21 @@ impl TypeChecker {
                         span: *span,
                         file_id,
                     })?;
+                let expr_type =
+                    self.check_expression_expecting(expression, &existing_type, &env, file_id)?;
+
+                // A local `let` of the same name shadows the constant, and
+                // is declared at a different span.
+                if let Some((_, const_span)) = self.constants.get(variable)
+                    && *const_span == declaration_span
+                {
+                    return Err(TypeError::AssignmentToConstant {
+                        name: variable.clone(),
+                        span: *span,
+                        declaration_span,
+                        file_id,
+                    });
+                }
 
                 if !self.types_equal(&expr_type, &existing_type) {
                     return Err(TypeError::VariableTypeMismatch {
@@ -161,7 +279,9 @@ impl TypeChecker {
 
                 Ok(env)
             }
-            Statement::ExpressionStatement(expr) => {
+            Statement::ExpressionStatement {
+                expression: expr, ..
+            } => {
                 self.check_expression(expr, &env, file_id)?;
                 Ok(env)
             }
@@ -170,6 +290,7 @@ impl TypeChecker {
                 body,
                 else_body,
                 span: _,
+                ..
             } => {
                 let cond_type = self.check_expression(condition, &env, file_id)?;
                 if !matches!(cond_type, AstType::Boolean) {
@@ -199,6 +320,7 @@ impl TypeChecker {
                 condition,
                 body,
                 span: _,
+                ..
             } => {
                 let cond_type = self.check_expression(condition, &env, file_id)?;
                 if !matches!(cond_type, AstType::Boolean) {
@@ -216,13 +338,44 @@ impl TypeChecker {
                 }
                 Ok(env)
             }
-            Statement::Return(expr) => {
-                let return_type = self.check_expression(expr, &env, file_id)?;
+            Statement::Assert {
+                condition,
+                message,
+                span: _,
+                ..
+            } => {
+                let cond_type = self.check_expression(condition, &env, file_id)?;
+                if !matches!(cond_type, AstType::Boolean) {
+                    return Err(TypeError::TypeMismatch {
+                        expected: "Boolean".to_string(),
+                        found: format!("{}", cond_type),
+                        span: condition.span(),
+                        file_id,
+                    });
+                }
+
+                let message_type = self.check_expression(message, &env, file_id)?;
+                if !matches!(message_type, AstType::String) {
+                    return Err(TypeError::TypeMismatch {
+                        expected: "String".to_string(),
+                        found: format!("{}", message_type),
+                        span: message.span(),
+                        file_id,
+                    });
+                }
+
+                Ok(env)
+            }
+            Statement::Return {
+                expression: expr, ..
+            } => {
                 let expected_type = &self
                     .function_signatures
                     .get(function_name)
                     .expect("Function signature not found")
                     .return_type;
+                let return_type =
+                    self.check_expression_expecting(expr, expected_type, &env, file_id)?;
 
                 if return_type != *expected_type {
                     return Err(TypeError::ReturnTypeMismatch {
@@ -238,6 +391,46 @@ impl TypeChecker {
         }
     }
 
+    // `None` and `[]` have no type of their own, so where the type is already
+    // known they take that type, as do a `Some` or list literal around them.
+    fn check_expression_expecting(
+        &self,
+        expression: &Expression,
+        expected: &AstType,
+        env: &TypeEnvironment,
+        file_id: FileId,
+    ) -> Result<AstType, TypeError> {
+        match (expression, expected) {
+            (Expression::OptionLiteral { value: None, .. }, AstType::Option(_)) => {
+                Ok(expected.clone())
+            }
+            (
+                Expression::OptionLiteral {
+                    value: Some(value), ..
+                },
+                AstType::Option(inner),
+            ) => Ok(AstType::Option(Box::new(
+                self.check_expression_expecting(value, inner, env, file_id)?,
+            ))),
+            (Expression::ListLiteral { elements, .. }, AstType::List(inner)) => {
+                for element in elements {
+                    let element_type =
+                        self.check_expression_expecting(element, inner, env, file_id)?;
+                    if !self.types_equal(&element_type, inner) {
+                        return Err(TypeError::TypeMismatch {
+                            expected: format!("{}", inner),
+                            found: format!("{}", element_type),
+                            span: element.span(),
+                            file_id,
+                        });
+                    }
+                }
+                Ok(expected.clone())
+            }
+            _ => self.check_expression(expression, env, file_id),
+        }
+    }
+
     fn check_expression(
         &self,
         expression: &Expression,
@@ -272,7 +465,12 @@ impl TypeChecker {
                     match arg {
                         Expression::Placeholder { .. } => {}
                         _ => {
-                            let arg_type = self.check_expression(arg, env, file_id)?;
+                            let arg_type = self.check_expression_expecting(
+                                arg,
+                                &param.param_type,
+                                env,
+                                file_id,
+                            )?;
                             if !self.types_equal(&arg_type, &param.param_type) {
                                 return Err(TypeError::ArgumentTypeMismatch {
                                     function: function.clone(),
@@ -299,7 +497,19 @@ impl TypeChecker {
             }
             Expression::StringLiteral { .. } => Ok(AstType::String),
             Expression::BooleanLiteral { .. } => Ok(AstType::Boolean),
+            Expression::NumberLiteral { .. } => Ok(AstType::Number),
             Expression::UnitLiteral { .. } => Ok(AstType::Unit),
+            Expression::OptionLiteral { value, span } => match value {
+                Some(value) => Ok(AstType::Option(Box::new(
+                    self.check_expression(value, env, file_id)?,
+                ))),
+                None => Err(TypeError::TypeMismatch {
+                    expected: "Option with a known type".to_string(),
+                    found: "None".to_string(),
+                    span: *span,
+                    file_id,
+                }),
+            },
             Expression::ListLiteral { elements, span } => {
                 if elements.is_empty() {
                     return Err(TypeError::TypeMismatch {
@@ -342,45 +552,38 @@ impl TypeChecker {
                     });
                 }
 
-                let first_clause = &select_expr.clauses[0];
-                let first_result_type =
-                    self.check_expression(&first_clause.expression_to_run, env, file_id)?;
-                let mut first_clause_env = env.create_child();
-                first_clause_env.declare_variable(
-                    first_clause.result_variable.clone(),
-                    first_result_type,
-                    first_clause.expression_to_run.span(),
-                );
-                let first_type = self.check_expression(
-                    &first_clause.expression_next,
-                    &first_clause_env,
-                    file_id,
-                )?;
-
-                for (i, clause) in select_expr.clauses.iter().enumerate().skip(1) {
-                    let result_type =
-                        self.check_expression(&clause.expression_to_run, env, file_id)?;
-                    let mut clause_env = env.create_child();
-                    clause_env.declare_variable(
-                        clause.result_variable.clone(),
-                        result_type,
-                        clause.expression_to_run.span(),
-                    );
-                    let clause_type =
-                        self.check_expression(&clause.expression_next, &clause_env, file_id)?;
-                    if !self.types_equal(&first_type, &clause_type) {
-                        return Err(TypeError::SelectBranchTypeMismatch {
-                            expected: format!("{}", first_type),
-                            found: format!("{}", clause_type),
-                            branch_index: i,
-                            span: clause.expression_next.span(),
-                            first_branch_span: first_clause.expression_next.span(),
+                let mut errors = Vec::new();
+                let mut clause_types = Vec::new();
+
+                for (i, clause) in select_expr.clauses.iter().enumerate() {
+                    match self.check_select_clause(clause, env, file_id) {
+                        Ok(clause_type) => clause_types.push((i, clause, clause_type)),
+                        Err(error) => errors.push(error),
+                    }
+                }
+
+                if let Some(((_, first_clause, first_type), rest)) = clause_types.split_first() {
+                    let first = (first_type, first_clause.expression_next.span());
+                    for (i, clause, clause_type) in rest {
+                        if let Some(error) = self.branch_type_mismatch(
+                            first,
+                            (*i, clause_type, clause.expression_next.span()),
                             file_id,
-                        });
+                        ) {
+                            errors.push(error);
+                        }
                     }
                 }
 
-                Ok(first_type)
+                match errors.len() {
+                    0 => Ok(clause_types.swap_remove(0).2),
+                    1 => Err(errors.remove(0)),
+                    _ => Err(TypeError::SelectClauseErrors {
+                        errors,
+                        span: select_expr.span,
+                        file_id,
+                    }),
+                }
             }
             Expression::IfElse {
                 condition,
@@ -412,7 +615,104 @@ impl TypeChecker {
 
                 Ok(then_type)
             }
+            Expression::Match {
+                scrutinee,
+                arms,
+                span,
+            } => {
+                let scrutinee_type = self.check_expression(scrutinee, env, file_id)?;
+                if !matches!(scrutinee_type, AstType::Boolean) {
+                    return Err(TypeError::TypeMismatch {
+                        expected: "Boolean".to_string(),
+                        found: format!("{}", scrutinee_type),
+                        span: scrutinee.span(),
+                        file_id,
+                    });
+                }
+
+                for (i, arm) in arms.iter().enumerate() {
+                    if let Some(first) = arms[..i].iter().find(|a| a.pattern == arm.pattern) {
+                        return Err(TypeError::DuplicateMatchArm {
+                            pattern: arm.pattern.to_string(),
+                            span: arm.span,
+                            first_span: first.span,
+                            file_id,
+                        });
+                    }
+                }
+                for pattern in [true, false] {
+                    if !arms.iter().any(|arm| arm.pattern == pattern) {
+                        return Err(TypeError::NonExhaustiveMatch {
+                            missing: pattern.to_string(),
+                            span: *span,
+                            file_id,
+                        });
+                    }
+                }
+
+                let first_type = self.check_expression(&arms[0].expression, env, file_id)?;
+                let first = (&first_type, arms[0].expression.span());
+                for (i, arm) in arms.iter().enumerate().skip(1) {
+                    let arm_type = self.check_expression(&arm.expression, env, file_id)?;
+                    if let Some(error) = self.branch_type_mismatch(
+                        first,
+                        (i, &arm_type, arm.expression.span()),
+                        file_id,
+                    ) {
+                        return Err(error);
+                    }
+                }
+
+                Ok(first_type)
+            }
+            Expression::Return { span, .. } => Err(TypeError::ReturnInExpressionPosition {
+                span: *span,
+                file_id,
+            }),
+            Expression::FieldAccess {
+                object,
+                field,
+                span,
+            } => match self.check_expression(object, env, file_id)? {
+                AstType::Json => Ok(AstType::Json),
+                other => Err(TypeError::InvalidFieldAccess {
+                    field: field.clone(),
+                    found: format!("{}", other),
+                    span: *span,
+                    file_id,
+                }),
+            },
+        }
+    }
+
+    fn check_select_clause(
+        &self,
+        clause: &SelectClause,
+        env: &TypeEnvironment,
+        file_id: FileId,
+    ) -> Result<AstType, TypeError> {
+        // The guard decides whether the clause is offered at all, so it runs
+        // before the call and cannot see the result variable.
+        if let Some(guard) = &clause.guard {
+            let guard_type = self.check_expression(guard, env, file_id)?;
+            if !matches!(guard_type, AstType::Boolean) {
+                return Err(TypeError::TypeMismatch {
+                    expected: "Boolean".to_string(),
+                    found: format!("{}", guard_type),
+                    span: guard.span(),
+                    file_id,
+                });
+            }
         }
+
+        let result_type = self.check_expression(&clause.expression_to_run, env, file_id)?;
+        let mut clause_env = env.create_child();
+        clause_env.declare_variable(
+            clause.result_variable.clone(),
+            result_type,
+            clause.expression_to_run.span(),
+        );
+        self.check_expression(&clause.expression_next, &clause_env, file_id)
     }
 
     fn types_equal(&self, type1: &AstType, type2: &AstType) -> bool {
@@ -62,6 +62,44 @@ pub enum TypeError {
         span: Span,
         file_id: FileId,
     },
+    SelectClauseErrors {
+        errors: Vec<TypeError>,
+        span: Span,
+        file_id: FileId,
+    },
+    ReturnInExpressionPosition {
+        span: Span,
+        file_id: FileId,
+    },
+    InvalidFieldAccess {
+        field: String,
+        found: String,
+        span: Span,
+        file_id: FileId,
+    },
+    AssignmentToConstant {
+        name: String,
+        span: Span,
+        declaration_span: Span,
+        file_id: FileId,
+    },
+    DuplicateConstant {
+        name: String,
+        span: Span,
+        previous_span: Span,
+        file_id: FileId,
+    },
+    NonExhaustiveMatch {
+        missing: String,
+        span: Span,
+        file_id: FileId,
+    },
+    DuplicateMatchArm {
+        pattern: String,
+        span: Span,
+        first_span: Span,
+        file_id: FileId,
+    },
 }
 
 impl TypeError {
@@ -76,6 +114,13 @@ impl TypeError {
             TypeError::ReturnTypeMismatch { span, .. } => *span,
             TypeError::SelectBranchTypeMismatch { span, .. } => *span,
             TypeError::UnsupportedType { span, .. } => *span,
+            TypeError::SelectClauseErrors { span, .. } => *span,
+            TypeError::ReturnInExpressionPosition { span, .. } => *span,
+            TypeError::InvalidFieldAccess { span, .. } => *span,
+            TypeError::AssignmentToConstant { span, .. } => *span,
+            TypeError::DuplicateConstant { span, .. } => *span,
+            TypeError::NonExhaustiveMatch { span, .. } => *span,
+            TypeError::DuplicateMatchArm { span, .. } => *span,
         }
     }
 
@@ -90,6 +135,13 @@ impl TypeError {
             TypeError::ReturnTypeMismatch { file_id, .. } => *file_id,
             TypeError::SelectBranchTypeMismatch { file_id, .. } => *file_id,
             TypeError::UnsupportedType { file_id, .. } => *file_id,
+            TypeError::SelectClauseErrors { file_id, .. } => *file_id,
+            TypeError::ReturnInExpressionPosition { file_id, .. } => *file_id,
+            TypeError::InvalidFieldAccess { file_id, .. } => *file_id,
+            TypeError::AssignmentToConstant { file_id, .. } => *file_id,
+            TypeError::DuplicateConstant { file_id, .. } => *file_id,
+            TypeError::NonExhaustiveMatch { file_id, .. } => *file_id,
+            TypeError::DuplicateMatchArm { file_id, .. } => *file_id,
         }
     }
 
@@ -217,6 +269,101 @@ impl TypeError {
                     Label::primary(*file_id, span.to_byte_range())
                         .with_message("type not supported"),
                 ]),
+            TypeError::SelectClauseErrors {
+                errors,
+                span,
+                file_id,
+            } => {
+                let mut labels = vec![
+                    Label::primary(*file_id, span.to_byte_range())
+                        .with_message(format!("{} clauses failed to check", errors.len())),
+                ];
+                labels.extend(errors.iter().map(|error| {
+                    Label::secondary(error.file_id(), error.span().to_byte_range())
+                        .with_message(error.to_string())
+                }));
+
+                Diagnostic::error()
+                    .with_message("select has invalid clauses")
+                    .with_labels(labels)
+            }
+            TypeError::ReturnInExpressionPosition { span, file_id } => Diagnostic::error()
+                .with_message("`return` used as an expression")
+                .with_labels(vec![
+                    Label::primary(*file_id, span.to_byte_range())
+                        .with_message("`return` is only allowed as a statement in a function body"),
+                ])
+                .with_notes(vec![
+                    "use an `if` statement with `return` in its body, or return the whole `if` expression"
+                        .to_string(),
+                ]),
+            TypeError::InvalidFieldAccess {
+                field,
+                found,
+                span,
+                file_id,
+            } => Diagnostic::error()
+                .with_message(format!("no field `{}` on type `{}`", field, found))
+                .with_labels(vec![
+                    Label::primary(*file_id, span.to_byte_range())
+                        .with_message("fields can only be read from `Json` values"),
+                ]),
+            TypeError::AssignmentToConstant {
+                name,
+                span,
+                declaration_span,
+                file_id,
+            } => Diagnostic::error()
+                .with_message(format!("cannot assign to constant `{}`", name))
+                .with_labels(vec![
+                    Label::primary(*file_id, span.to_byte_range())
+                        .with_message("constants cannot be reassigned"),
+                    Label::secondary(*file_id, declaration_span.to_byte_range())
+                        .with_message("constant declared here"),
+                ])
+                .with_notes(vec![format!(
+                    "use `let {} = ...` to shadow it with a local variable",
+                    name
+                )]),
+            TypeError::DuplicateConstant {
+                name,
+                span,
+                previous_span,
+                file_id,
+            } => Diagnostic::error()
+                .with_message(format!("constant `{}` is defined more than once", name))
+                .with_labels(vec![
+                    Label::primary(*file_id, span.to_byte_range())
+                        .with_message("redefined here"),
+                    Label::secondary(*file_id, previous_span.to_byte_range())
+                        .with_message("first defined here"),
+                ]),
+            TypeError::NonExhaustiveMatch {
+                missing,
+                span,
+                file_id,
+            } => Diagnostic::error()
+                .with_message(format!("match is missing a `{}` arm", missing))
+                .with_labels(vec![
+                    Label::primary(*file_id, span.to_byte_range())
+                        .with_message(format!("`{}` not covered", missing)),
+                ])
+                .with_notes(vec![
+                    "a match needs one arm for `true` and one for `false`".to_string(),
+                ]),
+            TypeError::DuplicateMatchArm {
+                pattern,
+                span,
+                first_span,
+                file_id,
+            } => Diagnostic::error()
+                .with_message(format!("match has more than one `{}` arm", pattern))
+                .with_labels(vec![
+                    Label::primary(*file_id, span.to_byte_range())
+                        .with_message("this arm is never used"),
+                    Label::secondary(*file_id, first_span.to_byte_range())
+                        .with_message("first matched here"),
+                ]),
         }
     }
 }
@@ -299,6 +446,31 @@ impl fmt::Display for TypeError {
             TypeError::UnsupportedType { type_name, .. } => {
                 write!(f, "Unsupported type: {}", type_name)
             }
+            TypeError::SelectClauseErrors { errors, .. } => {
+                let messages: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+                write!(f, "Select has invalid clauses: {}", messages.join("; "))
+            }
+            TypeError::ReturnInExpressionPosition { .. } => {
+                write!(
+                    f,
+                    "'return' is a statement and cannot be used in expression position"
+                )
+            }
+            TypeError::InvalidFieldAccess { field, found, .. } => {
+                write!(f, "Cannot access field {} on type {}", field, found)
+            }
+            TypeError::AssignmentToConstant { name, .. } => {
+                write!(f, "Cannot assign to constant {}", name)
+            }
+            TypeError::DuplicateConstant { name, .. } => {
+                write!(f, "Constant {} is defined more than once", name)
+            }
+            TypeError::NonExhaustiveMatch { missing, .. } => {
+                write!(f, "Match is missing a {} arm", missing)
+            }
+            TypeError::DuplicateMatchArm { pattern, .. } => {
+                write!(f, "Match has more than one {} arm", pattern)
+            }
         }
     }
 }
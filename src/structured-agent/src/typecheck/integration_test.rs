@@ -1,6 +1,6 @@
 #[cfg(test)]
 mod tests {
-    use crate::compiler::{CompilationUnit, Compiler};
+    use crate::compiler::{CompilationUnit, CompileError, Compiler};
 
     #[test]
     fn test_type_checker_integration_valid_program() {
@@ -50,7 +50,7 @@ fn main(): () {
             result.is_err(),
             "Program with type error should fail to compile"
         );
-        assert!(result.unwrap_err().contains("Type error"));
+        assert!(matches!(result, Err(CompileError::Type(_))));
     }
 
     #[test]
@@ -75,7 +75,7 @@ fn get_number(): String {
             result.is_err(),
             "Return type mismatch should fail to compile"
         );
-        let err = result.unwrap_err();
+        let err = result.unwrap_err().to_string();
         assert!(err.contains("Type error"));
         assert!(err.contains("return type mismatch"));
     }
@@ -159,7 +159,7 @@ fn main(): String {
             result.is_err(),
             "Select statement with mismatched types should fail"
         );
-        let err = result.unwrap_err();
+        let err = result.unwrap_err().to_string();
         assert!(err.contains("Type error"));
     }
 
@@ -212,7 +212,7 @@ fn main(): () {
             result.is_err(),
             "If/else else-branch type error should fail compilation"
         );
-        let err = result.unwrap_err();
+        let err = result.unwrap_err().to_string();
         assert!(err.contains("Type error"));
     }
 }
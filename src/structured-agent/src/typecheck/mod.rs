@@ -10,9 +10,18 @@ mod integration_test;
 pub use checker::TypeChecker;
 pub use error::TypeError;
 
-use crate::ast::Module;
+use crate::ast::{Expression, Module, Type};
 
 pub fn type_check_module(module: &Module, file_id: crate::types::FileId) -> Result<(), TypeError> {
     let mut checker = TypeChecker::new();
     checker.check_module(module, file_id)
 }
+
+pub fn type_check_expression(
+    declarations: &Module,
+    expression: &Expression,
+    file_id: crate::types::FileId,
+) -> Result<Type, TypeError> {
+    let mut checker = TypeChecker::new();
+    checker.check_standalone_expression(declarations, expression, file_id)
+}
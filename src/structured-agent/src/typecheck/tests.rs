@@ -22,20 +22,36 @@ fn create_test_function(
         name: name.to_string(),
         parameters,
         return_type,
+        return_type_span: crate::types::Span::dummy(),
         body: FunctionBody {
             statements,
             span: crate::types::Span::dummy(),
         },
         span: crate::types::Span::dummy(),
         documentation: None,
+        pure: false,
     }
 }
 
+fn parse_source(source: &str) -> Module {
+    use crate::compiler::{CodespanParser, CompilationUnit};
+    use crate::diagnostics::DiagnosticManager;
+
+    let unit = CompilationUnit::from_string(source.to_string());
+    let mut manager = DiagnosticManager::new();
+    let file_id = manager.add_file("test.sa".to_string(), source.to_string());
+    CodespanParser::new()
+        .parse(&unit, file_id, manager.reporter())
+        .unwrap()
+}
+
 fn create_parameter(name: &str, param_type: AstType) -> Parameter {
     Parameter {
         name: name.to_string(),
         param_type,
+        type_span: crate::types::Span::dummy(),
         span: crate::types::Span::dummy(),
+        comment: None,
     }
 }
 
@@ -49,10 +65,14 @@ mod tests {
             "greet",
             vec![create_parameter("name", AstType::String)],
             AstType::String,
-            vec![Statement::Return(Expression::Variable {
-                name: "name".to_string(),
+            vec![Statement::Return {
+                expression: Expression::Variable {
+                    name: "name".to_string(),
+                    span: crate::types::Span::dummy(),
+                },
+                comment: None,
                 span: crate::types::Span::dummy(),
-            })],
+            }],
         );
 
         let module = create_test_module(vec![Definition::Function(func)]);
@@ -67,10 +87,14 @@ mod tests {
             "test",
             vec![],
             AstType::Unit,
-            vec![Statement::Return(Expression::Variable {
-                name: "unknown".to_string(),
+            vec![Statement::Return {
+                expression: Expression::Variable {
+                    name: "unknown".to_string(),
+                    span: crate::types::Span::dummy(),
+                },
+                comment: None,
                 span: crate::types::Span::dummy(),
-            })],
+            }],
         );
 
         let module = create_test_module(vec![Definition::Function(func)]);
@@ -90,24 +114,32 @@ mod tests {
             "greet",
             vec![create_parameter("name", AstType::String)],
             AstType::String,
-            vec![Statement::Return(Expression::Variable {
-                name: "name".to_string(),
+            vec![Statement::Return {
+                expression: Expression::Variable {
+                    name: "name".to_string(),
+                    span: crate::types::Span::dummy(),
+                },
+                comment: None,
                 span: crate::types::Span::dummy(),
-            })],
+            }],
         );
 
         let main_func = create_test_function(
             "main",
             vec![],
             AstType::Unit,
-            vec![Statement::ExpressionStatement(Expression::Call {
-                function: "greet".to_string(),
-                arguments: vec![Expression::StringLiteral {
-                    value: "Alice".to_string(),
+            vec![Statement::ExpressionStatement {
+                expression: Expression::Call {
+                    function: "greet".to_string(),
+                    arguments: vec![Expression::StringLiteral {
+                        value: "Alice".to_string(),
+                        multiline: false,
+                        span: crate::types::Span::dummy(),
+                    }],
                     span: crate::types::Span::dummy(),
-                }],
-                span: crate::types::Span::dummy(),
-            })],
+                },
+                comment: None,
+            }],
         );
 
         let module = create_test_module(vec![
@@ -125,24 +157,31 @@ mod tests {
             "greet",
             vec![create_parameter("name", AstType::String)],
             AstType::String,
-            vec![Statement::Return(Expression::Variable {
-                name: "name".to_string(),
+            vec![Statement::Return {
+                expression: Expression::Variable {
+                    name: "name".to_string(),
+                    span: crate::types::Span::dummy(),
+                },
+                comment: None,
                 span: crate::types::Span::dummy(),
-            })],
+            }],
         );
 
         let main_func = create_test_function(
             "main",
             vec![],
             AstType::Unit,
-            vec![Statement::ExpressionStatement(Expression::Call {
-                function: "greet".to_string(),
-                arguments: vec![Expression::BooleanLiteral {
-                    value: true,
+            vec![Statement::ExpressionStatement {
+                expression: Expression::Call {
+                    function: "greet".to_string(),
+                    arguments: vec![Expression::BooleanLiteral {
+                        value: true,
+                        span: crate::types::Span::dummy(),
+                    }],
                     span: crate::types::Span::dummy(),
-                }],
-                span: crate::types::Span::dummy(),
-            })],
+                },
+                comment: None,
+            }],
         );
 
         let module = create_test_module(vec![
@@ -165,21 +204,28 @@ mod tests {
             "greet",
             vec![create_parameter("name", AstType::String)],
             AstType::String,
-            vec![Statement::Return(Expression::Variable {
-                name: "name".to_string(),
+            vec![Statement::Return {
+                expression: Expression::Variable {
+                    name: "name".to_string(),
+                    span: crate::types::Span::dummy(),
+                },
+                comment: None,
                 span: crate::types::Span::dummy(),
-            })],
+            }],
         );
 
         let main_func = create_test_function(
             "main",
             vec![],
             AstType::Unit,
-            vec![Statement::ExpressionStatement(Expression::Call {
-                function: "greet".to_string(),
-                arguments: vec![],
-                span: crate::types::Span::dummy(),
-            })],
+            vec![Statement::ExpressionStatement {
+                expression: Expression::Call {
+                    function: "greet".to_string(),
+                    arguments: vec![],
+                    span: crate::types::Span::dummy(),
+                },
+                comment: None,
+            }],
         );
 
         let module = create_test_module(vec![
@@ -209,13 +255,16 @@ mod tests {
             "main",
             vec![],
             AstType::Unit,
-            vec![Statement::ExpressionStatement(Expression::Call {
-                function: "test".to_string(),
-                arguments: vec![Expression::Placeholder {
+            vec![Statement::ExpressionStatement {
+                expression: Expression::Call {
+                    function: "test".to_string(),
+                    arguments: vec![Expression::Placeholder {
+                        span: crate::types::Span::dummy(),
+                    }],
                     span: crate::types::Span::dummy(),
-                }],
-                span: crate::types::Span::dummy(),
-            })],
+                },
+                comment: None,
+            }],
         );
 
         let module = create_test_module(vec![
@@ -237,10 +286,15 @@ mod tests {
             "get_name",
             vec![],
             AstType::String,
-            vec![Statement::Return(Expression::StringLiteral {
-                value: "Alice".to_string(),
+            vec![Statement::Return {
+                expression: Expression::StringLiteral {
+                    value: "Alice".to_string(),
+                    multiline: false,
+                    span: crate::types::Span::dummy(),
+                },
+                comment: None,
                 span: crate::types::Span::dummy(),
-            })],
+            }],
         );
 
         let main_func = create_test_function(
@@ -256,11 +310,15 @@ mod tests {
                         span: crate::types::Span::dummy(),
                     },
                     span: crate::types::Span::dummy(),
+                    comment: None,
+                },
+                Statement::ExpressionStatement {
+                    expression: Expression::Variable {
+                        name: "name".to_string(),
+                        span: crate::types::Span::dummy(),
+                    },
+                    comment: None,
                 },
-                Statement::ExpressionStatement(Expression::Variable {
-                    name: "name".to_string(),
-                    span: crate::types::Span::dummy(),
-                }),
             ],
         );
 
@@ -291,14 +349,17 @@ mod tests {
                         span: crate::types::Span::dummy(),
                     },
                     span: crate::types::Span::dummy(),
+                    comment: None,
                 },
                 Statement::VariableAssignment {
                     variable: "flag".to_string(),
                     expression: Expression::StringLiteral {
                         value: "hello".to_string(),
+                        multiline: false,
                         span: crate::types::Span::dummy(),
                     },
                     span: crate::types::Span::dummy(),
+                    comment: None,
                 },
             ],
         );
@@ -323,11 +384,13 @@ mod tests {
             vec![Statement::If {
                 condition: Expression::StringLiteral {
                     value: "hello".to_string(),
+                    multiline: false,
                     span: crate::types::Span::dummy(),
                 },
                 body: vec![],
                 else_body: None,
                 span: crate::types::Span::dummy(),
+                comment: None,
             }],
         );
 
@@ -351,10 +414,12 @@ mod tests {
             vec![Statement::While {
                 condition: Expression::StringLiteral {
                     value: "hello".to_string(),
+                    multiline: false,
                     span: crate::types::Span::dummy(),
                 },
                 body: vec![],
                 span: crate::types::Span::dummy(),
+                comment: None,
             }],
         );
 
@@ -370,272 +435,1027 @@ mod tests {
     }
 
     #[test]
-    fn test_return_type_mismatch() {
+    fn test_assert_condition_must_be_boolean() {
         let func = create_test_function(
             "test",
             vec![],
-            AstType::String,
-            vec![Statement::Return(Expression::BooleanLiteral {
-                value: true,
+            AstType::Unit,
+            vec![Statement::Assert {
+                condition: Expression::StringLiteral {
+                    value: "hello".to_string(),
+                    multiline: false,
+                    span: crate::types::Span::dummy(),
+                },
+                message: Expression::StringLiteral {
+                    value: "condition failed".to_string(),
+                    multiline: false,
+                    span: crate::types::Span::dummy(),
+                },
                 span: crate::types::Span::dummy(),
-            })],
+                comment: None,
+            }],
         );
 
         let module = create_test_module(vec![Definition::Function(func)]);
         let mut checker = TypeChecker::new();
 
         let result = checker.check_module(&module, 0);
-        assert!(result.is_err());
         assert!(matches!(
             result.unwrap_err(),
-            TypeError::ReturnTypeMismatch { .. }
+            TypeError::TypeMismatch { expected, .. } if expected == "Boolean"
         ));
     }
 
     #[test]
-    fn test_select_all_branches_same_type() {
-        let add_func = create_test_function(
-            "add",
-            vec![
-                create_parameter("a", AstType::String),
-                create_parameter("b", AstType::String),
-            ],
-            AstType::String,
-            vec![Statement::Return(Expression::StringLiteral {
-                value: "result".to_string(),
+    fn test_assert_message_must_be_string() {
+        let func = create_test_function(
+            "test",
+            vec![],
+            AstType::Unit,
+            vec![Statement::Assert {
+                condition: Expression::BooleanLiteral {
+                    value: true,
+                    span: crate::types::Span::dummy(),
+                },
+                message: Expression::BooleanLiteral {
+                    value: false,
+                    span: crate::types::Span::dummy(),
+                },
                 span: crate::types::Span::dummy(),
-            })],
+                comment: None,
+            }],
         );
 
-        let concat_func = create_test_function(
-            "concat",
-            vec![
-                create_parameter("value2", AstType::String),
-                create_parameter("value1", AstType::String),
-            ],
-            AstType::String,
-            vec![Statement::Return(Expression::StringLiteral {
-                value: "concatenated".to_string(),
-                span: crate::types::Span::dummy(),
-            })],
-        );
+        let module = create_test_module(vec![Definition::Function(func)]);
+        let mut checker = TypeChecker::new();
 
-        let main_func = create_test_function(
-            "main",
+        let result = checker.check_module(&module, 0);
+        assert!(matches!(
+            result.unwrap_err(),
+            TypeError::TypeMismatch { expected, .. } if expected == "String"
+        ));
+    }
+
+    #[test]
+    fn test_implicit_return_matches_declared_type() {
+        let func = create_test_function(
+            "test",
             vec![],
             AstType::String,
-            vec![Statement::Return(Expression::Select(SelectExpression {
-                clauses: vec![
-                    SelectClause {
-                        expression_to_run: Expression::Call {
-                            function: "add".to_string(),
-                            arguments: vec![
-                                Expression::Placeholder {
-                                    span: crate::types::Span::dummy(),
-                                },
-                                Expression::Placeholder {
-                                    span: crate::types::Span::dummy(),
-                                },
-                            ],
-                            span: crate::types::Span::dummy(),
-                        },
-                        result_variable: "sum".to_string(),
-                        expression_next: Expression::Variable {
-                            name: "sum".to_string(),
-                            span: crate::types::Span::dummy(),
-                        },
-                        span: crate::types::Span::dummy(),
-                    },
-                    SelectClause {
-                        expression_to_run: Expression::Call {
-                            function: "concat".to_string(),
-                            arguments: vec![
-                                Expression::Placeholder {
-                                    span: crate::types::Span::dummy(),
-                                },
-                                Expression::Placeholder {
-                                    span: crate::types::Span::dummy(),
-                                },
-                            ],
-                            span: crate::types::Span::dummy(),
-                        },
-                        result_variable: "text".to_string(),
-                        expression_next: Expression::Variable {
-                            name: "text".to_string(),
-                            span: crate::types::Span::dummy(),
-                        },
-                        span: crate::types::Span::dummy(),
-                    },
-                ],
-                span: crate::types::Span::dummy(),
-            }))],
+            vec![Statement::ExpressionStatement {
+                expression: Expression::StringLiteral {
+                    value: "value".to_string(),
+                    multiline: false,
+                    span: crate::types::Span::dummy(),
+                },
+                comment: None,
+            }],
         );
 
-        let module = create_test_module(vec![
-            Definition::Function(add_func),
-            Definition::Function(concat_func),
-            Definition::Function(main_func),
-        ]);
+        let module = create_test_module(vec![Definition::Function(func)]);
         let mut checker = TypeChecker::new();
 
         assert!(checker.check_module(&module, 0).is_ok());
     }
 
     #[test]
-    fn test_select_branch_type_mismatch() {
-        let get_string_func = create_test_function(
-            "get_string",
+    fn test_implicit_return_type_mismatch() {
+        let func = create_test_function(
+            "test",
             vec![],
             AstType::String,
-            vec![Statement::Return(Expression::StringLiteral {
-                value: "text".to_string(),
-                span: crate::types::Span::dummy(),
-            })],
+            vec![Statement::ExpressionStatement {
+                expression: Expression::BooleanLiteral {
+                    value: true,
+                    span: crate::types::Span::dummy(),
+                },
+                comment: None,
+            }],
         );
 
-        let get_bool_func = create_test_function(
-            "get_bool",
-            vec![],
-            AstType::Boolean,
-            vec![Statement::Return(Expression::BooleanLiteral {
-                value: true,
-                span: crate::types::Span::dummy(),
-            })],
-        );
+        let module = create_test_module(vec![Definition::Function(func)]);
+        let mut checker = TypeChecker::new();
 
-        let main_func = create_test_function(
-            "main",
+        assert!(matches!(
+            checker.check_module(&module, 0),
+            Err(TypeError::ReturnTypeMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_trailing_expression_in_unit_function_is_not_returned() {
+        let func = create_test_function(
+            "test",
             vec![],
-            AstType::String,
-            vec![Statement::Return(Expression::Select(SelectExpression {
-                clauses: vec![
-                    SelectClause {
-                        expression_to_run: Expression::Call {
-                            function: "get_string".to_string(),
-                            arguments: vec![],
-                            span: crate::types::Span::dummy(),
-                        },
-                        result_variable: "str_result".to_string(),
-                        expression_next: Expression::Variable {
-                            name: "str_result".to_string(),
-                            span: crate::types::Span::dummy(),
-                        },
-                        span: crate::types::Span::dummy(),
-                    },
-                    SelectClause {
-                        expression_to_run: Expression::Call {
-                            function: "get_bool".to_string(),
-                            arguments: vec![],
-                            span: crate::types::Span::dummy(),
-                        },
-                        result_variable: "bool_result".to_string(),
-                        expression_next: Expression::Variable {
-                            name: "bool_result".to_string(),
-                            span: crate::types::Span::dummy(),
-                        },
-                        span: crate::types::Span::dummy(),
-                    },
-                ],
-                span: crate::types::Span::dummy(),
-            }))],
+            AstType::Unit,
+            vec![Statement::ExpressionStatement {
+                expression: Expression::StringLiteral {
+                    value: "discarded".to_string(),
+                    multiline: false,
+                    span: crate::types::Span::dummy(),
+                },
+                comment: None,
+            }],
         );
 
-        let module = create_test_module(vec![
-            Definition::Function(get_string_func),
-            Definition::Function(get_bool_func),
-            Definition::Function(main_func),
-        ]);
+        let module = create_test_module(vec![Definition::Function(func)]);
         let mut checker = TypeChecker::new();
 
-        let result = checker.check_module(&module, 0);
-        assert!(result.is_err());
-        assert!(matches!(
-            result.unwrap_err(),
-            TypeError::SelectBranchTypeMismatch { .. }
-        ));
+        assert!(checker.check_module(&module, 0).is_ok());
     }
 
     #[test]
-    fn test_external_function_type_checking() {
+    fn test_wrong_concrete_argument_beside_placeholders_is_rejected() {
         use crate::ast::ExternalFunction;
 
-        let ext_func = ExternalFunction {
-            name: "concat".to_string(),
+        let compose = ExternalFunction {
+            name: "compose".to_string(),
             parameters: vec![
-                create_parameter("value1", AstType::String),
-                create_parameter("id", AstType::String),
+                create_parameter("greeting", AstType::String),
+                create_parameter("name", AstType::String),
+                create_parameter("loud", AstType::Boolean),
             ],
-            return_type: AstType::String,
+            return_type: AstType::Unit,
+            return_type_span: crate::types::Span::dummy(),
             span: crate::types::Span::dummy(),
         };
-
-        let main_func = create_test_function(
-            "main",
+        let func = create_test_function(
+            "test",
             vec![],
             AstType::Unit,
-            vec![Statement::ExpressionStatement(Expression::Call {
-                function: "concat".to_string(),
-                arguments: vec![
-                    Expression::StringLiteral {
-                        value: "hello".to_string(),
-                        span: crate::types::Span::dummy(),
-                    },
-                    Expression::StringLiteral {
-                        value: "world".to_string(),
-                        span: crate::types::Span::dummy(),
-                    },
-                ],
-                span: crate::types::Span::dummy(),
-            })],
+            vec![Statement::ExpressionStatement {
+                expression: Expression::Call {
+                    function: "compose".to_string(),
+                    arguments: vec![
+                        Expression::StringLiteral {
+                            value: "Hello".to_string(),
+                            multiline: false,
+                            span: crate::types::Span::dummy(),
+                        },
+                        Expression::Placeholder {
+                            span: crate::types::Span::dummy(),
+                        },
+                        Expression::StringLiteral {
+                            value: "yes".to_string(),
+                            multiline: false,
+                            span: crate::types::Span::dummy(),
+                        },
+                    ],
+                    span: crate::types::Span::dummy(),
+                },
+                comment: None,
+            }],
         );
 
         let module = create_test_module(vec![
-            Definition::ExternalFunction(ext_func),
-            Definition::Function(main_func),
+            Definition::ExternalFunction(compose),
+            Definition::Function(func),
         ]);
         let mut checker = TypeChecker::new();
 
-        assert!(checker.check_module(&module, 0).is_ok());
+        match checker.check_module(&module, 0) {
+            Err(TypeError::ArgumentTypeMismatch {
+                parameter,
+                expected,
+                found,
+                ..
+            }) => {
+                assert_eq!(parameter, "loud");
+                assert_eq!(expected, "Boolean");
+                assert_eq!(found, "String");
+            }
+            other => panic!("Expected ArgumentTypeMismatch, got {:?}", other),
+        }
     }
 
     #[test]
-    fn test_nested_scope_variable_isolation() {
+    fn test_field_access_on_json_is_json() {
         let func = create_test_function(
             "test",
-            vec![],
-            AstType::Unit,
-            vec![
-                Statement::If {
-                    condition: Expression::BooleanLiteral {
-                        value: true,
-                        span: crate::types::Span::dummy(),
-                    },
-                    body: vec![Statement::Assignment {
-                        variable: "inner_var".to_string(),
-                        expression: Expression::StringLiteral {
-                            value: "hello".to_string(),
-                            span: crate::types::Span::dummy(),
-                        },
+            vec![create_parameter("issue", AstType::Json)],
+            AstType::Json,
+            vec![Statement::Return {
+                expression: Expression::FieldAccess {
+                    object: Box::new(Expression::Variable {
+                        name: "issue".to_string(),
                         span: crate::types::Span::dummy(),
-                    }],
-                    else_body: None,
+                    }),
+                    field: "title".to_string(),
                     span: crate::types::Span::dummy(),
                 },
-                Statement::ExpressionStatement(Expression::Variable {
-                    name: "inner_var".to_string(),
-                    span: crate::types::Span::dummy(),
-                }),
-            ],
+                comment: None,
+                span: crate::types::Span::dummy(),
+            }],
         );
 
         let module = create_test_module(vec![Definition::Function(func)]);
         let mut checker = TypeChecker::new();
 
-        let result = checker.check_module(&module, 0);
-        assert!(result.is_err());
-        assert!(matches!(
-            result.unwrap_err(),
-            TypeError::UnknownVariable { .. }
+        assert!(checker.check_module(&module, 0).is_ok());
+    }
+
+    #[test]
+    fn test_field_access_on_string_is_rejected() {
+        let func = create_test_function(
+            "test",
+            vec![create_parameter("name", AstType::String)],
+            AstType::Json,
+            vec![Statement::Return {
+                expression: Expression::FieldAccess {
+                    object: Box::new(Expression::Variable {
+                        name: "name".to_string(),
+                        span: crate::types::Span::dummy(),
+                    }),
+                    field: "title".to_string(),
+                    span: crate::types::Span::dummy(),
+                },
+                comment: None,
+                span: crate::types::Span::dummy(),
+            }],
+        );
+
+        let module = create_test_module(vec![Definition::Function(func)]);
+        let mut checker = TypeChecker::new();
+
+        match checker.check_module(&module, 0) {
+            Err(TypeError::InvalidFieldAccess { field, found, .. }) => {
+                assert_eq!(field, "title");
+                assert_eq!(found, "String");
+            }
+            other => panic!("Expected InvalidFieldAccess, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_return_type_mismatch() {
+        let func = create_test_function(
+            "test",
+            vec![],
+            AstType::String,
+            vec![Statement::Return {
+                expression: Expression::BooleanLiteral {
+                    value: true,
+                    span: crate::types::Span::dummy(),
+                },
+                comment: None,
+                span: crate::types::Span::dummy(),
+            }],
+        );
+
+        let module = create_test_module(vec![Definition::Function(func)]);
+        let mut checker = TypeChecker::new();
+
+        let result = checker.check_module(&module, 0);
+        assert!(result.is_err());
+        assert!(matches!(
+            result.unwrap_err(),
+            TypeError::ReturnTypeMismatch { .. }
+        ));
+    }
+
+    #[test]
+    fn test_select_all_branches_same_type() {
+        let add_func = create_test_function(
+            "add",
+            vec![
+                create_parameter("a", AstType::String),
+                create_parameter("b", AstType::String),
+            ],
+            AstType::String,
+            vec![Statement::Return {
+                expression: Expression::StringLiteral {
+                    value: "result".to_string(),
+                    multiline: false,
+                    span: crate::types::Span::dummy(),
+                },
+                comment: None,
+                span: crate::types::Span::dummy(),
+            }],
+        );
+
+        let concat_func = create_test_function(
+            "concat",
+            vec![
+                create_parameter("value2", AstType::String),
+                create_parameter("value1", AstType::String),
+            ],
+            AstType::String,
+            vec![Statement::Return {
+                expression: Expression::StringLiteral {
+                    value: "concatenated".to_string(),
+                    multiline: false,
+                    span: crate::types::Span::dummy(),
+                },
+                comment: None,
+                span: crate::types::Span::dummy(),
+            }],
+        );
+
+        let main_func = create_test_function(
+            "main",
+            vec![],
+            AstType::String,
+            vec![Statement::Return {
+                expression: Expression::Select(SelectExpression {
+                    clauses: vec![
+                        SelectClause {
+                            expression_to_run: Expression::Call {
+                                function: "add".to_string(),
+                                arguments: vec![
+                                    Expression::Placeholder {
+                                        span: crate::types::Span::dummy(),
+                                    },
+                                    Expression::Placeholder {
+                                        span: crate::types::Span::dummy(),
+                                    },
+                                ],
+                                span: crate::types::Span::dummy(),
+                            },
+                            result_variable: "sum".to_string(),
+                            guard: None,
+                            expression_next: Expression::Variable {
+                                name: "sum".to_string(),
+                                span: crate::types::Span::dummy(),
+                            },
+                            span: crate::types::Span::dummy(),
+                        },
+                        SelectClause {
+                            expression_to_run: Expression::Call {
+                                function: "concat".to_string(),
+                                arguments: vec![
+                                    Expression::Placeholder {
+                                        span: crate::types::Span::dummy(),
+                                    },
+                                    Expression::Placeholder {
+                                        span: crate::types::Span::dummy(),
+                                    },
+                                ],
+                                span: crate::types::Span::dummy(),
+                            },
+                            result_variable: "text".to_string(),
+                            guard: None,
+                            expression_next: Expression::Variable {
+                                name: "text".to_string(),
+                                span: crate::types::Span::dummy(),
+                            },
+                            span: crate::types::Span::dummy(),
+                        },
+                    ],
+                    ranked: false,
+                    retry: false,
+                    span: crate::types::Span::dummy(),
+                }),
+                comment: None,
+                span: crate::types::Span::dummy(),
+            }],
+        );
+
+        let module = create_test_module(vec![
+            Definition::Function(add_func),
+            Definition::Function(concat_func),
+            Definition::Function(main_func),
+        ]);
+        let mut checker = TypeChecker::new();
+
+        assert!(checker.check_module(&module, 0).is_ok());
+    }
+
+    #[test]
+    fn test_select_branch_type_mismatch() {
+        let get_string_func = create_test_function(
+            "get_string",
+            vec![],
+            AstType::String,
+            vec![Statement::Return {
+                expression: Expression::StringLiteral {
+                    value: "text".to_string(),
+                    multiline: false,
+                    span: crate::types::Span::dummy(),
+                },
+                comment: None,
+                span: crate::types::Span::dummy(),
+            }],
+        );
+
+        let get_bool_func = create_test_function(
+            "get_bool",
+            vec![],
+            AstType::Boolean,
+            vec![Statement::Return {
+                expression: Expression::BooleanLiteral {
+                    value: true,
+                    span: crate::types::Span::dummy(),
+                },
+                comment: None,
+                span: crate::types::Span::dummy(),
+            }],
+        );
+
+        let main_func = create_test_function(
+            "main",
+            vec![],
+            AstType::String,
+            vec![Statement::Return {
+                expression: Expression::Select(SelectExpression {
+                    clauses: vec![
+                        SelectClause {
+                            expression_to_run: Expression::Call {
+                                function: "get_string".to_string(),
+                                arguments: vec![],
+                                span: crate::types::Span::dummy(),
+                            },
+                            result_variable: "str_result".to_string(),
+                            guard: None,
+                            expression_next: Expression::Variable {
+                                name: "str_result".to_string(),
+                                span: crate::types::Span::dummy(),
+                            },
+                            span: crate::types::Span::dummy(),
+                        },
+                        SelectClause {
+                            expression_to_run: Expression::Call {
+                                function: "get_bool".to_string(),
+                                arguments: vec![],
+                                span: crate::types::Span::dummy(),
+                            },
+                            result_variable: "bool_result".to_string(),
+                            guard: None,
+                            expression_next: Expression::Variable {
+                                name: "bool_result".to_string(),
+                                span: crate::types::Span::dummy(),
+                            },
+                            span: crate::types::Span::dummy(),
+                        },
+                    ],
+                    ranked: false,
+                    retry: false,
+                    span: crate::types::Span::dummy(),
+                }),
+                comment: None,
+                span: crate::types::Span::dummy(),
+            }],
+        );
+
+        let module = create_test_module(vec![
+            Definition::Function(get_string_func),
+            Definition::Function(get_bool_func),
+            Definition::Function(main_func),
+        ]);
+        let mut checker = TypeChecker::new();
+
+        let result = checker.check_module(&module, 0);
+        assert!(result.is_err());
+        assert!(matches!(
+            result.unwrap_err(),
+            TypeError::SelectBranchTypeMismatch { .. }
+        ));
+    }
+
+    #[test]
+    fn test_external_function_type_checking() {
+        use crate::ast::ExternalFunction;
+
+        let ext_func = ExternalFunction {
+            name: "concat".to_string(),
+            parameters: vec![
+                create_parameter("value1", AstType::String),
+                create_parameter("id", AstType::String),
+            ],
+            return_type: AstType::String,
+            return_type_span: crate::types::Span::dummy(),
+            span: crate::types::Span::dummy(),
+        };
+
+        let main_func = create_test_function(
+            "main",
+            vec![],
+            AstType::Unit,
+            vec![Statement::ExpressionStatement {
+                expression: Expression::Call {
+                    function: "concat".to_string(),
+                    arguments: vec![
+                        Expression::StringLiteral {
+                            value: "hello".to_string(),
+                            multiline: false,
+                            span: crate::types::Span::dummy(),
+                        },
+                        Expression::StringLiteral {
+                            value: "world".to_string(),
+                            multiline: false,
+                            span: crate::types::Span::dummy(),
+                        },
+                    ],
+                    span: crate::types::Span::dummy(),
+                },
+                comment: None,
+            }],
+        );
+
+        let module = create_test_module(vec![
+            Definition::ExternalFunction(ext_func),
+            Definition::Function(main_func),
+        ]);
+        let mut checker = TypeChecker::new();
+
+        assert!(checker.check_module(&module, 0).is_ok());
+    }
+
+    #[test]
+    fn test_external_const_type_checking() {
+        use crate::ast::ExternalConst;
+
+        let ext_const = ExternalConst {
+            name: "schema".to_string(),
+            const_type: AstType::String,
+            type_span: crate::types::Span::dummy(),
+            span: crate::types::Span::dummy(),
+        };
+
+        let main_func = create_test_function(
+            "main",
+            vec![],
+            AstType::Boolean,
+            vec![Statement::Return {
+                expression: Expression::Variable {
+                    name: "schema".to_string(),
+                    span: crate::types::Span::dummy(),
+                },
+                comment: None,
+                span: crate::types::Span::dummy(),
+            }],
+        );
+
+        let module = create_test_module(vec![
+            Definition::ExternalConst(ext_const),
+            Definition::Function(main_func),
+        ]);
+        let mut checker = TypeChecker::new();
+
+        assert!(matches!(
+            checker.check_module(&module, 0),
+            Err(TypeError::ReturnTypeMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_select_reports_diverging_third_clause() {
+        let source = r#"
+fn first(): String {
+    return "a"
+}
+
+fn second(): String {
+    return "b"
+}
+
+fn third(): Boolean {
+    return true
+}
+
+fn main(): String {
+    let result = select {
+        first() as a => a,
+        second() as b => b,
+        third() as c => c
+    }
+    return result
+}
+"#;
+        let module = parse_source(source);
+
+        let mut checker = TypeChecker::new();
+        let error = checker.check_module(&module, 0).unwrap_err();
+
+        match &error {
+            TypeError::SelectBranchTypeMismatch {
+                expected,
+                found,
+                branch_index,
+                span,
+                first_branch_span,
+                ..
+            } => {
+                assert_eq!(expected, "String");
+                assert_eq!(found, "Boolean");
+                assert_eq!(*branch_index, 2);
+                assert_eq!(source[span.to_byte_range()].trim(), "c");
+                assert_eq!(source[first_branch_span.to_byte_range()].trim(), "a");
+            }
+            other => panic!("Expected SelectBranchTypeMismatch, got: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_select_collects_errors_from_all_clauses() {
+        let source = r#"
+fn known(): String {
+    return "a"
+}
+
+fn main(): String {
+    let result = select {
+        missing_one() as a => a,
+        known() as b => b,
+        missing_two() as c => c
+    }
+    return result
+}
+"#;
+        let module = parse_source(source);
+
+        let mut checker = TypeChecker::new();
+        let error = checker.check_module(&module, 0).unwrap_err();
+
+        match &error {
+            TypeError::SelectClauseErrors { errors, .. } => {
+                let names: Vec<&str> = errors
+                    .iter()
+                    .map(|e| match e {
+                        TypeError::UnknownFunction { name, .. } => name.as_str(),
+                        other => panic!("Expected UnknownFunction, got: {:?}", other),
+                    })
+                    .collect();
+                assert_eq!(names, vec!["missing_one", "missing_two"]);
+            }
+            other => panic!("Expected SelectClauseErrors, got: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_select_guard_must_be_boolean() {
+        let source = r#"
+fn first(): String {
+    return "a"
+}
+
+fn pick(label: String): String {
+    let result = select {
+        first() as a if label => a
+    }
+    return result
+}
+"#;
+        let module = parse_source(source);
+
+        let mut checker = TypeChecker::new();
+        let error = checker.check_module(&module, 0).unwrap_err();
+
+        match &error {
+            TypeError::TypeMismatch {
+                expected,
+                found,
+                span,
+                ..
+            } => {
+                assert_eq!(expected, "Boolean");
+                assert_eq!(found, "String");
+                assert_eq!(&source[span.to_byte_range()], "label");
+            }
+            other => panic!("Expected TypeMismatch, got: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_unsupported_type_span_covers_type_annotation() {
+        let source = "fn lookup(key: Integer): String {\n    return key\n}\n";
+        let module = parse_source(source);
+
+        let mut checker = TypeChecker::new();
+        let error = checker.check_module(&module, 0).unwrap_err();
+
+        match &error {
+            TypeError::UnsupportedType { type_name, .. } => assert_eq!(type_name, "Integer"),
+            other => panic!("Expected UnsupportedType, got: {:?}", other),
+        }
+
+        let span = error.span();
+        assert_eq!(&source[span.to_byte_range()], "Integer");
+    }
+
+    #[test]
+    fn test_return_in_if_else_expression_is_rejected() {
+        let source = "fn pick(c: Boolean, x: String, y: String): String {\n    let r = if c { return x } else { y }\n    return r\n}\n";
+        let module = parse_source(source);
+
+        let mut checker = TypeChecker::new();
+        let error = checker.check_module(&module, 0).unwrap_err();
+
+        assert!(matches!(
+            error,
+            TypeError::ReturnInExpressionPosition { .. }
+        ));
+        assert_eq!(&source[error.span().to_byte_range()], "return x");
+        assert_eq!(
+            error.to_string(),
+            "'return' is a statement and cannot be used in expression position"
+        );
+    }
+
+    #[test]
+    fn test_return_statement_inside_if_body_is_allowed() {
+        let source = "fn pick(c: Boolean, x: String, y: String): String {\n    if c {\n        return x\n    }\n    return y\n}\n";
+        let module = parse_source(source);
+
+        let mut checker = TypeChecker::new();
+        assert!(checker.check_module(&module, 0).is_ok());
+    }
+
+    #[test]
+    fn test_number_literals_are_numbers() {
+        let source = "fn scale(factor: Number): Number {\n    let base = 42\n    return if true { base } else { factor }\n}\n\nfn main(): Number {\n    return scale(3.14)\n}\n";
+        let module = parse_source(source);
+
+        let mut checker = TypeChecker::new();
+        assert!(checker.check_module(&module, 0).is_ok());
+    }
+
+    #[test]
+    fn test_if_else_mixing_number_and_string_is_rejected() {
+        let source = "fn pick(c: Boolean): Number {\n    let r = if c { 1 } else { \"one\" }\n    return r\n}\n";
+        let module = parse_source(source);
+
+        let mut checker = TypeChecker::new();
+        match checker.check_module(&module, 0) {
+            Err(TypeError::TypeMismatch {
+                expected, found, ..
+            }) => {
+                assert_eq!(expected, "Number");
+                assert_eq!(found, "String");
+            }
+            other => panic!("Expected TypeMismatch, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_select_mixing_number_and_boolean_is_rejected() {
+        let source = "fn count(): Number {\n    return 2\n}\n\nfn ready(): Boolean {\n    return true\n}\n\nfn main(): Number {\n    return select {\n        count() as n => n,\n        ready() as r => r\n    }\n}\n";
+        let module = parse_source(source);
+
+        let mut checker = TypeChecker::new();
+        match checker.check_module(&module, 0) {
+            Err(TypeError::SelectBranchTypeMismatch {
+                expected, found, ..
+            }) => {
+                assert_eq!(expected, "Number");
+                assert_eq!(found, "Boolean");
+            }
+            other => panic!("Expected SelectBranchTypeMismatch, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_constants_are_visible_in_every_function() {
+        let source = "const GREETING = \"Hello\"\n\nfn greet(): String {\n    return GREETING\n}\n\nfn main(): String {\n    let GREETING = \"Hi\"\n    GREETING = \"Hey\"\n    return GREETING\n}\n";
+        let module = parse_source(source);
+
+        let mut checker = TypeChecker::new();
+        assert!(checker.check_module(&module, 0).is_ok());
+    }
+
+    #[test]
+    fn test_assigning_to_constant_is_rejected() {
+        let source = "const GREETING = \"Hello\"\n\nfn main(): String {\n    GREETING = \"Hey\"\n    return GREETING\n}\n";
+        let module = parse_source(source);
+
+        let mut checker = TypeChecker::new();
+        match checker.check_module(&module, 0) {
+            Err(TypeError::AssignmentToConstant {
+                name,
+                declaration_span,
+                ..
+            }) => {
+                assert_eq!(name, "GREETING");
+                assert_eq!(declaration_span.start, 0);
+            }
+            other => panic!("Expected AssignmentToConstant, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_redefining_constant_is_rejected() {
+        let source = "const GREETING = \"Hello\"\nconst GREETING = \"Hi\"\n\nfn main(): String {\n    return GREETING\n}\n";
+        let module = parse_source(source);
+
+        let mut checker = TypeChecker::new();
+        match checker.check_module(&module, 0) {
+            Err(TypeError::DuplicateConstant {
+                name,
+                span,
+                previous_span,
+                ..
+            }) => {
+                assert_eq!(name, "GREETING");
+                assert_eq!(previous_span.start, 0);
+                assert_eq!(span.start, source.find("\nconst").unwrap() + 1);
+            }
+            other => panic!("Expected DuplicateConstant, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_else_if_condition_must_be_boolean() {
+        let source = "fn pick(a: Boolean, b: String): () {\n    if a {\n        \"a\"!\n    } else if b {\n        \"b\"!\n    }\n}\n";
+        let module = parse_source(source);
+
+        let mut checker = TypeChecker::new();
+        match checker.check_module(&module, 0) {
+            Err(TypeError::TypeMismatch {
+                expected,
+                found,
+                span,
+                ..
+            }) => {
+                assert_eq!(expected, "Boolean");
+                assert_eq!(found, "String");
+                assert_eq!(span.start, source.find("if b").unwrap() + 3);
+            }
+            other => panic!("Expected TypeMismatch, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_match_on_boolean() {
+        let source = "fn label(flag: Boolean): String {\n    return match flag {\n        false => \"off\",\n        true => \"on\",\n    }\n}\n";
+        let module = parse_source(source);
+
+        let mut checker = TypeChecker::new();
+        assert!(checker.check_module(&module, 0).is_ok());
+    }
+
+    #[test]
+    fn test_match_scrutinee_must_be_boolean() {
+        let source = "fn label(flag: String): String {\n    return match flag {\n        true => \"on\",\n        false => \"off\"\n    }\n}\n";
+        let module = parse_source(source);
+
+        let mut checker = TypeChecker::new();
+        match checker.check_module(&module, 0) {
+            Err(TypeError::TypeMismatch {
+                expected, found, ..
+            }) => {
+                assert_eq!(expected, "Boolean");
+                assert_eq!(found, "String");
+            }
+            other => panic!("Expected TypeMismatch, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_match_missing_arm_is_rejected() {
+        let source = "fn label(flag: Boolean): String {\n    return match flag {\n        true => \"on\"\n    }\n}\n";
+        let module = parse_source(source);
+
+        let mut checker = TypeChecker::new();
+        match checker.check_module(&module, 0) {
+            Err(TypeError::NonExhaustiveMatch { missing, .. }) => assert_eq!(missing, "false"),
+            other => panic!("Expected NonExhaustiveMatch, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_match_duplicate_arm_is_rejected() {
+        let source = "fn label(flag: Boolean): String {\n    return match flag {\n        true => \"on\",\n        true => \"yes\",\n        false => \"off\"\n    }\n}\n";
+        let module = parse_source(source);
+
+        let mut checker = TypeChecker::new();
+        match checker.check_module(&module, 0) {
+            Err(TypeError::DuplicateMatchArm { pattern, .. }) => assert_eq!(pattern, "true"),
+            other => panic!("Expected DuplicateMatchArm, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_match_arms_must_share_a_type() {
+        let source = "fn label(flag: Boolean): String {\n    return match flag {\n        true => \"on\",\n        false => false\n    }\n}\n";
+        let module = parse_source(source);
+
+        let mut checker = TypeChecker::new();
+        match checker.check_module(&module, 0) {
+            Err(TypeError::SelectBranchTypeMismatch {
+                expected,
+                found,
+                branch_index,
+                ..
+            }) => {
+                assert_eq!(expected, "String");
+                assert_eq!(found, "Boolean");
+                assert_eq!(branch_index, 1);
+            }
+            other => panic!("Expected SelectBranchTypeMismatch, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_some_infers_option_of_its_value() {
+        let source = "fn wrap(text: String): Option<String> {\n    let wrapped = Some(text)\n    return wrapped\n}\n";
+        let module = parse_source(source);
+
+        let mut checker = TypeChecker::new();
+        assert!(checker.check_module(&module, 0).is_ok());
+    }
+
+    #[test]
+    fn test_none_takes_the_expected_type() {
+        let source = "fn take(value: Option<String>): Option<String> {\n    return value\n}\n\nfn main(): Option<Option<String>> {\n    let answer = take(None)\n    answer = None\n    return Some(None)\n}\n";
+        let module = parse_source(source);
+
+        let mut checker = TypeChecker::new();
+        assert!(checker.check_module(&module, 0).is_ok());
+    }
+
+    #[test]
+    fn test_none_without_an_expected_type_is_rejected() {
+        let source = "fn main(): () {\n    let nothing = None\n}\n";
+        let module = parse_source(source);
+
+        let mut checker = TypeChecker::new();
+        match checker.check_module(&module, 0) {
+            Err(TypeError::TypeMismatch { found, .. }) => assert_eq!(found, "None"),
+            other => panic!("Expected TypeMismatch, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_empty_list_takes_the_expected_type() {
+        let source = "fn count(items: List<String>): List<String> {\n    return items\n}\n\nfn main(): List<String> {\n    let names = count([])\n    names = []\n    return [\"a\", \"b\"]\n}\n";
+        let module = parse_source(source);
+
+        let mut checker = TypeChecker::new();
+        assert!(checker.check_module(&module, 0).is_ok());
+    }
+
+    #[test]
+    fn test_empty_list_without_an_expected_type_is_rejected() {
+        let source = "fn main(): () {\n    let nothing = []\n}\n";
+        let module = parse_source(source);
+
+        let mut checker = TypeChecker::new();
+        match checker.check_module(&module, 0) {
+            Err(TypeError::TypeMismatch { found, .. }) => assert_eq!(found, "empty list"),
+            other => panic!("Expected TypeMismatch, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_list_elements_must_share_a_type() {
+        let source = "fn main(): () {\n    let mixed = [\"a\", true]\n}\n";
+        let module = parse_source(source);
+
+        let mut checker = TypeChecker::new();
+        match checker.check_module(&module, 0) {
+            Err(TypeError::TypeMismatch {
+                expected, found, ..
+            }) => {
+                assert_eq!(expected, "String");
+                assert_eq!(found, "Boolean");
+            }
+            other => panic!("Expected TypeMismatch, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_nested_scope_variable_isolation() {
+        let func = create_test_function(
+            "test",
+            vec![],
+            AstType::Unit,
+            vec![
+                Statement::If {
+                    condition: Expression::BooleanLiteral {
+                        value: true,
+                        span: crate::types::Span::dummy(),
+                    },
+                    body: vec![Statement::Assignment {
+                        variable: "inner_var".to_string(),
+                        expression: Expression::StringLiteral {
+                            value: "hello".to_string(),
+                            multiline: false,
+                            span: crate::types::Span::dummy(),
+                        },
+                        span: crate::types::Span::dummy(),
+                        comment: None,
+                    }],
+                    else_body: None,
+                    span: crate::types::Span::dummy(),
+                    comment: None,
+                },
+                Statement::ExpressionStatement {
+                    expression: Expression::Variable {
+                        name: "inner_var".to_string(),
+                        span: crate::types::Span::dummy(),
+                    },
+                    comment: None,
+                },
+            ],
+        );
+
+        let module = create_test_module(vec![Definition::Function(func)]);
+        let mut checker = TypeChecker::new();
+
+        let result = checker.check_module(&module, 0);
+        assert!(result.is_err());
+        assert!(matches!(
+            result.unwrap_err(),
+            TypeError::UnknownVariable { .. }
         ));
     }
 
@@ -650,9 +1470,11 @@ mod tests {
                     variable: "shared".to_string(),
                     expression: Expression::StringLiteral {
                         value: "foo".to_string(),
+                        multiline: false,
                         span: crate::types::Span::dummy(),
                     },
                     span: crate::types::Span::dummy(),
+                    comment: None,
                 },
                 Statement::If {
                     condition: Expression::BooleanLiteral {
@@ -666,15 +1488,21 @@ mod tests {
                             span: crate::types::Span::dummy(),
                         },
                         span: crate::types::Span::dummy(),
+                        comment: None,
                     }],
                     else_body: None,
                     span: crate::types::Span::dummy(),
+                    comment: None,
                 },
                 // After if block, shared should still be String type from outer scope
-                Statement::Return(Expression::Variable {
-                    name: "shared".to_string(),
+                Statement::Return {
+                    expression: Expression::Variable {
+                        name: "shared".to_string(),
+                        span: crate::types::Span::dummy(),
+                    },
+                    comment: None,
                     span: crate::types::Span::dummy(),
-                }),
+                },
             ],
         );
 
@@ -706,9 +1534,11 @@ mod tests {
                         variable: "x".to_string(),
                         expression: Expression::StringLiteral {
                             value: "outer".to_string(),
+                            multiline: false,
                             span: crate::types::Span::dummy(),
                         },
                         span: crate::types::Span::dummy(),
+                        comment: None,
                     },
                     Statement::If {
                         condition: Expression::BooleanLiteral {
@@ -720,9 +1550,11 @@ mod tests {
                                 variable: "y".to_string(),
                                 expression: Expression::StringLiteral {
                                     value: "middle".to_string(),
+                                    multiline: false,
                                     span: crate::types::Span::dummy(),
                                 },
                                 span: crate::types::Span::dummy(),
+                                comment: None,
                             },
                             Statement::If {
                                 condition: Expression::BooleanLiteral {
@@ -734,34 +1566,49 @@ mod tests {
                                         variable: "z".to_string(),
                                         expression: Expression::StringLiteral {
                                             value: "inner".to_string(),
+                                            multiline: false,
                                             span: crate::types::Span::dummy(),
                                         },
                                         span: crate::types::Span::dummy(),
+                                        comment: None,
+                                    },
+                                    Statement::ExpressionStatement {
+                                        expression: Expression::Variable {
+                                            name: "x".to_string(),
+                                            span: crate::types::Span::dummy(),
+                                        },
+                                        comment: None,
+                                    },
+                                    Statement::ExpressionStatement {
+                                        expression: Expression::Variable {
+                                            name: "y".to_string(),
+                                            span: crate::types::Span::dummy(),
+                                        },
+                                        comment: None,
                                     },
-                                    Statement::ExpressionStatement(Expression::Variable {
-                                        name: "x".to_string(),
-                                        span: crate::types::Span::dummy(),
-                                    }),
-                                    Statement::ExpressionStatement(Expression::Variable {
-                                        name: "y".to_string(),
-                                        span: crate::types::Span::dummy(),
-                                    }),
                                 ],
                                 else_body: None,
                                 span: crate::types::Span::dummy(),
+                                comment: None,
                             },
                             // z should not be accessible here
                         ],
                         else_body: None,
                         span: crate::types::Span::dummy(),
+                        comment: None,
                     },
-                    Statement::Return(Expression::Variable {
-                        name: "x".to_string(),
+                    Statement::Return {
+                        expression: Expression::Variable {
+                            name: "x".to_string(),
+                            span: crate::types::Span::dummy(),
+                        },
+                        comment: None,
                         span: crate::types::Span::dummy(),
-                    }),
+                    },
                 ],
                 else_body: None,
                 span: crate::types::Span::dummy(),
+                comment: None,
             }],
         );
 
@@ -1,6 +1,7 @@
 use async_trait::async_trait;
 use std::any::Any;
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
 
 pub type FileId = usize;
 
@@ -59,9 +60,11 @@ impl SourceFiles {
 pub enum Type {
     String,
     Boolean,
+    Number,
     Unit,
     List(Box<Type>),
     Option(Box<Type>),
+    Json,
     Custom(String),
 }
 
@@ -77,6 +80,45 @@ pub struct ExternalFunctionDefinition {
     pub parameters: Vec<Parameter>,
     pub return_type: Type,
     pub documentation: Option<String>,
+    pub annotations: FunctionAnnotations,
+    /// The JSON schema a provider gave for the function's arguments, as MCP
+    /// tools do. Arguments are converted to fit it when the function is called.
+    pub input_schema: Option<Arc<serde_json::Map<String, serde_json::Value>>>,
+}
+
+/// What a provider says calling a function does, from MCP tool annotations.
+/// Functions without annotations are neither read-only nor destructive.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FunctionAnnotations {
+    pub read_only: bool,
+    pub destructive: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExternalConstDefinition {
+    pub name: String,
+    pub const_type: Type,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlaceholderTarget {
+    pub function_name: String,
+    pub documentation: Option<String>,
+}
+
+impl ExternalConstDefinition {
+    pub fn new(name: String, const_type: Type) -> Self {
+        Self { name, const_type }
+    }
+}
+
+impl PlaceholderTarget {
+    pub fn new(function_name: String, documentation: Option<String>) -> Self {
+        Self {
+            function_name,
+            documentation,
+        }
+    }
 }
 
 impl Type {
@@ -92,6 +134,10 @@ impl Type {
         Self::Boolean
     }
 
+    pub fn number() -> Self {
+        Self::Number
+    }
+
     pub fn custom(name: String) -> Self {
         Self::Custom(name)
     }
@@ -104,18 +150,60 @@ impl Type {
         Self::Option(Box::new(inner))
     }
 
+    pub fn json() -> Self {
+        Self::Json
+    }
+
     pub fn name(&self) -> String {
         match self {
             Type::String => "String".to_string(),
             Type::Boolean => "Boolean".to_string(),
+            Type::Number => "Number".to_string(),
             Type::Unit => "()".to_string(),
             Type::List(inner) => format!("List<{}>", inner.name()),
             Type::Option(inner) => format!("Option<{}>", inner.name()),
+            Type::Json => "Json".to_string(),
             Type::Custom(name) => name.clone(),
         }
     }
 }
 
+impl std::fmt::Display for Type {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl From<&crate::ast::Type> for Type {
+    fn from(ast_type: &crate::ast::Type) -> Self {
+        match ast_type {
+            crate::ast::Type::Unit => Type::unit(),
+            crate::ast::Type::Boolean => Type::boolean(),
+            crate::ast::Type::Number => Type::number(),
+            crate::ast::Type::String => Type::string(),
+            crate::ast::Type::List(inner) => Type::list(Type::from(inner.as_ref())),
+            crate::ast::Type::Option(inner) => Type::option(Type::from(inner.as_ref())),
+            crate::ast::Type::Json => Type::json(),
+            crate::ast::Type::Custom(name) => Type::custom(name.clone()),
+        }
+    }
+}
+
+impl From<&Type> for crate::ast::Type {
+    fn from(runtime_type: &Type) -> Self {
+        match runtime_type {
+            Type::Unit => crate::ast::Type::Unit,
+            Type::Boolean => crate::ast::Type::Boolean,
+            Type::Number => crate::ast::Type::Number,
+            Type::String => crate::ast::Type::String,
+            Type::List(inner) => crate::ast::Type::List(Box::new(inner.as_ref().into())),
+            Type::Option(inner) => crate::ast::Type::Option(Box::new(inner.as_ref().into())),
+            Type::Json => crate::ast::Type::Json,
+            Type::Custom(name) => crate::ast::Type::Custom(name.clone()),
+        }
+    }
+}
+
 impl Parameter {
     pub fn new(name: String, param_type: Type) -> Self {
         Self { name, param_type }
@@ -129,6 +217,8 @@ impl ExternalFunctionDefinition {
             parameters,
             return_type,
             documentation: None,
+            annotations: FunctionAnnotations::default(),
+            input_schema: None,
         }
     }
 
@@ -143,8 +233,23 @@ impl ExternalFunctionDefinition {
             parameters,
             return_type,
             documentation,
+            annotations: FunctionAnnotations::default(),
+            input_schema: None,
         }
     }
+
+    pub fn with_annotations(mut self, annotations: FunctionAnnotations) -> Self {
+        self.annotations = annotations;
+        self
+    }
+
+    pub fn with_input_schema(
+        mut self,
+        schema: Arc<serde_json::Map<String, serde_json::Value>>,
+    ) -> Self {
+        self.input_schema = Some(schema);
+        self
+    }
 }
 
 #[async_trait]
@@ -169,6 +274,87 @@ pub trait ExecutableFunction: Function + std::fmt::Debug + Send + Sync {
     fn clone_executable(&self) -> Box<dyn ExecutableFunction>;
 }
 
+/// Source of the current time for token expiry, request timeouts and event
+/// timestamps, so that time-dependent behaviour can be tested without
+/// sleeping.
+#[async_trait]
+pub(crate) trait Clock: std::fmt::Debug + Send + Sync {
+    fn now(&self) -> SystemTime;
+
+    /// Completes once `duration` has passed on this clock.
+    async fn sleep(&self, duration: Duration);
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct SystemClock;
+
+#[async_trait]
+impl Clock for SystemClock {
+    fn now(&self) -> SystemTime {
+        SystemTime::now()
+    }
+
+    async fn sleep(&self, duration: Duration) {
+        tokio::time::sleep(duration).await;
+    }
+}
+
+/// A clock that only moves when told to. Sleeps complete once the clock has
+/// been advanced past their end.
+#[cfg(test)]
+#[derive(Debug)]
+pub(crate) struct MockClock {
+    now: Mutex<SystemTime>,
+    advanced: tokio::sync::Notify,
+}
+
+#[cfg(test)]
+impl MockClock {
+    pub(crate) fn new(start: SystemTime) -> Self {
+        Self {
+            now: Mutex::new(start),
+            advanced: tokio::sync::Notify::new(),
+        }
+    }
+
+    pub(crate) fn advance(&self, duration: Duration) {
+        *self.now.lock().unwrap() += duration;
+        self.advanced.notify_waiters();
+    }
+}
+
+#[cfg(test)]
+impl Default for MockClock {
+    fn default() -> Self {
+        Self::new(SystemTime::UNIX_EPOCH)
+    }
+}
+
+#[cfg(test)]
+#[async_trait]
+impl Clock for MockClock {
+    fn now(&self) -> SystemTime {
+        *self.now.lock().unwrap()
+    }
+
+    async fn sleep(&self, duration: Duration) {
+        let end = self.now() + duration;
+        loop {
+            // Registered before the check so an advance in between still wakes it.
+            let advanced = self.advanced.notified();
+            if self.now() >= end {
+                return;
+            }
+            advanced.await;
+        }
+    }
+}
+
+/// Tracing target for engine prompts, responses and model thoughts. These
+/// events carry raw prompt text, so subscribers drop them unless configured to
+/// forward them.
+pub const ENGINE_THOUGHT_TARGET: &str = "structured_agent::engine_thought";
+
 #[async_trait]
 pub trait LanguageEngine: Send + Sync {
     async fn untyped(&self, context: &crate::runtime::Context) -> String;
@@ -182,50 +368,34 @@ pub trait LanguageEngine: Send + Sync {
         context: &crate::runtime::Context,
         options: &[crate::runtime::ExpressionValue],
     ) -> Result<usize, String>;
+    /// Orders `options` from most to least suitable for `select ranked`. The
+    /// default puts the engine's single pick first and keeps the rest in order.
+    async fn rank(
+        &self,
+        context: &crate::runtime::Context,
+        options: &[crate::runtime::ExpressionValue],
+    ) -> Result<Vec<usize>, String> {
+        let first = self.select(context, options).await?;
+        Ok(std::iter::once(first)
+            .chain((0..options.len()).filter(|index| *index != first))
+            .collect())
+    }
     async fn fill_parameter(
         &self,
         context: &crate::runtime::Context,
         param_name: &str,
         param_type: &Type,
+        target: Option<&PlaceholderTarget>,
     ) -> Result<crate::runtime::ExpressionValue, String>;
 }
 
 pub struct PrintEngine {}
 
-impl PrintEngine {
-    fn format_event(event: &crate::runtime::Event) -> String {
-        let content = event.content.format_for_llm();
-
-        if let Some(name) = &event.name {
-            let params_xml = if let Some(params) = &event.params {
-                let params_str = params
-                    .iter()
-                    .map(|p| {
-                        let value = p.value.format_for_llm();
-                        format!("    <param name=\"{}\">{}</param>", p.name, value)
-                    })
-                    .collect::<Vec<_>>()
-                    .join("\n");
-                format!("{}\n", params_str)
-            } else {
-                String::new()
-            };
-
-            format!(
-                "<{}>\n{}    <result>\n    {}\n    </result>\n</{}>",
-                name, params_xml, content, name
-            )
-        } else {
-            content
-        }
-    }
-}
-
 #[async_trait]
 impl LanguageEngine for PrintEngine {
     async fn untyped(&self, context: &crate::runtime::Context) -> String {
         if let Some(last_event) = context.last_event() {
-            Self::format_event(&last_event)
+            context.render_event(&last_event)
         } else {
             "PrintEngine {}".to_string()
         }
@@ -242,12 +412,19 @@ impl LanguageEngine for PrintEngine {
                 Ok(crate::runtime::ExpressionValue::String(value))
             }
             Type::Boolean => Ok(crate::runtime::ExpressionValue::Boolean(true)),
+            Type::Number => Ok(crate::runtime::ExpressionValue::Number(0.0)),
             Type::Unit => Ok(crate::runtime::ExpressionValue::Unit),
             Type::List(_) => {
                 let value = self.untyped(context).await;
                 Ok(crate::runtime::ExpressionValue::String(value))
             }
             Type::Option(_) => Ok(crate::runtime::ExpressionValue::Option(None)),
+            Type::Json => {
+                let value = self.untyped(context).await;
+                Ok(crate::runtime::ExpressionValue::Json(
+                    serde_json::Value::String(value),
+                ))
+            }
             Type::Custom(_) => {
                 let value = self.untyped(context).await;
                 Ok(crate::runtime::ExpressionValue::String(value))
@@ -268,6 +445,7 @@ impl LanguageEngine for PrintEngine {
         context: &crate::runtime::Context,
         param_name: &str,
         param_type: &Type,
+        _target: Option<&PlaceholderTarget>,
     ) -> Result<crate::runtime::ExpressionValue, String> {
         match param_type {
             Type::String => {
@@ -275,11 +453,18 @@ impl LanguageEngine for PrintEngine {
                 Ok(crate::runtime::ExpressionValue::String(value))
             }
             Type::Boolean => Ok(crate::runtime::ExpressionValue::Boolean(true)),
+            Type::Number => Ok(crate::runtime::ExpressionValue::Number(0.0)),
             Type::List(_) => {
                 let value = self.untyped(context).await;
                 Ok(crate::runtime::ExpressionValue::String(value))
             }
             Type::Option(_) => Ok(crate::runtime::ExpressionValue::Option(None)),
+            Type::Json => {
+                let value = self.untyped(context).await;
+                Ok(crate::runtime::ExpressionValue::Json(
+                    serde_json::Value::String(value),
+                ))
+            }
             Type::Unit | Type::Custom(_) => Ok(crate::runtime::ExpressionValue::String(format!(
                 "PrintEngine: {} ({})",
                 param_name,
@@ -305,6 +490,9 @@ pub trait NativeFunction: std::fmt::Debug + Send + Sync {
 
 #[async_trait]
 pub trait FunctionProvider: Send + Sync {
+    fn name(&self) -> String {
+        "unnamed provider".to_string()
+    }
     async fn list_functions(
         &self,
     ) -> Result<Vec<ExternalFunctionDefinition>, crate::runtime::RuntimeError>;
@@ -312,4 +500,48 @@ pub trait FunctionProvider: Send + Sync {
         &self,
         definition: &ExternalFunctionDefinition,
     ) -> Result<std::sync::Arc<dyn ExecutableFunction>, crate::runtime::RuntimeError>;
+    async fn resolve_constant(
+        &self,
+        _definition: &ExternalConstDefinition,
+    ) -> Result<Option<crate::runtime::ExpressionValue>, crate::runtime::RuntimeError> {
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Type;
+    use crate::ast;
+
+    #[test]
+    fn test_ast_and_runtime_types_render_identically() {
+        let cases = vec![
+            ast::Type::Unit,
+            ast::Type::Boolean,
+            ast::Type::Number,
+            ast::Type::String,
+            ast::Type::List(Box::new(ast::Type::String)),
+            ast::Type::Option(Box::new(ast::Type::Boolean)),
+            ast::Type::List(Box::new(ast::Type::Option(Box::new(ast::Type::Unit)))),
+            ast::Type::Option(Box::new(ast::Type::List(Box::new(ast::Type::String)))),
+        ];
+
+        for ast_type in cases {
+            let runtime_type = Type::from(&ast_type);
+            assert_eq!(ast_type.to_string(), runtime_type.name());
+            assert_eq!(ast_type.to_string(), runtime_type.to_string());
+        }
+    }
+
+    #[test]
+    fn test_canonical_type_names() {
+        assert_eq!(Type::unit().name(), "()");
+        assert_eq!(Type::boolean().name(), "Boolean");
+        assert_eq!(Type::string().name(), "String");
+        assert_eq!(Type::list(Type::string()).name(), "List<String>");
+        assert_eq!(
+            Type::option(Type::list(Type::boolean())).name(),
+            "Option<List<Boolean>>"
+        );
+    }
 }
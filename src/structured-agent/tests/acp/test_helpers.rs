@@ -29,6 +29,18 @@ impl TestAgent {
             with_default_functions: true,
             with_unstable_functions: false,
             with_acp_functions: false,
+            describe_bindings: false,
+            analysis_stats: false,
+            check_providers: false,
+            disabled_analyzers: Vec::new(),
+            profile: None,
+            audit_log: None,
+            thought_updates: false,
+            eager_connect: false,
+            max_call_depth: None,
+            run_stats: false,
+            relaxed_signature_matching: false,
+            confirm_destructive: false,
             mode: Mode::Acp,
         };
 
@@ -47,6 +59,18 @@ impl TestAgent {
             with_default_functions: true,
             with_unstable_functions: false,
             with_acp_functions: false,
+            describe_bindings: false,
+            analysis_stats: false,
+            check_providers: false,
+            disabled_analyzers: Vec::new(),
+            profile: None,
+            audit_log: None,
+            thought_updates: false,
+            eager_connect: false,
+            max_call_depth: None,
+            run_stats: false,
+            relaxed_signature_matching: false,
+            confirm_destructive: false,
             mode: Mode::Acp,
         };
 
@@ -1,6 +1,10 @@
 mod integration {
     mod assignment_test;
+    mod embedding_test;
     mod integration_test;
+    mod option_test;
+    mod parse_source_test;
     mod return_statement_test;
+    mod samples_test;
     mod simple_test;
 }
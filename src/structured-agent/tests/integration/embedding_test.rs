@@ -0,0 +1,91 @@
+//! Embeds the runtime the way a host application would, using only the
+//! public API, so changes to its shape show up here first.
+
+use async_trait::async_trait;
+use std::sync::Arc;
+use structured_agent::compiler::CompilationUnit;
+use structured_agent::runtime::{Context, EventRole, ExpressionValue, Runtime};
+use structured_agent::types::{LanguageEngine, Parameter, PlaceholderTarget, Type};
+
+const PROGRAM: &str = r#"
+extern fn shout(text: String): String
+
+## Greets someone by name.
+fn greet(name: String): String {
+    "Write a greeting."!
+    shout(name)!
+}
+
+fn main(): String {
+    let greeting = greet(_)
+    greeting!
+    greeting
+}
+"#;
+
+/// Fills every placeholder with "ada" and answers generations by greeting
+/// whatever was injected last.
+struct GreetingEngine;
+
+#[async_trait]
+impl LanguageEngine for GreetingEngine {
+    async fn untyped(&self, _context: &Context) -> String {
+        String::new()
+    }
+
+    async fn typed(
+        &self,
+        context: &Context,
+        _return_type: &Type,
+    ) -> Result<ExpressionValue, String> {
+        let last = context.iter_all_events().last().ok_or("nothing to greet")?;
+        let name = String::try_from(last.content)?;
+        Ok(format!("Hello, {}!", name).into())
+    }
+
+    async fn select(
+        &self,
+        _context: &Context,
+        _options: &[ExpressionValue],
+    ) -> Result<usize, String> {
+        Ok(0)
+    }
+
+    async fn fill_parameter(
+        &self,
+        _context: &Context,
+        _param_name: &str,
+        _param_type: &Type,
+        _target: Option<&PlaceholderTarget>,
+    ) -> Result<ExpressionValue, String> {
+        Ok(ExpressionValue::from("ada".to_string()))
+    }
+}
+
+#[tokio::test]
+async fn test_embedded_run_with_custom_engine_and_closure_function() {
+    let runtime = Runtime::builder(CompilationUnit::from_string(PROGRAM.to_string()))
+        .with_language_engine(Arc::new(GreetingEngine))
+        .with_native_fn(
+            "shout",
+            vec![Parameter::new("text".to_string(), Type::string())],
+            Type::string(),
+            |args| {
+                let text = args.into_iter().next().ok_or("shout takes one argument")?;
+                Ok(String::try_from(text)?.to_uppercase().into())
+            },
+        )
+        .build();
+
+    let (result, events) = runtime.run_collecting().await.unwrap();
+
+    assert_eq!(String::try_from(result).unwrap(), "Hello, ADA!");
+
+    let greeting = events.last().unwrap();
+    assert_eq!(greeting.name.as_deref(), Some("greet"));
+    assert_eq!(greeting.role, EventRole::Model);
+    assert_eq!(
+        greeting.content,
+        ExpressionValue::String("Hello, ADA!".to_string())
+    );
+}
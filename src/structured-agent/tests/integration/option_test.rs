@@ -0,0 +1,58 @@
+use std::sync::Arc;
+use structured_agent::compiler::CompilationUnit;
+use structured_agent::functions::IsSomeFunction;
+use structured_agent::runtime::{ExpressionValue, Runtime};
+
+async fn run(source: &str) -> ExpressionValue {
+    let program = CompilationUnit::from_string(source.to_string());
+    Runtime::builder(program)
+        .with_native_function(Arc::new(IsSomeFunction::new()))
+        .build()
+        .run()
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_is_some_sees_a_parsed_some() {
+    let result = run(r#"
+extern fn is_some(option: Option<String>): Boolean
+
+fn main(): Boolean {
+    return is_some(Some("found"))
+}
+"#)
+    .await;
+
+    assert_eq!(result, ExpressionValue::Boolean(true));
+}
+
+#[tokio::test]
+async fn test_is_some_sees_a_parsed_none() {
+    let result = run(r#"
+extern fn is_some(option: Option<String>): Boolean
+
+fn main(): Boolean {
+    return is_some(None)
+}
+"#)
+    .await;
+
+    assert_eq!(result, ExpressionValue::Boolean(false));
+}
+
+#[tokio::test]
+async fn test_some_keeps_its_value() {
+    let result = run(r#"
+fn main(): Option<String> {
+    let greeting = "hello"
+    return Some(greeting)
+}
+"#)
+    .await;
+
+    assert_eq!(
+        result,
+        ExpressionValue::Option(Some(Box::new(ExpressionValue::String("hello".to_string()))))
+    );
+}
@@ -0,0 +1,33 @@
+use structured_agent::ast::Definition;
+use structured_agent::parse_source;
+
+#[test]
+fn test_parse_source_returns_module_for_valid_program() {
+    let module = parse_source(
+        r#"
+fn main(): String {
+    return "hello"
+}
+"#,
+    )
+    .unwrap();
+
+    assert_eq!(module.definitions.len(), 1);
+    assert!(matches!(
+        &module.definitions[0],
+        Definition::Function(func) if func.name == "main"
+    ));
+}
+
+#[test]
+fn test_parse_source_returns_diagnostics_for_malformed_program() {
+    let source = "fn main(: () {";
+
+    let diagnostics = parse_source(source).unwrap_err();
+
+    assert_eq!(diagnostics.len(), 1);
+    let labels = &diagnostics[0].labels;
+    assert_eq!(labels.len(), 1);
+    assert!(labels[0].range.start < source.len());
+    assert!(labels[0].range.start < labels[0].range.end);
+}
@@ -0,0 +1,74 @@
+use std::path::{Path, PathBuf};
+use structured_agent::compiler::CompilationUnit;
+use structured_agent::runtime::{ExpressionValue, Runtime};
+
+// Broken on purpose, to show off diagnostics.
+const INVALID_SAMPLES: &[&str] = &["invalid.sa"];
+
+// Samples that need neither MCP tools nor user input, so they run to the end
+// under the default PrintEngine.
+const SELF_CONTAINED_SAMPLES: &[&str] = &[
+    "boolean.sa",
+    "control_flow.sa",
+    "example.sa",
+    "hello.sa",
+    "if_statement.sa",
+    "simple_boolean.sa",
+    "simple_control_flow.sa",
+    "test.sa",
+    "variables.sa",
+    "verbose.sa",
+    "while_statement.sa",
+];
+
+fn samples_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("samples")
+}
+
+fn sample_paths(dir: &Path, paths: &mut Vec<PathBuf>) {
+    for entry in std::fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.is_dir() {
+            sample_paths(&path, paths);
+        } else if path.extension().is_some_and(|ext| ext == "sa") {
+            paths.push(path);
+        }
+    }
+}
+
+fn load(path: &Path) -> Runtime {
+    let source = std::fs::read_to_string(path).unwrap();
+    let program = CompilationUnit::from_file(path.display().to_string(), source);
+    Runtime::builder(program).build()
+}
+
+#[test]
+fn test_samples_compile() {
+    let mut paths = Vec::new();
+    sample_paths(&samples_dir(), &mut paths);
+    assert!(!paths.is_empty(), "no samples found");
+
+    for path in paths {
+        let name = path.file_name().unwrap().to_str().unwrap();
+        let result = load(&path).check();
+        if INVALID_SAMPLES.contains(&name) {
+            assert!(result.is_err(), "{} should not compile", path.display());
+        } else {
+            assert!(
+                result.is_ok(),
+                "{} failed to compile: {:?}",
+                path.display(),
+                result
+            );
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_self_contained_samples_run_with_print_engine() {
+    for name in SELF_CONTAINED_SAMPLES {
+        let path = samples_dir().join(name);
+        let result = load(&path).run().await;
+        assert_eq!(result, Ok(ExpressionValue::Unit), "{}", name);
+    }
+}
@@ -1,3 +1,6 @@
 mod mcp {
+    mod eager_connect_test;
     mod echo_integration_test;
+    mod list_tools_test;
+    mod retry_test;
 }
@@ -0,0 +1,68 @@
+use structured_agent::cli::config::{EngineType, McpServerConfig, ProgramSource};
+use structured_agent::cli::{Config, Mode};
+use structured_agent::compiler::CompilationUnit;
+use structured_agent::runtime::Runtime;
+
+const PROGRAM: &str = r#"
+fn main(): () {
+}
+"#;
+
+fn run_config(eager_connect: bool) -> Config {
+    Config {
+        program_source: ProgramSource::Inline(PROGRAM.to_string()),
+        mcp_servers: vec![McpServerConfig {
+            command: "structured-agent-missing-mcp-server".to_string(),
+            args: vec![],
+        }],
+        engine: EngineType::Print,
+        with_default_functions: false,
+        with_unstable_functions: false,
+        with_acp_functions: false,
+        describe_bindings: false,
+        analysis_stats: false,
+        check_providers: false,
+        disabled_analyzers: Vec::new(),
+        profile: None,
+        audit_log: None,
+        thought_updates: false,
+        eager_connect,
+        max_call_depth: None,
+        run_stats: false,
+        relaxed_signature_matching: false,
+        confirm_destructive: false,
+        mode: Mode::Run,
+    }
+}
+
+fn builder() -> structured_agent::runtime::RuntimeBuilder {
+    Runtime::builder(CompilationUnit::from_string(PROGRAM.to_string()))
+}
+
+#[tokio::test]
+async fn test_eager_connect_reports_bad_server_at_build_time() {
+    let result = builder().from_config(&run_config(true)).await;
+
+    let error = result.err().expect("eager build should fail");
+    assert!(
+        error.contains("Failed to connect to MCP server 'structured-agent-missing-mcp-server'"),
+        "Unexpected error: {}",
+        error
+    );
+}
+
+#[tokio::test]
+async fn test_lazy_connect_fails_only_on_first_use() {
+    let runtime = builder()
+        .from_config(&run_config(false))
+        .await
+        .expect("lazy build should not start the server");
+
+    let error = runtime.run().await.expect_err("first run should fail");
+
+    assert!(
+        error.to_string().contains("Failed to list MCP tools"),
+        "Unexpected error: {}",
+        error
+    );
+}
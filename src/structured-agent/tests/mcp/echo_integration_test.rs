@@ -308,3 +308,134 @@ fn main(): String {{
         ),
     }
 }
+
+#[cfg(target_os = "linux")]
+fn process_is_running(pid: u32) -> bool {
+    // A reaped process has no /proc entry; a zombie has exited but not been waited on.
+    match std::fs::read_to_string(format!("/proc/{}/stat", pid)) {
+        Ok(stat) => stat
+            .rsplit_once(") ")
+            .is_some_and(|(_, rest)| !rest.starts_with('Z')),
+        Err(_) => false,
+    }
+}
+
+#[cfg(target_os = "linux")]
+#[tokio::test]
+async fn test_mcp_server_stops_when_runtime_is_dropped() {
+    let mcp_client = McpClient::new_stdio(
+        "uv",
+        vec![
+            "run".to_string(),
+            "python".to_string(),
+            "tests/mcp/mcp_echo_server.py".to_string(),
+        ],
+    )
+    .await
+    .unwrap();
+
+    mcp_client.list_tools().await.unwrap();
+    let pid = mcp_client
+        .process_id()
+        .expect("connected client should know its server's pid");
+    assert!(process_is_running(pid));
+
+    let program = CompilationUnit::from_string(
+        r#"
+extern fn echo(message: String): String
+
+fn main(): String {
+    return echo("bye")
+}
+"#
+        .to_string(),
+    );
+    let runtime = Runtime::builder(program)
+        .with_mcp_client(mcp_client)
+        .build();
+    runtime.run().await.unwrap();
+    drop(runtime);
+
+    let deadline = tokio::time::Instant::now() + std::time::Duration::from_secs(10);
+    while process_is_running(pid) {
+        assert!(
+            tokio::time::Instant::now() < deadline,
+            "MCP server process {} outlived its runtime",
+            pid
+        );
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    }
+}
+
+#[tokio::test]
+async fn test_mcp_boolean_tool_binds_to_boolean_extern() {
+    let mcp_client = McpClient::new_stdio(
+        "uv",
+        vec![
+            "run".to_string(),
+            "python".to_string(),
+            "tests/mcp/mcp_echo_server.py".to_string(),
+        ],
+    )
+    .await
+    .unwrap();
+
+    let program = CompilationUnit::from_string(
+        r#"
+extern fn is_empty(message: String): Boolean
+
+fn main(): Boolean {
+    return is_empty("")
+}
+"#
+        .to_string(),
+    );
+    let runtime = Runtime::builder(program)
+        .with_compiler(Arc::new(Compiler::new()))
+        .with_mcp_client(mcp_client)
+        .build();
+
+    let result = runtime.run().await;
+
+    use structured_agent::runtime::ExpressionValue;
+    assert_eq!(result, Ok(ExpressionValue::Boolean(true)));
+}
+
+#[tokio::test]
+async fn test_mcp_resource_binds_to_extern_const() {
+    let mcp_client = McpClient::new_stdio(
+        "uv",
+        vec![
+            "run".to_string(),
+            "python".to_string(),
+            "tests/mcp/mcp_echo_server.py".to_string(),
+        ],
+    )
+    .await
+    .unwrap();
+
+    let program = CompilationUnit::from_string(
+        r#"
+extern const greeting: String
+
+fn main(): String {
+    return greeting
+}
+"#
+        .to_string(),
+    );
+    let runtime = Runtime::builder(program)
+        .with_compiler(Arc::new(Compiler::new()))
+        .with_mcp_client(mcp_client)
+        .build();
+
+    let result = runtime.run().await;
+
+    use structured_agent::runtime::ExpressionValue;
+    assert_eq!(
+        result,
+        Ok(ExpressionValue::String(
+            "Hello from the echo server".to_string()
+        ))
+    );
+}
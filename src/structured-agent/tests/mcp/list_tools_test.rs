@@ -0,0 +1,91 @@
+use structured_agent::cli::config::{EngineType, McpServerConfig, ProgramSource};
+use structured_agent::cli::tools::list_server_tools;
+use structured_agent::cli::{App, Config, Mode};
+
+fn list_tools_config(mcp_servers: Vec<McpServerConfig>) -> Config {
+    Config {
+        program_source: ProgramSource::Inline(String::new()),
+        mcp_servers,
+        engine: EngineType::Print,
+        with_default_functions: false,
+        with_unstable_functions: false,
+        with_acp_functions: false,
+        describe_bindings: false,
+        analysis_stats: false,
+        check_providers: false,
+        disabled_analyzers: Vec::new(),
+        profile: None,
+        audit_log: None,
+        thought_updates: false,
+        eager_connect: false,
+        max_call_depth: None,
+        run_stats: false,
+        relaxed_signature_matching: false,
+        confirm_destructive: false,
+        mode: Mode::ListTools,
+    }
+}
+
+fn echo_server() -> McpServerConfig {
+    McpServerConfig {
+        command: "uv".to_string(),
+        args: vec![
+            "run".to_string(),
+            "python".to_string(),
+            "tests/mcp/mcp_echo_server.py".to_string(),
+        ],
+    }
+}
+
+fn invalid_server() -> McpServerConfig {
+    McpServerConfig {
+        command: "structured-agent-missing-mcp-server".to_string(),
+        args: vec![],
+    }
+}
+
+#[tokio::test]
+async fn test_list_tools_reports_invalid_server_and_lists_valid_one() {
+    let results = list_server_tools(&[invalid_server(), echo_server()]).await;
+
+    assert_eq!(results.len(), 2);
+
+    let error = results[0]
+        .result
+        .as_ref()
+        .expect_err("invalid server should fail");
+    assert!(
+        error.contains("Failed to list MCP tools"),
+        "Unexpected error: {}",
+        error
+    );
+
+    let definitions = results[1]
+        .result
+        .as_ref()
+        .expect("echo server should list its tools");
+    assert!(definitions.iter().any(|d| d.name == "echo"));
+}
+
+#[tokio::test]
+async fn test_list_tools_succeeds_when_at_least_one_server_succeeds() {
+    let config = list_tools_config(vec![invalid_server(), echo_server()]);
+
+    let result = App::run(config).await;
+
+    assert!(result.is_ok(), "Expected success, got: {:?}", result.err());
+}
+
+#[tokio::test]
+async fn test_list_tools_fails_when_all_servers_fail() {
+    let config = list_tools_config(vec![invalid_server()]);
+
+    let result = App::run(config).await;
+
+    let error = result.expect_err("all servers failing should be an error");
+    assert!(
+        error
+            .to_string()
+            .contains("Failed to list tools from all 1")
+    );
+}
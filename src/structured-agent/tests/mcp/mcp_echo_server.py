@@ -17,11 +17,23 @@ def echo_with_prefix(message: str, prefix: str = "Echo: ") -> str:
     return f"{prefix}{message}"
 
 
+@mcp.tool
+def is_empty(message: str) -> bool:
+    """Report whether the message is empty."""
+    return message == ""
+
+
 @mcp.tool
 def echo_json(data: dict[str, Any]) -> dict[str, Any]:
     """Echo back JSON data exactly as received."""
     return data
 
 
+@mcp.resource("config://greeting", name="greeting")
+def greeting() -> str:
+    """A fixed greeting, read as an extern const."""
+    return "Hello from the echo server"
+
+
 if __name__ == "__main__":
     mcp.run()
@@ -0,0 +1,80 @@
+use std::path::Path;
+use std::time::Duration;
+use structured_agent::mcp::{McpClient, McpError, RetryPolicy};
+
+/// Writes a script that exits with an error on its first `failures` runs and
+/// starts the echo server after that, counting runs in `dir/attempts`.
+fn flaky_server(dir: &Path, failures: u32) -> String {
+    let server = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/mcp/mcp_echo_server.py");
+    let counter = dir.join("attempts");
+    let script = dir.join("flaky_server.sh");
+    std::fs::write(
+        &script,
+        format!(
+            r#"count=$(cat "{counter}" 2>/dev/null || echo 0)
+count=$((count + 1))
+echo "$count" > "{counter}"
+if [ "$count" -le {failures} ]; then
+    exit 1
+fi
+exec uv run python "{server}"
+"#,
+            counter = counter.display(),
+            failures = failures,
+            server = server.display(),
+        ),
+    )
+    .unwrap();
+    script.display().to_string()
+}
+
+fn attempts(dir: &Path) -> u32 {
+    std::fs::read_to_string(dir.join("attempts"))
+        .unwrap()
+        .trim()
+        .parse()
+        .unwrap()
+}
+
+fn policy(max_attempts: u32) -> RetryPolicy {
+    RetryPolicy {
+        max_attempts,
+        initial_delay: Duration::from_millis(10),
+        backoff_factor: 2.0,
+    }
+}
+
+#[tokio::test]
+async fn test_retry_policy_reconnects_to_flaky_server() {
+    let dir = tempfile::tempdir().unwrap();
+    let client = McpClient::new_stdio("sh", vec![flaky_server(dir.path(), 2)])
+        .await
+        .unwrap()
+        .with_retry_policy(policy(3));
+
+    let tools = client.list_tools().await;
+
+    assert!(
+        tools.is_ok(),
+        "Expected retries to connect: {:?}",
+        tools.err()
+    );
+    assert_eq!(attempts(dir.path()), 3);
+}
+
+#[tokio::test]
+async fn test_retry_policy_gives_up_after_max_attempts() {
+    let dir = tempfile::tempdir().unwrap();
+    let client = McpClient::new_stdio("sh", vec![flaky_server(dir.path(), 5)])
+        .await
+        .unwrap()
+        .with_retry_policy(policy(2));
+
+    match client.ensure_connected().await {
+        Err(McpError::ConnectionError(msg)) => {
+            assert!(msg.contains("gave up after 2 attempts"), "{}", msg)
+        }
+        other => panic!("Expected connection error, got: {:?}", other),
+    }
+    assert_eq!(attempts(dir.path()), 2);
+}